@@ -2,12 +2,14 @@ use std::cell::RefCell;
 use std::collections::*;
 use std::option::*;
 use std::rc::Rc;
+use std::str::FromStr;
 use std::thread::*;
 use std::time::*;
 
 use argh::FromArgs;
 
 use fcpeg::*;
+use fcpeg::formatter::*;
 
 use rustnutlib::*;
 use rustnutlib::console::*;
@@ -24,6 +26,8 @@ fn main() {
     };
 
     match cmd.subcmd {
+        Subcommand::Format(subcmd) => spawn(move || proc_format_subcmd(&subcmd, cons)).join().unwrap(),
+        Subcommand::Generate(subcmd) => spawn(move || proc_generate_subcmd(&subcmd, cons)).join().unwrap(),
         Subcommand::Manual(subcmd) => spawn(move || proc_manual_subcommand(&subcmd, cons)).join().unwrap(),
         Subcommand::Parse(subcmd) => spawn(move || proc_parse_subcmd(&subcmd, cons)).join().unwrap(),
     }
@@ -40,10 +44,42 @@ struct MainCommand {
 #[derive(FromArgs, PartialEq)]
 #[argh(subcommand)]
 enum Subcommand {
+    Format(FormatSubcommand),
+    Generate(GenerateSubcommand),
     Manual(ManualSubcommand),
     Parse(ParseSubcommand),
 }
 
+/// fmt subcommand
+#[derive(Clone, FromArgs, PartialEq)]
+#[argh(subcommand, name = "fmt")]
+struct FormatSubcommand {
+    /// whether to check formatting without rewriting file
+    #[argh(switch)]
+    check: bool,
+
+    /// file path of fcpeg source
+    #[argh(option, short = 'f')]
+    fcpeg: String,
+}
+
+/// gen subcommand
+#[derive(Clone, FromArgs, PartialEq)]
+#[argh(subcommand, name = "gen")]
+struct GenerateSubcommand {
+    /// file path of fcpeg source
+    #[argh(option, short = 'f')]
+    fcpeg: String,
+
+    /// file path of generated rust source
+    #[argh(option, short = 'o')]
+    output: String,
+
+    /// whether to generate typed ast source instead of parser
+    #[argh(switch)]
+    ast: bool,
+}
+
 /// man subcommand
 #[derive(Clone, FromArgs, PartialEq)]
 #[argh(subcommand, name = "man")]
@@ -57,10 +93,18 @@ struct ParseSubcommand {
     #[argh(option, short = 'f')]
     fcpeg: String,
 
+    /// output format of syntax tree (text, json, sexpr or xml)
+    #[argh(option, default = "TreeFormat::Text")]
+    format: TreeFormat,
+
     /// file path of input source
     #[argh(option, short = 'i')]
     input: String,
 
+    /// whether to keep hidden elements in output syntax tree
+    #[argh(switch)]
+    keep_hidden: bool,
+
     /// whether to enable monitoring mode
     #[argh(switch)]
     mon: bool,
@@ -73,26 +117,92 @@ struct ParseSubcommand {
     #[argh(switch, short = 'o')]
     output: bool,
 
+    /// whether to recover from syntax errors
+    #[argh(switch)]
+    recover: bool,
+
     /// whether to output processing time
     #[argh(switch, short = 't')]
     time: bool,
 }
 
+#[derive(Clone, Copy, PartialEq)]
+enum TreeFormat {
+    Json,
+    SExpression,
+    Text,
+    Xml,
+}
+
+impl FromStr for TreeFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<TreeFormat, String> {
+        return match s {
+            "json" => Ok(TreeFormat::Json),
+            "sexpr" => Ok(TreeFormat::SExpression),
+            "text" => Ok(TreeFormat::Text),
+            "xml" => Ok(TreeFormat::Xml),
+            _ => Err(format!("unknown tree format '{}'", s)),
+        };
+    }
+}
+
+// spec: parse サブコマンドでパース及び出力の方法を指定するオプション
+#[derive(Clone)]
+struct ParseOptions {
+    output_tree: bool,
+    tree_format: TreeFormat,
+    count_duration: bool,
+    disable_opt: bool,
+    enable_recovery: bool,
+    ignore_hidden_elems: bool,
+}
+
+impl ParseOptions {
+    pub fn from_subcmd(subcmd: &ParseSubcommand) -> ParseOptions {
+        return ParseOptions {
+            // note: text 以外の形式の指定は構文木の出力を含む
+            output_tree: subcmd.output || subcmd.format != TreeFormat::Text,
+            tree_format: subcmd.format,
+            count_duration: subcmd.time,
+            disable_opt: subcmd.noopt,
+            enable_recovery: subcmd.recover,
+            ignore_hidden_elems: !subcmd.keep_hidden,
+        };
+    }
+}
+
 fn proc_parse_subcmd(subcmd: &ParseSubcommand, cons: Console) {
     let fcpeg_file_path = subcmd.fcpeg.clone();
     let input_file_path = subcmd.input.clone();
-    let output_tree = subcmd.output;
-    let disable_opt = subcmd.noopt;
     let is_monitored = subcmd.mon;
-    let count_duration = subcmd.time;
+    let options = ParseOptions::from_subcmd(subcmd);
 
     let cons_ptr = Rc::from(RefCell::new(cons));
 
     if is_monitored {
         cons_ptr.borrow_mut().append_log(log!(Note, "command help", "You can quit parsing with '^C'."));
-        parse_with_monitoring(&cons_ptr, fcpeg_file_path, input_file_path, 1, Some(600), output_tree, count_duration, disable_opt);
+        parse_with_monitoring(&cons_ptr, fcpeg_file_path, input_file_path, 1, Some(600), &options);
     } else {
-        parse(&cons_ptr, fcpeg_file_path, input_file_path, output_tree, count_duration, disable_opt);
+        parse(&cons_ptr, fcpeg_file_path, input_file_path, &options);
+    }
+}
+
+fn proc_format_subcmd(subcmd: &FormatSubcommand, cons: Console) {
+    let cons_ptr = Rc::from(RefCell::new(cons));
+
+    if !format(&cons_ptr, subcmd.fcpeg.clone(), subcmd.check) {
+        // note: CI などで検査結果を判定できるよう終了コードを返す
+        std::process::exit(1);
+    }
+}
+
+fn proc_generate_subcmd(subcmd: &GenerateSubcommand, cons: Console) {
+    let cons_ptr = Rc::from(RefCell::new(cons));
+
+    if !generate(&cons_ptr, subcmd.fcpeg.clone(), subcmd.output.clone(), subcmd.ast) {
+        std::process::exit(1);
     }
 }
 
@@ -100,27 +210,38 @@ fn proc_manual_subcommand(_: &ManualSubcommand, cons: Console) {
     let cons_ptr = Rc::from(RefCell::new(cons));
 
     let log = log!(Note, "command help",
+        "fmt:\tformat specified .fcpeg file",
+            "\t-f:\tspecify .fcpeg file",
+            "\t--check:\tcheck formatting without rewriting file",
+        "gen:\tgenerate rust parser source from specified .fcpeg file",
+            "\t-f:\tspecify .fcpeg file",
+            "\t-o:\tspecify output .rs file",
+            "\t--ast:\tgenerate typed ast source instead of parser",
         "parse:\tparse specified files",
             "\t-f:\tspecify .fcpeg file",
+            "\t--format:\tspecify output format of syntax trees (text, json, sexpr, xml)",
             "\t-i:\tspecify input files",
+            "\t--keep-hidden:\tkeep hidden elements in syntax trees",
             "\t-o:\toutput syntax trees",
             "\t-t:\toutput processing time",
             "\t--man:\tshow help",
             "\t--mon:\tmonitor source files",
-            "\t--noopt:\tdisable optimization"
+            "\t--noopt:\tdisable optimization",
+            "\t--recover:\trecover from syntax errors"
     );
 
     cons_ptr.borrow_mut().append_log(log);
     cons_ptr.borrow().print_all();
 }
 
-fn parse(cons: &Rc<RefCell<Console>>, fcpeg_file_path: String, input_file_path: String, output_tree: bool, count_duration: bool, disable_opt: bool) {
+fn parse(cons: &Rc<RefCell<Console>>, fcpeg_file_path: String, input_file_path: String, options: &ParseOptions) {
     let start_count = Instant::now();
     // let mut file_alias_map = HashMap::<String, String>::new();
     // file_alias_map.insert("A".to_string(), "src/a.fcpeg".to_string());
-    let mut parser = match FCPEGParser::load(cons.clone(), fcpeg_file_path, HashMap::<String, String>::new(), !disable_opt) {
+    let parser = match FCPEGParser::load(fcpeg_file_path, HashMap::<String, String>::new(), !options.disable_opt) {
         Ok(v) => v,
-        Err(()) => {
+        Err(e) => {
+            append_error_logs(cons, &e);
             cons.borrow().print_all();
             cons.borrow_mut().clear_log();
 
@@ -131,9 +252,26 @@ fn parse(cons: &Rc<RefCell<Console>>, fcpeg_file_path: String, input_file_path:
         },
     };
 
-    let tree = match parser.parse(input_file_path.clone()) {
-        Ok(v) => v,
-        Err(()) => {
+    for each_warning in parser.get_warnings() {
+        cons.borrow_mut().append_log(each_warning.get_log());
+    }
+
+    let parsing_result = if options.enable_recovery {
+        parser.parse_from_path_with_recovery(input_file_path.clone())
+    } else {
+        parser.parse_from_path(input_file_path.clone()).map(|tree| (tree, Vec::new()))
+    };
+
+    let tree = match parsing_result {
+        Ok((tree, recovered_errs)) => {
+            for each_err in &recovered_errs {
+                append_error_logs(cons, each_err);
+            }
+
+            tree
+        },
+        Err(e) => {
+            append_error_logs(cons, &e);
             cons.borrow().print_all();
             cons.borrow_mut().clear_log();
 
@@ -146,15 +284,22 @@ fn parse(cons: &Rc<RefCell<Console>>, fcpeg_file_path: String, input_file_path:
 
     let duration = start_count.elapsed();
 
-    if output_tree {
-        println!("--- Syntax Tree ---");
-        println!();
-        println!("{}", input_file_path);
-        tree.print(true);
-        println!();
+    if options.output_tree {
+        match options.tree_format {
+            TreeFormat::Json => print!("{}", tree.to_json(options.ignore_hidden_elems)),
+            TreeFormat::SExpression => print!("{}", tree.to_sexpr(options.ignore_hidden_elems)),
+            TreeFormat::Text => {
+                println!("--- Syntax Tree ---");
+                println!();
+                println!("{}", input_file_path);
+                tree.print(options.ignore_hidden_elems);
+                println!();
+            },
+            TreeFormat::Xml => print!("{}", tree.to_xml(options.ignore_hidden_elems)),
+        }
     }
 
-    if count_duration {
+    if options.count_duration {
         println!("{} msec | {} μsec", duration.as_millis(), duration.as_micros());
         println!();
     }
@@ -162,29 +307,123 @@ fn parse(cons: &Rc<RefCell<Console>>, fcpeg_file_path: String, input_file_path:
     cons.borrow().print_all();
     cons.borrow_mut().clear_log();
 
-    println!("--- End ---");
-    println!();
+    // note: text 以外の形式で出力した後には区切りを出力しない
+    if !options.output_tree || options.tree_format == TreeFormat::Text {
+        println!("--- End ---");
+        println!();
+    }
+}
+
+// ret: 整形に成功したか; 検査モードでは整形済みであるか
+fn format(cons: &Rc<RefCell<Console>>, fcpeg_file_path: String, check: bool) -> bool {
+    let fcpeg_source = match FileMan::read_all(&fcpeg_file_path) {
+        Ok(v) => v,
+        Err(_) => {
+            cons.borrow_mut().append_log(log!(Error, format!("failed to read '{}'", fcpeg_file_path)));
+            cons.borrow().print_all();
+            return false;
+        },
+    };
+
+    let formatted_source = match FCPEGFormatter::format(fcpeg_file_path.clone(), Box::new(fcpeg_source.clone())) {
+        Ok(v) => v,
+        Err(e) => {
+            append_error_logs(cons, &e);
+            cons.borrow().print_all();
+            return false;
+        },
+    };
+
+    // note: 改行コード 0x0d は整形時に排除される
+    let is_formatted = formatted_source == fcpeg_source.replace("\r", "");
+
+    if check {
+        if !is_formatted {
+            cons.borrow_mut().append_log(log!(Error, format!("'{}' is not formatted", fcpeg_file_path)));
+            cons.borrow().print_all();
+        }
+
+        return is_formatted;
+    }
+
+    if !is_formatted {
+        match std::fs::write(&fcpeg_file_path, formatted_source) {
+            Ok(()) => (),
+            Err(_) => {
+                cons.borrow_mut().append_log(log!(Error, format!("failed to write '{}'", fcpeg_file_path)));
+                cons.borrow().print_all();
+                return false;
+            },
+        }
+    }
+
+    return true;
 }
 
-fn parse_with_monitoring(cons: &Rc<RefCell<Console>>, fcpeg_file_path: String, input_file_path: String, interval_sec: usize, quit_limit_sec: Option<usize>, output_tree: bool, count_duration: bool, disable_opt: bool) {
+// ret: 生成に成功したか
+fn generate(cons: &Rc<RefCell<Console>>, fcpeg_file_path: String, output_file_path: String, is_ast: bool) -> bool {
+    let parser = match FCPEGParser::load(fcpeg_file_path, HashMap::<String, String>::new(), true) {
+        Ok(v) => v,
+        Err(e) => {
+            append_error_logs(cons, &e);
+            cons.borrow().print_all();
+            return false;
+        },
+    };
+
+    for each_warning in parser.get_warnings() {
+        cons.borrow_mut().append_log(each_warning.get_log());
+    }
+
+    let generated_source_result = if is_ast {
+        parser.generate_typed_ast_source()
+    } else {
+        parser.generate_source()
+    };
+
+    let generated_source = match generated_source_result {
+        Ok(v) => v,
+        Err(e) => {
+            append_error_logs(cons, &e);
+            cons.borrow().print_all();
+            return false;
+        },
+    };
+
+    match std::fs::write(&output_file_path, generated_source) {
+        Ok(()) => (),
+        Err(_) => {
+            cons.borrow_mut().append_log(log!(Error, format!("failed to write '{}'", output_file_path)));
+            cons.borrow().print_all();
+            return false;
+        },
+    }
+
+    cons.borrow().print_all();
+    return true;
+}
+
+fn append_error_logs(cons: &Rc<RefCell<Console>>, e: &FCPEGError) {
+    for each_log in e.get_logs() {
+        cons.borrow_mut().append_log(each_log);
+    }
+}
+
+fn parse_with_monitoring(cons: &Rc<RefCell<Console>>, fcpeg_file_path: String, input_file_path: String, interval_sec: usize, quit_limit_sec: Option<usize>, options: &ParseOptions) {
     let detector_target_file_paths = vec![fcpeg_file_path.clone(), input_file_path.clone()];
     let mut detector = FileChangeDetector::new(detector_target_file_paths);
     let mut loop_count = 0;
 
-    parse(cons, fcpeg_file_path.clone(), input_file_path.clone(), output_tree, count_duration, disable_opt);
+    parse(cons, fcpeg_file_path.clone(), input_file_path.clone(), options);
 
     loop {
         match quit_limit_sec {
-            Some(v) => {
-                if loop_count + 1 * interval_sec >= v {
-                    break;
-                }
-            },
-            None => (),
+            Some(v) if loop_count + interval_sec >= v => break,
+            _ => (),
         }
 
         if detector.detect_multiple_file_changes() {
-            parse(cons, fcpeg_file_path.clone(), input_file_path.clone(), output_tree, count_duration, disable_opt);
+            parse(cons, fcpeg_file_path.clone(), input_file_path.clone(), options);
         }
 
         loop_count += 1;
@@ -0,0 +1,121 @@
+// note: 入力サイズに対してパース時間が線形に増加することを確認する
+// note: cargo bench --bench parse で実行する
+// note: メモ化を有効にするとメモ化マップが構文木の複製を保持してメモリ使用量が大きくなるため、ここでは無効にして計測する
+
+use std::collections::*;
+use std::time::*;
+
+use fcpeg::*;
+use fcpeg::file::*;
+
+const FCPEG_SOURCE: &str = r#"[Main]{
+    Main <- Line* "\z",
+    Line <- Key "=" Value ";" "\n",
+    Key <- [a-z]+,
+    Value <- List : Num,
+    List <- "(" Value ("," Value)* ")",
+    Num <- [0-9]+,
+}
+"#;
+
+const INPUT_SIZES_MB: [usize; 3] = [1, 2, 4];
+
+// note: 左再帰の規則と、同じ入力を受理する繰り返しの規則のパース時間を比較し、種の成長が項数に対して線形であることを確認する
+const LEFT_RECURSION_FCPEG_SOURCE: &str = r##"[Main]{
+    Main <- Sum "\z"#,
+    Sum <- Sum "+" Num : Num,
+    Num <- [0-9]+,
+}
+"##;
+
+const LOOP_FCPEG_SOURCE: &str = r##"[Main]{
+    Main <- Sum "\z"#,
+    Sum <- Num ("+" Num)*,
+    Num <- [0-9]+,
+}
+"##;
+
+const TERM_COUNTS: [usize; 3] = [1000, 2000, 4000];
+
+fn main() {
+    let parser = match load_parser(FCPEG_SOURCE) {
+        Some(v) => v,
+        None => return,
+    };
+
+    for each_size in INPUT_SIZES_MB.iter() {
+        let input = generate_input(each_size * 1024 * 1024);
+
+        let duration = match measure_parse(&parser, input) {
+            Some(v) => v,
+            None => return,
+        };
+
+        println!("{} MB:\t{} msec\t({} msec/MB)", each_size, duration.as_millis(), duration.as_millis() / *each_size as u128);
+    }
+
+    for (each_name, each_source) in [("left recursion", LEFT_RECURSION_FCPEG_SOURCE), ("loop", LOOP_FCPEG_SOURCE)].iter() {
+        let parser = match load_parser(each_source) {
+            Some(v) => v,
+            None => return,
+        };
+
+        for each_term_count in TERM_COUNTS.iter() {
+            let input = vec!["1"; *each_term_count].join("+");
+
+            let duration = match measure_parse(&parser, input) {
+                Some(v) => v,
+                None => return,
+            };
+
+            println!("{} ({} terms):\t{} msec", each_name, each_term_count, duration.as_millis());
+        }
+    }
+}
+
+fn load_parser(fcpeg_source: &str) -> Option<FCPEGParser> {
+    let resolver = MemorySourceResolver::new();
+
+    match FCPEGParser::load_from_str("bench.fcpeg".to_string(), fcpeg_source.to_string(), String::new(), HashMap::new(), &resolver, false) {
+        Ok(v) => Some(v),
+        Err(e) => {
+            for each_log in e.get_logs() {
+                println!("{} {:?}", each_log.title, each_log.descs);
+            }
+
+            None
+        },
+    }
+}
+
+// ret: パースに失敗した場合は None
+fn measure_parse(parser: &FCPEGParser, input: String) -> Option<Duration> {
+    let start_count = Instant::now();
+
+    match parser.parse_from_str("bench.txt".to_string(), input) {
+        Ok(_) => (),
+        Err(e) => {
+            for each_log in e.get_logs() {
+                println!("{} {:?}", each_log.title, each_log.descs);
+            }
+
+            return None;
+        },
+    }
+
+    Some(start_count.elapsed())
+}
+
+// ret: 指定したバイト数以上の入力
+fn generate_input(min_len: usize) -> String {
+    let mut input = String::new();
+    let mut line_i = 0usize;
+
+    while input.len() < min_len {
+        input += &format!("key={};\n", line_i);
+        input += &format!("list=({},({},{}),{});\n", line_i, line_i + 1, line_i + 2, line_i + 3);
+        line_i += 1;
+    }
+
+    input
+}
@@ -0,0 +1,167 @@
+use std::any::Any;
+use std::collections::*;
+use std::sync::Arc;
+
+use crate::tree::*;
+
+use rustnutlib::*;
+use rustnutlib::console::*;
+
+use uuid::Uuid;
+
+// spec: 規則の成功時に呼び出され、規則のノードから利用者定義の値を生成する
+pub type SemanticAction = Arc<dyn Fn(&SemanticActionArgs) -> Box<dyn Any> + Send + Sync>;
+
+// note: <規則 ID, 意味アクション>
+pub type SemanticActionMap = HashMap<String, SemanticAction>;
+
+#[derive(Clone, Debug)]
+pub enum SemanticActionLog {
+    UnknownRuleID { rule_id: String },
+    // note: 開始規則に意味アクションが登録されていない場合
+    StartRuleValueNotFound { rule_id: String },
+    UnexpectedValueType { rule_id: String, type_name: String },
+}
+
+impl ConsoleLogger for SemanticActionLog {
+    fn get_log(&self) -> ConsoleLog {
+        return match self {
+            SemanticActionLog::UnknownRuleID { rule_id } => log!(Error, format!("unknown rule id '{}'", rule_id)),
+            SemanticActionLog::StartRuleValueNotFound { rule_id } => log!(Error, "start rule value not found", format!("rule id:\t{}", rule_id)),
+            SemanticActionLog::UnexpectedValueType { rule_id, type_name } => log!(Error, "unexpected value type", format!("rule id:\t{}", rule_id), format!("type:\t{}", type_name)),
+        };
+    }
+}
+
+// spec: 意味アクションに渡す規則のノード及び子孫の規則の値
+// note: 意味アクションを持つ子孫の規則の部分木は、値を参照する ACTION_VALUE_NODE_NAME のノードに置き換えられている
+pub struct SemanticActionArgs<'a> {
+    rule_id: &'a String,
+    pos: CharacterPosition,
+    node: &'a SyntaxNode,
+    value_map: &'a HashMap<Uuid, Box<dyn Any>>,
+}
+
+impl<'a> SemanticActionArgs<'a> {
+    pub fn new(rule_id: &'a String, pos: CharacterPosition, node: &'a SyntaxNode, value_map: &'a HashMap<Uuid, Box<dyn Any>>) -> SemanticActionArgs<'a> {
+        return SemanticActionArgs {
+            rule_id: rule_id,
+            pos: pos,
+            node: node,
+            value_map: value_map,
+        };
+    }
+
+    pub fn get_rule_id(&self) -> &String {
+        return self.rule_id;
+    }
+
+    // ret: 規則の開始位置
+    pub fn get_position(&self) -> &CharacterPosition {
+        return &self.pos;
+    }
+
+    pub fn get_node(&self) -> &SyntaxNode {
+        return self.node;
+    }
+
+    // note: Reflectable な子孫のリーフの値を結合して返す
+    pub fn get_string(&self) -> String {
+        return self.node.join_child_leaf_values();
+    }
+
+    // ret: 要素が値のノードもしくは値のノードのみを持つ規則のノードであれば、その値; 型が異なれば None
+    pub fn get_value<T: 'static>(&self, elem: &SyntaxNodeElement) -> Option<&T> {
+        let node = match elem {
+            SyntaxNodeElement::Node(node) => node,
+            SyntaxNodeElement::Leaf(_) => return None,
+        };
+
+        if node.is_action_value_node() {
+            return match self.value_map.get(&node.uuid) {
+                Some(v) => v.downcast_ref::<T>(),
+                None => None,
+            };
+        }
+
+        return match node.sub_elems.as_slice() {
+            [SyntaxNodeElement::Node(sub_node)] if sub_node.is_action_value_node() => {
+                match self.value_map.get(&sub_node.uuid) {
+                    Some(v) => v.downcast_ref::<T>(),
+                    None => None,
+                }
+            },
+            _ => None,
+        };
+    }
+
+    // spec: Reflectable な子孫の値を深さ優先で順に取得する; 型が異なる値は含めない
+    pub fn get_values<T: 'static>(&self) -> Vec<&'a T> {
+        let mut values = Vec::<&'a T>::new();
+        self.collect_values(self.node, &mut values);
+        return values;
+    }
+
+    fn collect_values<T: 'static>(&self, node: &SyntaxNode, values: &mut Vec<&'a T>) {
+        let value_map = self.value_map;
+
+        for each_elem in node.get_reflectable_children() {
+            match each_elem {
+                SyntaxNodeElement::Node(sub_node) => {
+                    if sub_node.is_action_value_node() {
+                        match value_map.get(&sub_node.uuid) {
+                            Some(v) => {
+                                match v.downcast_ref::<T>() {
+                                    Some(v) => values.push(v),
+                                    None => (),
+                                }
+                            },
+                            None => (),
+                        }
+                    } else {
+                        self.collect_values(sub_node, values);
+                    }
+                },
+                SyntaxNodeElement::Leaf(_) => (),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use crate::action::*;
+
+    const FCPEG_SOURCE: &str = "[Main]{\n    Main <- Sum \"\\z\",\n    Sum <- Num (\"+\" Num)*,\n    Num <- [0-9]+,\n}\n";
+
+    fn load_action_parser() -> FCPEGParser {
+        let mut parser = load_test_parser(FCPEG_SOURCE);
+        parser.add_action(".Main.Num", |args| args.get_string().parse::<i64>().unwrap()).unwrap();
+        parser.add_action(".Main.Sum", |args| args.get_values::<i64>().iter().map(|v| **v).sum::<i64>()).unwrap();
+        parser.add_action(".Main.Main", |args| (args.get_rule_id().clone(), args.get_position().index, *args.get_value::<i64>(&args.get_node().sub_elems[0]).unwrap())).unwrap();
+        return parser;
+    }
+
+    #[test]
+    fn action_values_are_passed_to_parent_rules() {
+        let value = load_action_parser().parse_from_str_with_actions::<(String, usize, i64)>("input.txt".to_string(), "1+20+300".to_string()).unwrap();
+        assert_eq!(value, (".Main.Main".to_string(), 0, 321));
+    }
+
+    #[test]
+    fn unknown_rule_id_is_rejected() {
+        match load_action_parser().add_action(".Main.Unknown", |_| 0) {
+            Err(FCPEGError::SemanticAction(SemanticActionLog::UnknownRuleID { rule_id })) => assert_eq!(rule_id, ".Main.Unknown"),
+            _ => panic!("unexpected result"),
+        }
+    }
+
+    #[test]
+    fn unexpected_value_type_is_rejected() {
+        match load_action_parser().parse_from_str_with_actions::<i64>("input.txt".to_string(), "1".to_string()) {
+            Err(FCPEGError::SemanticAction(SemanticActionLog::UnexpectedValueType { rule_id, type_name: _ })) => assert_eq!(rule_id, ".Main.Main"),
+            _ => panic!("unexpected result"),
+        }
+    }
+}
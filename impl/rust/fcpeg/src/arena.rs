@@ -0,0 +1,464 @@
+use std::sync::Arc;
+
+use crate::*;
+use crate::tree::*;
+
+// note: アリーナ内の要素を識別する連番; 根要素は 0 となる
+pub type ArenaElementId = usize;
+
+// spec: リーフの値; 入力と一致しない値 (入力終端の "\0" や JOIN により結合された値など) のみ文字列を所有する
+#[derive(Clone)]
+pub enum ArenaLeafValue {
+    // note: 入力におけるバイト単位の範囲 (開始位置を含み終了位置を含まない)
+    Span { start: usize, end: usize },
+    Owned(String),
+}
+
+#[derive(Clone)]
+pub enum ArenaElementKind {
+    // note: 子要素の ID は child_ids[children_start..children_start + children_len] に格納される
+    Node { children_start: usize, children_len: usize },
+    // note: index は入力における文字単位の位置
+    Leaf { index: usize, value: ArenaLeafValue },
+}
+
+#[derive(Clone)]
+pub struct ArenaElement {
+    pub kind: ArenaElementKind,
+    pub ast_reflection_style: ASTReflectionStyle,
+}
+
+// spec: アリーナに格納する構文木の表現; 要素を連番の ID でアリーナに格納し、リーフの値は共有された入力への範囲として保持する
+// note: UUID を持たず、位置情報は入力位置から必要な時に算出する
+#[derive(Clone)]
+pub struct ArenaSyntaxTree {
+    src: Arc<str>,
+    file_path: Option<String>,
+    // note: 各行の先頭の入力位置 (文字単位)
+    line_start_indexes: Vec<usize>,
+    elems: Vec<ArenaElement>,
+    child_ids: Vec<ArenaElementId>,
+}
+
+impl ArenaSyntaxTree {
+    // spec: 構文木を消費してアリーナに移す; src はリーフの位置が指す入力 (CR を除去したもの)
+    pub fn from_tree(tree: SyntaxTree, src: Arc<str>, file_path: Option<String>) -> ArenaSyntaxTree {
+        let mut line_start_indexes = vec![0usize];
+        // note: 文字単位の位置からバイト単位の位置への変換表; 構築後に破棄する
+        let mut byte_indexes = Vec::<usize>::new();
+
+        for (char_i, (byte_i, each_char)) in src.char_indices().enumerate() {
+            byte_indexes.push(byte_i);
+
+            if each_char == '\n' {
+                line_start_indexes.push(char_i + 1);
+            }
+        }
+
+        byte_indexes.push(src.len());
+
+        let mut arena_tree = ArenaSyntaxTree {
+            src: src,
+            file_path: file_path,
+            line_start_indexes: line_start_indexes,
+            elems: Vec::new(),
+            child_ids: Vec::new(),
+        };
+
+        arena_tree.push_elem(tree.into_child(), &byte_indexes);
+        return arena_tree;
+    }
+
+    // note: 前順に ID を割り当てる; 子要素の ID 列は子孫をすべて格納した後に連続して追加する
+    fn push_elem(&mut self, elem: SyntaxNodeElement, byte_indexes: &Vec<usize>) -> ArenaElementId {
+        let elem_id = self.elems.len();
+
+        match elem {
+            SyntaxNodeElement::Node(node) => {
+                let node = *node;
+
+                self.elems.push(ArenaElement {
+                    kind: ArenaElementKind::Node { children_start: 0, children_len: 0 },
+                    ast_reflection_style: node.ast_reflection_style,
+                });
+
+                let sub_elem_ids = node.sub_elems.into_iter().map(|each_elem| self.push_elem(each_elem, byte_indexes)).collect::<Vec<ArenaElementId>>();
+
+                self.elems[elem_id].kind = ArenaElementKind::Node {
+                    children_start: self.child_ids.len(),
+                    children_len: sub_elem_ids.len(),
+                };
+
+                self.child_ids.extend(sub_elem_ids);
+            },
+            SyntaxNodeElement::Leaf(leaf) => {
+                let leaf = *leaf;
+                let end_i = leaf.pos.index + leaf.value.chars().count();
+
+                let value = if end_i < byte_indexes.len() && self.src[byte_indexes[leaf.pos.index]..byte_indexes[end_i]] == leaf.value {
+                    ArenaLeafValue::Span { start: byte_indexes[leaf.pos.index], end: byte_indexes[end_i] }
+                } else {
+                    ArenaLeafValue::Owned(leaf.value)
+                };
+
+                self.elems.push(ArenaElement {
+                    kind: ArenaElementKind::Leaf { index: leaf.pos.index, value: value },
+                    ast_reflection_style: leaf.ast_reflection_style,
+                });
+            },
+        }
+
+        return elem_id;
+    }
+
+    pub fn get_source(&self) -> &Arc<str> {
+        return &self.src;
+    }
+
+    pub fn get_elem_count(&self) -> usize {
+        return self.elems.len();
+    }
+
+    pub fn get_root(&self) -> ArenaNodeElement<'_> {
+        return self.get_elem(0);
+    }
+
+    // note: id が範囲外であれば panic する
+    pub fn get_elem(&self, id: ArenaElementId) -> ArenaNodeElement<'_> {
+        return match &self.elems[id].kind {
+            ArenaElementKind::Node { children_start: _, children_len: _ } => ArenaNodeElement::Node(ArenaNode { tree: self, id: id }),
+            ArenaElementKind::Leaf { index: _, value: _ } => ArenaNodeElement::Leaf(ArenaLeaf { tree: self, id: id }),
+        };
+    }
+
+    fn get_char_position_at(&self, src_i: usize) -> CharacterPosition {
+        // note: src_i 以下で最大の行頭位置を持つ行
+        let line = match self.line_start_indexes.binary_search(&src_i) {
+            Ok(v) => v,
+            Err(v) => v - 1,
+        };
+
+        return CharacterPosition::new(self.file_path.clone(), src_i, line, src_i - self.line_start_indexes[line]);
+    }
+
+    // spec: UUID を新たに割り当てて通常の構文木に変換する
+    pub fn to_tree(&self) -> SyntaxTree {
+        return SyntaxTree::from_node(self.get_root().to_elem());
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum ArenaNodeElement<'a> {
+    Node(ArenaNode<'a>),
+    Leaf(ArenaLeaf<'a>),
+}
+
+impl<'a> ArenaNodeElement<'a> {
+    pub fn get_id(&self) -> ArenaElementId {
+        return match self {
+            ArenaNodeElement::Node(node) => node.id,
+            ArenaNodeElement::Leaf(leaf) => leaf.id,
+        };
+    }
+
+    pub fn get_node(&self) -> FCPEGResult<ArenaNode<'a>> {
+        return match self {
+            ArenaNodeElement::Node(node) => Ok(*node),
+            ArenaNodeElement::Leaf(leaf) => {
+                return Err(TreeLog::ArenaElementNotNode {
+                    id: leaf.id,
+                }.into());
+            },
+        };
+    }
+
+    pub fn get_leaf(&self) -> FCPEGResult<ArenaLeaf<'a>> {
+        return match self {
+            ArenaNodeElement::Node(node) => {
+                return Err(TreeLog::ArenaElementNotLeaf {
+                    id: node.id,
+                }.into());
+            },
+            ArenaNodeElement::Leaf(leaf) => Ok(*leaf),
+        };
+    }
+
+    pub fn is_node(&self) -> bool {
+        return matches!(self, ArenaNodeElement::Node(_));
+    }
+
+    pub fn get_ast_reflection_style(&self) -> &'a ASTReflectionStyle {
+        return match self {
+            ArenaNodeElement::Node(node) => node.get_ast_reflection_style(),
+            ArenaNodeElement::Leaf(leaf) => leaf.get_ast_reflection_style(),
+        };
+    }
+
+    pub fn is_reflectable(&self) -> bool {
+        return self.get_ast_reflection_style().is_reflectable();
+    }
+
+    fn to_elem(self) -> SyntaxNodeElement {
+        return match self {
+            ArenaNodeElement::Node(node) => {
+                let sub_elems = node.get_children().map(|each_elem| each_elem.to_elem()).collect::<Vec<SyntaxNodeElement>>();
+                SyntaxNodeElement::from_node_args(sub_elems, node.get_ast_reflection_style().clone())
+            },
+            ArenaNodeElement::Leaf(leaf) => SyntaxNodeElement::from_leaf_args(leaf.get_position(), leaf.get_value().to_string(), leaf.get_ast_reflection_style().clone()),
+        };
+    }
+}
+
+// note: アリーナ内のノードへの参照; SyntaxNode と同様のアクセサを持つ
+#[derive(Clone, Copy)]
+pub struct ArenaNode<'a> {
+    tree: &'a ArenaSyntaxTree,
+    id: ArenaElementId,
+}
+
+impl<'a> ArenaNode<'a> {
+    pub fn get_id(&self) -> ArenaElementId {
+        return self.id;
+    }
+
+    pub fn get_ast_reflection_style(&self) -> &'a ASTReflectionStyle {
+        return &self.tree.elems[self.id].ast_reflection_style;
+    }
+
+    pub fn is_reflectable(&self) -> bool {
+        return self.get_ast_reflection_style().is_reflectable();
+    }
+
+    pub fn is_error_node(&self) -> bool {
+        return *self.get_ast_reflection_style() == ASTReflectionStyle::Reflection(ERROR_NODE_NAME.to_string());
+    }
+
+    fn get_child_ids(&self) -> &'a [ArenaElementId] {
+        return match &self.tree.elems[self.id].kind {
+            ArenaElementKind::Node { children_start, children_len } => &self.tree.child_ids[*children_start..*children_start + *children_len],
+            ArenaElementKind::Leaf { index: _, value: _ } => &[],
+        };
+    }
+
+    // note: 非反映的な要素も含む
+    pub fn get_children(&self) -> impl Iterator<Item = ArenaNodeElement<'a>> + 'a {
+        let tree = self.tree;
+        return self.get_child_ids().iter().map(move |each_id| tree.get_elem(*each_id));
+    }
+
+    pub fn get_child_len(&self) -> usize {
+        return self.get_child_ids().len();
+    }
+
+    pub fn get_reflectable_children(&self) -> Vec<ArenaNodeElement<'a>> {
+        return self.get_children().filter(|each_elem| each_elem.is_reflectable()).collect();
+    }
+
+    // note: index は Reflectable な子要素の中での位置
+    pub fn get_child_at(&self, index: usize) -> FCPEGResult<ArenaNodeElement<'a>> {
+        return match self.get_children().filter(|each_elem| each_elem.is_reflectable()).nth(index) {
+            Some(v) => Ok(v),
+            None => {
+                return Err(TreeLog::ArenaReflectableChildNotFound {
+                    parent_id: self.id,
+                    index: index,
+                }.into());
+            },
+        };
+    }
+
+    pub fn get_node_child_at(&self, index: usize) -> FCPEGResult<ArenaNode<'a>> {
+        return self.get_child_at(index)?.get_node();
+    }
+
+    pub fn get_leaf_child_at(&self, index: usize) -> FCPEGResult<ArenaLeaf<'a>> {
+        return self.get_child_at(index)?.get_leaf();
+    }
+
+    pub fn exists_child_node(&self, patterns: Vec<&str>) -> bool {
+        return self.find_first_child_node(patterns).is_some();
+    }
+
+    // ret: 最初にマッチした Reflectable な子ノード
+    pub fn find_first_child_node(&self, patterns: Vec<&str>) -> Option<ArenaNode<'a>> {
+        return self.find_child_nodes(patterns).into_iter().next();
+    }
+
+    // ret: すべてのマッチした Reflectable な子ノードの列
+    pub fn find_child_nodes(&self, patterns: Vec<&str>) -> Vec<ArenaNode<'a>> {
+        let mut nodes = Vec::<ArenaNode<'a>>::new();
+
+        for each_elem in self.get_children() {
+            match each_elem {
+                ArenaNodeElement::Node(node) => {
+                    match node.get_ast_reflection_style() {
+                        ASTReflectionStyle::Reflection(name) if patterns.iter().any(|s| s == name) => nodes.push(node),
+                        _ => (),
+                    }
+                },
+                _ => (),
+            }
+        }
+
+        return nodes;
+    }
+
+    // ret: 子孫リーフの先頭位置と末尾位置; 非反映的なリーフも対象にする; リーフが存在しなければ None
+    pub fn get_span(&self) -> Option<(CharacterPosition, CharacterPosition)> {
+        let mut start_pos = None;
+        let mut end_pos = None;
+
+        for each_elem in self.get_children() {
+            let each_span = match each_elem {
+                ArenaNodeElement::Node(node) => node.get_span(),
+                ArenaNodeElement::Leaf(leaf) => Some((leaf.get_position(), leaf.get_end_position())),
+            };
+
+            match each_span {
+                Some((each_start_pos, each_end_pos)) => {
+                    if start_pos.is_none() {
+                        start_pos = Some(each_start_pos);
+                    }
+
+                    end_pos = Some(each_end_pos);
+                },
+                None => (),
+            }
+        }
+
+        return match (start_pos, end_pos) {
+            (Some(start_pos), Some(end_pos)) => Some((start_pos, end_pos)),
+            _ => None,
+        };
+    }
+
+    // note: Reflectable な子孫ノードの値をすべて結合して返す
+    pub fn join_child_leaf_values(&self) -> String {
+        let mut s = String::new();
+
+        for each_elem in self.get_children() {
+            match each_elem {
+                ArenaNodeElement::Node(node) => s += node.join_child_leaf_values().as_str(),
+                ArenaNodeElement::Leaf(leaf) => {
+                    match leaf.get_ast_reflection_style() {
+                        ASTReflectionStyle::Reflection(_) => s += leaf.get_value(),
+                        _ => (),
+                    }
+                },
+            }
+        }
+
+        return s;
+    }
+}
+
+// note: アリーナ内のリーフへの参照; 値は入力を借用する
+#[derive(Clone, Copy)]
+pub struct ArenaLeaf<'a> {
+    tree: &'a ArenaSyntaxTree,
+    id: ArenaElementId,
+}
+
+impl<'a> ArenaLeaf<'a> {
+    pub fn get_id(&self) -> ArenaElementId {
+        return self.id;
+    }
+
+    pub fn get_ast_reflection_style(&self) -> &'a ASTReflectionStyle {
+        return &self.tree.elems[self.id].ast_reflection_style;
+    }
+
+    pub fn is_reflectable(&self) -> bool {
+        return self.get_ast_reflection_style().is_reflectable();
+    }
+
+    pub fn get_value(&self) -> &'a str {
+        return match &self.tree.elems[self.id].kind {
+            ArenaElementKind::Leaf { index: _, value: ArenaLeafValue::Span { start, end } } => &self.tree.src[*start..*end],
+            ArenaElementKind::Leaf { index: _, value: ArenaLeafValue::Owned(value) } => value.as_str(),
+            ArenaElementKind::Node { children_start: _, children_len: _ } => "",
+        };
+    }
+
+    pub fn get_position(&self) -> CharacterPosition {
+        return match &self.tree.elems[self.id].kind {
+            ArenaElementKind::Leaf { index, value: _ } => self.tree.get_char_position_at(*index),
+            ArenaElementKind::Node { children_start: _, children_len: _ } => CharacterPosition::get_empty(),
+        };
+    }
+
+    // ret: トークン末尾の直後の位置
+    pub fn get_end_position(&self) -> CharacterPosition {
+        let mut end_pos = self.get_position();
+
+        for each_char in self.get_value().chars() {
+            end_pos.index += 1;
+
+            if each_char == '\n' {
+                end_pos.line += 1;
+                end_pos.column = 0;
+            } else {
+                end_pos.column += 1;
+            }
+        }
+
+        return end_pos;
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::arena::*;
+
+    const FCPEG_SOURCE: &str = "[Main]{\n    Main <- Line* \"\\z\",\n    Line <- Word \"\\n\",\n    Word <- [a-zあ-ん]+,\n}\n";
+    const INPUT_SOURCE: &str = "ab\r\nいう\nc\n";
+
+    #[test]
+    fn arena_tree_converts_back_to_equal_tree() {
+        let parser = load_test_parser(FCPEG_SOURCE);
+        let tree = parser.parse_from_str("input.txt".to_string(), INPUT_SOURCE.to_string()).unwrap();
+        let arena_tree = parser.parse_from_str_into_arena("input.txt".to_string(), INPUT_SOURCE.to_string()).unwrap();
+
+        assert_eq!(arena_tree.to_tree().to_sexpr(false), tree.to_sexpr(false));
+    }
+
+    #[test]
+    fn leaf_values_are_spans_of_source() {
+        let arena_tree = load_test_parser(FCPEG_SOURCE).parse_from_str_into_arena("input.txt".to_string(), INPUT_SOURCE.to_string()).unwrap();
+        let root = arena_tree.get_root().get_node().unwrap();
+        let words = root.find_child_nodes(vec![".Main.Line"]).iter().flat_map(|each_line| each_line.find_child_nodes(vec![".Main.Word"])).collect::<Vec<ArenaNode>>();
+
+        assert_eq!(words.iter().map(|each_word| each_word.join_child_leaf_values()).collect::<Vec<String>>(), vec!["ab", "いう", "c"]);
+
+        // note: 位置は CR を除去した入力における文字単位
+        let (start_pos, end_pos) = words[2].get_span().unwrap();
+        assert_eq!((start_pos.index, start_pos.line, start_pos.column), (6, 2, 0));
+        assert_eq!((end_pos.index, end_pos.line, end_pos.column), (7, 2, 1));
+
+        for each_id in 0..arena_tree.get_elem_count() {
+            match &arena_tree.elems[each_id].kind {
+                ArenaElementKind::Leaf { index: _, value: ArenaLeafValue::Owned(value) } => assert_eq!(value, "\0"),
+                _ => (),
+            }
+        }
+    }
+
+    // note: JOIN により結合された値も入力と一致すれば範囲として保持する
+    #[test]
+    fn joined_leaf_values_are_spans_of_source() {
+        let arena_tree = load_test_parser("[Main]{\n    Main <- Word \",\" Word \"\\z\",\n    Word <- JOIN<[a-z]+>,\n}\n").parse_from_str_into_arena("input.txt".to_string(), "ab,cde".to_string()).unwrap();
+        let root = arena_tree.get_root().get_node().unwrap();
+        let words = root.find_child_nodes(vec![".Main.Word"]);
+
+        assert_eq!(words.iter().map(|each_word| each_word.join_child_leaf_values()).collect::<Vec<String>>(), vec!["ab", "cde"]);
+        assert_eq!(words.iter().map(|each_word| each_word.get_leaf_child_at(0).unwrap().get_position().index).collect::<Vec<usize>>(), vec![0, 3]);
+
+        for each_word in words {
+            match &arena_tree.elems[each_word.get_leaf_child_at(0).unwrap().get_id()].kind {
+                ArenaElementKind::Leaf { index: _, value: ArenaLeafValue::Span { start: _, end: _ } } => (),
+                _ => panic!("joined leaf is not span"),
+            }
+        }
+    }
+}
@@ -1,9 +1,8 @@
 use std::collections::*;
-use std::rc::Rc;
-use std::cell::RefCell;
 use std::sync::Arc;
 
 use crate::*;
+use crate::config::*;
 use crate::parser::*;
 use crate::rule::*;
 use crate::tree::*;
@@ -124,6 +123,7 @@ macro_rules! expr {
 
 pub type BlockMap = HashMap<String, Box<Block>>;
 
+#[derive(Clone, Debug)]
 pub enum BlockParsingLog {
     AttemptToAccessPrivateItem { pos: CharacterPosition, item_id: String },
     BlockAliasNotFoundOrUsed { pos: CharacterPosition, block_alias_name: String },
@@ -131,11 +131,16 @@ pub enum BlockParsingLog {
     DuplicateBlockName { pos: CharacterPosition, block_name: String },
     DuplicateArgumentID { pos: CharacterPosition, arg_id: String },
     DuplicateRuleName { pos: CharacterPosition, rule_name: String },
+    DuplicateAlternative { severity: LintSeverity, pos: CharacterPosition, rule_id: String, prev_pos: CharacterPosition },
     DuplicateStartCommand { pos: CharacterPosition },
+    InvalidCharacterClass { pos: CharacterPosition, value: String },
     InvalidID { pos: CharacterPosition, id: String },
     InvalidLoopRange { pos: CharacterPosition, msg: String },
+    LeftRecursiveRule { pos: CharacterPosition, rule_id: String, cycle: Vec<String> },
     NamingRuleViolation { pos: CharacterPosition, id: String },
+    NullableRepetition { severity: LintSeverity, pos: CharacterPosition, rule_id: String },
     RandomOrderInExpression { pos: CharacterPosition },
+    ShadowedAlternative { severity: LintSeverity, pos: CharacterPosition, rule_id: String, prev_pos: CharacterPosition },
     StartCommandOutsideMainBlock { pos: CharacterPosition },
     UnexpectedChildName { parent_uuid: Uuid, unexpected: String, expected: String },
     UnexpectedNodeName { uuid: Uuid, unexpected: String, expected: String },
@@ -145,7 +150,44 @@ pub enum BlockParsingLog {
     UnnecessaryBlockAliasName { pos: CharacterPosition, alias_name: String, },
     UnnecessaryStartCommand { pos: CharacterPosition, msg: String },
     UnnecessaryUseCommand { pos: CharacterPosition, msg: String },
+    UnreachableRule { severity: LintSeverity, pos: CharacterPosition, rule_id: String },
     UnrecommendedLoopRange { pos: CharacterPosition, msg: String },
+    UnusedBlock { severity: LintSeverity, pos: CharacterPosition, block_id: String },
+}
+
+impl BlockParsingLog {
+    // ret: ログが位置情報を持たない場合は None
+    pub fn get_position(&self) -> Option<CharacterPosition> {
+        return match self {
+            BlockParsingLog::AttemptToAccessPrivateItem { pos, item_id: _ } => Some(pos.clone()),
+            BlockParsingLog::BlockAliasNotFoundOrUsed { pos, block_alias_name: _ } => Some(pos.clone()),
+            BlockParsingLog::DuplicateBlockName { pos, block_name: _ } => Some(pos.clone()),
+            BlockParsingLog::DuplicateArgumentID { pos, arg_id: _ } => Some(pos.clone()),
+            BlockParsingLog::DuplicateRuleName { pos, rule_name: _ } => Some(pos.clone()),
+            BlockParsingLog::DuplicateAlternative { severity: _, pos, rule_id: _, prev_pos: _ } => Some(pos.clone()),
+            BlockParsingLog::DuplicateStartCommand { pos } => Some(pos.clone()),
+            BlockParsingLog::InvalidCharacterClass { pos, value: _ } => Some(pos.clone()),
+            BlockParsingLog::InvalidID { pos, id: _ } => Some(pos.clone()),
+            BlockParsingLog::InvalidLoopRange { pos, msg: _ } => Some(pos.clone()),
+            BlockParsingLog::LeftRecursiveRule { pos, rule_id: _, cycle: _ } => Some(pos.clone()),
+            BlockParsingLog::NamingRuleViolation { pos, id: _ } => Some(pos.clone()),
+            BlockParsingLog::NullableRepetition { severity: _, pos, rule_id: _ } => Some(pos.clone()),
+            BlockParsingLog::RandomOrderInExpression { pos } => Some(pos.clone()),
+            BlockParsingLog::ShadowedAlternative { severity: _, pos, rule_id: _, prev_pos: _ } => Some(pos.clone()),
+            BlockParsingLog::StartCommandOutsideMainBlock { pos } => Some(pos.clone()),
+            BlockParsingLog::UnexpectedChildName { parent_uuid: _, unexpected: _, expected: _ } => None,
+            BlockParsingLog::UnexpectedNodeName { uuid: _, unexpected: _, expected: _ } => None,
+            BlockParsingLog::UnknownEscapeSequenceCharacter { pos } => Some(pos.clone()),
+            BlockParsingLog::UnknownBlockID { pos, block_id: _ } => Some(pos.clone()),
+            BlockParsingLog::UnknownRuleID { pos, rule_id: _ } => Some(pos.clone()),
+            BlockParsingLog::UnnecessaryBlockAliasName { pos, alias_name: _ } => Some(pos.clone()),
+            BlockParsingLog::UnnecessaryStartCommand { pos, msg: _ } => Some(pos.clone()),
+            BlockParsingLog::UnnecessaryUseCommand { pos, msg: _ } => Some(pos.clone()),
+            BlockParsingLog::UnreachableRule { severity: _, pos, rule_id: _ } => Some(pos.clone()),
+            BlockParsingLog::UnrecommendedLoopRange { pos, msg: _ } => Some(pos.clone()),
+            BlockParsingLog::UnusedBlock { severity: _, pos, block_id: _ } => Some(pos.clone()),
+        };
+    }
 }
 
 impl ConsoleLogger for BlockParsingLog {
@@ -157,11 +199,16 @@ impl ConsoleLogger for BlockParsingLog {
             BlockParsingLog::DuplicateBlockName { pos, block_name } => log!(Error, format!("duplicate block name '{}'", block_name), format!("at:\t{}", pos)),
             BlockParsingLog::DuplicateArgumentID { pos, arg_id } => log!(Error, format!("duplicate argument id '{}'", arg_id), format!("at:\t{}", pos)),
             BlockParsingLog::DuplicateRuleName { pos, rule_name } => log!(Error, format!("duplicate rule name '{}'", rule_name), format!("at:\t{}", pos)),
+            BlockParsingLog::DuplicateAlternative { severity, pos, rule_id, prev_pos } => severity.apply_to(log!(Warning, format!("duplicate alternative in rule '{}'", rule_id), format!("at:\t{}", pos), format!("same as:\t{}", prev_pos))),
             BlockParsingLog::DuplicateStartCommand { pos } => log!(Error, "duplicate start command", format!("at:\t{}", pos)),
+            BlockParsingLog::InvalidCharacterClass { pos, value } => log!(Error, format!("invalid character class '{}'", value), format!("at:\t{}", pos)),
             BlockParsingLog::InvalidID { pos, id } => log!(Error, format!("invalid id '{}'", id), format!("at:\t{}", pos)),
             BlockParsingLog::InvalidLoopRange { pos, msg } => log!(Error, format!("invalid loop range"), format!("at:\t{}", pos), format!("{}", msg.bright_black())),
+            BlockParsingLog::LeftRecursiveRule { pos, rule_id, cycle } => log!(Note, format!("left-recursive rule '{}'", rule_id), format!("at:\t{}", pos), format!("cycle:\t{}", cycle.join(" -> ")), format!("{}", "left recursion is parsed by seed growing".bright_black())),
             BlockParsingLog::NamingRuleViolation { pos, id } => log!(Warning, "naming rule violation", format!("at:\t{}", pos), format!("id:\t{}", id)),
+            BlockParsingLog::NullableRepetition { severity, pos, rule_id } => severity.apply_to(log!(Error, format!("nullable repetition in rule '{}'", rule_id), format!("at:\t{}", pos), format!("{}", "repeated element can match empty string and loops infinitely".bright_black()))),
             BlockParsingLog::RandomOrderInExpression { pos } => log!(Error, "random order in expression", format!("at:\t{}", pos), format!("{}", "cannot specify random order symbol to expression".bright_black())),
+            BlockParsingLog::ShadowedAlternative { severity, pos, rule_id, prev_pos } => severity.apply_to(log!(Warning, format!("shadowed alternative in rule '{}'", rule_id), format!("at:\t{}", pos), format!("shadowed by:\t{}", prev_pos), format!("{}", "earlier alternative matches wherever this alternative matches".bright_black()))),
             BlockParsingLog::StartCommandOutsideMainBlock { pos } => log!(Error, "start command outside main block", format!("at:\t{}", pos)),
             BlockParsingLog::UnexpectedChildName { parent_uuid, unexpected, expected } => log!(Error, format!("unknown node name {}, expected {}", unexpected, expected), format!("parent uuid:\t{}", parent_uuid)),
             BlockParsingLog::UnexpectedNodeName { uuid, unexpected, expected } => log!(Error, format!("unknown node name {}, expected {}", unexpected, expected), format!("uuid:\t{}", uuid)),
@@ -171,11 +218,80 @@ impl ConsoleLogger for BlockParsingLog {
             BlockParsingLog::UnnecessaryBlockAliasName { pos, alias_name } => log!(Warning, format!("unnecessary block alias name"), format!("at:\t{}", pos), format!("alias name:\t{}", alias_name)),
             BlockParsingLog::UnnecessaryStartCommand { pos, msg } => log!(Warning, format!("unnecessary start command"), format!("at:\t{}", pos), format!("{}", msg.bright_black())),
             BlockParsingLog::UnnecessaryUseCommand { pos, msg } => log!(Warning, format!("unnecessary use command"), format!("at:\t{}", pos), format!("{}", msg.bright_black())),
+            BlockParsingLog::UnreachableRule { severity, pos, rule_id } => severity.apply_to(log!(Warning, format!("unreachable rule '{}'", rule_id), format!("at:\t{}", pos), format!("{}", "rule is never called from start rule".bright_black()))),
             BlockParsingLog::UnrecommendedLoopRange { pos, msg } => log!(Warning, format!("unrecommended loop range"), format!("at:\t{}", pos), format!("{}", msg.bright_black())),
+            BlockParsingLog::UnusedBlock { severity, pos, block_id } => severity.apply_to(log!(Warning, format!("unused block '{}'", block_id), format!("at:\t{}", pos), format!("{}", "no rule in block is called from start rule".bright_black()))),
         }
     }
 }
 
+// spec: 規則マップに対する静的検査 (リント) の種類; 構成ファイルで重大度を設定できる
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+pub enum LintKind {
+    DuplicateAlternative,
+    NullableRepetition,
+    ShadowedAlternative,
+    UnreachableRule,
+    UnusedBlock,
+}
+
+impl LintKind {
+    // ret: 名前がマッチしなければ None
+    pub fn from(name: &str) -> Option<LintKind> {
+        let kind = match name {
+            "DuplicateAlternative" => LintKind::DuplicateAlternative,
+            "NullableRepetition" => LintKind::NullableRepetition,
+            "ShadowedAlternative" => LintKind::ShadowedAlternative,
+            "UnreachableRule" => LintKind::UnreachableRule,
+            "UnusedBlock" => LintKind::UnusedBlock,
+            _ => return None,
+        };
+
+        return Some(kind);
+    }
+
+    // note: 空文字列にマッチしうる要素の無制限な繰り返しはパース時に無限ループするためエラーとする
+    pub fn get_default_severity(&self) -> LintSeverity {
+        return match self {
+            LintKind::NullableRepetition => LintSeverity::Error,
+            _ => LintSeverity::Warning,
+        };
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LintSeverity {
+    Error,
+    Warning,
+    Note,
+    Off,
+}
+
+impl LintSeverity {
+    pub fn from(value: &str) -> Option<LintSeverity> {
+        return match value.to_lowercase().as_str() {
+            "error" => Some(LintSeverity::Error),
+            "warning" => Some(LintSeverity::Warning),
+            "note" => Some(LintSeverity::Note),
+            "off" => Some(LintSeverity::Off),
+            _ => None,
+        };
+    }
+
+    // spec: ログの種類を重大度に合わせる; Off のログは生成されない
+    pub fn apply_to(&self, log: ConsoleLog) -> ConsoleLog {
+        let mut log = log;
+
+        log.kind = match self {
+            LintSeverity::Error => ConsoleLogKind::Error,
+            LintSeverity::Warning => ConsoleLogKind::Warning,
+            LintSeverity::Note | LintSeverity::Off => ConsoleLogKind::Note,
+        };
+
+        return log;
+    }
+}
+
 pub struct RawRange {
     pub min_num: usize,
     pub max_num: Infinitable<usize>,
@@ -186,42 +302,103 @@ pub struct RawRange {
     pub max_num_pos: Option<CharacterPosition>,
 }
 
+#[derive(Clone, PartialEq)]
+pub enum BlockSymbolKind {
+    Block,
+    Rule,
+}
+
+// spec: ブロック ID・規則 ID の出現箇所; ID は解決後のもの
+#[derive(Clone)]
+pub struct BlockSymbol {
+    pub kind: BlockSymbolKind,
+    pub id: String,
+    pub pos: CharacterPosition,
+    // note: 出現箇所の文字数
+    pub len: usize,
+    pub is_definition: bool,
+}
+
+// spec: ブロック解析時に収集したシンボルの一覧; 定義への移動や参照の検索などのエディタ支援に用いる
+#[derive(Clone)]
+pub struct BlockSymbolTable {
+    pub symbols: Vec<BlockSymbol>,
+}
+
+impl BlockSymbolTable {
+    pub fn new() -> BlockSymbolTable {
+        return BlockSymbolTable {
+            symbols: Vec::new(),
+        };
+    }
+
+    // ret: 位置 (行・列) を含むシンボル; 末尾の直後の位置も含む
+    pub fn find_at(&self, file_path: &String, line: usize, column: usize) -> Option<&BlockSymbol> {
+        return self.symbols.iter().find(|each_symbol| {
+            each_symbol.pos.file_path.as_ref() == Some(file_path) && each_symbol.pos.line == line
+                && each_symbol.pos.column <= column && column <= each_symbol.pos.column + each_symbol.len
+        });
+    }
+
+    pub fn find_definition(&self, kind: &BlockSymbolKind, id: &String) -> Option<&BlockSymbol> {
+        return self.symbols.iter().find(|each_symbol| each_symbol.is_definition && each_symbol.kind == *kind && each_symbol.id == *id);
+    }
+
+    // ret: 定義位置を含む出現箇所の一覧
+    pub fn find_references(&self, kind: &BlockSymbolKind, id: &String) -> Vec<&BlockSymbol> {
+        return self.symbols.iter().filter(|each_symbol| each_symbol.kind == *kind && each_symbol.id == *id).collect();
+    }
+}
+
+impl Default for BlockSymbolTable {
+    fn default() -> BlockSymbolTable {
+        return BlockSymbolTable::new();
+    }
+}
+
 // note: プリミティブ規則名の一覧
-pub const PRIMITIVE_RULE_NAMES: &[&'static str] = &["JOIN"];
+pub const PRIMITIVE_RULE_NAMES: &[&str] = &["JOIN"];
 // note: デフォルトの開始規則 ID
-pub const DEFAULT_START_RULE_ID: &'static str = ".Main.Main";
+pub const DEFAULT_START_RULE_ID: &str = ".Main.Main";
 
 pub struct BlockParser {
-    cons: Rc<RefCell<Console>>,
+    warnings: Vec<BlockParsingLog>,
+    symbols: Vec<BlockSymbol>,
     start_rule_id: Option<String>,
     file_alias_name: String,
     replaced_file_alias_names: Arc<HashMap<String, String>>,
-    used_block_ids: Box<HashMap<String, CharacterPosition>>,
-    used_rule_ids: Box<HashMap<String, CharacterPosition>>,
+    used_block_ids: HashMap<String, CharacterPosition>,
+    used_rule_ids: HashMap<String, CharacterPosition>,
     block_name: String,
     // note: <ブロックエイリアス名, ブロック ID>
     block_alias_map: HashMap<String, String>,
     block_id_map: Vec::<String>,
     file_path: String,
-    file_content: Box<String>,
+    file_content: String,
 }
 
 impl BlockParser {
     // note: FileMap から最終的な RuleMap を取得する
-    pub fn get_rule_map(cons: Rc<RefCell<Console>>, fcpeg_file_map: &mut FCPEGFileMap, enable_memoization: bool) -> ConsoleResult<Arc<Box<RuleMap>>> {
-        let block_map = FCPEGBlock::get_block_map();
-        let rule_map = Arc::new(Box::new(RuleMap::new(vec![block_map], ".Syntax.FCPEG".to_string())?));
+    pub fn get_rule_map(fcpeg_file_map: &mut FCPEGFileMap, enable_memoization: bool, warnings: &mut Vec<BlockParsingLog>) -> FCPEGResult<Arc<RuleMap>> {
+        return BlockParser::get_rule_map_with_symbols(fcpeg_file_map, enable_memoization, warnings, &mut BlockSymbolTable::new());
+    }
+
+    // spec: 規則マップと合わせてブロック ID・規則 ID の出現箇所を収集する
+    // note: 失敗した場合もそれまでに解析したファイルの警告及びシンボルは warnings 及び symbol_table に残る
+    pub fn get_rule_map_with_symbols(fcpeg_file_map: &mut FCPEGFileMap, enable_memoization: bool, warnings: &mut Vec<BlockParsingLog>, symbol_table: &mut BlockSymbolTable) -> FCPEGResult<Arc<RuleMap>> {
+        let rule_map = BlockParser::get_fcpeg_rule_map()?;
         let mut block_maps = Vec::<BlockMap>::new();
 
-        let mut used_block_ids = Box::new(HashMap::<String, CharacterPosition>::new());
-        let mut used_rule_ids = Box::new(HashMap::<String, CharacterPosition>::new());
+        let mut used_block_ids = HashMap::<String, CharacterPosition>::new();
+        let mut used_rule_ids = HashMap::<String, CharacterPosition>::new();
         let mut block_id_map = Vec::<String>::new();
 
         let mut start_rule_id = Option::<String>::None;
 
         for (file_alias_name, fcpeg_file) in fcpeg_file_map.iter() {
             let mut block_parser = BlockParser {
-                cons: cons.clone(),
+                warnings: Vec::new(),
+                symbols: Vec::new(),
                 start_rule_id: None,
                 file_alias_name: file_alias_name.clone(),
                 replaced_file_alias_names: fcpeg_file_map.replaced_file_alias_names.clone(),
@@ -231,13 +408,16 @@ impl BlockParser {
                 block_alias_map: HashMap::new(),
                 block_id_map: block_id_map,
                 file_path: fcpeg_file.file_path.clone(),
-                file_content: fcpeg_file.file_content.clone(),
+                file_content: fcpeg_file.file_content.as_ref().clone(),
             };
 
-            let tree = Box::new(block_parser.to_syntax_tree(rule_map.clone(), enable_memoization)?);
-            block_maps.push(block_parser.to_block_map(tree)?);
+            let tree = block_parser.to_syntax_tree(rule_map.clone(), enable_memoization)?;
+            let new_block_map = block_parser.to_block_map(&tree);
+            symbol_table.symbols.append(&mut block_parser.symbols);
+            warnings.append(&mut block_parser.warnings);
+            block_maps.push(new_block_map?);
 
-            if block_parser.file_alias_name == "" {
+            if block_parser.file_alias_name.is_empty() {
                 start_rule_id = block_parser.start_rule_id.clone();
             }
 
@@ -251,97 +431,261 @@ impl BlockParser {
             None => DEFAULT_START_RULE_ID.to_string(),
         };
 
-        let rule_map = Arc::new(Box::new(RuleMap::new(block_maps, start_rule_id_str)?));
+        let mut rule_map = RuleMap::new(block_maps, start_rule_id_str)?;
+        let mut errs = Vec::<FCPEGError>::new();
+
+        for (file_alias_name, fcpeg_file) in fcpeg_file_map.iter() {
+            for ((block_name, rule_name), sync_strings) in &fcpeg_file.config.sync_point_map {
+                let rule_id = BlockParser::to_rule_id_from_elements(&fcpeg_file_map.replaced_file_alias_names, file_alias_name, block_name, rule_name);
+
+                if rule_map.rule_map.contains_key(&rule_id) {
+                    rule_map.sync_point_map.insert(rule_id, sync_strings.clone());
+                } else {
+                    errs.push(ConfigurationLog::UnknownSyncPointRule {
+                        rule_id: rule_id,
+                    }.into());
+                }
+            }
+        }
+
+        for (file_alias_name, fcpeg_file) in fcpeg_file_map.iter() {
+            // note: 既定値と上限はメインファイルの構成のみを参照する
+            if file_alias_name.is_empty() {
+                rule_map.set_all_memoization(fcpeg_file.config.memoize_by_default);
+                rule_map.memoization_limit = fcpeg_file.config.memoization_limit;
+            }
+        }
+
+        for (file_alias_name, fcpeg_file) in fcpeg_file_map.iter() {
+            for ((block_name, rule_name), is_memoized) in &fcpeg_file.config.memoization_rule_map {
+                let rule_id = BlockParser::to_rule_id_from_elements(&fcpeg_file_map.replaced_file_alias_names, file_alias_name, block_name, rule_name);
+
+                if !rule_map.set_rule_memoization(&rule_id, *is_memoized) {
+                    errs.push(ConfigurationLog::UnknownMemoizationRule {
+                        rule_id: rule_id,
+                    }.into());
+                }
+            }
+        }
 
-        let mut has_id_error = false;
+        let rule_map = Arc::new(rule_map);
 
-        for (each_block_id, each_pos) in *used_block_ids {
+        for (each_block_id, each_pos) in used_block_ids {
             if !block_id_map.contains(&each_block_id) {
-                cons.borrow_mut().append_log(BlockParsingLog::UnknownBlockID {
+                errs.push(BlockParsingLog::UnknownBlockID {
                     pos: each_pos,
                     block_id: each_block_id,
-                }.get_log());
-
-                has_id_error = true;
+                }.into());
             }
         }
 
-        for (each_rule_id, each_pos) in *used_rule_ids {
+        for (each_rule_id, each_pos) in used_rule_ids {
             if !rule_map.rule_map.contains_key(&each_rule_id) && !PRIMITIVE_RULE_NAMES.contains(&each_rule_id.as_str()) {
-                cons.borrow_mut().append_log(BlockParsingLog::UnknownRuleID {
+                errs.push(BlockParsingLog::UnknownRuleID {
                     pos: each_pos,
                     rule_id: each_rule_id,
-                }.get_log());
+                }.into());
+            }
+        }
+
+        // note: リントの重大度はメインファイルの構成のみを参照する
+        let lint_severity_map = match fcpeg_file_map.file_map.get("") {
+            Some(v) => v.config.lint_severity_map.clone(),
+            None => HashMap::new(),
+        };
 
-                has_id_error = true;
+        BlockParser::analyze_rule_map(&rule_map, symbol_table, &lint_severity_map, &mut errs, warnings);
+
+        return match errs.len() {
+            0 => Ok(rule_map),
+            1 => Err(errs.remove(0)),
+            _ => Err(FCPEGError::Multiple(errs)),
+        };
+    }
+
+    // spec: FCPEG 構文の規則マップを取得する
+    pub fn get_fcpeg_rule_map() -> FCPEGResult<Arc<RuleMap>> {
+        let block_map = FCPEGBlock::get_block_map();
+        return Ok(Arc::new(RuleMap::new(vec![block_map], ".Syntax.FCPEG".to_string())?));
+    }
+
+    // spec: FCPEG コードを FCPEG 構文の構文木に変換する; 非反映的な要素 (空白・区切り文字など) も構文木に残る
+    pub fn to_fcpeg_syntax_tree(file_path: String, file_content: Box<String>) -> FCPEGResult<SyntaxTree> {
+        return SyntaxParser::parse(BlockParser::get_fcpeg_rule_map()?, file_path, file_content, true);
+    }
+
+    // spec: 規則マップを静的に解析して左再帰及び各リントの対象を報告する
+    // note: リントは重大度に応じてエラーまたは警告として報告し、Off であれば報告しない
+    fn analyze_rule_map(rule_map: &RuleMap, symbol_table: &BlockSymbolTable, lint_severity_map: &HashMap<LintKind, LintSeverity>, errs: &mut Vec<FCPEGError>, warnings: &mut Vec<BlockParsingLog>) {
+        let get_severity = |kind: LintKind| match lint_severity_map.get(&kind) {
+            Some(v) => *v,
+            None => kind.get_default_severity(),
+        };
+
+        let nullable_rule_ids = rule_map.get_nullable_rule_ids();
+
+        for each_cycle in rule_map.get_left_recursive_cycles(&nullable_rule_ids) {
+            let rule_id = each_cycle.first().unwrap().clone();
+            let pos = rule_map.rule_map.get(&rule_id).unwrap().pos.clone();
+
+            warnings.push(BlockParsingLog::LeftRecursiveRule {
+                pos: pos,
+                rule_id: rule_id,
+                cycle: each_cycle,
+            });
+        }
+
+        let severity = get_severity(LintKind::NullableRepetition);
+
+        for (each_rule_id, each_pos) in rule_map.get_nullable_repetitions(&nullable_rule_ids) {
+            BlockParser::push_lint_log(&severity, BlockParsingLog::NullableRepetition {
+                severity: severity,
+                pos: each_pos,
+                rule_id: each_rule_id,
+            }, errs, warnings);
+        }
+
+        let severity = get_severity(LintKind::DuplicateAlternative);
+
+        for (each_rule_id, each_pos, each_prev_pos) in rule_map.get_duplicate_alternatives() {
+            BlockParser::push_lint_log(&severity, BlockParsingLog::DuplicateAlternative {
+                severity: severity,
+                pos: each_pos,
+                rule_id: each_rule_id,
+                prev_pos: each_prev_pos,
+            }, errs, warnings);
+        }
+
+        let severity = get_severity(LintKind::ShadowedAlternative);
+
+        for (each_rule_id, each_pos, each_prev_pos) in rule_map.get_shadowed_alternatives() {
+            BlockParser::push_lint_log(&severity, BlockParsingLog::ShadowedAlternative {
+                severity: severity,
+                pos: each_pos,
+                rule_id: each_rule_id,
+                prev_pos: each_prev_pos,
+            }, errs, warnings);
+        }
+
+        // note: 開始規則が存在しない場合はすべての規則が到達不能となるため検査しない
+        if !rule_map.rule_map.contains_key(&rule_map.start_rule_id) {
+            return;
+        }
+
+        // note: 外部ファイルの規則は他の文法から利用されうるため、メインファイルの規則 (ID が "." で始まるもの) のみを対象とする
+        let reachable_rule_ids = rule_map.get_reachable_rule_ids();
+        let reachable_block_ids = reachable_rule_ids.iter().map(|each_rule_id| BlockParser::to_block_id_from_rule_id(each_rule_id)).collect::<HashSet<String>>();
+        let main_block_id = ".Main".to_string();
+        let mut unused_block_ids = Vec::<String>::new();
+
+        let severity = get_severity(LintKind::UnusedBlock);
+
+        for each_symbol in &symbol_table.symbols {
+            if each_symbol.kind != BlockSymbolKind::Block || !each_symbol.is_definition || !each_symbol.id.starts_with(".") {
+                continue;
+            }
+
+            if each_symbol.id == main_block_id || reachable_block_ids.contains(&each_symbol.id) || unused_block_ids.contains(&each_symbol.id) {
+                continue;
+            }
+
+            unused_block_ids.push(each_symbol.id.clone());
+
+            BlockParser::push_lint_log(&severity, BlockParsingLog::UnusedBlock {
+                severity: severity,
+                pos: each_symbol.pos.clone(),
+                block_id: each_symbol.id.clone(),
+            }, errs, warnings);
+        }
+
+        let mut rule_ids = rule_map.rule_map.keys().cloned().collect::<Vec<String>>();
+        rule_ids.sort();
+
+        let severity = get_severity(LintKind::UnreachableRule);
+
+        // note: 未使用のブロック内の規則はブロック単位で報告済みのため対象外とする
+        for each_rule_id in rule_ids {
+            if !each_rule_id.starts_with(".") || reachable_rule_ids.contains(&each_rule_id) || unused_block_ids.contains(&BlockParser::to_block_id_from_rule_id(&each_rule_id)) {
+                continue;
             }
+
+            BlockParser::push_lint_log(&severity, BlockParsingLog::UnreachableRule {
+                severity: severity,
+                pos: rule_map.rule_map.get(&each_rule_id).unwrap().pos.clone(),
+                rule_id: each_rule_id,
+            }, errs, warnings);
         }
+    }
+
+    fn push_lint_log(severity: &LintSeverity, log: BlockParsingLog, errs: &mut Vec<FCPEGError>, warnings: &mut Vec<BlockParsingLog>) {
+        match severity {
+            LintSeverity::Error => errs.push(log.into()),
+            LintSeverity::Warning | LintSeverity::Note => warnings.push(log),
+            LintSeverity::Off => (),
+        }
+    }
 
-        return if has_id_error {
-            Err(())
-        } else {
-            Ok(rule_map)
+    fn to_block_id_from_rule_id(rule_id: &str) -> String {
+        return match rule_id.rfind('.') {
+            Some(v) => rule_id[..v].to_string(),
+            None => rule_id.to_string(),
         };
     }
 
-    fn to_syntax_tree(&mut self, rule_map: Arc<Box<RuleMap>>, enable_memoization: bool) -> ConsoleResult<SyntaxTree> {
-        let tree = SyntaxParser::parse(self.cons.clone(), rule_map, self.file_path.clone(), self.file_content.clone(), enable_memoization)?;
+    fn to_syntax_tree(&mut self, rule_map: Arc<RuleMap>, enable_memoization: bool) -> FCPEGResult<SyntaxTree> {
+        let tree = SyntaxParser::parse(rule_map, self.file_path.clone(), Box::new(self.file_content.clone()), enable_memoization)?;
         return Ok(tree);
     }
 
     // note: FCPEG コードの構文木 → ブロックマップの変換
-    fn to_block_map(&mut self, tree: Box<SyntaxTree>) -> ConsoleResult<BlockMap> {
+    fn to_block_map(&mut self, tree: &SyntaxTree) -> FCPEGResult<BlockMap> {
         let mut block_map = BlockMap::new();
         let root = tree.get_child_ref();
-        let block_nodes = match root.get_node(&self.cons)?.get_node_child_at(&self.cons, 0) {
+        let block_nodes = match root.get_node()?.get_node_child_at(0) {
             Ok(v) => v.get_reflectable_children(),
-            Err(()) => {
-                self.cons.borrow_mut().pop_log();
-                return Ok(block_map)
-            },
+            Err(_) => return Ok(block_map),
         };
 
         for each_block_elem in &block_nodes {
-            let each_block_node = each_block_elem.get_node(&self.cons)?;
-            let block_name_node = each_block_node.get_node_child_at(&self.cons, 0)?;
-            let block_pos = block_name_node.get_position(&self.cons)?;
+            let each_block_node = each_block_elem.get_node()?;
+            let block_name_node = each_block_node.get_node_child_at(0)?;
+            let block_pos = block_name_node.get_position()?;
             self.block_name = block_name_node.join_child_leaf_values();
 
+            let block_id = BlockParser::to_block_id_from_elements(&self.replaced_file_alias_names, &self.file_alias_name, &self.block_name);
+            self.push_symbol(BlockSymbolKind::Block, block_id, block_pos.clone(), &self.block_name.clone(), true);
+
             if !BlockParser::is_pascal_case(&self.block_name) {
-                self.cons.borrow_mut().append_log(BlockParsingLog::NamingRuleViolation {
+                self.warnings.push(BlockParsingLog::NamingRuleViolation {
                     pos: block_pos.clone(),
                     id: self.block_name.clone(),
-                }.get_log());
+                });
             }
 
             if block_map.contains_key(&self.block_name) {
-                self.cons.borrow_mut().append_log(BlockParsingLog::DuplicateBlockName {
-                    pos: block_name_node.get_position(&self.cons)?,
+                return Err(BlockParsingLog::DuplicateBlockName {
+                    pos: block_name_node.get_position()?,
                     block_name: self.block_name.clone(),
-                }.get_log());
-
-                return Err(());
+                }.into());
             }
 
             let mut cmds = Vec::<BlockCommand>::new();
             let mut rule_names = Vec::<String>::new();
 
-            match each_block_node.get_node_child_at(&self.cons, 1) {
+            match each_block_node.get_node_child_at(1) {
                 Ok(cmd_elems) => {
                     for each_cmd_elem in &cmd_elems.get_reflectable_children() {
-                        let each_cmd_node = each_cmd_elem.get_node(&self.cons)?.get_node_child_at(&self.cons, 0)?;
+                        let each_cmd_node = each_cmd_elem.get_node()?.get_node_child_at(0)?;
                         let new_cmd = self.to_block_cmd(each_cmd_node)?;
 
                         // ルール名の重複チェック
                         match &new_cmd {
                             BlockCommand::Define { pos: _, rule } => {
                                 if rule_names.contains(&rule.name) {
-                                    self.cons.borrow_mut().append_log(BlockParsingLog::DuplicateRuleName {
+                                    return Err(BlockParsingLog::DuplicateRuleName {
                                         pos: rule.pos.clone(),
                                         rule_name: rule.name.clone(),
-                                    }.get_log());
-
-                                    return Err(());
+                                    }.into());
                                 }
 
                                 rule_names.push(rule.name.clone())
@@ -352,7 +696,7 @@ impl BlockParser {
                         cmds.push(new_cmd);
                     }
                 },
-                Err(()) => self.cons.borrow_mut().pop_log(),
+                Err(_) => (),
             }
 
             self.block_id_map.push(BlockParser::to_block_id_from_elements(&self.replaced_file_alias_names, &self.file_alias_name, &self.block_name));
@@ -365,7 +709,7 @@ impl BlockParser {
         return Ok(block_map);
     }
 
-    fn to_block_cmd(&mut self, cmd_node: &SyntaxNode) -> ConsoleResult<BlockCommand> {
+    fn to_block_cmd(&mut self, cmd_node: &SyntaxNode) -> FCPEGResult<BlockCommand> {
         return match &cmd_node.ast_reflection_style {
             ASTReflectionStyle::Reflection(node_name) => match node_name.as_str() {
                 ".Block.CommentCmd" => self.to_comment_cmd(cmd_node),
@@ -376,20 +720,16 @@ impl BlockParser {
                     match start_cmd.clone() {
                         BlockCommand::Start { pos, file_alias_name, block_name, rule_name } => {
                             if self.block_name != "Main" {
-                                self.cons.borrow_mut().append_log(BlockParsingLog::StartCommandOutsideMainBlock {
+                                return Err(BlockParsingLog::StartCommandOutsideMainBlock {
                                     pos: pos,
-                                }.get_log());
-
-                                return Err(());
+                                }.into());
                             }
 
-                            if self.file_alias_name == "" {
+                            if self.file_alias_name.is_empty() {
                                 if self.start_rule_id.is_some() {
-                                    self.cons.borrow_mut().append_log(BlockParsingLog::DuplicateStartCommand {
+                                    return Err(BlockParsingLog::DuplicateStartCommand {
                                         pos: pos,
-                                    }.get_log());
-
-                                    return Err(());
+                                    }.into());
                                 }
 
                                 let rule_id = BlockParser::to_rule_id_from_elements(&self.replaced_file_alias_names, &file_alias_name, &block_name, &rule_name);
@@ -416,10 +756,10 @@ impl BlockParser {
 
                             // note: ブロック ID が自身のブロックと同じであれば警告
                             if block_id == used_from {
-                                self.cons.borrow_mut().append_log(BlockParsingLog::UnnecessaryUseCommand {
+                                self.warnings.push(BlockParsingLog::UnnecessaryUseCommand {
                                     pos: pos.clone(),
                                     msg: format!("block '{}' is the self block", block_id),
-                                }.get_log());
+                                });
                             }
 
                             let mut disable_map_insert = false;
@@ -427,17 +767,17 @@ impl BlockParser {
                             // note: ブロック ID / ブロックエイリアスがすでに use されていれば警告
                             for (each_alias_name, each_id) in &self.block_alias_map {
                                 if *each_id == block_id {
-                                    self.cons.borrow_mut().append_log(BlockParsingLog::UnnecessaryUseCommand {
+                                    self.warnings.push(BlockParsingLog::UnnecessaryUseCommand {
                                         pos: pos.clone(),
                                         msg: format!("block '{}' is already used", block_id),
-                                    }.get_log());
+                                    });
 
                                     disable_map_insert = true;
                                 } else if each_alias_name == block_alias_name {
-                                    self.cons.borrow_mut().append_log(BlockParsingLog::UnnecessaryUseCommand {
+                                    self.warnings.push(BlockParsingLog::UnnecessaryUseCommand {
                                         pos: pos.clone(),
                                         msg: format!("block alias '{}' is already used", block_alias_name),
-                                    }.get_log());
+                                    });
 
                                     disable_map_insert = true;
                                 }
@@ -458,41 +798,37 @@ impl BlockParser {
                     Ok(use_cmd)
                 },
                 _ => {
-                    self.cons.borrow_mut().append_log(BlockParsingLog::UnexpectedNodeName {
-                        uuid: cmd_node.uuid.clone(),
+                    return Err(BlockParsingLog::UnexpectedNodeName {
+                        uuid: cmd_node.uuid,
                         unexpected: format!("'{}'", node_name),
                         expected: "block command node name".to_string(),
-                    }.get_log());
-
-                    return Err(());
+                    }.into());
                 },
             },
             _ => {
-                self.cons.borrow_mut().append_log(BlockParsingLog::UnexpectedNodeName {
-                    uuid: cmd_node.uuid.clone(),
+                return Err(BlockParsingLog::UnexpectedNodeName {
+                    uuid: cmd_node.uuid,
                     unexpected: "no name".to_string(),
                     expected: "block command node name".to_string(),
-                }.get_log());
-
-                return Err(());
+                }.into());
             },
         };
     }
 
-    fn to_comment_cmd(&mut self, cmd_node: &SyntaxNode) -> ConsoleResult<BlockCommand> {
-        return Ok(BlockCommand::Comment { pos: cmd_node.get_position(&self.cons)?, value: cmd_node.join_child_leaf_values() });
+    fn to_comment_cmd(&mut self, cmd_node: &SyntaxNode) -> FCPEGResult<BlockCommand> {
+        return Ok(BlockCommand::Comment { pos: cmd_node.get_position()?, value: cmd_node.join_child_leaf_values() });
     }
 
-    fn to_define_cmd(&mut self, cmd_node: &SyntaxNode) -> ConsoleResult<BlockCommand> {
-        let rule_name_node = cmd_node.get_node_child_at(&self.cons, 0)?;
-        let rule_pos = rule_name_node.get_position(&self.cons)?;
+    fn to_define_cmd(&mut self, cmd_node: &SyntaxNode) -> FCPEGResult<BlockCommand> {
+        let rule_name_node = cmd_node.get_node_child_at(0)?;
+        let rule_pos = rule_name_node.get_position()?;
         let rule_name = rule_name_node.join_child_leaf_values();
 
         if !BlockParser::is_pascal_case(&rule_name) {
-            self.cons.borrow_mut().append_log(BlockParsingLog::NamingRuleViolation {
+            self.warnings.push(BlockParsingLog::NamingRuleViolation {
                 pos: rule_pos.clone(),
                 id: rule_name.clone(),
-            }.get_log());
+            });
         }
 
         let generics_args = match cmd_node.find_first_child_node(vec![".Block.DefineCmdGenerics"]) {
@@ -508,39 +844,37 @@ impl BlockParser {
         let new_choice = match cmd_node.find_first_child_node(vec![".Rule.PureChoice"]) {
             Some(choice_node) => Box::new(self.to_rule_choice_elem(choice_node, &generics_args)?),
             None => {
-                self.cons.borrow_mut().append_log(BlockParsingLog::UnexpectedChildName {
-                    parent_uuid: cmd_node.uuid.clone(),
+                return Err(BlockParsingLog::UnexpectedChildName {
+                    parent_uuid: cmd_node.uuid,
                     unexpected: "unknown".to_string(),
                     expected: "pure choice node".to_string(),
-                }.get_log());
-
-                return Err(());
+                }.into());
             },
         };
 
         let rule_id = BlockParser::to_rule_id_from_elements(&self.replaced_file_alias_names, &self.file_alias_name, &self.block_name, &rule_name);
+        self.push_symbol(BlockSymbolKind::Rule, rule_id.clone(), rule_pos.clone(), &rule_name, true);
+
         let rule = Rule::new(rule_pos.clone(), rule_id, rule_name, generics_args, template_args, new_choice);
         return Ok(BlockCommand::Define { pos: rule_pos, rule: rule });
     }
 
-    fn to_define_cmd_arg_ids(&mut self, cmd_node: &SyntaxNode) -> ConsoleResult<Vec<String>> {
+    fn to_define_cmd_arg_ids(&mut self, cmd_node: &SyntaxNode) -> FCPEGResult<Vec<String>> {
         let mut args = Vec::<String>::new();
 
         for each_elem in &cmd_node.sub_elems {
             match each_elem {
-                SyntaxNodeElement::Node(each_node) => {
-                    if each_node.ast_reflection_style == ASTReflectionStyle::Reflection(".Rule.ArgID".to_string()) {
-                        let new_arg = each_node.join_child_leaf_values();
-
-                        if args.contains(&new_arg) {
-                            self.cons.borrow_mut().append_log(BlockParsingLog::DuplicateArgumentID {
-                                pos: each_node.get_position(&self.cons)?,
-                                arg_id: new_arg.clone(),
-                            }.get_log());
-                        }
-
-                        args.push(new_arg);
+                SyntaxNodeElement::Node(each_node) if each_node.ast_reflection_style == ASTReflectionStyle::Reflection(".Rule.ArgID".to_string()) => {
+                    let new_arg = each_node.join_child_leaf_values();
+
+                    if args.contains(&new_arg) {
+                        return Err(BlockParsingLog::DuplicateArgumentID {
+                            pos: each_node.get_position()?,
+                            arg_id: new_arg.clone(),
+                        }.into());
                     }
+
+                    args.push(new_arg);
                 },
                 _ => (),
             }
@@ -549,34 +883,36 @@ impl BlockParser {
         return Ok(args);
     }
 
-    fn to_start_cmd(&mut self, cmd_node: &SyntaxNode) -> ConsoleResult<BlockCommand> {
-        let raw_id_node = cmd_node.get_node_child_at(&self.cons, 0)?;
+    fn to_start_cmd(&mut self, cmd_node: &SyntaxNode) -> FCPEGResult<BlockCommand> {
+        let raw_id_node = cmd_node.get_node_child_at(0)?;
         let raw_id = self.to_chain_id(raw_id_node)?;
         let divided_raw_id = raw_id.split(".").collect::<Vec<&str>>();
 
         let (file_alias_name, block_name, rule_name) = match divided_raw_id.len() {
-            2 => (String::new(), divided_raw_id.get(0).unwrap().to_string(), divided_raw_id.get(1).unwrap().to_string()),
-            3 => (divided_raw_id.get(0).unwrap().to_string(), divided_raw_id.get(1).unwrap().to_string(), divided_raw_id.get(2).unwrap().to_string()),
+            2 => (String::new(), divided_raw_id.first().unwrap().to_string(), divided_raw_id.get(1).unwrap().to_string()),
+            3 => (divided_raw_id.first().unwrap().to_string(), divided_raw_id.get(1).unwrap().to_string(), divided_raw_id.get(2).unwrap().to_string()),
             _ => {
-                self.cons.borrow_mut().append_log(BlockParsingLog::InvalidID {
-                    pos: raw_id_node.get_position(&self.cons)?,
+                return Err(BlockParsingLog::InvalidID {
+                    pos: raw_id_node.get_position()?,
                     id: raw_id,
-                }.get_log());
-
-                return Err(());
+                }.into());
             },
         };
 
+        let block_id = BlockParser::to_block_id_from_elements(&self.replaced_file_alias_names, &file_alias_name, &block_name);
+        let rule_id = BlockParser::to_rule_id_from_elements(&self.replaced_file_alias_names, &file_alias_name, &block_name, &rule_name);
+        self.push_chain_id_symbols(raw_id_node, vec![(BlockSymbolKind::Block, block_id), (BlockSymbolKind::Rule, rule_id.clone())])?;
+
         // note: ブロック ID がデフォルトと同じであれば警告
-        if DEFAULT_START_RULE_ID == BlockParser::to_rule_id_from_elements(&self.replaced_file_alias_names, &file_alias_name, &block_name, &rule_name) {
-            self.cons.borrow_mut().append_log(BlockParsingLog::UnnecessaryStartCommand {
-                pos: cmd_node.get_position(&self.cons)?,
+        if DEFAULT_START_RULE_ID == rule_id {
+            self.warnings.push(BlockParsingLog::UnnecessaryStartCommand {
+                pos: cmd_node.get_position()?,
                 msg: format!("rule '{}' is the same as the default", DEFAULT_START_RULE_ID),
-            }.get_log());
+            });
         }
 
         let cmd = BlockCommand::Start {
-            pos: cmd_node.get_position(&self.cons)?,
+            pos: cmd_node.get_position()?,
             file_alias_name: file_alias_name,
             block_name: block_name,
             rule_name: rule_name,
@@ -585,34 +921,38 @@ impl BlockParser {
         return Ok(cmd);
     }
 
-    fn to_use_cmd(&mut self, cmd_node: &SyntaxNode) -> ConsoleResult<BlockCommand> {
-        let raw_id_node = cmd_node.get_node_child_at(&self.cons, 0)?;
+    fn to_use_cmd(&mut self, cmd_node: &SyntaxNode) -> FCPEGResult<BlockCommand> {
+        let raw_id_node = cmd_node.get_node_child_at(0)?;
         let raw_id = self.to_chain_id(raw_id_node)?;
         let divided_raw_id = raw_id.split(".").collect::<Vec<&str>>();
 
         let (file_alias_name, block_name) = match divided_raw_id.len() {
-            1 => (self.file_alias_name.clone(), divided_raw_id.get(0).unwrap().to_string()),
-            2 => (divided_raw_id.get(0).unwrap().to_string(), divided_raw_id.get(1).unwrap().to_string()),
+            1 => (self.file_alias_name.clone(), divided_raw_id.first().unwrap().to_string()),
+            2 => (divided_raw_id.first().unwrap().to_string(), divided_raw_id.get(1).unwrap().to_string()),
             _ => {
-                self.cons.borrow_mut().append_log(BlockParsingLog::InvalidID {
-                    pos: raw_id_node.get_position(&self.cons)?,
+                return Err(BlockParsingLog::InvalidID {
+                    pos: raw_id_node.get_position()?,
                     id: raw_id,
-                }.get_log());
-
-                return Err(());
+                }.into());
             },
         };
 
+        let block_id = BlockParser::to_block_id_from_elements(&self.replaced_file_alias_names, &file_alias_name, &block_name);
+        self.push_chain_id_symbols(raw_id_node, vec![(BlockSymbolKind::Block, block_id.clone())])?;
+
         let block_alias_name = match cmd_node.find_first_child_node(vec![".Block.UseCmdBlockAlias"]) {
             Some(block_alias_node) => {
-                let block_alias_name = block_alias_node.get_node_child_at(&self.cons, 0)?.join_child_leaf_values();
+                let block_alias_name_node = block_alias_node.get_node_child_at(0)?;
+                let block_alias_name = block_alias_name_node.join_child_leaf_values();
+                // note: エイリアス名は参照先のブロックの出現箇所とする
+                self.push_symbol(BlockSymbolKind::Block, block_id, block_alias_name_node.get_position()?, &block_alias_name, false);
 
                 // note: ブロック名とエイリアス名が同じであれば警告
                 if block_name == block_alias_name {
-                    self.cons.borrow_mut().append_log(BlockParsingLog::UnnecessaryBlockAliasName {
-                        pos: block_alias_node.get_position(&self.cons)?,
+                    self.warnings.push(BlockParsingLog::UnnecessaryBlockAliasName {
+                        pos: block_alias_node.get_position()?,
                         alias_name: block_name.clone(),
-                    }.get_log());
+                    });
                 }
 
                 block_alias_name
@@ -621,40 +961,45 @@ impl BlockParser {
         };
 
         return match divided_raw_id.len() {
-            1 | 2 => Ok(BlockCommand::Use { pos: cmd_node.get_position(&self.cons)?, file_alias_name: file_alias_name, block_name: block_name, block_alias_name: block_alias_name }),
+            1 | 2 => Ok(BlockCommand::Use { pos: cmd_node.get_position()?, file_alias_name: file_alias_name, block_name: block_name, block_alias_name: block_alias_name }),
             _ => {
-                self.cons.borrow_mut().append_log(BlockParsingLog::InvalidID {
-                    pos: raw_id_node.get_position(&self.cons)?,
+                return Err(BlockParsingLog::InvalidID {
+                    pos: raw_id_node.get_position()?,
                     id: raw_id,
-                }.get_log());
-
-                return Err(());
+                }.into());
             },
         };
     }
 
     // note: Seq を解析する
-    fn to_seq_elem(&mut self, seq_node: &SyntaxNode, generics_args: &Vec<String>) -> ConsoleResult<RuleElement> {
+    fn to_seq_elem(&mut self, seq_node: &SyntaxNode, generics_args: &Vec<String>) -> FCPEGResult<RuleElement> {
         let mut children = Vec::<RuleElement>::new();
 
         // note: SeqElem ノードをループ
         for each_seq_elem_elem in &seq_node.get_reflectable_children() {
-            let each_seq_elem_node = each_seq_elem_elem.get_node(&self.cons)?;
+            let each_seq_elem_node = each_seq_elem_elem.get_node()?;
+
+            // note: Cut ノード
+            match each_seq_elem_node.find_first_child_node(vec![".Rule.Cut"]) {
+                Some(cut_node) => {
+                    children.push(RuleElement::Cut(cut_node.get_position()?));
+                    continue;
+                },
+                None => (),
+            }
 
             // note: Lookahead ノード
             let lookahead_kind = match each_seq_elem_node.find_first_child_node(vec![".Rule.Lookahead"]) {
                 Some(lookahead_node) => {
-                    let kind_str = lookahead_node.get_leaf_child_at(&self.cons, 0)?.value.as_str();
+                    let kind_str = lookahead_node.get_leaf_child_at(0)?.value.as_str();
                     let kind = RuleElementLookaheadKind::new(kind_str);
 
                     match kind {
                         RuleElementLookaheadKind::None => {
-                            self.cons.borrow_mut().append_log(SyntaxParsingLog::UnknownLookaheadKind {
+                            return Err(SyntaxParsingLog::UnknownLookaheadKind {
                                 uuid: lookahead_node.uuid,
                                 kind: kind_str.to_string(),
-                            }.get_log());
-
-                            return Err(());
+                            }.into());
                         },
                         _ => kind,
                     }
@@ -665,7 +1010,7 @@ impl BlockParser {
             // note: Loop ノード
             let loop_range = match each_seq_elem_node.find_first_child_node(vec![".Rule.Loop"]) {
                 Some(loop_node) => {
-                    match loop_node.get_child_at(&self.cons, 0)? {
+                    match loop_node.get_child_at(0)? {
                         SyntaxNodeElement::Node(range_node) => {
                             let raw_range = self.to_raw_range(range_node)?;
 
@@ -686,64 +1031,58 @@ impl BlockParser {
                                 Infinitable::Finite(max_v) => {
                                     if raw_range.min_num > *max_v {
                                         // note: 最小回数が最大回数より大きかった場合
-                                        self.cons.borrow_mut().append_log(BlockParsingLog::InvalidLoopRange {
+                                        return Err(BlockParsingLog::InvalidLoopRange {
                                             pos: raw_range.range_node_pos.clone(),
                                             msg: format!("min value '{}' is bigger than max value '{}'", raw_range.min_num, max_v),
-                                        }.get_log());
-
-                                        return Err(());
+                                        }.into());
                                     }
 
                                     if raw_range.is_min_num_specified && !raw_range.is_max_num_specified && raw_range.min_num == 0 && *max_v == 0 {
                                         // note: {0} の場合
-                                        self.cons.borrow_mut().append_log(BlockParsingLog::InvalidLoopRange {
+                                        return Err(BlockParsingLog::InvalidLoopRange {
                                             pos: raw_range.range_node_pos.clone(),
-                                            msg: format!("loop range '{{0}}' is invalid"),
-                                        }.get_log());
-
-                                        return Err(());
+                                            msg: "loop range '{0}' is invalid".to_string(),
+                                        }.into());
                                     } else if raw_range.min_num == 1 && *max_v == 1 {
                                         if raw_range.is_min_num_specified && !raw_range.is_max_num_specified {
                                             // note: {1} の場合
-                                            self.cons.borrow_mut().append_log(BlockParsingLog::UnrecommendedLoopRange {
+                                            self.warnings.push(BlockParsingLog::UnrecommendedLoopRange {
                                                 pos: raw_range.range_node_pos.clone(),
-                                                msg: format!("loop range '{{1}}' is unnecessary"),
-                                            }.get_log());
+                                                msg: "loop range '{1}' is unnecessary".to_string(),
+                                            });
                                         } else {
                                             // note: {1,1} の場合
-                                            self.cons.borrow_mut().append_log(BlockParsingLog::UnrecommendedLoopRange {
+                                            self.warnings.push(BlockParsingLog::UnrecommendedLoopRange {
                                                 pos: raw_range.range_node_pos.clone(),
-                                                msg: format!("loop range '{{1,1}}' is unnecessary"),
-                                            }.get_log());
+                                                msg: "loop range '{1,1}' is unnecessary".to_string(),
+                                            });
                                         }
                                     } else if *max_v == 0 {
                                         // note: 最大回数に 0 が指定された場合
-                                        self.cons.borrow_mut().append_log(BlockParsingLog::InvalidLoopRange {
+                                        return Err(BlockParsingLog::InvalidLoopRange {
                                             pos: raw_range.max_num_pos.unwrap(),
                                             msg: format!("max number '{}' is invalid", raw_range.min_num),
-                                        }.get_log());
-
-                                        return Err(());
+                                        }.into());
                                     } else if raw_range.is_max_num_specified && raw_range.min_num == *max_v {
                                         // note: 最小回数と最大回数が同じだった場合
-                                        self.cons.borrow_mut().append_log(BlockParsingLog::UnrecommendedLoopRange {
+                                        self.warnings.push(BlockParsingLog::UnrecommendedLoopRange {
                                             pos: raw_range.range_node_pos.clone(),
                                             msg: format!("modify '{}' to '{{{}}}'", raw_loop_range_txt, raw_range.min_num),
-                                        }.get_log());
+                                        });
                                     } else if raw_range.is_min_num_specified && raw_range.min_num == 0 {
                                         // note: 最小回数に 0 が指定された場合
-                                        self.cons.borrow_mut().append_log(BlockParsingLog::UnrecommendedLoopRange {
+                                        self.warnings.push(BlockParsingLog::UnrecommendedLoopRange {
                                             pos: raw_range.range_node_pos.clone(),
                                             msg: format!("modify '{}' to '{{,{}}}'", raw_loop_range_txt, max_v),
-                                        }.get_log());
+                                        });
                                     }
                                 },
                                 Infinitable::Infinite if raw_range.is_min_num_specified && raw_range.min_num == 0 => {
                                     // note: {0,} の場合
-                                    self.cons.borrow_mut().append_log(BlockParsingLog::UnrecommendedLoopRange {
+                                    self.warnings.push(BlockParsingLog::UnrecommendedLoopRange {
                                         pos: raw_range.range_node_pos.clone(),
                                         msg: format!("modify '{}' to '{{,}}'", raw_loop_range_txt),
-                                    }.get_log());
+                                    });
                                 },
                                 _ => (),
                             }
@@ -752,10 +1091,10 @@ impl BlockParser {
 
                             match loop_range.to_symbol_string() {
                                 Some(symbol_str) => {
-                                    self.cons.borrow_mut().append_log(BlockParsingLog::UnrecommendedLoopRange {
+                                    self.warnings.push(BlockParsingLog::UnrecommendedLoopRange {
                                         pos: raw_range.range_node_pos.clone(),
                                         msg: format!("prefer {} to {}", raw_loop_range_txt, symbol_str),
-                                    }.get_log());
+                                    });
                                 },
                                 None => (),
                             }
@@ -768,12 +1107,10 @@ impl BlockParser {
                             match kind_str {
                                 "?" | "*" | "+" => RuleElementLoopRange::from(&leaf.value),
                                 _ => {
-                                    self.cons.borrow_mut().append_log(SyntaxParsingLog::UnknownLookaheadKind {
-                                        uuid: leaf.uuid.clone(),
+                                    return Err(SyntaxParsingLog::UnknownLookaheadKind {
+                                        uuid: leaf.uuid,
                                         kind: kind_str.to_string(),
-                                    }.get_log());
-
-                                    return Err(());
+                                    }.into());
                                 },
                             }
                         }
@@ -785,7 +1122,7 @@ impl BlockParser {
             // note: RandomOrder ノード
             let (elem_order, random_order_node_pos) = match each_seq_elem_node.find_first_child_node(vec![".Rule.RandomOrder"]) {
                 Some(random_order_node) => {
-                    let random_order_node_pos = random_order_node.get_position(&self.cons)?;
+                    let random_order_node_pos = random_order_node.get_position()?;
 
                     let (min_num, max_num) = match random_order_node.find_first_child_node(vec![".Rule.RandomOrderRange"]) {
                         Some(range_node) => {
@@ -808,64 +1145,58 @@ impl BlockParser {
                                 Infinitable::Finite(max_v) => {
                                     if raw_range.min_num > *max_v {
                                         // note: 最小回数が最大回数より大きかった場合
-                                        self.cons.borrow_mut().append_log(BlockParsingLog::InvalidLoopRange {
+                                        return Err(BlockParsingLog::InvalidLoopRange {
                                             pos: raw_range.range_node_pos.clone(),
                                             msg: format!("min value '{}' is bigger than max value '{}'", raw_range.min_num, max_v),
-                                        }.get_log());
-
-                                        return Err(());
+                                        }.into());
                                     }
 
                                     if raw_range.is_min_num_specified && !raw_range.is_max_num_specified && raw_range.min_num == 0 && *max_v == 0 {
                                         // note: [0] の場合
-                                        self.cons.borrow_mut().append_log(BlockParsingLog::InvalidLoopRange {
+                                        return Err(BlockParsingLog::InvalidLoopRange {
                                             pos: raw_range.range_node_pos.clone(),
-                                            msg: format!("loop range '[0]' is invalid"),
-                                        }.get_log());
-
-                                        return Err(());
+                                            msg: "loop range '[0]' is invalid".to_string(),
+                                        }.into());
                                     } else if raw_range.min_num == 1 && *max_v == 1 {
                                         if raw_range.is_min_num_specified && !raw_range.is_max_num_specified {
                                             // note: [1] の場合
-                                            self.cons.borrow_mut().append_log(BlockParsingLog::UnrecommendedLoopRange {
+                                            self.warnings.push(BlockParsingLog::UnrecommendedLoopRange {
                                                 pos: raw_range.range_node_pos.clone(),
-                                                msg: format!("loop range '[1]' is unnecessary"),
-                                            }.get_log());
+                                                msg: "loop range '[1]' is unnecessary".to_string(),
+                                            });
                                         } else {
                                             // note: [1,1] の場合
-                                            self.cons.borrow_mut().append_log(BlockParsingLog::UnrecommendedLoopRange {
+                                            self.warnings.push(BlockParsingLog::UnrecommendedLoopRange {
                                                 pos: raw_range.range_node_pos.clone(),
-                                                msg: format!("loop range [1-1]' is unnecessary"),
-                                            }.get_log());
+                                                msg: "loop range [1-1]' is unnecessary".to_string(),
+                                            });
                                         }
                                     } else if *max_v == 0 {
                                         // note: 最大回数に 0 が指定された場合
-                                        self.cons.borrow_mut().append_log(BlockParsingLog::InvalidLoopRange {
+                                        return Err(BlockParsingLog::InvalidLoopRange {
                                             pos: raw_range.max_num_pos.unwrap(),
                                             msg: format!("max number '{}' is invalid", raw_range.min_num),
-                                        }.get_log());
-
-                                        return Err(());
+                                        }.into());
                                     } else if raw_range.is_max_num_specified && raw_range.min_num == *max_v {
                                         // note: 最小回数と最大回数が同じだった場合
-                                        self.cons.borrow_mut().append_log(BlockParsingLog::UnrecommendedLoopRange {
+                                        self.warnings.push(BlockParsingLog::UnrecommendedLoopRange {
                                             pos: raw_range.range_node_pos.clone(),
                                             msg: format!("modify '{}' to '[{}]'", raw_loop_range_txt, raw_range.min_num),
-                                        }.get_log());
+                                        });
                                     } else if raw_range.is_min_num_specified && raw_range.min_num == 0 {
                                         // note: 最小回数に 0 が指定された場合
-                                        self.cons.borrow_mut().append_log(BlockParsingLog::UnrecommendedLoopRange {
+                                        self.warnings.push(BlockParsingLog::UnrecommendedLoopRange {
                                             pos: raw_range.range_node_pos.clone(),
                                             msg: format!("modify '{}' to '[-{}]'", raw_loop_range_txt, max_v),
-                                        }.get_log());
+                                        });
                                     }
                                 },
                                 Infinitable::Infinite if raw_range.is_min_num_specified && raw_range.min_num == 0 => {
                                     // note: [0-] の場合
-                                    self.cons.borrow_mut().append_log(BlockParsingLog::UnrecommendedLoopRange {
+                                    self.warnings.push(BlockParsingLog::UnrecommendedLoopRange {
                                         pos: raw_range.range_node_pos.clone(),
                                         msg: format!("modify '{}' to '[,]'", raw_loop_range_txt),
-                                    }.get_log());
+                                    });
                                 },
                                 _ => (),
                             }
@@ -885,7 +1216,7 @@ impl BlockParser {
             // todo: 構成ファイルによって切り替える
             let ast_reflection_style = match each_seq_elem_node.find_first_child_node(vec![".Rule.ASTReflectionStyle"]) {
                 Some(style_node) => {
-                    match style_node.get_leaf_child_at(&self.cons, 0) {
+                    match style_node.get_leaf_child_at(0) {
                         Ok(leaf) => {
                             if leaf.value == "##" {
                                 ASTReflectionStyle::Expansion
@@ -893,10 +1224,7 @@ impl BlockParser {
                                 ASTReflectionStyle::Reflection(style_node.join_child_leaf_values())
                             }
                         },
-                        Err(()) => {
-                            self.cons.borrow_mut().pop_log();
-                            ASTReflectionStyle::from_config(false, true, String::new())
-                        },
+                        Err(_) => ASTReflectionStyle::from_config(false, true, String::new()),
                     }
                 },
                 None => ASTReflectionStyle::from_config(false, false, String::new()),
@@ -906,13 +1234,11 @@ impl BlockParser {
             let choice_or_expr_node = match each_seq_elem_node.find_first_child_node(vec![".Rule.Choice", ".Rule.Expr"]) {
                 Some(v) => v,
                 None => {
-                    self.cons.borrow_mut().append_log(BlockParsingLog::UnexpectedChildName {
-                        parent_uuid: each_seq_elem_node.uuid.clone(),
+                    return Err(BlockParsingLog::UnexpectedChildName {
+                        parent_uuid: each_seq_elem_node.uuid,
                         unexpected: "unknown".to_string(),
                         expected: "choice or expression node".to_string(),
-                    }.get_log());
-
-                    return Err(());
+                    }.into());
                 },
             };
 
@@ -920,7 +1246,7 @@ impl BlockParser {
                 ASTReflectionStyle::Reflection(name) => {
                     let new_elem = match name.as_str() {
                         ".Rule.Choice" => {
-                            let mut new_choice = Box::new(self.to_rule_choice_elem(choice_or_expr_node.get_node_child_at(&self.cons, 0)?, generics_args)?);
+                            let mut new_choice = Box::new(self.to_rule_choice_elem(choice_or_expr_node.get_node_child_at(0)?, generics_args)?);
                             new_choice.ast_reflection_style = ast_reflection_style;
                             new_choice.lookahead_kind = lookahead_kind;
                             new_choice.loop_range = loop_range;
@@ -929,11 +1255,9 @@ impl BlockParser {
                         },
                         ".Rule.Expr" => {
                             if elem_order.is_random() {
-                                self.cons.borrow_mut().append_log(BlockParsingLog::RandomOrderInExpression {
+                                return Err(BlockParsingLog::RandomOrderInExpression {
                                     pos: random_order_node_pos,
-                                }.get_log());
-
-                                return Err(());
+                                }.into());
                             }
 
                             let mut new_expr = Box::new(self.to_rule_expr_elem(choice_or_expr_node, generics_args)?);
@@ -943,26 +1267,22 @@ impl BlockParser {
                             RuleElement::Expression(new_expr)
                         },
                         _ => {
-                            self.cons.borrow_mut().append_log(BlockParsingLog::UnexpectedNodeName {
-                                uuid: choice_or_expr_node.uuid.clone(),
+                            return Err(BlockParsingLog::UnexpectedNodeName {
+                                uuid: choice_or_expr_node.uuid,
                                 unexpected: format!("'{}'", name),
                                 expected: "choice or expression node".to_string(),
-                            }.get_log());
-
-                            return Err(());
+                            }.into());
                         },
                     };
 
                     children.push(new_elem);
                 },
                 _ => {
-                    self.cons.borrow_mut().append_log(BlockParsingLog::UnexpectedNodeName {
-                        uuid: choice_or_expr_node.uuid.clone(),
+                    return Err(BlockParsingLog::UnexpectedNodeName {
+                        uuid: choice_or_expr_node.uuid,
                         unexpected: "no name".to_string(),
                         expected: "choice or expression node".to_string(),
-                    }.get_log());
-
-                    return Err(());
+                    }.into());
                 },
             };
         }
@@ -972,46 +1292,42 @@ impl BlockParser {
         return Ok(RuleElement::Group(seq));
     }
 
-    fn to_raw_range(&mut self, range_node: &SyntaxNode) -> ConsoleResult<RawRange> {
-        let range_node_pos = range_node.get_position(&self.cons)?;
+    fn to_raw_range(&mut self, range_node: &SyntaxNode) -> FCPEGResult<RawRange> {
+        let range_node_pos = range_node.get_position()?;
 
-        let (min_num, min_num_pos, is_min_num_specified) = match range_node.find_child_nodes(vec!["MinNum"]).get(0) {
+        let (min_num, min_num_pos, is_min_num_specified) = match range_node.find_child_nodes(vec!["MinNum"]).first() {
             Some(min_num_node) => {
-                let min_num_pos = min_num_node.get_position(&self.cons)?;
+                let min_num_pos = min_num_node.get_position()?;
                 let min_str = min_num_node.join_child_leaf_values();
 
                 match min_str.parse::<usize>() {
                     Ok(v) => (v, Some(min_num_pos), true),
                     Err(_) => {
-                        self.cons.borrow_mut().append_log(BlockParsingLog::InvalidLoopRange {
-                            pos: min_num_node.get_position(&self.cons)?,
+                        return Err(BlockParsingLog::InvalidLoopRange {
+                            pos: min_num_node.get_position()?,
                             msg: format!("'{}' is too long or not a number", min_str),
-                        }.get_log());
-
-                        return Err(());
+                        }.into());
                     },
                 }
             },
             None => (0usize, None, false),
         };
 
-        let (max_num, max_num_pos, is_max_num_specified) = match range_node.find_child_nodes(vec!["MaxNumGroup"]).get(0) {
+        let (max_num, max_num_pos, is_max_num_specified) = match range_node.find_child_nodes(vec!["MaxNumGroup"]).first() {
             Some(max_node_group) => {
-                match max_node_group.find_child_nodes(vec!["MaxNum"]).get(0) {
+                match max_node_group.find_child_nodes(vec!["MaxNum"]).first() {
                     Some(max_num_node) => {
                         // note: {n,m} の場合 (#MaxNumGroup 内に #MaxNum が存在する)
-                        let max_num_pos = max_num_node.get_position(&self.cons)?;
+                        let max_num_pos = max_num_node.get_position()?;
                         let max_str = max_num_node.join_child_leaf_values();
 
                         match max_str.parse::<usize>() {
                             Ok(v) => (Infinitable::Finite(v), Some(max_num_pos), true),
                             Err(_) => {
-                                self.cons.borrow_mut().append_log(BlockParsingLog::InvalidLoopRange {
+                                return Err(BlockParsingLog::InvalidLoopRange {
                                     pos: max_num_pos,
                                     msg: format!("'{}' is too long or not a number", max_str),
-                                }.get_log());
-
-                                return Err(());
+                                }.into());
                             },
                         }
                     },
@@ -1022,12 +1338,10 @@ impl BlockParser {
             None => {
                 if !is_min_num_specified {
                     // note: 最小, 最大回数どちらも指定されていない場合
-                    self.cons.borrow_mut().append_log(BlockParsingLog::InvalidLoopRange {
+                    return Err(BlockParsingLog::InvalidLoopRange {
                         pos: range_node_pos,
-                        msg: format!("no number specified"),
-                    }.get_log());
-
-                    return Err(());
+                        msg: "no number specified".to_string(),
+                    }.into());
                 }
 
                 (Infinitable::Finite(min_num), None, false)
@@ -1048,7 +1362,7 @@ impl BlockParser {
     }
 
     // note: Rule.PureChoice ノードの解析
-    fn to_rule_choice_elem(&mut self, choice_node: &SyntaxNode, generics_args: &Vec<String>) -> ConsoleResult<RuleGroup> {
+    fn to_rule_choice_elem(&mut self, choice_node: &SyntaxNode, generics_args: &Vec<String>) -> FCPEGResult<RuleGroup> {
         let mut children = Vec::<RuleElement>::new();
         let mut group_kind = RuleGroupKind::Sequence;
 
@@ -1057,7 +1371,7 @@ impl BlockParser {
             match &seq_elem {
                 SyntaxNodeElement::Node(node) => {
                     match &seq_elem.get_ast_reflection_style() {
-                        ASTReflectionStyle::Reflection(name) => if name == ".Rule.Seq" {
+                        ASTReflectionStyle::Reflection(name) if name == ".Rule.Seq" => {
                             let new_child = self.to_seq_elem(node, generics_args)?;
                             children.push(new_child);
                         },
@@ -1082,17 +1396,17 @@ impl BlockParser {
         return Ok(tmp_root_group);
     }
 
-    fn to_rule_expr_elem(&mut self, expr_node: &SyntaxNode, generics_args: &Vec<String>) -> ConsoleResult<RuleExpression> {
-        let expr_child_node = expr_node.get_node_child_at(&self.cons, 0)?;
+    fn to_rule_expr_elem(&mut self, expr_node: &SyntaxNode, generics_args: &Vec<String>) -> FCPEGResult<RuleExpression> {
+        let expr_child_node = expr_node.get_node_child_at(0)?;
         let (pos, kind, value) = match &expr_child_node.ast_reflection_style {
             ASTReflectionStyle::Reflection(name) => {
                 match name.as_str() {
-                    ".Rule.ArgID" => (expr_child_node.get_position(&self.cons)?, RuleExpressionKind::ArgId, expr_child_node.join_child_leaf_values()),
-                    ".Rule.CharClass" => (expr_child_node.get_position(&self.cons)?, RuleExpressionKind::CharClass, format!("[{}]", expr_child_node.join_child_leaf_values())),
+                    ".Rule.ArgID" => (expr_child_node.get_position()?, RuleExpressionKind::ArgId, expr_child_node.join_child_leaf_values()),
+                    ".Rule.CharClass" => (expr_child_node.get_position()?, RuleExpressionKind::CharClass, format!("[{}]", expr_child_node.join_child_leaf_values())),
                     ".Rule.ID" => {
-                        let chain_id_node = expr_child_node.get_node_child_at(&self.cons, 0)?;
-                        let parent_node = chain_id_node.get_node_child_at(&self.cons, 0)?;
-                        let pos = parent_node.get_position(&self.cons)?;
+                        let chain_id_node = expr_child_node.get_node_child_at(0)?;
+                        let parent_node = chain_id_node.get_node_child_at(0)?;
+                        let pos = parent_node.get_position()?;
 
                         let new_generics_args = match expr_child_node.find_first_child_node(vec![".Rule.Generics"]) {
                             Some(generics_node) => {
@@ -1126,7 +1440,22 @@ impl BlockParser {
                             None => Vec::new(),
                         };
 
-                        let id = BlockParser::to_rule_id(&self.cons, &pos, &BlockParser::to_string_vec(&self.cons, chain_id_node)?, &self.block_alias_map, &self.file_alias_name, &self.block_name, &self.replaced_file_alias_names)?;
+                        let id_tokens = BlockParser::to_string_vec(chain_id_node)?;
+                        let id = BlockParser::to_rule_id(&mut self.warnings, &pos, &id_tokens, &self.block_alias_map, &self.file_alias_name, &self.block_name, &self.replaced_file_alias_names)?;
+
+                        // note: ブロック名がエイリアスである場合は解決後のブロック ID を記録する
+                        let id_block_id = match id_tokens.len() {
+                            2 => self.block_alias_map.get(&id_tokens[0]).cloned(),
+                            3 => Some(BlockParser::to_block_id_from_elements(&self.replaced_file_alias_names, &id_tokens[0], &id_tokens[1])),
+                            _ => None,
+                        };
+
+                        let id_symbols = match id_block_id {
+                            Some(v) => vec![(BlockSymbolKind::Block, v), (BlockSymbolKind::Rule, id.clone())],
+                            None => vec![(BlockSymbolKind::Rule, id.clone())],
+                        };
+
+                        self.push_chain_id_symbols(chain_id_node, id_symbols)?;
 
                         if !self.used_rule_ids.contains_key(&id) {
                             self.used_rule_ids.insert(id.clone(), pos.clone());
@@ -1139,27 +1468,23 @@ impl BlockParser {
 
                         (pos, id_expr_kind, id)
                     },
-                    ".Rule.Str" => (expr_child_node.get_position(&self.cons)?, RuleExpressionKind::String, self.to_string_value(expr_child_node)?),
-                    ".Rule.Wildcard" => (expr_child_node.get_position(&self.cons)?, RuleExpressionKind::Wildcard, ".".to_string()),
+                    ".Rule.Str" => (expr_child_node.get_position()?, RuleExpressionKind::String, self.to_string_value(expr_child_node)?),
+                    ".Rule.Wildcard" => (expr_child_node.get_position()?, RuleExpressionKind::Wildcard, ".".to_string()),
                     _ => {
-                        self.cons.borrow_mut().append_log(BlockParsingLog::UnexpectedChildName {
-                            parent_uuid: expr_child_node.uuid.clone(),
+                        return Err(BlockParsingLog::UnexpectedChildName {
+                            parent_uuid: expr_child_node.uuid,
                             unexpected: format!("'{}'", name),
                             expected: "rule expression node".to_string(),
-                        }.get_log());
-
-                        return Err(());
+                        }.into());
                     },
                 }
             },
             _ => {
-                self.cons.borrow_mut().append_log(BlockParsingLog::UnexpectedChildName {
-                    parent_uuid: expr_child_node.uuid.clone(),
+                return Err(BlockParsingLog::UnexpectedChildName {
+                    parent_uuid: expr_child_node.uuid,
                     unexpected: "no name".to_string(),
                     expected: "rule expression node".to_string(),
-                }.get_log());
-
-                return Err(());
+                }.into());
             },
         };
 
@@ -1167,46 +1492,44 @@ impl BlockParser {
         return Ok(expr);
     }
 
-    fn to_string_vec(cons: &Rc<RefCell<Console>>, str_vec_node: &SyntaxNode) -> ConsoleResult<Vec<String>> {
+    fn to_string_vec(str_vec_node: &SyntaxNode) -> FCPEGResult<Vec<String>> {
         let mut str_vec = Vec::<String>::new();
 
         for str_elem in str_vec_node.get_reflectable_children() {
-            str_vec.push(str_elem.get_node(cons)?.join_child_leaf_values());
+            str_vec.push(str_elem.get_node()?.join_child_leaf_values());
         }
 
         return Ok(str_vec);
     }
 
-    fn to_rule_id(cons: &Rc<RefCell<Console>>, pos: &CharacterPosition, id_tokens: &Vec<String>, block_alias_map: &HashMap<String, String>, file_alias_name: &String, block_name: &String, replaced_file_alias_names: &Arc<HashMap<String, String>>) -> ConsoleResult<String> {
+    fn to_rule_id(warnings: &mut Vec<BlockParsingLog>, pos: &CharacterPosition, id_tokens: &[String], block_alias_map: &HashMap<String, String>, file_alias_name: &String, block_name: &String, replaced_file_alias_names: &Arc<HashMap<String, String>>) -> FCPEGResult<String> {
         let (new_id, id_block_name, id_rule_name) = match id_tokens.len() {
             1 => {
-                let id_rule_name = id_tokens.get(0).unwrap();
+                let id_rule_name = id_tokens.first().unwrap();
                 let new_id = BlockParser::to_rule_id_from_elements(replaced_file_alias_names, file_alias_name, block_name, id_rule_name);
 
                 (new_id, block_name, id_rule_name.clone())
             },
             2 => {
-                let block_name = id_tokens.get(0).unwrap().to_string();
+                let block_name = id_tokens.first().unwrap().to_string();
                 let rule_name = id_tokens.get(1).unwrap().to_string();
 
                 if block_alias_map.contains_key(&block_name.to_string()) {
                     // note: ブロック名がエイリアスである場合
                     let block_name = block_alias_map.get(&block_name.to_string()).unwrap();
-                    let new_id = BlockParser::to_block_id_from_elements(&replaced_file_alias_names, &block_name, &rule_name);
+                    let new_id = BlockParser::to_block_id_from_elements(replaced_file_alias_names, block_name, &rule_name);
 
                     (new_id, block_name, rule_name.clone())
                 } else {
                     // note: ブロック名がエイリアスでない場合
-                    cons.borrow_mut().append_log(BlockParsingLog::BlockAliasNotFoundOrUsed {
+                    return Err(BlockParsingLog::BlockAliasNotFoundOrUsed {
                         pos: pos.clone(),
                         block_alias_name: block_name.to_string(),
-                    }.get_log());
-
-                    return Err(());
+                    }.into());
                 }
             },
             3 => {
-                let file_alias_name = id_tokens.get(0).unwrap();
+                let file_alias_name = id_tokens.first().unwrap();
                 let block_name = id_tokens.get(1).unwrap();
                 let rule_name = id_tokens.get(2).unwrap();
                 let new_id = BlockParser::to_rule_id_from_elements(replaced_file_alias_names, file_alias_name, block_name, rule_name);
@@ -1214,12 +1537,10 @@ impl BlockParser {
                 (new_id, block_name, rule_name.to_string())
             },
             _ => {
-                cons.borrow_mut().append_log(BlockParsingLog::InvalidID {
+                return Err(BlockParsingLog::InvalidID {
                     pos: pos.clone(),
                     id: id_tokens.join("."),
-                }.get_log());
-
-                return Err(());
+                }.into());
             },
         };
 
@@ -1227,16 +1548,44 @@ impl BlockParser {
         // todo: プライベートブロックに対応
         // todo: 異なるファイルでの同ブロック名を除外
         if id_rule_name.starts_with("_") && *block_name != *id_block_name {
-            cons.borrow_mut().append_log(BlockParsingLog::AttemptToAccessPrivateItem {
+            warnings.push(BlockParsingLog::AttemptToAccessPrivateItem {
                 pos: pos.clone(),
                 item_id: new_id.clone(),
-            }.get_log());
+            });
         }
 
         return Ok(new_id);
     }
 
-    fn to_string_value(&mut self, str_node: &SyntaxNode) -> ConsoleResult<String> {
+    fn push_symbol(&mut self, kind: BlockSymbolKind, id: String, pos: CharacterPosition, name: &str, is_definition: bool) {
+        self.symbols.push(BlockSymbol {
+            kind: kind,
+            id: id,
+            pos: pos,
+            len: name.chars().count(),
+            is_definition: is_definition,
+        });
+    }
+
+    // spec: ID の各要素の出現箇所を記録する; symbol_ids は ID の末尾の要素から対応させ、対応しない先頭の要素 (ファイルエイリアス名など) は記録しない
+    fn push_chain_id_symbols(&mut self, chain_id_node: &SyntaxNode, symbol_ids: Vec<(BlockSymbolKind, String)>) -> FCPEGResult<()> {
+        let id_elems = chain_id_node.get_reflectable_children();
+
+        if id_elems.len() < symbol_ids.len() {
+            return Ok(());
+        }
+
+        let skipped_len = id_elems.len() - symbol_ids.len();
+
+        for (each_elem, (each_kind, each_id)) in id_elems.iter().skip(skipped_len).zip(symbol_ids) {
+            let each_node = each_elem.get_node()?;
+            self.push_symbol(each_kind, each_id, each_node.get_position()?, &each_node.join_child_leaf_values(), false);
+        }
+
+        return Ok(());
+    }
+
+    fn to_string_value(&mut self, str_node: &SyntaxNode) -> FCPEGResult<String> {
         let mut s = String::new();
 
         for each_elem in &str_node.sub_elems {
@@ -1244,18 +1593,16 @@ impl BlockParser {
                 SyntaxNodeElement::Node(node) => {
                     match node.ast_reflection_style {
                         ASTReflectionStyle::Reflection(_) => {
-                            s += match node.get_leaf_child_at(&self.cons, 0)?.value.as_str() {
+                            s += match node.get_leaf_child_at(0)?.value.as_str() {
                                 "\\" => "\\",
                                 "\"" => "\"",
                                 "n" => "\n",
                                 "t" => "\t",
                                 "z" => "\0",
                                 _ => {
-                                    self.cons.borrow_mut().append_log(BlockParsingLog::UnknownEscapeSequenceCharacter {
-                                        pos: node.get_position(&self.cons)?,
-                                    }.get_log());
-
-                                    return Err(());
+                                    return Err(BlockParsingLog::UnknownEscapeSequenceCharacter {
+                                        pos: node.get_position()?,
+                                    }.into());
                                 },
                             };
                         },
@@ -1274,18 +1621,18 @@ impl BlockParser {
         return Ok(s);
     }
 
-    fn to_chain_id(&mut self, chain_id_node: &SyntaxNode) -> ConsoleResult<String> {
+    fn to_chain_id(&mut self, chain_id_node: &SyntaxNode) -> FCPEGResult<String> {
         let mut ids = Vec::<String>::new();
 
         for chain_id_elem in &chain_id_node.get_reflectable_children() {
-            ids.push(chain_id_elem.get_node(&self.cons)?.join_child_leaf_values());
+            ids.push(chain_id_elem.get_node()?.join_child_leaf_values());
         }
 
         return Ok(ids.join("."));
     }
 
     // ret: 空文字の場合は false
-    fn is_pascal_case(id: &String) -> bool {
+    fn is_pascal_case(id: &str) -> bool {
         let mut id_chars = id.chars();
 
         return match id_chars.next() {
@@ -1350,11 +1697,11 @@ impl FCPEGBlock {
         let fcpeg_rule = rule!{
             ".Syntax.FCPEG",
             group!{
-                vec![],
+                [],
                 expr!(Id, ".Symbol.Space", "*", "#"),
                 expr!(Id, ".Symbol.LineEnd", "*", "#"),
                 group!{
-                    vec!["*"],
+                    ["*"],
                     expr!(Id, ".Block.Block"),
                     expr!(Id, ".Symbol.Div", "*", "#"),
                 },
@@ -1370,7 +1717,7 @@ impl FCPEGBlock {
         let space_rule = rule!{
             ".Symbol.Space",
             group!{
-                vec![],
+                [],
                 expr!(String, " "),
             },
         };
@@ -1379,7 +1726,7 @@ impl FCPEGBlock {
         let line_end_rule = rule!{
             ".Symbol.LineEnd",
             group!{
-                vec![],
+                [],
                 expr!(Id, ".Symbol.Space", "*"),
                 expr!(String, "\n"),
                 expr!(Id, ".Symbol.Space", "*"),
@@ -1390,15 +1737,15 @@ impl FCPEGBlock {
         let div_rule = rule!{
             ".Symbol.Div",
             group!{
-                vec![],
+                [],
                 group!{
-                    vec![":"],
+                    [":"],
                     group!{
-                        vec![],
+                        [],
                         expr!(Id, ".Symbol.Space"),
                     },
                     group!{
-                        vec![],
+                        [],
                         expr!(String, "\n"),
                     },
                 },
@@ -1409,18 +1756,18 @@ impl FCPEGBlock {
         let comma_div_rule = rule!{
             ".Symbol.CommaDiv",
             group!{
-                vec![],
+                [],
                 expr!(Id, ".Symbol.Div", "*"),
                 group!{
-                    vec![":"],
+                    [":"],
                     group!{
-                        vec![],
+                        [],
                         expr!(String, ",,"),
                         expr!(Id, ".Symbol.LineEnd"),
                         expr!(Id, ".Symbol.Div", "*"),
                     },
                     group!{
-                        vec![],
+                        [],
                         expr!(String, ","),
                         expr!(Id, ".Symbol.Space", "*"),
                     },
@@ -1432,7 +1779,7 @@ impl FCPEGBlock {
         let eof_rule = rule!{
             ".Symbol.EOF",
             group!{
-                vec![],
+                [],
                 expr!(String, "\0", "#"),
             },
         };
@@ -1445,7 +1792,7 @@ impl FCPEGBlock {
         let single_id_rule = rule!{
             ".Misc.SingleID",
             group!{
-                vec![],
+                [],
                 expr!(CharClass, "[a-zA-Z_]"),
                 expr!(CharClass, "[a-zA-Z0-9_]", "*"),
             },
@@ -1455,12 +1802,12 @@ impl FCPEGBlock {
         let chain_id_rule = rule!{
             ".Misc.ChainID",
             group!{
-                vec![],
+                [],
                 expr!(Id, ".Misc.SingleID"),
                 group!{
-                    vec!["*", "##"],
+                    ["*", "##"],
                     group!{
-                        vec![],
+                        [],
                         expr!(String, ".", "#"),
                         expr!(Id, ".Misc.SingleID"),
                     },
@@ -1476,7 +1823,7 @@ impl FCPEGBlock {
         let block_rule = rule!{
             ".Block.Block",
             group!{
-                vec![],
+                [],
                 expr!(String, "[", "#"),
                 expr!(Id, ".Symbol.Div", "*", "#"),
                 expr!(Id, ".Misc.SingleID"),
@@ -1486,9 +1833,9 @@ impl FCPEGBlock {
                 expr!(String, "{", "#"),
                 expr!(Id, ".Symbol.Div", "*", "#"),
                 group!{
-                    vec!["*"],
+                    ["*"],
                     group!{
-                        vec![],
+                        [],
                         expr!(Id, ".Block.Cmd"),
                         expr!(Id, ".Symbol.Div", "*", "#"),
                     },
@@ -1501,21 +1848,21 @@ impl FCPEGBlock {
         let cmd_rule = rule!{
             ".Block.Cmd",
             group!{
-                vec![":"],
+                [":"],
                 group!{
-                    vec![],
+                    [],
                     expr!(Id, ".Block.CommentCmd"),
                 },
                 group!{
-                    vec![],
+                    [],
                     expr!(Id, ".Block.DefineCmd"),
                 },
                 group!{
-                    vec![],
+                    [],
                     expr!(Id, ".Block.StartCmd"),
                 },
                 group!{
-                    vec![],
+                    [],
                     expr!(Id, ".Block.UseCmd"),
                 },
             },
@@ -1525,19 +1872,19 @@ impl FCPEGBlock {
         let comment_rule = rule!{
             ".Block.CommentCmd",
             group!{
-                vec![],
+                [],
                 expr!(String, "%", "#"),
                 group!{
-                    vec!["*", "##"],
+                    ["*", "##"],
                     group!{
-                        vec![":"],
+                        [":"],
                         group!{
-                            vec![],
+                            [],
                             expr!(String, ",", "!"),
                             expr!(Wildcard, "."),
                         },
                         group!{
-                            vec![],
+                            [],
                             expr!(String, ",,"),
                         },
                     },
@@ -1550,7 +1897,7 @@ impl FCPEGBlock {
         let define_cmd_rule = rule!{
             ".Block.DefineCmd",
             group!{
-                vec![],
+                [],
                 expr!(Id, ".Misc.SingleID"),
                 expr!(Id, ".Block.DefineCmdGenerics", "?"),
                 expr!(Id, ".Block.DefineCmdTemplate", "?"),
@@ -1567,13 +1914,13 @@ impl FCPEGBlock {
         let define_cmd_generics_rule = rule!{
             ".Block.DefineCmdGenerics",
             group!{
-                vec![],
+                [],
                 expr!(Id, ".Symbol.Div", "*", "#"),
                 expr!(String, "<", "#"),
                 expr!(Id, ".Symbol.Div", "*", "#"),
                 expr!(Id, ".Rule.ArgID"),
                 group!{
-                    vec!["*", "##"],
+                    ["*", "##"],
                     expr!(Id, ".Symbol.Div", "*", "#"),
                     expr!(String, ",", "#"),
                     expr!(Id, ".Symbol.Div", "*", "#"),
@@ -1589,13 +1936,13 @@ impl FCPEGBlock {
         let define_cmd_template_rule = rule!{
             ".Block.DefineCmdTemplate",
             group!{
-                vec![],
+                [],
                 expr!(Id, ".Symbol.Div", "*", "#"),
                 expr!(String, "(", "#"),
                 expr!(Id, ".Symbol.Div", "*", "#"),
                 expr!(Id, ".Rule.ArgID"),
                 group!{
-                    vec!["*", "##"],
+                    ["*", "##"],
                     expr!(Id, ".Symbol.Div", "*", "#"),
                     expr!(String, ",", "#"),
                     expr!(Id, ".Symbol.Div", "*", "#"),
@@ -1611,7 +1958,7 @@ impl FCPEGBlock {
         let start_cmd_rule = rule!{
             ".Block.StartCmd",
             group!{
-                vec![],
+                [],
                 expr!(String, "+", "#"),
                 expr!(Id, ".Symbol.Div", "*", "#"),
                 expr!(String, "start", "#"),
@@ -1626,7 +1973,7 @@ impl FCPEGBlock {
         let use_cmd_rule = rule!{
             ".Block.UseCmd",
             group!{
-                vec![],
+                [],
                 expr!(String, "+", "#"),
                 expr!(Id, ".Symbol.Div", "*", "#"),
                 expr!(String, "use", "#"),
@@ -1642,7 +1989,7 @@ impl FCPEGBlock {
         let use_cmd_block_alias_rule = rule!{
             ".Block.UseCmdBlockAlias",
             group!{
-                vec![],
+                [],
                 expr!(Id, ".Symbol.Div", "+", "#"),
                 expr!(String, "as", "#"),
                 expr!(Id, ".Symbol.Div", "+", "#"),
@@ -1658,22 +2005,22 @@ impl FCPEGBlock {
         let pure_choice_rule = rule!{
             ".Rule.PureChoice",
             group!{
-                vec![],
+                [],
                 expr!(Id, ".Rule.Seq"),
                 group!{
-                    vec!["*", "##"],
+                    ["*", "##"],
                     group!{
-                        vec!["##"],
+                        ["##"],
                         group!{
-                            vec![":"],
+                            [":"],
                             group!{
-                                vec!["##"],
+                                ["##"],
                                 expr!(Id, ".Symbol.Div", "+", "#"),
                                 expr!(String, ":"),
                                 expr!(Id, ".Symbol.Div", "+", "#"),
                             },
                             group!{
-                                vec!["##"],
+                                ["##"],
                                 expr!(String, ","),
                                 expr!(Id, ".Symbol.Space", "#"),
                             },
@@ -1688,7 +2035,7 @@ impl FCPEGBlock {
         let choice_rule = rule!{
             ".Rule.Choice",
             group!{
-                vec![],
+                [],
                 expr!(String, "(", "#"),
                 expr!(Id, ".Rule.PureChoice"),
                 expr!(String, ")", "#"),
@@ -1699,14 +2046,14 @@ impl FCPEGBlock {
         let seq_rule = rule!{
             ".Rule.Seq",
             group!{
-                vec![],
+                [],
                 expr!(Id, ".Rule.SeqElem"),
                 group!{
-                    vec!["*", "##"],
+                    ["*", "##"],
                     group!{
-                        vec![],
+                        [],
                         group!{
-                            vec![],
+                            [],
                             expr!(Id, ".Symbol.Div", "+", "#"),
                             expr!(Id, ".Rule.SeqElem"),
                         },
@@ -1715,29 +2062,39 @@ impl FCPEGBlock {
             },
         };
 
-        // code: SeqElem <- Lookahead? (Choice : Expr) Loop? RandomOrder? ASTReflectionStyle?,
+        // code: SeqElem <- Cut : Lookahead? (Choice : Expr) Loop? RandomOrder? ASTReflectionStyle?,
         let seq_elem_rule = rule!{
             ".Rule.SeqElem",
             group!{
-                vec![],
-                expr!(Id, ".Rule.Lookahead", "?"),
+                [],
                 group!{
-                    vec!["##"],
+                    [":"],
                     group!{
-                        vec![":"],
-                        group!{
-                            vec![],
-                            expr!(Id, ".Rule.Choice"),
-                        },
+                        [],
+                        expr!(Id, ".Rule.Cut"),
+                    },
+                    group!{
+                        [],
+                        expr!(Id, ".Rule.Lookahead", "?"),
                         group!{
-                            vec![],
-                            expr!(Id, ".Rule.Expr"),
+                            ["##"],
+                            group!{
+                                [":"],
+                                group!{
+                                    [],
+                                    expr!(Id, ".Rule.Choice"),
+                                },
+                                group!{
+                                    [],
+                                    expr!(Id, ".Rule.Expr"),
+                                },
+                            },
                         },
+                        expr!(Id, ".Rule.Loop", "?"),
+                        expr!(Id, ".Rule.RandomOrder", "?"),
+                        expr!(Id, ".Rule.ASTReflectionStyle", "?"),
                     },
                 },
-                expr!(Id, ".Rule.Loop", "?"),
-                expr!(Id, ".Rule.RandomOrder", "?"),
-                expr!(Id, ".Rule.ASTReflectionStyle", "?"),
             },
         };
 
@@ -1745,46 +2102,55 @@ impl FCPEGBlock {
         let expr_rule = rule!{
             ".Rule.Expr",
             group!{
-                vec![],
+                [],
                 group!{
-                    vec![":"],
+                    [":"],
                     group!{
-                        vec![],
+                        [],
                         expr!(Id, ".Rule.ArgID"),
                     },
                     group!{
-                        vec![],
+                        [],
                         expr!(Id, ".Rule.ID"),
                     },
                     group!{
-                        vec![],
+                        [],
                         expr!(Id, ".Rule.Str"),
                     },
                     group!{
-                        vec![],
+                        [],
                         expr!(Id, ".Rule.CharClass"),
                     },
                     group!{
-                        vec![],
+                        [],
                         expr!(Id, ".Rule.Wildcard"),
                     },
                 },
             },
         };
 
+        // code: Cut <- "~",
+        let cut_rule = rule!{
+            ".Rule.Cut",
+            group!{
+                [],
+                expr!(String, "~"),
+            },
+        };
+
         // code: Lookahead <- "!" : "&",
         let lookahead_rule = rule!{
             ".Rule.Lookahead",
             group!{
-                vec![],
+                [],
                 group!{
-                    vec![":"],
+                    [":"],
                     group!{
-                        vec![],
+                        [],
                         expr!(String, "!"),
                     },
                     group!{
-                        vec![],
+                        [],
                         expr!(String, "&"),
                     },
                 },
@@ -1795,23 +2161,23 @@ impl FCPEGBlock {
         let loop_rule = rule!{
             ".Rule.Loop",
             group!{
-                vec![],
+                [],
                 group!{
-                    vec![":"],
+                    [":"],
                     group!{
-                        vec![],
+                        [],
                         expr!(String, "?"),
                     },
                     group!{
-                        vec![],
+                        [],
                         expr!(String, "*"),
                     },
                     group!{
-                        vec![],
+                        [],
                         expr!(String, "+"),
                     },
                     group!{
-                        vec![],
+                        [],
                         expr!(Id, ".Rule.LoopRange"),
                     },
                 },
@@ -1822,12 +2188,12 @@ impl FCPEGBlock {
         let loop_range_rule = rule!{
             ".Rule.LoopRange",
             group!{
-                vec![],
+                [],
                 expr!(String, "{", "#"),
                 expr!(Id, ".Symbol.Div", "*", "#"),
                 expr!(Id, ".Rule.Num", "?", "#MinNum"),
                 group!{
-                    vec!["?", "#MaxNumGroup"],
+                    ["?", "#MaxNumGroup"],
                     expr!(Id, ".Symbol.CommaDiv", "#"),
                     expr!(Id, ".Rule.Num", "?", "#MaxNum"),
                 },
@@ -1840,7 +2206,7 @@ impl FCPEGBlock {
         let random_order_rule = rule!{
             ".Rule.RandomOrder",
             group!{
-                vec![],
+                [],
                 expr!(String, "^", "#"),
                 expr!(Id, ".Rule.RandomOrderRange", "?"),
             },
@@ -1850,12 +2216,12 @@ impl FCPEGBlock {
         let random_order_range_rule = rule!{
             ".Rule.RandomOrderRange",
             group!{
-                vec![],
+                [],
                 expr!(String, "[", "#"),
                 expr!(Id, ".Symbol.Div", "*", "#"),
                 expr!(Id, ".Rule.Num", "?", "#MinNum"),
                 group!{
-                    vec!["?", "#MaxNumGroup"],
+                    ["?", "#MaxNumGroup"],
                     expr!(Id, ".Symbol.Div", "*", "#"),
                     expr!(String, "-", "#"),
                     expr!(Id, ".Symbol.Div", "*", "#"),
@@ -1870,15 +2236,15 @@ impl FCPEGBlock {
         let ast_reflection_rule = rule!{
             ".Rule.ASTReflectionStyle",
             group!{
-                vec![],
+                [],
                 group!{
-                    vec![":"],
+                    [":"],
                     group!{
-                        vec![],
+                        [],
                         expr!(String, "##"),
                     },
                     group!{
-                        vec![],
+                        [],
                         expr!(String, "#", "#"),
                         expr!(Id, ".Misc.SingleID", "?", "##"),
                     },
@@ -1890,8 +2256,8 @@ impl FCPEGBlock {
         let num_rule = rule!{
             ".Rule.Num",
             group!{
-                vec![],
-                expr!(CharClass, "[0-9]+", "+"),
+                [],
+                expr!(CharClass, "[0-9]", "+"),
             },
         };
 
@@ -1899,7 +2265,7 @@ impl FCPEGBlock {
         let id_rule = rule!{
             ".Rule.ID",
             group!{
-                vec![],
+                [],
                 expr!(Id, ".Misc.ChainID"),
                 expr!(Id, ".Rule.Generics", "?"),
                 expr!(Id, ".Rule.Template", "?"),
@@ -1910,7 +2276,7 @@ impl FCPEGBlock {
         let arg_id_rule = rule!{
             ".Rule.ArgID",
             group!{
-                vec![],
+                [],
                 expr!(String, "$", "#"),
                 expr!(Id, ".Misc.SingleID", "##"),
             },
@@ -1920,14 +2286,14 @@ impl FCPEGBlock {
         let generics_rule = rule!{
             ".Rule.Generics",
             group!{
-                vec![],
+                [],
                 expr!(String, "<", "#"),
                 expr!(Id, ".Symbol.Div", "*", "#"),
                 expr!(Id, ".Rule.Seq"),
                 group!{
-                    vec!["*", "##"],
+                    ["*", "##"],
                     group!{
-                        vec!["##"],
+                        ["##"],
                         expr!(Id, ".Symbol.Div", "*", "#"),
                         expr!(String, ",", "#"),
                         expr!(Id, ".Symbol.Div", "*", "#"),
@@ -1943,14 +2309,14 @@ impl FCPEGBlock {
         let template_rule = rule!{
             ".Rule.Template",
             group!{
-                vec![],
+                [],
                 expr!(String, "(", "#"),
                 expr!(Id, ".Symbol.Div", "*", "#"),
                 expr!(Id, ".Rule.Seq"),
                 group!{
-                    vec!["*", "##"],
+                    ["*", "##"],
                     group!{
-                        vec!["##"],
+                        ["##"],
                         expr!(Id, ".Symbol.Div", "*", "#"),
                         expr!(String, ",", "#"),
                         expr!(Id, ".Symbol.Div", "*", "#"),
@@ -1966,30 +2332,30 @@ impl FCPEGBlock {
         let esc_seq_rule = rule!{
             ".Rule.EscSeq",
             group!{
-                vec![],
+                [],
                 expr!(String, "\\", "#"),
                 group!{
-                    vec!["##"],
+                    ["##"],
                     group!{
-                        vec![":"],
+                        [":"],
                         group!{
-                            vec![],
+                            [],
                             expr!(String, "\\"),
                         },
                         group!{
-                            vec![],
+                            [],
                             expr!(String, "\""),
                         },
                         group!{
-                            vec![],
+                            [],
                             expr!(String, "n"),
                         },
                         group!{
-                            vec![],
+                            [],
                             expr!(String, "t"),
                         },
                         group!{
-                            vec![],
+                            [],
                             expr!(String, "z"),
                         },
                     },
@@ -2001,28 +2367,28 @@ impl FCPEGBlock {
         let str_rule = rule!{
             ".Rule.Str",
             group!{
-                vec![],
+                [],
                 expr!(String, "\"", "#"),
                 group!{
-                    vec!["*", "##"],
+                    ["*", "##"],
                     group!{
-                        vec![":"],
+                        [":"],
                         group!{
-                            vec![],
+                            [],
                             expr!(Id, ".Rule.EscSeq"),
                         },
                         group!{
-                            vec![],
+                            [],
                             group!{
-                                vec!["!"],
+                                ["!"],
                                 group!{
-                                    vec![":"],
+                                    [":"],
                                     group!{
-                                        vec![],
+                                        [],
                                         expr!(String, "\\"),
                                     },
                                     group!{
-                                        vec![],
+                                        [],
                                         expr!(String, "\""),
                                     },
                                 },
@@ -2039,31 +2405,31 @@ impl FCPEGBlock {
         let char_class_rule = rule!{
             ".Rule.CharClass",
             group!{
-                vec![],
+                [],
                 expr!(String, "[", "#"),
                 group!{
-                    vec!["+", "##"],
+                    ["+", "##"],
                     expr!(String, "[", "!"),
                     expr!(String, "]", "!"),
                     expr!(Id, ".Symbol.LineEnd", "!"),
                     group!{
-                        vec!["##"],
+                        ["##"],
                         group!{
-                            vec![":"],
+                            [":"],
                             group!{
-                                vec![],
+                                [],
                                 expr!(String, "\\["),
                             },
                             group!{
-                                vec![],
+                                [],
                                 expr!(String, "\\]"),
                             },
                             group!{
-                                vec![],
+                                [],
                                 expr!(String, "\\\\"),
                             },
                             group!{
-                                vec![],
+                                [],
                                 expr!(Wildcard, "."),
                             },
                         },
@@ -2077,11 +2443,150 @@ impl FCPEGBlock {
         let wildcard_rule = rule!{
             ".Rule.Wildcard",
             group!{
-                vec![],
+                [],
                 expr!(String, "."),
             },
         };
 
-        return block!(".Rule", vec![pure_choice_rule, choice_rule, seq_rule, seq_elem_rule, expr_rule, lookahead_rule, loop_rule, loop_range_rule, random_order_rule, random_order_range_rule, ast_reflection_rule, num_rule, id_rule, arg_id_rule, generics_rule, template_rule, esc_seq_rule, str_rule, char_class_rule, wildcard_rule]);
+        return block!(".Rule", vec![pure_choice_rule, choice_rule, seq_rule, seq_elem_rule, expr_rule, cut_rule, lookahead_rule, loop_rule, loop_range_rule, random_order_rule, random_order_range_rule, ast_reflection_rule, num_rule, id_rule, arg_id_rule, generics_rule, template_rule, esc_seq_rule, str_rule, char_class_rule, wildcard_rule]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::block::*;
+
+    // ret: (規則 ID, 循環, 行, 列) の一覧; 行及び列は 0 始まり
+    fn get_left_recursions(fcpeg_source: &str) -> Vec<(String, Vec<String>, usize, usize)> {
+        return load_test_parser(fcpeg_source).get_warnings().iter().filter_map(|each_log| match each_log {
+            BlockParsingLog::LeftRecursiveRule { pos, rule_id, cycle } => Some((rule_id.clone(), cycle.clone(), pos.line, pos.column)),
+            _ => None,
+        }).collect();
+    }
+
+    // ret: 読み込みに失敗したエラー
+    fn load_err(fcpeg_source: &str) -> FCPEGError {
+        return load_err_with_config(fcpeg_source, "");
+    }
+
+    fn load_err_with_config(fcpeg_source: &str, config_source: &str) -> FCPEGError {
+        return match FCPEGParser::load_from_str("test.fcpeg".to_string(), fcpeg_source.to_string(), config_source.to_string(), HashMap::new(), &MemorySourceResolver::new(), true) {
+            Ok(_) => panic!("unexpected success"),
+            Err(e) => e,
+        };
+    }
+
+    // ret: (リント名, 対象の ID, 重大度, 行, 列) の一覧; 行及び列は 0 始まり
+    fn get_lints(fcpeg_source: &str, config_source: &str) -> Vec<(&'static str, String, LintSeverity, usize, usize)> {
+        let parser = FCPEGParser::load_from_str("test.fcpeg".to_string(), fcpeg_source.to_string(), config_source.to_string(), HashMap::new(), &MemorySourceResolver::new(), true).unwrap();
+        return parser.get_warnings().iter().filter_map(to_lint).collect();
+    }
+
+    fn to_lint(log: &BlockParsingLog) -> Option<(&'static str, String, LintSeverity, usize, usize)> {
+        return match log {
+            BlockParsingLog::DuplicateAlternative { severity, pos, rule_id, prev_pos: _ } => Some(("DuplicateAlternative", rule_id.clone(), *severity, pos.line, pos.column)),
+            BlockParsingLog::NullableRepetition { severity, pos, rule_id } => Some(("NullableRepetition", rule_id.clone(), *severity, pos.line, pos.column)),
+            BlockParsingLog::ShadowedAlternative { severity, pos, rule_id, prev_pos: _ } => Some(("ShadowedAlternative", rule_id.clone(), *severity, pos.line, pos.column)),
+            BlockParsingLog::UnreachableRule { severity, pos, rule_id } => Some(("UnreachableRule", rule_id.clone(), *severity, pos.line, pos.column)),
+            BlockParsingLog::UnusedBlock { severity, pos, block_id } => Some(("UnusedBlock", block_id.clone(), *severity, pos.line, pos.column)),
+            _ => None,
+        };
+    }
+
+    #[test]
+    fn direct_left_recursion_is_reported() {
+        let left_recursions = get_left_recursions("[Main]{\n    Main <- Sum \"\\z\",\n    Sum <- Sum \"+\" Num : Num,\n    Num <- [0-9]+,\n}\n");
+        assert_eq!(left_recursions, vec![(".Main.Sum".to_string(), vec![".Main.Sum".to_string(), ".Main.Sum".to_string()], 2, 4)]);
+    }
+
+    #[test]
+    fn indirect_left_recursion_is_reported_once() {
+        let left_recursions = get_left_recursions("[Main]{\n    Main <- A \"\\z\",\n    A <- B \"a\" : \"a\",\n    B <- \"x\"? A \"b\",\n}\n");
+        assert_eq!(left_recursions, vec![(".Main.A".to_string(), vec![".Main.A".to_string(), ".Main.B".to_string(), ".Main.A".to_string()], 2, 4)]);
+    }
+
+    // note: 生成引数を介した呼び出しは呼び出し元からの直接の呼び出しとして循環に現れる
+    #[test]
+    fn left_recursion_through_generics_argument_is_reported() {
+        let left_recursions = get_left_recursions("[Main]{\n    Main <- Expr \"\\z\",\n    Expr <- List<Expr> : \"x\",\n    List<$T> <- Head<$T> (\",\" $T)*,\n    Head<$U> <- $U,\n}\n");
+        assert_eq!(left_recursions, vec![(".Main.Expr".to_string(), vec![".Main.Expr".to_string(), ".Main.Expr".to_string()], 2, 4)]);
+    }
+
+    #[test]
+    fn generics_argument_after_input_is_not_left_call() {
+        let left_recursions = get_left_recursions("[Main]{\n    Main <- Expr \"\\z\",\n    Expr <- List<Expr> : \"x\",\n    List<$T> <- \"(\" $T \")\",\n}\n");
+        assert!(left_recursions.is_empty(), "{:?}", left_recursions);
+    }
+
+    #[test]
+    fn nullable_repetition_is_reported_at_repeated_element() {
+        match load_err("[Main]{\n    Main <- Item* \"\\z\",\n    Item <- \"a\" Opt+,\n    Opt <- \"b\"?,\n}\n") {
+            FCPEGError::BlockParsing(log) => match *log {
+                BlockParsingLog::NullableRepetition { severity, pos, rule_id } => {
+                    assert_eq!(severity, LintSeverity::Error);
+                    assert_eq!(rule_id, ".Main.Item");
+                    assert_eq!((pos.line, pos.column), (2, 16));
+                },
+                v => panic!("unexpected log {:?}", v),
+            },
+            e => panic!("unexpected error {:?}", e),
+        }
+    }
+
+    #[test]
+    fn nullable_repetition_of_empty_loop_is_reported() {
+        match load_err("[Main]{\n    Main <- (\"a\"?)* \"\\z\",\n}\n") {
+            FCPEGError::BlockParsing(log) => assert_eq!(to_lint(&log), Some(("NullableRepetition", ".Main.Main".to_string(), LintSeverity::Error, 1, 13))),
+            e => panic!("unexpected error {:?}", e),
+        }
+    }
+
+    #[test]
+    fn unreachable_rule_is_reported() {
+        let lints = get_lints("[Main]{\n    Main <- \"a\" \"\\z\",\n    Unused <- \"b\",\n}\n", "");
+        assert_eq!(lints, vec![("UnreachableRule", ".Main.Unused".to_string(), LintSeverity::Warning, 2, 4)]);
+    }
+
+    // note: 未使用のブロック内の規則は到達不能な規則として報告しない
+    #[test]
+    fn unused_block_is_reported_instead_of_its_rules() {
+        let lints = get_lints("[Main]{\n    Main <- \"a\" \"\\z\",\n}\n\n[Sub]{\n    Item <- \"b\",\n}\n", "");
+        assert_eq!(lints, vec![("UnusedBlock", ".Sub".to_string(), LintSeverity::Warning, 4, 1)]);
+    }
+
+    #[test]
+    fn shadowed_alternative_is_reported() {
+        let lints = get_lints("[Main]{\n    Main <- Item \"\\z\",\n    Item <- \"a\" : \"ab\",\n}\n", "");
+        assert_eq!(lints, vec![("ShadowedAlternative", ".Main.Item".to_string(), LintSeverity::Warning, 2, 18)]);
+    }
+
+    #[test]
+    fn duplicate_alternative_is_reported() {
+        let lints = get_lints("[Main]{\n    Main <- Item \"\\z\",\n    Item <- \"a\" \"b\" : \"c\" : \"a\" \"b\",\n}\n", "");
+        assert_eq!(lints, vec![("DuplicateAlternative", ".Main.Item".to_string(), LintSeverity::Warning, 2, 28)]);
+    }
+
+    #[test]
+    fn lint_severity_is_overridden_by_config() {
+        let fcpeg_source = "[Main]{\n    Main <- Item \"\\z\",\n    Item <- \"a\" : \"ab\" : \"a\",\n    Unused <- \"b\",\n}\n";
+        let lints = get_lints(fcpeg_source, "Lint:\n|| ShadowedAlternative: note,\n|| DuplicateAlternative: off,\n");
+        assert_eq!(lints, vec![
+            ("ShadowedAlternative", ".Main.Item".to_string(), LintSeverity::Note, 2, 18),
+            ("UnreachableRule", ".Main.Unused".to_string(), LintSeverity::Warning, 3, 4),
+        ]);
+    }
+
+    #[test]
+    fn lint_warning_overridden_to_error_fails_to_load() {
+        match load_err_with_config("[Main]{\n    Main <- \"a\" \"\\z\",\n    Unused <- \"b\",\n}\n", "Lint:\n|| UnreachableRule: error,\n") {
+            FCPEGError::BlockParsing(log) => assert_eq!(to_lint(&log), Some(("UnreachableRule", ".Main.Unused".to_string(), LintSeverity::Error, 2, 4))),
+            e => panic!("unexpected error {:?}", e),
+        }
+    }
+
+    #[test]
+    fn nullable_repetition_turned_off_is_not_reported() {
+        let lints = get_lints("[Main]{\n    Main <- (\"a\"?)* \"\\z\",\n}\n", "Lint:\n|| NullableRepetition: off,\n");
+        assert!(lints.is_empty(), "{:?}", lints);
     }
 }
@@ -1,11 +1,10 @@
-use std::cell::RefCell;
 use std::collections::HashMap;
 use std::fmt::*;
-use std::rc::Rc;
 use std::sync::Arc;
 
 use crate::*;
 use crate::block::*;
+use crate::file::*;
 use crate::parser::*;
 use crate::rule::*;
 
@@ -13,6 +12,7 @@ use rustnutlib::*;
 use rustnutlib::console::*;
 use rustnutlib::file::*;
 
+#[derive(Clone, Debug)]
 pub enum ConfigurationLog {
     DuplicateFileAliasName { alias_name: String },
     DuplicatePropertyName { prop_name: String },
@@ -22,8 +22,10 @@ pub enum ConfigurationLog {
     InvalidSyntax { line: usize, msg: String },
     UnknownASTReflectionValue { id: String, value: String },
     UnknownEscapeCharacter { esc_char: String },
+    UnknownMemoizationRule { rule_id: String },
     UnknownPropertyName { prop_name: String },
     UnknownRegexMode { input: String },
+    UnknownSyncPointRule { rule_id: String },
 }
 
 impl ConsoleLogger for ConfigurationLog {
@@ -37,8 +39,10 @@ impl ConsoleLogger for ConfigurationLog {
             ConfigurationLog::InvalidSyntax { line, msg } => log!(Error, "invalid syntax", format!("{}", msg), format!("line:\t{}", line)),
             ConfigurationLog::UnknownASTReflectionValue { id, value } => log!(Error, "unknown AST reflection value", format!("id:\t{}", id), format!("value:\t{}", value.replace("\n", "\\n"))),
             ConfigurationLog::UnknownEscapeCharacter { esc_char } => log!(Error, "unknown escape character", format!("escape character:\t{}", esc_char)),
+            ConfigurationLog::UnknownMemoizationRule { rule_id } => log!(Error, format!("unknown memoization rule '{}'", rule_id)),
             ConfigurationLog::UnknownPropertyName { prop_name } => log!(Error, format!("unknown property name '{}'", prop_name)),
             ConfigurationLog::UnknownRegexMode { input } => log!(Error, format!("unknown regex mode '{}'", input)),
+            ConfigurationLog::UnknownSyncPointRule { rule_id } => log!(Error, format!("unknown sync point rule '{}'", rule_id)),
         };
     }
 }
@@ -58,17 +62,15 @@ impl PropertyItem {
         };
     }
 
-    pub fn add_values(&mut self, cons: &Rc<RefCell<Console>>, key_stack: Vec<String>, key_stack_offset: usize, key: String, values: Vec<String>) -> ConsoleResult<()> {
+    pub fn add_values(&mut self, key_stack: Vec<String>, key_stack_offset: usize, key: String, values: Vec<String>) -> FCPEGResult<()> {
         if key_stack_offset >= key_stack.len() {
             if self.children.contains_key(&key) {
-                cons.borrow_mut().append_log(ConfigurationLog::DuplicatePropertyName {
+                return Err(ConfigurationLog::DuplicatePropertyName {
                     prop_name: {
-                        let id_div = if key_stack.len() == 0 { "" } else { "." };
+                        let id_div = if key_stack.is_empty() { "" } else { "." };
                         format!("{}{}{}", key_stack.join("."), id_div, key)
                     },
-                }.get_log());
-
-                return Err(());
+                }.into());
             }
 
             let new_map = PropertyItem::new(values);
@@ -81,18 +83,18 @@ impl PropertyItem {
                 self.children.insert(child_key.clone(), new_child);
             };
 
-            self.children.get_mut(child_key).unwrap().add_values(cons, key_stack, key_stack_offset + 1, key, values)?;
+            self.children.get_mut(child_key).unwrap().add_values(key_stack, key_stack_offset + 1, key, values)?;
         }
 
         return Ok(());
     }
 
     pub fn print(&self, hierarchy_count: usize, key: String) {
-        if self.values.len() != 0 {
+        if !self.values.is_empty() {
             println!("{}{}: {},", "||".repeat(hierarchy_count), key, self.values.join(", ").replace("\n", "\\n"));
         }
 
-        if self.children.len() != 0 {
+        if !self.children.is_empty() {
             println!("{}{}:", "||".repeat(hierarchy_count), key);
 
             for (each_key, each_prop_item) in &*self.children {
@@ -135,6 +137,9 @@ impl Display for RegexMode {
 pub enum ConfigurationItemKind {
     ASTReflection,
     FileAliases,
+    Lint,
+    Memoization,
+    Recovery,
     Regex,
 }
 
@@ -143,6 +148,9 @@ impl ConfigurationItemKind {
         let kind = match v {
             "ASTReflection" => ConfigurationItemKind::ASTReflection,
             "FileAliases" => ConfigurationItemKind::FileAliases,
+            "Lint" => ConfigurationItemKind::Lint,
+            "Memoization" => ConfigurationItemKind::Memoization,
+            "Recovery" => ConfigurationItemKind::Recovery,
             "Regex" => ConfigurationItemKind::Regex,
             _ => return None,
         };
@@ -155,46 +163,64 @@ pub struct Configuration {
     pub file_alias_map: HashMap<String, String>,
     pub regex_mode: RegexMode,
     pub reverse_ast_reflection_style: bool,
+    // spec: エラー回復時に読み飛ばす終端の文字列
+    // note: <(block_name, rule_name), sync_strings>
+    pub sync_point_map: HashMap<(String, String), Vec<String>>,
+    // spec: 規則毎の指定がない場合にメモ化を行うかどうか
+    pub memoize_by_default: bool,
+    // spec: メモ化する結果の最大件数
+    pub memoization_limit: Option<usize>,
+    // note: <(block_name, rule_name), is_memoized>
+    pub memoization_rule_map: HashMap<(String, String), bool>,
+    // spec: リント毎の重大度; 指定がなければ既定の重大度を用いる
+    pub lint_severity_map: HashMap<LintKind, LintSeverity>,
 }
 
 impl Configuration {
-    pub fn load(cons: Rc<RefCell<Console>>, file_path: &String) -> ConsoleResult<Configuration> {
+    pub fn load(file_path: &String) -> FCPEGResult<Configuration> {
         let file_content = match FileMan::read_all(file_path) {
-            Ok(v) => Box::new(v),
-            Err(e) => {
-                cons.borrow_mut().append_log(e.get_log());
-                return Err(());
+            Ok(v) => v,
+            Err(_) => {
+                return Err(FileLog::FailedToReadSource {
+                    file_path: file_path.clone(),
+                }.into());
             },
         };
 
+        return Configuration::load_from_str(file_path, file_content);
+    }
+
+    // note: file_path はログ上の位置表示にのみ用いる
+    pub fn load_from_str(file_path: &str, file_content: String) -> FCPEGResult<Configuration> {
         let mut file_alias_map = HashMap::<String, String>::new();
         let mut reverse_ast_reflection_style = false;
         let mut regex_mode = RegexMode::get_default_mode();
+        let mut sync_point_map = HashMap::<(String, String), Vec<String>>::new();
+        let mut memoize_by_default = true;
+        let mut memoization_limit = Option::<usize>::None;
+        let mut memoization_rule_map = HashMap::<(String, String), bool>::new();
+        let mut lint_severity_map = HashMap::<LintKind, LintSeverity>::new();
 
-        let prop_map = ConfigurationParser::parse(cons.clone(), file_path.clone(), file_content)?;
+        let prop_map = ConfigurationParser::parse(file_path.to_string(), file_content)?;
 
         for (top_item_name, top_item) in &*prop_map {
             let top_item_kind = match ConfigurationItemKind::from(top_item_name) {
                 Some(v) => v,
                 None => {
-                    cons.borrow_mut().append_log(ConfigurationLog::UnknownPropertyName {
+                    return Err(ConfigurationLog::UnknownPropertyName {
                         prop_name: top_item_name.clone(),
-                    }.get_log());
-
-                    return Err(());
+                    }.into());
                 },
             };
 
             match top_item_kind {
                 ConfigurationItemKind::ASTReflection => {
-                    let style = match top_item.values.get(0) {
+                    let style = match top_item.values.first() {
                         Some(v) => v,
                         None => {
-                            cons.borrow_mut().append_log(ConfigurationLog::InvalidPropertyValueLength {
+                            return Err(ConfigurationLog::InvalidPropertyValueLength {
                                 prop_name: top_item_name.to_string(),
-                            }.get_log());
-
-                            return Err(());
+                            }.into());
                         },
                     };
 
@@ -202,51 +228,136 @@ impl Configuration {
                         "normal" => false,
                         "reversed" => true,
                         _ => {
-                            cons.borrow_mut().append_log(ConfigurationLog::UnknownASTReflectionValue {
+                            return Err(ConfigurationLog::UnknownASTReflectionValue {
                                 id: top_item_name.clone(),
                                 value: style.clone(),
-                            }.get_log());
-
-                            return Err(());
+                            }.into());
                         },
                     };
                 },
                 ConfigurationItemKind::FileAliases => {
                     for (alias_name, alias_path_item) in &*top_item.children {
-                        let alias_path = match alias_path_item.values.get(0) {
+                        let alias_path = match alias_path_item.values.first() {
                             Some(v) => v,
                             None => {
-                                cons.borrow_mut().append_log(ConfigurationLog::InvalidPropertyValueLength {
+                                return Err(ConfigurationLog::InvalidPropertyValueLength {
                                     prop_name: alias_name.clone()
-                                }.get_log());
-
-                                return Err(());
+                                }.into());
                             },
                         };
 
                         file_alias_map.insert(alias_name.clone(), alias_path.clone());
                     }
                 },
+                ConfigurationItemKind::Lint => {
+                    for (lint_name, lint_item) in &*top_item.children {
+                        let full_prop_name = format!("{}.{}", top_item_name, lint_name);
+
+                        let lint_kind = match LintKind::from(lint_name) {
+                            Some(v) => v,
+                            None => {
+                                return Err(ConfigurationLog::UnknownPropertyName {
+                                    prop_name: full_prop_name,
+                                }.into());
+                            },
+                        };
+
+                        let severity_str = match lint_item.values.first() {
+                            Some(v) => v,
+                            None => {
+                                return Err(ConfigurationLog::InvalidPropertyValueLength {
+                                    prop_name: full_prop_name,
+                                }.into());
+                            },
+                        };
+
+                        let severity = match LintSeverity::from(severity_str) {
+                            Some(v) => v,
+                            None => {
+                                return Err(ConfigurationLog::InvalidPropertyValue {
+                                    prop_name: full_prop_name,
+                                    prop_value: severity_str.clone(),
+                                }.into());
+                            },
+                        };
+
+                        lint_severity_map.insert(lint_kind, severity);
+                    }
+                },
+                ConfigurationItemKind::Memoization => {
+                    for (prop_name, prop_item) in &*top_item.children {
+                        let full_prop_name = format!("{}.{}", top_item_name, prop_name);
+
+                        match prop_name.as_str() {
+                            "Default" => memoize_by_default = Configuration::to_switch_value(&full_prop_name, prop_item)?,
+                            "Limit" => {
+                                let limit_str = match prop_item.values.first() {
+                                    Some(v) => v,
+                                    None => {
+                                        return Err(ConfigurationLog::InvalidPropertyValueLength {
+                                            prop_name: full_prop_name,
+                                        }.into());
+                                    },
+                                };
+
+                                memoization_limit = match limit_str.parse::<usize>() {
+                                    Ok(v) => Some(v),
+                                    Err(_) => {
+                                        return Err(ConfigurationLog::InvalidPropertyValue {
+                                            prop_name: full_prop_name,
+                                            prop_value: limit_str.clone(),
+                                        }.into());
+                                    },
+                                };
+                            },
+                            "Rules" => {
+                                for (block_name, block_item) in &*prop_item.children {
+                                    for (rule_name, rule_item) in &*block_item.children {
+                                        let rule_prop_name = format!("{}.{}.{}", full_prop_name, block_name, rule_name);
+                                        let is_memoized = Configuration::to_switch_value(&rule_prop_name, rule_item)?;
+                                        memoization_rule_map.insert((block_name.clone(), rule_name.clone()), is_memoized);
+                                    }
+                                }
+                            },
+                            _ => {
+                                return Err(ConfigurationLog::UnknownPropertyName {
+                                    prop_name: full_prop_name,
+                                }.into());
+                            },
+                        }
+                    }
+                },
+                ConfigurationItemKind::Recovery => {
+                    for (block_name, block_item) in &*top_item.children {
+                        for (rule_name, rule_item) in &*block_item.children {
+                            let sync_strings = rule_item.values.iter().filter(|each_value| !each_value.is_empty()).cloned().collect::<Vec<String>>();
+
+                            if sync_strings.is_empty() {
+                                return Err(ConfigurationLog::InvalidPropertyValueLength {
+                                    prop_name: format!("{}.{}.{}", top_item_name, block_name, rule_name),
+                                }.into());
+                            }
+
+                            sync_point_map.insert((block_name.clone(), rule_name.clone()), sync_strings);
+                        }
+                    }
+                },
                 ConfigurationItemKind::Regex => {
-                    let regex_mode_str = match top_item.values.get(0) {
+                    let regex_mode_str = match top_item.values.first() {
                         Some(v) => v,
                         None => {
-                            cons.borrow_mut().append_log(ConfigurationLog::InvalidPropertyValueLength {
+                            return Err(ConfigurationLog::InvalidPropertyValueLength {
                                 prop_name: top_item_name.clone(),
-                            }.get_log());
-
-                            return Err(());
+                            }.into());
                         },
                     };
 
                     regex_mode = match RegexMode::from(regex_mode_str) {
                         Some(v) => v,
                         None => {
-                            cons.borrow_mut().append_log(ConfigurationLog::UnknownRegexMode {
+                            return Err(ConfigurationLog::UnknownRegexMode {
                                 input: regex_mode_str.clone(),
-                            }.get_log());
-
-                            return Err(());
+                            }.into());
                         },
                     }
                 },
@@ -257,13 +368,37 @@ impl Configuration {
             file_alias_map: file_alias_map,
             regex_mode: regex_mode,
             reverse_ast_reflection_style: reverse_ast_reflection_style,
+            sync_point_map: sync_point_map,
+            memoize_by_default: memoize_by_default,
+            memoization_limit: memoization_limit,
+            memoization_rule_map: memoization_rule_map,
+            lint_severity_map: lint_severity_map,
         };
 
-        config.print();
-
         return Ok(config);
     }
 
+    // ret: on であれば true, off であれば false
+    fn to_switch_value(prop_name: &str, prop_item: &PropertyItem) -> FCPEGResult<bool> {
+        let value = match prop_item.values.first() {
+            Some(v) => v,
+            None => {
+                return Err(ConfigurationLog::InvalidPropertyValueLength {
+                    prop_name: prop_name.to_string(),
+                }.into());
+            },
+        };
+
+        return match value.to_lowercase().as_str() {
+            "on" => Ok(true),
+            "off" => Ok(false),
+            _ => Err(ConfigurationLog::InvalidPropertyValue {
+                prop_name: prop_name.to_string(),
+                prop_value: value.clone(),
+            }.into()),
+        };
+    }
+
     pub fn print(&self) {
         println!("-- Config Data --");
         println!();
@@ -279,61 +414,53 @@ impl Configuration {
 }
 
 struct ConfigurationParser {
-    cons: Rc<RefCell<Console>>,
     // spec: 親要素の階層 (キー一覧)
     key_stack: Vec<String>,
 }
 
 impl ConfigurationParser {
-    fn parse(cons: Rc<RefCell<Console>>, src_path: String, src_content: Box<String>) -> ConsoleResult<Box<PropertyMap>> {
+    fn parse(src_path: String, src_content: String) -> FCPEGResult<Box<PropertyMap>> {
         let block_map = ConfigurationBlock::get_block_map();
-        let rule_map = Arc::new(Box::new(RuleMap::new(vec![block_map], DEFAULT_START_RULE_ID.to_string())?));
-        let tree = SyntaxParser::parse(cons.clone(), rule_map, src_path, src_content, true)?;
-        tree.print(true);
+        let rule_map = Arc::new(RuleMap::new(vec![block_map], DEFAULT_START_RULE_ID.to_string())?);
+        let tree = SyntaxParser::parse(rule_map, src_path, Box::new(src_content), true)?;
 
         let mut config_parser = ConfigurationParser {
-            cons: cons.clone(),
             key_stack: Vec::new(),
         };
 
         let prop_map = config_parser.to_property_map(&tree)?;
-        for (key, each_item) in &*prop_map { each_item.print(0, key.clone()) }
         return Ok(prop_map);
     }
 
-    fn to_property_map(&mut self, tree: &SyntaxTree) -> ConsoleResult<Box<PropertyMap>> {
+    fn to_property_map(&mut self, tree: &SyntaxTree) -> FCPEGResult<Box<PropertyMap>> {
         let mut root_item = PropertyItem::new(Vec::new());
 
-        for prop_item_node in tree.get_child_ref().get_node(&self.cons)?.find_child_nodes(vec![".Prop.Item"]) {
-            let sub_item = prop_item_node.get_node_child_at(&self.cons, 0)?;
+        for prop_item_node in tree.get_child_ref().get_node()?.find_child_nodes(vec![".Prop.Item"]) {
+            let sub_item = prop_item_node.get_node_child_at(0)?;
 
             match &sub_item.ast_reflection_style {
                 ASTReflectionStyle::Reflection(name) => {
                     match name.as_str() {
                         ".Prop.ChildItem" => {
                             let (key_stack, key, values) = self.to_child_property(sub_item)?;
-                            root_item.add_values(&self.cons, key_stack, 0, key, values)?;
+                            root_item.add_values(key_stack, 0, key, values)?;
                         },
                         ".Prop.ParentItem" => self.to_parent_property(sub_item)?,
                         _ => {
-                            self.cons.borrow_mut().append_log(BlockParsingLog::UnexpectedNodeName {
-                                uuid: sub_item.uuid.clone(),
+                            return Err(BlockParsingLog::UnexpectedNodeName {
+                                uuid: sub_item.uuid,
                                 unexpected: format!("'{}'", name),
                                 expected: "parent or child item node name".to_string(),
-                            }.get_log());
-
-                            return Err(());
+                            }.into());
                         },
                     }
                 },
                 _ => {
-                    self.cons.borrow_mut().append_log(BlockParsingLog::UnexpectedNodeName {
-                        uuid: sub_item.uuid.clone(),
+                    return Err(BlockParsingLog::UnexpectedNodeName {
+                        uuid: sub_item.uuid,
                         unexpected: "no name".to_string(),
                         expected: "parent or child item node name".to_string(),
-                    }.get_log());
-
-                    return Err(());
+                    }.into());
                 },
             };
         }
@@ -341,14 +468,12 @@ impl ConfigurationParser {
         return Ok(root_item.children);
     }
 
-    fn to_parent_property(&mut self, prop_item_node: &SyntaxNode) -> ConsoleResult<()> {
-        let (hierarchy_count, key) = self.to_property_key(prop_item_node.get_node_child_at(&self.cons, 0)?)?;
+    fn to_parent_property(&mut self, prop_item_node: &SyntaxNode) -> FCPEGResult<()> {
+        let (hierarchy_count, key) = self.to_property_key(prop_item_node.get_node_child_at(0)?)?;
         if self.key_stack.len() < hierarchy_count {
-            self.cons.borrow_mut().append_log(ConfigurationLog::InvalidHierarchy {
+            return Err(ConfigurationLog::InvalidHierarchy {
                 hierarchy_count: hierarchy_count,
-            }.get_log());
-
-            return Err(());
+            }.into());
         }
 
         for _ in 0..self.key_stack.len() - hierarchy_count {
@@ -359,16 +484,14 @@ impl ConfigurationParser {
         return Ok(());
     }
 
-    fn to_child_property(&mut self, prop_item_node: &SyntaxNode) -> ConsoleResult<(Vec<String>, String, Vec<String>)> {
-        let (hierarchy_count, key) = self.to_property_key(prop_item_node.get_node_child_at(&self.cons, 0)?)?;
-        let values = self.to_property_values(prop_item_node.get_node_child_at(&self.cons, 1)?)?;
+    fn to_child_property(&mut self, prop_item_node: &SyntaxNode) -> FCPEGResult<(Vec<String>, String, Vec<String>)> {
+        let (hierarchy_count, key) = self.to_property_key(prop_item_node.get_node_child_at(0)?)?;
+        let values = self.to_property_values(prop_item_node.get_node_child_at(1)?)?;
 
         if self.key_stack.len() < hierarchy_count {
-            self.cons.borrow_mut().append_log(ConfigurationLog::InvalidHierarchy {
+            return Err(ConfigurationLog::InvalidHierarchy {
                 hierarchy_count: hierarchy_count,
-            }.get_log());
-
-            return Err(());
+            }.into());
         }
 
         for _ in 0..self.key_stack.len() - hierarchy_count {
@@ -378,7 +501,7 @@ impl ConfigurationParser {
         return Ok((self.key_stack.clone(), key, values));
     }
 
-    fn to_property_key(&mut self, key_node: &SyntaxNode) -> ConsoleResult<(usize, String)> {
+    fn to_property_key(&mut self, key_node: &SyntaxNode) -> FCPEGResult<(usize, String)> {
         let hierarchy_count = match key_node.find_first_child_node(vec!["Pipes"]) {
             Some(v) => v.get_reflectable_children().len(),
             None => 0,
@@ -387,20 +510,18 @@ impl ConfigurationParser {
         let key = match key_node.find_first_child_node(vec![".Prop.Id"]) {
             Some(v) => v.join_child_leaf_values(),
             None => {
-                self.cons.borrow_mut().append_log(BlockParsingLog::UnexpectedNodeName {
-                    uuid: key_node.uuid.clone(),
+                return Err(BlockParsingLog::UnexpectedNodeName {
+                    uuid: key_node.uuid,
                     unexpected: "no name".to_string(),
                     expected: "parent or child item node name".to_string(),
-                }.get_log());
-
-                return Err(());
+                }.into());
             },
         };
 
         return Ok((hierarchy_count, key));
     }
 
-    fn to_property_values(&mut self, value_node: &SyntaxNode) -> ConsoleResult<Vec<String>> {
+    fn to_property_values(&mut self, value_node: &SyntaxNode) -> FCPEGResult<Vec<String>> {
         let mut raw_values = String::new();
 
         for each_char_elem in value_node.get_reflectable_children() {
@@ -415,8 +536,8 @@ impl ConfigurationParser {
         return Ok(raw_values.split(",").collect::<Vec<&str>>().iter().map(|v| v.to_string()).collect());
     }
 
-    fn to_esc_seq_string(&mut self, esc_seq_node: &SyntaxNode) -> ConsoleResult<String> {
-        let esc_char = esc_seq_node.get_leaf_child_at(&self.cons, 0)?.value.as_str();
+    fn to_esc_seq_string(&mut self, esc_seq_node: &SyntaxNode) -> FCPEGResult<String> {
+        let esc_char = esc_seq_node.get_leaf_child_at(0)?.value.as_str();
 
         let value = match esc_char {
             "\\" => "\\",
@@ -424,11 +545,9 @@ impl ConfigurationParser {
             "n" => "\n",
             "," => ",",
             _ => {
-                self.cons.borrow_mut().append_log(ConfigurationLog::UnknownEscapeCharacter {
+                return Err(ConfigurationLog::UnknownEscapeCharacter {
                     esc_char: esc_char.to_string(),
-                }.get_log());
-
-                return Err(());
+                }.into());
             },
         };
 
@@ -452,13 +571,13 @@ impl ConfigurationBlock {
         let main_rule = rule!{
             ".Main.Main",
             group!{
-                vec![],
+                [],
                 expr!(Id, ".Symbol.Space", "*", "#"),
                 expr!(Id, ".Symbol.LineEnd", "*", "#"),
                 group!{
-                    vec!["*", "##"],
+                    ["*", "##"],
                     group!{
-                        vec![],
+                        [],
                         expr!(Id, ".Prop.Item"),
                         expr!(Id, ".Symbol.Div", "*", "#"),
                     },
@@ -475,7 +594,7 @@ impl ConfigurationBlock {
         let space_rule = rule!{
             ".Symbol.Space",
             group!{
-                vec![],
+                [],
                 expr!(String, " "),
             },
         };
@@ -484,7 +603,7 @@ impl ConfigurationBlock {
         let line_end_rule = rule!{
             ".Symbol.LineEnd",
             group!{
-                vec![],
+                [],
                 expr!(Id, ".Symbol.Space", "*"),
                 expr!(String, "\n"),
                 expr!(Id, ".Symbol.Space", "*"),
@@ -495,15 +614,15 @@ impl ConfigurationBlock {
         let div_rule = rule!{
             ".Symbol.Div",
             group!{
-                vec![],
+                [],
                 group!{
-                    vec![":"],
+                    [":"],
                     group!{
-                        vec![],
+                        [],
                         expr!(Id, ".Symbol.Space"),
                     },
                     group!{
-                        vec![],
+                        [],
                         expr!(String, "\n"),
                     },
                 },
@@ -518,13 +637,13 @@ impl ConfigurationBlock {
         let item_rule = rule!{
             ".Prop.Item",
             group!{
-                vec![":"],
+                [":"],
                 group!{
-                    vec![],
+                    [],
                     expr!(Id, ".Prop.ChildItem"),
                 },
                 group!{
-                    vec![],
+                    [],
                     expr!(Id, ".Prop.ParentItem"),
                 },
             },
@@ -534,7 +653,7 @@ impl ConfigurationBlock {
         let parent_item_rule = rule!{
             ".Prop.ParentItem",
             group!{
-                vec![],
+                [],
                 expr!(Id, ".Prop.Key"),
             },
         };
@@ -543,7 +662,7 @@ impl ConfigurationBlock {
         let child_item_rule = rule!{
             ".Prop.ChildItem",
             group!{
-            vec![],
+            [],
                 expr!(Id, ".Prop.Key"),
                 expr!(Id, ".Prop.Value"),
                 expr!(String, ",", "#"),
@@ -554,9 +673,9 @@ impl ConfigurationBlock {
         let key_rule = rule!{
             ".Prop.Key",
             group!{
-                vec![],
+                [],
                 group!{
-                    vec!["#Pipes"],
+                    ["#Pipes"],
                     expr!(String, "||", "*"),
                 },
                 expr!(Id, ".Symbol.Space", "*", "#"),
@@ -571,15 +690,15 @@ impl ConfigurationBlock {
         let value_rule = rule!{
             ".Prop.Value",
             group!{
-                vec!["*", "##"],
+                ["*", "##"],
                 group!{
-                    vec![":"],
+                    [":"],
                     group!{
-                        vec![],
+                        [],
                         expr!(Id, ".Prop.EscSeq"),
                     },
                     group!{
-                        vec![],
+                        [],
                         expr!(String, "\n", "!"),
                         expr!(String, ",", "!"),
                         expr!(String, "\\", "!"),
@@ -593,7 +712,7 @@ impl ConfigurationBlock {
         let id_rule = rule!{
             ".Prop.Id",
             group!{
-                vec![],
+                [],
                 expr!(CharClass, "[a-zA-Z_]"),
                 expr!(CharClass, "[a-zA-Z0-9_]", "*"),
             },
@@ -603,24 +722,24 @@ impl ConfigurationBlock {
         let esc_seq_rule = rule!{
             ".Prop.EscSeq",
             group!{
-                vec![],
+                [],
                 expr!(String, "\\", "#"),
                 group!{
-                    vec![":", "##"],
+                    [":", "##"],
                     group!{
-                        vec!["##"],
+                        ["##"],
                         expr!(String, "\\"),
                     },
                     group!{
-                        vec!["##"],
+                        ["##"],
                         expr!(String, "n"),
                     },
                     group!{
-                        vec!["##"],
+                        ["##"],
                         expr!(String, "t"),
                     },
                     group!{
-                        vec!["##"],
+                        ["##"],
                         expr!(String, ","),
                     },
                 },
@@ -630,3 +749,32 @@ impl ConfigurationBlock {
         return block!(".Prop", vec![item_rule, parent_item_rule, child_item_rule, key_rule, value_rule, id_rule, esc_seq_rule]);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::config::*;
+
+    fn load_config(config_source: &str) -> FCPEGResult<Configuration> {
+        return Configuration::load_from_str("test.cfg", config_source.to_string());
+    }
+
+    // note: docs/config/index.md の記述例と同じ構成
+    #[test]
+    fn recovery_example_is_loaded() {
+        let config = load_config("Recovery:\n|| Main:\n|||| Statement: ;,\n").unwrap();
+        assert_eq!(config.sync_point_map.get(&("Main".to_string(), "Statement".to_string())), Some(&vec![";".to_string()]));
+    }
+
+    #[test]
+    fn memoization_example_is_loaded() {
+        let config = load_config("Memoization:\n|| Default: off,\n|| Limit: 1000000,\n|| Rules:\n|||| Main:\n|||||| Expression: on,\n").unwrap();
+        assert!(!config.memoize_by_default);
+        assert_eq!(config.memoization_limit, Some(1000000));
+        assert_eq!(config.memoization_rule_map.get(&("Main".to_string(), "Expression".to_string())), Some(&true));
+    }
+
+    #[test]
+    fn lint_example_is_loaded() {
+        assert!(load_config("Lint:\n|| UnreachableRule: error,\n|| ShadowedAlternative: note,\n|| DuplicateAlternative: off,\n").is_ok());
+    }
+}
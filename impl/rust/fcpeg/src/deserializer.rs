@@ -0,0 +1,658 @@
+use crate::*;
+use crate::tree::*;
+
+use rustnutlib::*;
+use rustnutlib::console::*;
+
+use uuid::Uuid;
+
+#[derive(Clone, Debug)]
+pub enum DeserializationLog {
+    InvalidFieldValue { field_name: String, value: String },
+    InvalidSyntax { index: usize, msg: String },
+    MissingField { field_name: String },
+}
+
+impl ConsoleLogger for DeserializationLog {
+    fn get_log(&self) -> ConsoleLog {
+        return match self {
+            DeserializationLog::InvalidFieldValue { field_name, value } => log!(Error, format!("invalid value of field '{}'", field_name), format!("value:\t{}", value.replace("\n", "\\n"))),
+            DeserializationLog::InvalidSyntax { index, msg } => log!(Error, "invalid syntax", format!("{}", msg), format!("index:\t{}", index)),
+            DeserializationLog::MissingField { field_name } => log!(Error, format!("missing field '{}'", field_name)),
+        };
+    }
+}
+
+enum JSONValue {
+    Array(Vec<JSONValue>),
+    Bool(bool),
+    Null,
+    // note: 数値は文字列のまま保持し、使用時に変換する
+    Number(String),
+    // note: フィールドの順序を保持する
+    Object(Vec<(String, JSONValue)>),
+    String(String),
+}
+
+impl JSONValue {
+    fn get_field(&self, field_name: &str) -> FCPEGResult<&JSONValue> {
+        match self {
+            JSONValue::Object(fields) => {
+                for (each_name, each_value) in fields {
+                    if each_name == field_name {
+                        return Ok(each_value);
+                    }
+                }
+            },
+            _ => (),
+        }
+
+        return Err(DeserializationLog::MissingField {
+            field_name: field_name.to_string(),
+        }.into());
+    }
+
+    fn get_string(&self, field_name: &str) -> FCPEGResult<String> {
+        return match self.get_field(field_name)? {
+            JSONValue::String(s) => Ok(s.clone()),
+            _ => Err(DeserializationLog::InvalidFieldValue {
+                field_name: field_name.to_string(),
+                value: self.get_field(field_name)?.to_string(),
+            }.into()),
+        };
+    }
+
+    fn get_usize(&self, field_name: &str) -> FCPEGResult<usize> {
+        let value = self.get_field(field_name)?;
+
+        return match value {
+            JSONValue::Number(s) => match s.parse::<usize>() {
+                Ok(v) => Ok(v),
+                Err(_) => Err(DeserializationLog::InvalidFieldValue {
+                    field_name: field_name.to_string(),
+                    value: s.clone(),
+                }.into()),
+            },
+            _ => Err(DeserializationLog::InvalidFieldValue {
+                field_name: field_name.to_string(),
+                value: value.to_string(),
+            }.into()),
+        };
+    }
+}
+
+impl std::fmt::Display for JSONValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let s = match self {
+            JSONValue::Array(_) => "[...]".to_string(),
+            JSONValue::Bool(v) => v.to_string(),
+            JSONValue::Null => "null".to_string(),
+            JSONValue::Number(v) => v.clone(),
+            JSONValue::Object(_) => "{...}".to_string(),
+            JSONValue::String(v) => format!("\"{}\"", v),
+        };
+
+        return write!(f, "{}", s);
+    }
+}
+
+// spec: JSONSerializer の出力から構文木を復元する
+pub struct JSONDeserializer {
+    src_chars: Vec<char>,
+    src_i: usize,
+}
+
+impl JSONDeserializer {
+    pub fn deserialize(source: &str) -> FCPEGResult<SyntaxTree> {
+        let mut deserializer = JSONDeserializer {
+            src_chars: source.chars().collect(),
+            src_i: 0,
+        };
+
+        let value = deserializer.read_value()?;
+        deserializer.skip_spaces();
+
+        if deserializer.src_i < deserializer.src_chars.len() {
+            return Err(deserializer.get_invalid_syntax_log("expected end of source").into());
+        }
+
+        let root_elem = JSONDeserializer::to_elem(&value)?;
+        return Ok(SyntaxTree::from_node(root_elem));
+    }
+
+    fn to_elem(value: &JSONValue) -> FCPEGResult<SyntaxNodeElement> {
+        let ast_reflection_style = JSONDeserializer::to_ast_reflection_style(value)?;
+        let uuid = JSONDeserializer::to_uuid(&value.get_string("uuid")?)?;
+
+        return match value.get_string("kind")?.as_str() {
+            "node" => {
+                let sub_elems = match value.get_field("children")? {
+                    JSONValue::Array(children) => {
+                        let mut sub_elems = Vec::<SyntaxNodeElement>::new();
+
+                        for each_child in children {
+                            sub_elems.push(JSONDeserializer::to_elem(each_child)?);
+                        }
+
+                        sub_elems
+                    },
+                    children => {
+                        return Err(DeserializationLog::InvalidFieldValue {
+                            field_name: "children".to_string(),
+                            value: children.to_string(),
+                        }.into());
+                    },
+                };
+
+                Ok(SyntaxNodeElement::Node(Box::new(SyntaxNode::new(sub_elems, ast_reflection_style, uuid))))
+            },
+            "leaf" => {
+                let pos = JSONDeserializer::to_position(value.get_field("span")?.get_field("start")?)?;
+                let value = value.get_string("value")?;
+                Ok(SyntaxNodeElement::Leaf(Box::new(SyntaxLeaf::new(pos, value, ast_reflection_style, uuid))))
+            },
+            kind => Err(DeserializationLog::InvalidFieldValue {
+                field_name: "kind".to_string(),
+                value: kind.to_string(),
+            }.into()),
+        };
+    }
+
+    fn to_ast_reflection_style(value: &JSONValue) -> FCPEGResult<ASTReflectionStyle> {
+        return match value.get_string("reflection")?.as_str() {
+            "reflection" => Ok(ASTReflectionStyle::Reflection(value.get_string("name")?)),
+            "noReflection" => Ok(ASTReflectionStyle::NoReflection),
+            "expansion" => Ok(ASTReflectionStyle::Expansion),
+            reflection => Err(DeserializationLog::InvalidFieldValue {
+                field_name: "reflection".to_string(),
+                value: reflection.to_string(),
+            }.into()),
+        };
+    }
+
+    fn to_position(value: &JSONValue) -> FCPEGResult<CharacterPosition> {
+        let file_path = match value.get_field("file_path")? {
+            JSONValue::String(s) => Some(s.clone()),
+            _ => None,
+        };
+
+        return Ok(CharacterPosition::new(file_path, value.get_usize("index")?, value.get_usize("line")?, value.get_usize("column")?));
+    }
+
+    fn to_uuid(s: &str) -> FCPEGResult<Uuid> {
+        return match Uuid::parse_str(s) {
+            Ok(v) => Ok(v),
+            Err(_) => Err(DeserializationLog::InvalidFieldValue {
+                field_name: "uuid".to_string(),
+                value: s.to_string(),
+            }.into()),
+        };
+    }
+
+    fn read_value(&mut self) -> FCPEGResult<JSONValue> {
+        self.skip_spaces();
+
+        return match self.peek_char() {
+            Some('{') => {
+                self.src_i += 1;
+                let mut fields = Vec::<(String, JSONValue)>::new();
+                self.skip_spaces();
+
+                if self.peek_char() == Some('}') {
+                    self.src_i += 1;
+                    return Ok(JSONValue::Object(fields));
+                }
+
+                loop {
+                    self.skip_spaces();
+                    let field_name = self.read_string()?;
+                    self.skip_spaces();
+                    self.expect_char(':')?;
+                    let field_value = self.read_value()?;
+                    fields.push((field_name, field_value));
+                    self.skip_spaces();
+
+                    match self.peek_char() {
+                        Some(',') => self.src_i += 1,
+                        Some('}') => {
+                            self.src_i += 1;
+                            break;
+                        },
+                        _ => return Err(self.get_invalid_syntax_log("expected ',' or '}'").into()),
+                    }
+                }
+
+                Ok(JSONValue::Object(fields))
+            },
+            Some('[') => {
+                self.src_i += 1;
+                let mut elems = Vec::<JSONValue>::new();
+                self.skip_spaces();
+
+                if self.peek_char() == Some(']') {
+                    self.src_i += 1;
+                    return Ok(JSONValue::Array(elems));
+                }
+
+                loop {
+                    elems.push(self.read_value()?);
+                    self.skip_spaces();
+
+                    match self.peek_char() {
+                        Some(',') => self.src_i += 1,
+                        Some(']') => {
+                            self.src_i += 1;
+                            break;
+                        },
+                        _ => return Err(self.get_invalid_syntax_log("expected ',' or ']'").into()),
+                    }
+                }
+
+                Ok(JSONValue::Array(elems))
+            },
+            Some('"') => Ok(JSONValue::String(self.read_string()?)),
+            Some(c) if c == '-' || c.is_ascii_digit() => {
+                let mut s = String::new();
+
+                while let Some(c) = self.peek_char() {
+                    if c == '-' || c == '+' || c == '.' || c == 'e' || c == 'E' || c.is_ascii_digit() {
+                        s.push(c);
+                        self.src_i += 1;
+                    } else {
+                        break;
+                    }
+                }
+
+                Ok(JSONValue::Number(s))
+            },
+            _ => {
+                for (keyword, value) in [("true", JSONValue::Bool(true)), ("false", JSONValue::Bool(false)), ("null", JSONValue::Null)] {
+                    if self.src_chars[self.src_i..].starts_with(&keyword.chars().collect::<Vec<char>>()) {
+                        self.src_i += keyword.len();
+                        return Ok(value);
+                    }
+                }
+
+                Err(self.get_invalid_syntax_log("expected value").into())
+            },
+        };
+    }
+
+    fn read_string(&mut self) -> FCPEGResult<String> {
+        self.expect_char('"')?;
+        let mut s = String::new();
+
+        loop {
+            let next_char = match self.peek_char() {
+                Some(v) => v,
+                None => return Err(self.get_invalid_syntax_log("unterminated string").into()),
+            };
+
+            self.src_i += 1;
+
+            match next_char {
+                '"' => return Ok(s),
+                '\\' => {
+                    let esc_char = match self.peek_char() {
+                        Some(v) => v,
+                        None => return Err(self.get_invalid_syntax_log("unterminated string").into()),
+                    };
+
+                    self.src_i += 1;
+
+                    match esc_char {
+                        '"' => s.push('"'),
+                        '\\' => s.push('\\'),
+                        '/' => s.push('/'),
+                        'b' => s.push('\u{8}'),
+                        'f' => s.push('\u{c}'),
+                        'n' => s.push('\n'),
+                        'r' => s.push('\r'),
+                        't' => s.push('\t'),
+                        'u' => s.push(self.read_unicode_escape()?),
+                        _ => return Err(self.get_invalid_syntax_log(&format!("unknown escape character '{}'", esc_char)).into()),
+                    }
+                },
+                c => s.push(c),
+            }
+        }
+    }
+
+    // note: サロゲートペアは扱わない; JSONSerializer は制御文字のみを \u でエスケープする
+    fn read_unicode_escape(&mut self) -> FCPEGResult<char> {
+        if self.src_i + 4 > self.src_chars.len() {
+            return Err(self.get_invalid_syntax_log("invalid unicode escape").into());
+        }
+
+        let hex = self.src_chars[self.src_i..self.src_i + 4].iter().collect::<String>();
+        self.src_i += 4;
+
+        return match u32::from_str_radix(&hex, 16).ok().and_then(std::char::from_u32) {
+            Some(v) => Ok(v),
+            None => Err(self.get_invalid_syntax_log("invalid unicode escape").into()),
+        };
+    }
+
+    fn expect_char(&mut self, expected_char: char) -> FCPEGResult<()> {
+        if self.peek_char() != Some(expected_char) {
+            return Err(self.get_invalid_syntax_log(&format!("expected '{}'", expected_char)).into());
+        }
+
+        self.src_i += 1;
+        return Ok(());
+    }
+
+    fn peek_char(&self) -> Option<char> {
+        return self.src_chars.get(self.src_i).cloned();
+    }
+
+    fn skip_spaces(&mut self) {
+        while let Some(c) = self.peek_char() {
+            if !c.is_whitespace() {
+                break;
+            }
+
+            self.src_i += 1;
+        }
+    }
+
+    fn get_invalid_syntax_log(&self, msg: &str) -> DeserializationLog {
+        return DeserializationLog::InvalidSyntax {
+            index: self.src_i,
+            msg: msg.to_string(),
+        };
+    }
+}
+
+enum SExpression {
+    Atom(String),
+    List(Vec<SExpression>),
+    String(String),
+}
+
+impl std::fmt::Display for SExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        return match self {
+            SExpression::Atom(v) => write!(f, "{}", v),
+            SExpression::List(_) => write!(f, "(...)"),
+            SExpression::String(v) => write!(f, "\"{}\"", v),
+        };
+    }
+}
+
+// spec: SExpressionSerializer の出力から構文木を復元する
+// note: S 式は UUID とファイルパスを持たないため、UUID は新たに生成し、ファイルパスは引数の値を用いる
+pub struct SExpressionDeserializer {
+    src_chars: Vec<char>,
+    src_i: usize,
+}
+
+impl SExpressionDeserializer {
+    pub fn deserialize(source: &str, file_path: Option<String>) -> FCPEGResult<SyntaxTree> {
+        let mut deserializer = SExpressionDeserializer {
+            src_chars: source.chars().collect(),
+            src_i: 0,
+        };
+
+        let expr = deserializer.read_expr()?;
+        deserializer.skip_spaces();
+
+        if deserializer.src_i < deserializer.src_chars.len() {
+            return Err(deserializer.get_invalid_syntax_log("expected end of source").into());
+        }
+
+        let root_elem = SExpressionDeserializer::to_elem(&expr, &file_path)?;
+        return Ok(SyntaxTree::from_node(root_elem));
+    }
+
+    fn to_elem(expr: &SExpression, file_path: &Option<String>) -> FCPEGResult<SyntaxNodeElement> {
+        let exprs = match expr {
+            SExpression::List(exprs) => exprs,
+            _ => {
+                return Err(DeserializationLog::InvalidFieldValue {
+                    field_name: "element".to_string(),
+                    value: expr.to_string(),
+                }.into());
+            },
+        };
+
+        let ast_reflection_style = match exprs.first() {
+            Some(SExpression::Atom(atom)) => SExpressionDeserializer::to_ast_reflection_style(atom)?,
+            Some(v) => {
+                return Err(DeserializationLog::InvalidFieldValue {
+                    field_name: "reflection".to_string(),
+                    value: v.to_string(),
+                }.into());
+            },
+            None => {
+                return Err(DeserializationLog::MissingField {
+                    field_name: "reflection".to_string(),
+                }.into());
+            },
+        };
+
+        // note: 2 番目の要素が文字列であればリーフ
+        return match exprs.get(1) {
+            Some(SExpression::String(value)) => {
+                let pos = match exprs.get(2) {
+                    Some(SExpression::Atom(atom)) if exprs.len() == 3 => SExpressionDeserializer::to_position(atom, file_path)?,
+                    _ => {
+                        return Err(DeserializationLog::MissingField {
+                            field_name: "position".to_string(),
+                        }.into());
+                    },
+                };
+
+                Ok(SyntaxNodeElement::from_leaf_args(pos, value.clone(), ast_reflection_style))
+            },
+            _ => {
+                let mut sub_elems = Vec::<SyntaxNodeElement>::new();
+
+                for each_expr in &exprs[1..] {
+                    sub_elems.push(SExpressionDeserializer::to_elem(each_expr, file_path)?);
+                }
+
+                Ok(SyntaxNodeElement::from_node_args(sub_elems, ast_reflection_style))
+            },
+        };
+    }
+
+    fn to_ast_reflection_style(atom: &str) -> FCPEGResult<ASTReflectionStyle> {
+        return match atom {
+            "!" => Ok(ASTReflectionStyle::NoReflection),
+            "##" => Ok(ASTReflectionStyle::Expansion),
+            _ if atom.starts_with("#") => Ok(ASTReflectionStyle::Reflection(atom[1..].to_string())),
+            _ => Err(DeserializationLog::InvalidFieldValue {
+                field_name: "reflection".to_string(),
+                value: atom.to_string(),
+            }.into()),
+        };
+    }
+
+    fn to_position(atom: &str, file_path: &Option<String>) -> FCPEGResult<CharacterPosition> {
+        let nums = atom.split(':').map(|each_num| each_num.parse::<usize>()).collect::<Vec<std::result::Result<usize, std::num::ParseIntError>>>();
+
+        return match nums.as_slice() {
+            [Ok(index), Ok(line), Ok(column)] => Ok(CharacterPosition::new(file_path.clone(), *index, *line, *column)),
+            _ => Err(DeserializationLog::InvalidFieldValue {
+                field_name: "position".to_string(),
+                value: atom.to_string(),
+            }.into()),
+        };
+    }
+
+    fn read_expr(&mut self) -> FCPEGResult<SExpression> {
+        self.skip_spaces();
+
+        return match self.peek_char() {
+            Some('(') => {
+                self.src_i += 1;
+                let mut exprs = Vec::<SExpression>::new();
+
+                loop {
+                    self.skip_spaces();
+
+                    match self.peek_char() {
+                        Some(')') => {
+                            self.src_i += 1;
+                            break;
+                        },
+                        Some(_) => exprs.push(self.read_expr()?),
+                        None => return Err(self.get_invalid_syntax_log("expected ')'").into()),
+                    }
+                }
+
+                Ok(SExpression::List(exprs))
+            },
+            Some(')') => Err(self.get_invalid_syntax_log("unexpected ')'").into()),
+            Some('"') => {
+                self.src_i += 1;
+                Ok(SExpression::String(self.read_chars(true)?))
+            },
+            Some(_) => Ok(SExpression::Atom(self.read_chars(false)?)),
+            None => Err(self.get_invalid_syntax_log("expected expression").into()),
+        };
+    }
+
+    // note: 文字列の場合は終端の引用符まで、アトムの場合は空白・括弧の直前まで読む
+    fn read_chars(&mut self, is_quoted: bool) -> FCPEGResult<String> {
+        let mut s = String::new();
+
+        loop {
+            let next_char = match self.peek_char() {
+                Some(v) => v,
+                None if is_quoted => return Err(self.get_invalid_syntax_log("unterminated string").into()),
+                None => return Ok(s),
+            };
+
+            match next_char {
+                '"' if is_quoted => {
+                    self.src_i += 1;
+                    return Ok(s);
+                },
+                c if !is_quoted && (c.is_whitespace() || c == '(' || c == ')' || c == '"') => return Ok(s),
+                '\\' => {
+                    self.src_i += 1;
+
+                    let esc_char = match self.peek_char() {
+                        Some(v) => v,
+                        None => return Err(self.get_invalid_syntax_log("unterminated escape sequence").into()),
+                    };
+
+                    self.src_i += 1;
+
+                    match esc_char {
+                        '"' => s.push('"'),
+                        '\\' => s.push('\\'),
+                        '(' => s.push('('),
+                        ')' => s.push(')'),
+                        'n' => s.push('\n'),
+                        'r' => s.push('\r'),
+                        's' => s.push(' '),
+                        't' => s.push('\t'),
+                        'u' => s.push(self.read_unicode_escape()?),
+                        _ => return Err(self.get_invalid_syntax_log(&format!("unknown escape character '{}'", esc_char)).into()),
+                    }
+                },
+                c => {
+                    self.src_i += 1;
+                    s.push(c);
+                },
+            }
+        }
+    }
+
+    fn read_unicode_escape(&mut self) -> FCPEGResult<char> {
+        if self.src_i + 4 > self.src_chars.len() {
+            return Err(self.get_invalid_syntax_log("invalid unicode escape").into());
+        }
+
+        let hex = self.src_chars[self.src_i..self.src_i + 4].iter().collect::<String>();
+        self.src_i += 4;
+
+        return match u32::from_str_radix(&hex, 16).ok().and_then(std::char::from_u32) {
+            Some(v) => Ok(v),
+            None => Err(self.get_invalid_syntax_log("invalid unicode escape").into()),
+        };
+    }
+
+    fn peek_char(&self) -> Option<char> {
+        return self.src_chars.get(self.src_i).cloned();
+    }
+
+    fn skip_spaces(&mut self) {
+        while let Some(c) = self.peek_char() {
+            if !c.is_whitespace() {
+                break;
+            }
+
+            self.src_i += 1;
+        }
+    }
+
+    fn get_invalid_syntax_log(&self, msg: &str) -> DeserializationLog {
+        return DeserializationLog::InvalidSyntax {
+            index: self.src_i,
+            msg: msg.to_string(),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::deserializer::*;
+
+    // note: エスケープが必要な文字・マルチバイト文字・子要素を持たないノードを含む構文木
+    fn build_tree() -> SyntaxTree {
+        let file_path = Some("input.txt".to_string());
+        let text_leaf = SyntaxNodeElement::from_leaf_args(CharacterPosition::new(file_path.clone(), 0, 0, 0), "a \"b\"\\\n(c)\tあ".to_string(), ASTReflectionStyle::Reflection("Text".to_string()));
+        let symbol_leaf = SyntaxNodeElement::from_leaf_args(CharacterPosition::new(file_path.clone(), 11, 1, 7), ";".to_string(), ASTReflectionStyle::NoReflection);
+        let expansion_node = SyntaxNodeElement::from_node_args(vec![symbol_leaf], ASTReflectionStyle::Expansion);
+        let empty_node = SyntaxNodeElement::from_node_args(Vec::new(), ASTReflectionStyle::Reflection("Empty".to_string()));
+        return SyntaxTree::from_node_args(vec![text_leaf, expansion_node, empty_node], ASTReflectionStyle::Reflection("Main".to_string()));
+    }
+
+    #[test]
+    fn json_round_trip_keeps_tree() {
+        let json = build_tree().to_json(false);
+        assert_eq!(SyntaxTree::from_json(&json).unwrap().to_json(false), json);
+    }
+
+    #[test]
+    fn sexpr_round_trip_keeps_tree() {
+        let sexpr = build_tree().to_sexpr(false);
+        let tree = SyntaxTree::from_sexpr(&sexpr, Some("input.txt".to_string())).unwrap();
+
+        assert_eq!(tree.to_sexpr(false), sexpr);
+        // note: S 式は入力ファイルのパスを含まないため、引数のパスが位置情報に設定される
+        assert_eq!(tree.get_child_ref().get_node().unwrap().get_leaf_child_at(0).unwrap().pos, CharacterPosition::new(Some("input.txt".to_string()), 0, 0, 0));
+    }
+
+    #[test]
+    fn invalid_json_is_rejected() {
+        let json = build_tree().to_json(false).replacen("\"kind\": \"node\"", "\"kind\": \"branch\"", 1);
+
+        match SyntaxTree::from_json(&json) {
+            Err(FCPEGError::Deserialization(DeserializationLog::InvalidFieldValue { field_name, value })) => assert_eq!((field_name.as_str(), value.as_str()), ("kind", "branch")),
+            _ => panic!("unexpected result"),
+        }
+
+        match SyntaxTree::from_json("{} {}") {
+            Err(FCPEGError::Deserialization(DeserializationLog::InvalidSyntax { index, msg: _ })) => assert_eq!(index, 3),
+            _ => panic!("unexpected result"),
+        }
+    }
+
+    #[test]
+    fn invalid_sexpr_is_rejected() {
+        match SyntaxTree::from_sexpr("(#Main (! \"a\"))", None) {
+            Err(FCPEGError::Deserialization(DeserializationLog::MissingField { field_name })) => assert_eq!(field_name, "position"),
+            _ => panic!("unexpected result"),
+        }
+
+        match SyntaxTree::from_sexpr("(Main)", None) {
+            Err(FCPEGError::Deserialization(DeserializationLog::InvalidFieldValue { field_name, value })) => assert_eq!((field_name.as_str(), value.as_str()), ("reflection", "Main")),
+            _ => panic!("unexpected result"),
+        }
+    }
+}
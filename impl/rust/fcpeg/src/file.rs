@@ -1,13 +1,111 @@
-use std::cell::RefCell;
 use std::collections::*;
-use std::rc::Rc;
 use std::sync::Arc;
 
+use crate::*;
 use crate::config::*;
 
+use rustnutlib::*;
 use rustnutlib::console::*;
 use rustnutlib::file::*;
 
+#[derive(Clone, Debug)]
+pub enum FileLog {
+    FailedToReadSource { file_path: String },
+    SourceNotFound { file_path: String },
+}
+
+impl ConsoleLogger for FileLog {
+    fn get_log(&self) -> ConsoleLog {
+        return match self {
+            FileLog::FailedToReadSource { file_path } => log!(Error, format!("failed to read source '{}'", file_path)),
+            FileLog::SourceNotFound { file_path } => log!(Error, format!("source '{}' not found", file_path)),
+        };
+    }
+}
+
+// spec: FCPEG ファイル・構成ファイル・入力ファイルの内容を取得する方法を定義する
+pub trait SourceResolver {
+    // ret: ソースの内容
+    fn read_source(&self, file_path: &str) -> std::result::Result<String, FileLog>;
+
+    // ret: 2 つのパスが同じソースを指すかどうか
+    fn is_same_source(&self, file_path: &str, other_file_path: &str) -> std::result::Result<bool, FileLog>;
+}
+
+// note: ディスク上のファイルからソースを取得する
+pub struct FileSourceResolver {}
+
+impl FileSourceResolver {
+    pub fn new() -> FileSourceResolver {
+        return FileSourceResolver {};
+    }
+}
+
+impl Default for FileSourceResolver {
+    fn default() -> FileSourceResolver {
+        return FileSourceResolver::new();
+    }
+}
+
+impl SourceResolver for FileSourceResolver {
+    fn read_source(&self, file_path: &str) -> std::result::Result<String, FileLog> {
+        return match FileMan::read_all(&file_path.to_string()) {
+            Ok(v) => Ok(v),
+            Err(_) => Err(FileLog::FailedToReadSource {
+                file_path: file_path.to_string(),
+            }),
+        };
+    }
+
+    fn is_same_source(&self, file_path: &str, other_file_path: &str) -> std::result::Result<bool, FileLog> {
+        return match FileMan::is_same(&file_path.to_string(), &other_file_path.to_string()) {
+            Ok(v) => Ok(v),
+            Err(_) => Err(FileLog::FailedToReadSource {
+                file_path: other_file_path.to_string(),
+            }),
+        };
+    }
+}
+
+// note: 仮想ファイル名とソース文字列のマップからソースを取得する
+pub struct MemorySourceResolver {
+    // note: <file_path, source>
+    source_map: HashMap<String, String>,
+}
+
+impl MemorySourceResolver {
+    pub fn new() -> MemorySourceResolver {
+        return MemorySourceResolver {
+            source_map: HashMap::new(),
+        };
+    }
+
+    pub fn add_source(&mut self, file_path: String, source: String) {
+        self.source_map.insert(file_path, source);
+    }
+}
+
+impl Default for MemorySourceResolver {
+    fn default() -> MemorySourceResolver {
+        return MemorySourceResolver::new();
+    }
+}
+
+impl SourceResolver for MemorySourceResolver {
+    fn read_source(&self, file_path: &str) -> std::result::Result<String, FileLog> {
+        return match self.source_map.get(file_path) {
+            Some(v) => Ok(v.clone()),
+            None => Err(FileLog::SourceNotFound {
+                file_path: file_path.to_string(),
+            }),
+        };
+    }
+
+    fn is_same_source(&self, file_path: &str, other_file_path: &str) -> std::result::Result<bool, FileLog> {
+        return Ok(file_path == other_file_path);
+    }
+}
+
 pub struct FCPEGFileMap {
     pub file_map: HashMap<String, FCPEGFile>,
     // spec: メインファイルを参照するエイリアス名; ID 変換時にエイリアスを空文字に置換する
@@ -16,19 +114,39 @@ pub struct FCPEGFileMap {
 
 impl FCPEGFileMap {
     // todo: config 読んでサブファイル対応
-    pub fn load(cons: Rc<RefCell<Console>>, fcpeg_file_path: String, lib_fcpeg_file_map: HashMap<String, String>) -> ConsoleResult<FCPEGFileMap> {
-        // note: ルートファイルのエイリアス名は空文字; 除外エイリアスなし
-        let (file_map, replaced_file_alias_names) = FCPEGFileLoader::load(cons, fcpeg_file_path, lib_fcpeg_file_map)?;
+    pub fn load(fcpeg_file_path: String, lib_fcpeg_file_map: HashMap<String, String>) -> FCPEGResult<FCPEGFileMap> {
+        return FCPEGFileMap::load_with_resolver(fcpeg_file_path, lib_fcpeg_file_map, &FileSourceResolver::new());
+    }
+
+    pub fn load_with_resolver(fcpeg_file_path: String, lib_fcpeg_file_map: HashMap<String, String>, resolver: &dyn SourceResolver) -> FCPEGResult<FCPEGFileMap> {
+        let mut loader = FCPEGFileLoader::new(resolver);
+        // note: メインファイルのエイリアス名は空文字
+        loader.load_file(String::new(), fcpeg_file_path)?;
+        return FCPEGFileMap::load_lib_files(loader, lib_fcpeg_file_map);
+    }
+
+    // note: メインファイルはソース文字列から読み込む; サブファイル及び外部ファイルは resolver から取得する
+    pub fn load_from_str(fcpeg_file_path: String, fcpeg_source: String, config_source: String, lib_fcpeg_file_map: HashMap<String, String>, resolver: &dyn SourceResolver) -> FCPEGResult<FCPEGFileMap> {
+        let mut loader = FCPEGFileLoader::new(resolver);
+        // note: メインファイルのエイリアス名は空文字
+        loader.load_source(String::new(), fcpeg_file_path, fcpeg_source, config_source)?;
+        return FCPEGFileMap::load_lib_files(loader, lib_fcpeg_file_map);
+    }
+
+    fn load_lib_files(mut loader: FCPEGFileLoader, lib_fcpeg_file_map: HashMap<String, String>) -> FCPEGResult<FCPEGFileMap> {
+        for (each_alias_name, each_fcpeg_file_path) in lib_fcpeg_file_map {
+            loader.load_file(each_alias_name, each_fcpeg_file_path)?;
+        }
 
         let file_map_wrapper = FCPEGFileMap {
-            replaced_file_alias_names: Arc::new(replaced_file_alias_names),
-            file_map: file_map,
+            replaced_file_alias_names: Arc::new(loader.replaced_file_alias_names),
+            file_map: loader.file_map_result,
         };
 
         return Ok(file_map_wrapper);
     }
 
-    pub fn iter(&self) -> hash_map::Iter<String, FCPEGFile> {
+    pub fn iter(&self) -> hash_map::Iter<'_, String, FCPEGFile> {
         return self.file_map.iter();
     }
 }
@@ -41,8 +159,8 @@ pub struct FCPEGFile {
     pub config: Configuration,
 }
 
-struct FCPEGFileLoader {
-    cons: Rc<RefCell<Console>>,
+struct FCPEGFileLoader<'a> {
+    resolver: &'a dyn SourceResolver,
     file_map_result: HashMap<String, FCPEGFile>,
     // note: <alias_name, fcpeg_file_path>
     loaded_fcpeg_files: HashMap<String, String>,
@@ -51,37 +169,27 @@ struct FCPEGFileLoader {
     replaced_file_alias_names: HashMap<String, String>,
 }
 
-impl FCPEGFileLoader {
-    pub fn load(cons: Rc<RefCell<Console>>, fcpeg_file_path: String, lib_fcpeg_file_map: HashMap<String, String>) -> ConsoleResult<(HashMap<String, FCPEGFile>, HashMap<String, String>)> {
-        let mut loader = FCPEGFileLoader {
-            cons: cons,
+impl<'a> FCPEGFileLoader<'a> {
+    fn new(resolver: &'a dyn SourceResolver) -> FCPEGFileLoader<'a> {
+        return FCPEGFileLoader {
+            resolver: resolver,
             file_map_result: HashMap::new(),
             loaded_fcpeg_files: HashMap::new(),
             replaced_file_alias_names: HashMap::new(),
         };
+    }
 
-        // note: メインファイルのエイリアス名は空文字
-        loader.load_file(String::new(), fcpeg_file_path)?;
-
-        for (each_alias_name, each_fcpeg_file_path) in lib_fcpeg_file_map {
-            loader.load_file(each_alias_name, each_fcpeg_file_path)?;
-        }
+    fn load_file(&mut self, alias_name: String, fcpeg_file_path: String) -> FCPEGResult<()> {
+        let file_content = self.resolver.read_source(&fcpeg_file_path)?;
+        let config_file_path = FileMan::rename_ext(&fcpeg_file_path, "cfg");
+        let config_content = self.resolver.read_source(&config_file_path)?;
 
-        return Ok((loader.file_map_result, loader.replaced_file_alias_names));
+        return self.load_source(alias_name, fcpeg_file_path, file_content, config_content);
     }
 
-    // ret: サブファイルのマップ
-    fn load_file(&mut self, alias_name: String, fcpeg_file_path: String) -> ConsoleResult<()> {
-        let file_content = match FileMan::read_all(&fcpeg_file_path) {
-            Ok(v) => v,
-            Err(e) => {
-                self.cons.borrow_mut().append_log(e.get_log());
-                return Err(());
-            },
-        };
-
+    fn load_source(&mut self, alias_name: String, fcpeg_file_path: String, file_content: String, config_content: String) -> FCPEGResult<()> {
         let config_file_path = FileMan::rename_ext(&fcpeg_file_path, "cfg");
-        let config = Configuration::load(self.cons.clone(), &config_file_path)?;
+        let config = Configuration::load_from_str(&config_file_path, config_content)?;
         let sub_file_alias_map = config.file_alias_map.clone();
 
         let new_file = FCPEGFile {
@@ -98,26 +206,21 @@ impl FCPEGFileLoader {
         'map_loop: for (sub_alias_name, sub_file_path) in sub_file_alias_map {
             // note: エイリアス名の重複チェック
             if self.loaded_fcpeg_files.contains_key(&sub_alias_name) || self.replaced_file_alias_names.contains_key(&sub_alias_name) {
-                self.cons.borrow_mut().append_log(ConfigurationLog::DuplicateFileAliasName {
+                return Err(ConfigurationLog::DuplicateFileAliasName {
                     alias_name: sub_alias_name.clone(),
-                }.get_log());
-
-                return Err(());
+                }.into());
             }
 
             // note: ロード済みであれば無視
             for (loaded_alias_name, loaded_file_path) in &self.loaded_fcpeg_files {
-                match FileMan::is_same(loaded_file_path, &sub_file_path) {
+                match self.resolver.is_same_source(loaded_file_path, &sub_file_path) {
                     Ok(is_same_path) => {
                         if is_same_path {
                             self.replaced_file_alias_names.insert(sub_alias_name.clone(), loaded_alias_name.clone());
                             continue 'map_loop;
                         }
                     },
-                    Err(e) => {
-                        self.cons.borrow_mut().append_log(e.get_log());
-                        return Err(());
-                    },
+                    Err(e) => return Err(e.into()),
                 }
             }
 
@@ -126,4 +229,5 @@ impl FCPEGFileLoader {
 
         return Ok(());
     }
+
 }
@@ -0,0 +1,355 @@
+use crate::*;
+use crate::block::*;
+use crate::tree::*;
+
+// note: ブロック内のコマンド及び複数行に配置した選択肢の字下げ
+pub const FORMATTER_INDENT: &str = "    ";
+
+// spec: FCPEG コードを整形する; FCPEG 構文の構文木から字下げ・空白・選択の配置を正規化したコードを生成する
+// note: コメントの内容はそのまま出力する; コマンド間の空行は 1 行にまとめる
+// note: 規則定義の選択肢が元のコードで複数行に渡る場合は各選択肢を 1 行ずつ配置する
+pub struct FCPEGFormatter {
+    output: String,
+}
+
+impl FCPEGFormatter {
+    pub fn format(file_path: String, file_content: Box<String>) -> FCPEGResult<String> {
+        let tree = BlockParser::to_fcpeg_syntax_tree(file_path, file_content)?;
+
+        let mut formatter = FCPEGFormatter {
+            output: String::new(),
+        };
+
+        formatter.write_fcpeg(tree.get_child_ref().get_node()?)?;
+        return Ok(formatter.output);
+    }
+
+    fn write_fcpeg(&mut self, root_node: &SyntaxNode) -> FCPEGResult<()> {
+        let mut block_nodes = Vec::<&SyntaxNode>::new();
+
+        for each_elem in root_node.get_reflectable_children() {
+            match each_elem {
+                SyntaxNodeElement::Node(node) => block_nodes.append(&mut node.find_child_nodes(vec![".Block.Block"])),
+                _ => (),
+            }
+        }
+
+        for (block_i, each_block_node) in block_nodes.iter().enumerate() {
+            if block_i != 0 {
+                self.output += "\n";
+            }
+
+            self.write_block(each_block_node)?;
+        }
+
+        return Ok(());
+    }
+
+    fn write_block(&mut self, block_node: &SyntaxNode) -> FCPEGResult<()> {
+        let block_name = block_node.get_node_child_at(0)?.join_child_leaf_values();
+        let mut cmd_nodes = Vec::<&SyntaxNode>::new();
+
+        for each_elem in block_node.get_reflectable_children() {
+            match each_elem {
+                SyntaxNodeElement::Node(node) => cmd_nodes.append(&mut node.find_child_nodes(vec![".Block.Cmd"])),
+                _ => (),
+            }
+        }
+
+        self.output += &format!("[{}]{{\n", block_name);
+
+        // note: 直前のコマンドの末尾の行数
+        let mut prev_end_line = None;
+
+        for each_cmd_node in cmd_nodes {
+            let span = each_cmd_node.get_span();
+
+            match (&prev_end_line, &span) {
+                (Some(prev_end_line), Some((start_pos, _))) if start_pos.line > *prev_end_line + 1 => self.output += "\n",
+                _ => (),
+            }
+
+            prev_end_line = match span {
+                Some((_, end_pos)) => Some(end_pos.line),
+                None => None,
+            };
+
+            self.output += FORMATTER_INDENT;
+            self.write_cmd(each_cmd_node.get_node_child_at(0)?)?;
+            self.output += "\n";
+        }
+
+        self.output += "}\n";
+        return Ok(());
+    }
+
+    fn write_cmd(&mut self, cmd_node: &SyntaxNode) -> FCPEGResult<()> {
+        match FCPEGFormatter::get_node_name(cmd_node) {
+            ".Block.CommentCmd" => self.output += &format!("%{},", cmd_node.join_child_leaf_values()),
+            ".Block.DefineCmd" => self.write_define_cmd(cmd_node)?,
+            ".Block.StartCmd" => self.output += &format!("+ start {},", FCPEGFormatter::to_chain_id(cmd_node.get_node_child_at(0)?)),
+            ".Block.UseCmd" => {
+                self.output += &format!("+ use {}", FCPEGFormatter::to_chain_id(cmd_node.get_node_child_at(0)?));
+
+                match cmd_node.find_first_child_node(vec![".Block.UseCmdBlockAlias"]) {
+                    Some(alias_node) => {
+                        let alias_name = match alias_node.find_first_child_node(vec![".Misc.SingleID"]) {
+                            Some(v) => v.join_child_leaf_values(),
+                            None => String::new(),
+                        };
+
+                        self.output += &format!(" as {}", alias_name);
+                    },
+                    None => (),
+                }
+
+                self.output += ",";
+            },
+            _ => (),
+        }
+
+        return Ok(());
+    }
+
+    fn write_define_cmd(&mut self, cmd_node: &SyntaxNode) -> FCPEGResult<()> {
+        self.output += &cmd_node.get_node_child_at(0)?.join_child_leaf_values();
+
+        match cmd_node.find_first_child_node(vec![".Block.DefineCmdGenerics"]) {
+            Some(generics_node) => self.output += &format!("<{}>", FCPEGFormatter::to_arg_id_list(generics_node)),
+            None => (),
+        }
+
+        match cmd_node.find_first_child_node(vec![".Block.DefineCmdTemplate"]) {
+            Some(template_node) => self.output += &format!("({})", FCPEGFormatter::to_arg_id_list(template_node)),
+            None => (),
+        }
+
+        self.output += " <- ";
+
+        let choice_node = match cmd_node.find_first_child_node(vec![".Rule.PureChoice"]) {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+
+        let seq_nodes = choice_node.find_child_nodes(vec![".Rule.Seq"]);
+        let first_line = match seq_nodes.first().and_then(|seq_node| seq_node.get_span()) {
+            Some((start_pos, _)) => start_pos.line,
+            None => 0,
+        };
+
+        let is_multiline = seq_nodes.iter().any(|each_seq_node| match each_seq_node.get_span() {
+            Some((start_pos, _)) => start_pos.line != first_line,
+            None => false,
+        });
+
+        if is_multiline {
+            for (seq_i, each_seq_node) in seq_nodes.iter().enumerate() {
+                if seq_i != 0 {
+                    self.output += &format!("\n{}: ", FORMATTER_INDENT.repeat(2));
+                }
+
+                self.output += &FCPEGFormatter::to_seq(each_seq_node)?;
+            }
+        } else {
+            self.output += &FCPEGFormatter::to_pure_choice(choice_node)?;
+        }
+
+        self.output += ",";
+        return Ok(());
+    }
+
+    // note: 選択の区切り文字 ":" 及び "," は ":" に統一する
+    fn to_pure_choice(choice_node: &SyntaxNode) -> FCPEGResult<String> {
+        let mut seqs = Vec::<String>::new();
+
+        for each_seq_node in choice_node.find_child_nodes(vec![".Rule.Seq"]) {
+            seqs.push(FCPEGFormatter::to_seq(each_seq_node)?);
+        }
+
+        return Ok(seqs.join(" : "));
+    }
+
+    fn to_seq(seq_node: &SyntaxNode) -> FCPEGResult<String> {
+        let mut seq_elems = Vec::<String>::new();
+
+        for each_seq_elem_node in seq_node.find_child_nodes(vec![".Rule.SeqElem"]) {
+            seq_elems.push(FCPEGFormatter::to_seq_elem(each_seq_elem_node)?);
+        }
+
+        return Ok(seq_elems.join(" "));
+    }
+
+    fn to_seq_elem(seq_elem_node: &SyntaxNode) -> FCPEGResult<String> {
+        let mut s = String::new();
+
+        for each_elem in seq_elem_node.get_reflectable_children() {
+            let node = match each_elem {
+                SyntaxNodeElement::Node(node) => node,
+                SyntaxNodeElement::Leaf(_) => continue,
+            };
+
+            match FCPEGFormatter::get_node_name(node) {
+                ".Rule.Cut" | ".Rule.Lookahead" => s += &node.join_child_leaf_values(),
+                ".Rule.Choice" => {
+                    let choice_node = node.get_node_child_at(0)?;
+                    s += &format!("({})", FCPEGFormatter::to_pure_choice(choice_node)?);
+                },
+                ".Rule.Expr" => s += &FCPEGFormatter::to_expr(node.get_node_child_at(0)?)?,
+                ".Rule.Loop" => s += &FCPEGFormatter::to_loop(node)?,
+                ".Rule.RandomOrder" => {
+                    s += "^";
+
+                    match node.find_first_child_node(vec![".Rule.RandomOrderRange"]) {
+                        Some(range_node) => s += &format!("[{}]", FCPEGFormatter::to_range(range_node, "-")),
+                        None => (),
+                    }
+                },
+                ".Rule.ASTReflectionStyle" => {
+                    // note: "##" 以外は "#" が非反映的であるため反映名のみが値となる
+                    let style = node.join_child_leaf_values();
+
+                    if style == "##" {
+                        s += "##";
+                    } else {
+                        s += &format!("#{}", style);
+                    }
+                },
+                _ => (),
+            }
+        }
+
+        return Ok(s);
+    }
+
+    fn to_expr(expr_node: &SyntaxNode) -> FCPEGResult<String> {
+        let s = match FCPEGFormatter::get_node_name(expr_node) {
+            ".Rule.ArgID" => format!("${}", expr_node.join_child_leaf_values()),
+            ".Rule.ID" => {
+                let mut s = FCPEGFormatter::to_chain_id(expr_node.get_node_child_at(0)?);
+
+                match expr_node.find_first_child_node(vec![".Rule.Generics"]) {
+                    Some(generics_node) => s += &format!("<{}>", FCPEGFormatter::to_seq_list(generics_node)?),
+                    None => (),
+                }
+
+                match expr_node.find_first_child_node(vec![".Rule.Template"]) {
+                    Some(template_node) => s += &format!("({})", FCPEGFormatter::to_seq_list(template_node)?),
+                    None => (),
+                }
+
+                s
+            },
+            ".Rule.Str" => {
+                let mut s = String::new();
+
+                for each_elem in expr_node.get_reflectable_children() {
+                    match each_elem {
+                        SyntaxNodeElement::Node(node) => s += &format!("\\{}", node.join_child_leaf_values()),
+                        SyntaxNodeElement::Leaf(leaf) => s += &leaf.value,
+                    }
+                }
+
+                format!("\"{}\"", s)
+            },
+            ".Rule.CharClass" => format!("[{}]", expr_node.join_child_leaf_values()),
+            ".Rule.Wildcard" => ".".to_string(),
+            _ => String::new(),
+        };
+
+        return Ok(s);
+    }
+
+    fn to_loop(loop_node: &SyntaxNode) -> FCPEGResult<String> {
+        return match loop_node.find_first_child_node(vec![".Rule.LoopRange"]) {
+            Some(range_node) => Ok(format!("{{{}}}", FCPEGFormatter::to_range(range_node, ","))),
+            None => Ok(loop_node.join_child_leaf_values()),
+        };
+    }
+
+    // note: MaxNumGroup ノードが存在すれば区切り文字を出力する
+    fn to_range(range_node: &SyntaxNode, delimiter: &str) -> String {
+        let mut s = match range_node.find_first_child_node(vec!["MinNum"]) {
+            Some(min_num_node) => min_num_node.join_child_leaf_values(),
+            None => String::new(),
+        };
+
+        match range_node.find_first_child_node(vec!["MaxNumGroup"]) {
+            Some(max_num_group_node) => {
+                s += delimiter;
+
+                match max_num_group_node.find_first_child_node(vec!["MaxNum"]) {
+                    Some(max_num_node) => s += &max_num_node.join_child_leaf_values(),
+                    None => (),
+                }
+            },
+            None => (),
+        }
+
+        return s;
+    }
+
+    fn to_chain_id(chain_id_node: &SyntaxNode) -> String {
+        let ids = chain_id_node.find_child_nodes(vec![".Misc.SingleID"]).iter().map(|each_node| each_node.join_child_leaf_values()).collect::<Vec<String>>();
+        return ids.join(".");
+    }
+
+    fn to_arg_id_list(node: &SyntaxNode) -> String {
+        let arg_ids = node.find_child_nodes(vec![".Rule.ArgID"]).iter().map(|each_node| format!("${}", each_node.join_child_leaf_values())).collect::<Vec<String>>();
+        return arg_ids.join(", ");
+    }
+
+    fn to_seq_list(node: &SyntaxNode) -> FCPEGResult<String> {
+        let mut seqs = Vec::<String>::new();
+
+        for each_seq_node in node.find_child_nodes(vec![".Rule.Seq"]) {
+            seqs.push(FCPEGFormatter::to_seq(each_seq_node)?);
+        }
+
+        return Ok(seqs.join(", "));
+    }
+
+    fn get_node_name(node: &SyntaxNode) -> &str {
+        return match &node.ast_reflection_style {
+            ASTReflectionStyle::Reflection(name) => name.as_str(),
+            _ => "",
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::formatter::*;
+
+    const UNFORMATTED_SOURCE: &str = "[Main]  {\n\n+start   Main.Main,\n  %  comment,\n\n\n  Main <-Item+   \"\\z\",\n Item<-Num \",\"?  :  Str,\n    Str <- \"\\\"\" [a-z]* \"\\\"\"\n        , Num <- [0-9]+ \n   : \"-\" [0-9]+ ,\n}\n";
+
+    const FORMATTED_SOURCE: &str = "[Main]{\n    + start Main.Main,\n    %  comment,\n\n    Main <- Item+ \"\\z\",\n    Item <- Num \",\"? : Str,\n    Str <- \"\\\"\" [a-z]* \"\\\"\",\n    Num <- [0-9]+\n        : \"-\" [0-9]+,\n}\n";
+
+    fn format(src: &str) -> FCPEGResult<String> {
+        return FCPEGFormatter::format("test.fcpeg".to_string(), Box::new(src.to_string()));
+    }
+
+    fn parse(fcpeg_source: &str, input: &str) -> String {
+        let parser = FCPEGParser::load_from_str("test.fcpeg".to_string(), fcpeg_source.to_string(), String::new(), HashMap::new(), &MemorySourceResolver::new(), true).unwrap();
+        return parser.parse_from_str("input.txt".to_string(), input.to_string()).unwrap().to_sexpr(false);
+    }
+
+    #[test]
+    fn source_is_normalized() {
+        assert_eq!(format(UNFORMATTED_SOURCE).unwrap(), FORMATTED_SOURCE);
+        assert_eq!(format(FORMATTED_SOURCE).unwrap(), FORMATTED_SOURCE);
+    }
+
+    #[test]
+    fn formatted_source_parses_same_input() {
+        let input = "1,\"ab\"-3";
+        assert_eq!(parse(FORMATTED_SOURCE, input), parse(UNFORMATTED_SOURCE, input));
+    }
+
+    #[test]
+    fn invalid_source_is_rejected() {
+        match format("[Main]{\n    Main <- ,\n}\n") {
+            Err(FCPEGError::SyntaxParsing(_)) => (),
+            _ => panic!("unexpected result"),
+        }
+    }
+}
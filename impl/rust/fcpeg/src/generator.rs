@@ -0,0 +1,428 @@
+use std::collections::*;
+
+use crate::*;
+use crate::parser::*;
+use crate::rule::*;
+use crate::tree::*;
+
+pub type GeneratedGroupResult = FCPEGResult<Option<Vec<SyntaxNodeElement>>>;
+
+// note: <ジェネリクス引数 ID, 引数のグループ>; 同じ ID の引数は外側の規則呼び出しのものを優先する
+pub type ArgumentEnvironment<'a> = BTreeMap<String, &'a Box<RuleGroup>>;
+
+// spec: 規則マップから SyntaxParser と同じ構文木を生成する再帰下降パーサの Rust コードを生成する
+// note: 生成されたコードは規則の検査関数を SyntaxParser の検査処理に渡すため、実行時に FCPEG ファイルを読み込まない
+// note: 開始規則から到達可能な規則のみを生成する; ジェネリクス引数を持つ規則は引数毎に生成する
+pub struct FCPEGGenerator<'a> {
+    rule_map: &'a RuleMap,
+    // note: <生成対象のキー, 関数名>
+    fn_name_map: HashMap<String, String>,
+    fn_sources: Vec<String>,
+}
+
+impl<'a> FCPEGGenerator<'a> {
+    pub fn generate(rule_map: &'a RuleMap) -> FCPEGResult<String> {
+        let mut generator = FCPEGGenerator {
+            rule_map: rule_map,
+            fn_name_map: HashMap::new(),
+            fn_sources: Vec::new(),
+        };
+
+        let start_rule_fn_name = generator.generate_rule(&rule_map.start_rule_id, &rule_map.start_rule_pos, &ArgumentEnvironment::new())?;
+
+        let memoization_limit = match rule_map.memoization_limit {
+            Some(v) => format!("Some({})", v),
+            None => "None".to_string(),
+        };
+
+        let mut s = String::new();
+        s += "// This file was generated by FCPEG. Do not edit it manually.\n\n";
+        s += "use fcpeg::FCPEGResult;\n";
+        s += "use fcpeg::generator::*;\n";
+        s += "use fcpeg::parser::SyntaxParser;\n";
+        s += "use fcpeg::tree::*;\n\n";
+        s += &format!("pub const START_RULE_ID: &str = {:?};\n\n", rule_map.start_rule_id);
+        s += "pub fn parse(src_path: String, src_content: String, enable_memoization: bool) -> FCPEGResult<SyntaxTree> {\n";
+        s += &format!("    return SyntaxParser::parse_generated(src_path, Box::new(src_content), enable_memoization, {}, START_RULE_ID, {});\n", memoization_limit, start_rule_fn_name);
+        s += "}\n";
+
+        for each_source in &generator.fn_sources {
+            s += "\n";
+            s += each_source;
+        }
+
+        return Ok(s);
+    }
+
+    // ret: 規則を検査する関数名
+    fn generate_rule(&mut self, rule_id: &String, pos: &CharacterPosition, env: &ArgumentEnvironment<'a>) -> FCPEGResult<String> {
+        let rule = match self.rule_map.rule_map.get(rule_id) {
+            Some(v) => v,
+            None => {
+                return Err(SyntaxParsingLog::UnknownRuleID {
+                    pos: pos.clone(),
+                    rule_id: rule_id.clone(),
+                }.into());
+            },
+        };
+
+        let key = format!("rule {} {}", rule_id, FCPEGGenerator::to_env_key(env));
+
+        match self.fn_name_map.get(&key) {
+            Some(v) => return Ok(v.clone()),
+            None => (),
+        }
+
+        // note: 再帰的な規則呼び出しのため本体を生成する前に関数名を登録する
+        let fn_name = format!("parse_rule_{}", self.fn_name_map.len());
+        self.fn_name_map.insert(key, fn_name.clone());
+
+        let group = &rule.group;
+        let group_fn_name = self.generate_group(group, &group.elem_order, None, env)?;
+
+        let ast_reflection_style = SyntaxParser::get_rule_ast_reflection_style(group);
+        let is_memoized = self.rule_map.memoized_group_flags[group.memo_id];
+        let mut s = format!("// rule: {}\n", rule_id);
+        s += &format!("fn {}(p: &mut SyntaxParser) -> FCPEGResult<Option<SyntaxNodeElement>> {{\n", fn_name);
+        s += &format!("    return p.parse_rule_with({:?}, {}, {}, &{}, {});\n", rule_id, group.memo_id, is_memoized, FCPEGGenerator::to_style_code(ast_reflection_style), group_fn_name);
+        s += "}\n";
+
+        self.fn_sources.push(s);
+        return Ok(fn_name);
+    }
+
+    // spec: 先読み・繰り返し・要素の順序を含むグループの検査関数を生成する
+    // note: loop_range は順不同グループの子グループの繰り返し範囲を置き換える場合に指定する
+    // ret: グループを検査する関数名
+    fn generate_group(&mut self, group: &'a RuleGroup, parent_elem_order: &RuleElementOrder, loop_range: Option<&RuleElementLoopRange>, env: &ArgumentEnvironment<'a>) -> FCPEGResult<String> {
+        let loop_range = match loop_range {
+            Some(v) => v,
+            None => &group.loop_range,
+        };
+
+        let (min_count, max_count) = loop_range.to_tuple();
+        let key = format!("group {} {} {:?} {}", group.uuid, parent_elem_order, (min_count, max_count), FCPEGGenerator::to_env_key(env));
+
+        match self.fn_name_map.get(&key) {
+            Some(v) => return Ok(v.clone()),
+            None => (),
+        }
+
+        let fn_name = format!("parse_group_{}", self.fn_name_map.len());
+        self.fn_name_map.insert(key, fn_name.clone());
+
+        if max_count != -1 && min_count as isize > max_count {
+            return Err(SyntaxParsingLog::InvalidLoopRange {
+                msg: format!("invalid loop range {{{},{}}} was detected", min_count, max_count),
+            }.into());
+        }
+
+        let mut code = match parent_elem_order {
+            RuleElementOrder::Random(random_order_loop_range) => {
+                let tar_elems = match group.sub_elems.first() {
+                    Some(RuleElement::Group(tar_parent_group)) => &tar_parent_group.sub_elems,
+                    Some(_) => {
+                        return Err(SyntaxParsingLog::InvalidRuleElementStructure {
+                            uuid: group.uuid,
+                            msg: "child element of random order group must be a group".to_string(),
+                        }.into());
+                    },
+                    None => {
+                        return Err(SyntaxParsingLog::InvalidRuleElementStructure {
+                            uuid: group.uuid,
+                            msg: "random order group must have a child group".to_string(),
+                        }.into());
+                    },
+                };
+
+                let mut subgroup_arms = Vec::<String>::new();
+
+                for (subgroup_i, each_elem) in tar_elems.iter().enumerate() {
+                    match each_elem {
+                        RuleElement::Group(subgroup) => subgroup_arms.push(format!("{} => {}(p)", subgroup_i, self.generate_group(subgroup, &RuleElementOrder::Sequential, Some(random_order_loop_range), env)?)),
+                        _ => (),
+                    }
+                }
+
+                subgroup_arms.push("_ => Ok(None)".to_string());
+                format!("p.parse_random_order_with({}, |p, subgroup_i| match subgroup_i {{ {} }})", tar_elems.len(), subgroup_arms.join(", "))
+            },
+            RuleElementOrder::Sequential => format!("{}(p)", self.generate_group_elems(&fn_name, group, env)?),
+        };
+
+        code = format!("p.parse_loop_with({}, {}, |p| {})", min_count, max_count, code);
+
+        if !group.lookahead_kind.is_none() {
+            code = format!("p.parse_lookahead_with({}, |p| {})", group.lookahead_kind == RuleElementLookaheadKind::Positive, code);
+        }
+
+        let is_memoized = self.rule_map.memoized_group_flags[group.memo_id];
+        let mut s = format!("fn {}(p: &mut SyntaxParser) -> GeneratedGroupResult {{\n", fn_name);
+        s += &format!("    return p.parse_group_with({}, {}, |p| {});\n", group.memo_id, is_memoized, code);
+        s += "}\n";
+
+        self.fn_sources.push(s);
+        return Ok(fn_name);
+    }
+
+    // spec: グループの子要素を順に検査する関数を生成する
+    // ret: 子要素を検査する関数名
+    fn generate_group_elems(&mut self, group_fn_name: &String, group: &'a RuleGroup, env: &ArgumentEnvironment<'a>) -> FCPEGResult<String> {
+        let fn_name = format!("{}_elems", group_fn_name);
+        let is_single_elem = group.sub_elems.len() == 1;
+        let has_cut = group.sub_elems.iter().any(|each_elem| matches!(each_elem, RuleElement::Cut(_)));
+
+        let mut stmts = Vec::<String>::new();
+
+        for each_elem in &group.sub_elems {
+            let elem_code = match each_elem {
+                RuleElement::Group(each_group) => {
+                    match each_group.kind {
+                        RuleGroupKind::Choice => {
+                            let mut choice_arms = Vec::<String>::new();
+
+                            for (choice_i, each_sub_elem) in each_group.sub_elems.iter().enumerate() {
+                                match each_sub_elem {
+                                    RuleElement::Group(each_sub_group) => {
+                                        let sub_group_fn_name = self.generate_group(each_sub_group, &each_group.elem_order, None, env)?;
+                                        choice_arms.push(format!("{} => match {}(p)? {{ Some(v) => Ok(Some((v, {}))), None => Ok(None) }}", choice_i, sub_group_fn_name, FCPEGGenerator::to_style_code(&each_sub_group.ast_reflection_style)));
+                                    },
+                                    _ => (),
+                                }
+                            }
+
+                            choice_arms.push("_ => Ok(None)".to_string());
+                            format!("p.parse_choice_with(&mut children, is_cut, {}, {}, |p, choice_i| match choice_i {{ {} }})?", is_single_elem, each_group.sub_elems.len(), choice_arms.join(", "))
+                        },
+                        RuleGroupKind::Sequence => {
+                            let sub_group_fn_name = self.generate_group(each_group, &each_group.elem_order, None, env)?;
+                            format!("p.parse_sequence_group_with(&mut children, is_cut, {}, &{}, {})?", is_single_elem, FCPEGGenerator::to_style_code(&each_group.ast_reflection_style), sub_group_fn_name)
+                        },
+                    }
+                },
+                RuleElement::Expression(each_expr) => format!("p.parse_sequence_expr_with(&mut children, is_cut, |p| {})?", self.generate_expr(each_expr, env)?),
+                RuleElement::Cut(_) => {
+                    stmts.push("    is_cut = true;\n    p.cut_backtrack_src_indexes();\n".to_string());
+                    continue;
+                },
+            };
+
+            stmts.push(format!("    if !{} {{\n        return Ok(None);\n    }}\n", elem_code));
+        }
+
+        let mut s = String::new();
+
+        if has_cut {
+            s += "#[allow(unused_assignments)]\n";
+        }
+
+        s += &format!("fn {}(p: &mut SyntaxParser) -> GeneratedGroupResult {{\n", fn_name);
+        s += "    let mut children = Vec::<SyntaxNodeElement>::new();\n";
+
+        if has_cut {
+            s += "    let mut is_cut = false;\n\n";
+        } else {
+            s += "    let is_cut = false;\n\n";
+        }
+
+        s += &stmts.join("\n");
+
+        if !stmts.is_empty() {
+            s += "\n";
+        }
+
+        s += "    return Ok(Some(children));\n";
+        s += "}\n";
+
+        self.fn_sources.push(s);
+        return Ok(fn_name);
+    }
+
+    // ret: 先読み・繰り返しを含む式を検査するコード
+    fn generate_expr(&mut self, expr: &'a RuleExpression, env: &ArgumentEnvironment<'a>) -> FCPEGResult<String> {
+        let (min_count, max_count) = expr.loop_range.to_tuple();
+
+        if max_count != -1 && min_count as isize > max_count {
+            return Err(SyntaxParsingLog::InvalidLoopRange {
+                msg: format!("invalid loop range {{{},{}}} was detected", min_count, max_count),
+            }.into());
+        }
+
+        let style_code = format!("&{}", FCPEGGenerator::to_style_code(&expr.ast_reflection_style));
+
+        let raw_code = match &expr.kind {
+            RuleExpressionKind::ArgId => {
+                let arg_group = match env.get(&expr.value) {
+                    Some(v) => *v,
+                    None => {
+                        return Err(SyntaxParsingLog::UnknownGenericsArgumentID {
+                            arg_id: expr.value.clone(),
+                        }.into());
+                    },
+                };
+
+                format!("p.parse_arg_id_with({}, {})", style_code, self.generate_group(arg_group, &RuleElementOrder::Sequential, None, env)?)
+            },
+            RuleExpressionKind::CharClass => {
+                let char_class = match &expr.char_class {
+                    Some(v) => v,
+                    None => {
+                        return Err(SyntaxParsingLog::InvalidCharClassFormat {
+                            value: expr.to_string(),
+                        }.into());
+                    },
+                };
+
+                format!("p.parse_char_class_with({:?}, {}, {})", expr.value, FCPEGGenerator::to_char_class_matcher_code(char_class.get_ranges()), style_code)
+            },
+            RuleExpressionKind::Id => {
+                // note: ジェネリクス引数を持たない規則の呼び出しでは引数を引き継がない
+                let callee_env = match self.rule_map.rule_map.get(&expr.value) {
+                    Some(rule) if !rule.generics_arg_ids.is_empty() => env.clone(),
+                    _ => ArgumentEnvironment::new(),
+                };
+
+                let rule_fn_name = self.generate_rule(&expr.value, &expr.pos, &callee_env)?;
+                format!("p.parse_id_with({:?}, {}, {})", expr.value, style_code, rule_fn_name)
+            },
+            RuleExpressionKind::IdWithArgs { generics_args, template_args } => {
+                let rule_id = &expr.value;
+
+                if rule_id == "JOIN" {
+                    match generics_args.first() {
+                        Some(tar_arg) if generics_args.len() == 1 => {
+                            if !template_args.is_empty() {
+                                return Err(SyntaxParsingLog::InvalidTemplateArgumentLength {
+                                    pos: expr.pos.clone(),
+                                    expected_arg_len: 0,
+                                }.into());
+                            }
+
+                            let arg_group_fn_name = self.generate_group(tar_arg, &RuleElementOrder::Sequential, None, env)?;
+                            format!("p.parse_join_with({}, {})", style_code, arg_group_fn_name)
+                        },
+                        _ => {
+                            return Err(SyntaxParsingLog::InvalidGenericsArgumentLength {
+                                pos: expr.pos.clone(),
+                                expected_arg_len: 1,
+                            }.into());
+                        },
+                    }
+                } else {
+                    if PRIMITIVE_RULE_NAMES.contains(&rule_id.as_str()) {
+                        return Err(SyntaxParsingLog::UncoveredPrimitiveRule {
+                            pos: expr.pos.clone(),
+                            rule_name: rule_id.clone(),
+                        }.into());
+                    }
+
+                    let rule = match self.rule_map.rule_map.get(rule_id) {
+                        Some(v) => v,
+                        None => {
+                            return Err(SyntaxParsingLog::UnknownRuleID {
+                                pos: expr.pos.clone(),
+                                rule_id: rule_id.clone(),
+                            }.into());
+                        },
+                    };
+
+                    if generics_args.len() != rule.generics_arg_ids.len() {
+                        return Err(SyntaxParsingLog::InvalidGenericsArgumentLength {
+                            pos: expr.pos.clone(),
+                            expected_arg_len: rule.generics_arg_ids.len(),
+                        }.into());
+                    }
+
+                    if template_args.len() != rule.template_arg_ids.len() {
+                        return Err(SyntaxParsingLog::InvalidTemplateArgumentLength {
+                            pos: expr.pos.clone(),
+                            expected_arg_len: rule.template_arg_ids.len(),
+                        }.into());
+                    }
+
+                    // note: SyntaxParser は引数マップのスタックを外側から探索するため、既に存在する ID は置き換えない
+                    let mut callee_env = env.clone();
+
+                    for (each_arg_id, each_arg_group) in rule.generics_arg_ids.iter().zip(generics_args.iter()) {
+                        callee_env.entry(each_arg_id.clone()).or_insert(each_arg_group);
+                    }
+
+                    let rule_fn_name = self.generate_rule(rule_id, &expr.pos, &callee_env)?;
+                    format!("p.parse_id_with({:?}, {}, {})", rule_id, style_code, rule_fn_name)
+                }
+            },
+            RuleExpressionKind::String => format!("p.parse_string({:?}, {})", expr.value, style_code),
+            RuleExpressionKind::Wildcard => format!("p.parse_wildcard({})", style_code),
+        };
+
+        let mut code = format!("p.parse_loop_with({}, {}, |p| {})", min_count, max_count, raw_code);
+
+        if !expr.lookahead_kind.is_none() {
+            code = format!("p.parse_lookahead_with({}, |p| {})", expr.lookahead_kind == RuleElementLookaheadKind::Positive, code);
+        }
+
+        return Ok(code);
+    }
+
+    fn to_style_code(ast_reflection_style: &ASTReflectionStyle) -> String {
+        return match ast_reflection_style {
+            ASTReflectionStyle::Reflection(elem_name) => format!("ASTReflectionStyle::Reflection({:?}.to_string())", elem_name),
+            ASTReflectionStyle::NoReflection => "ASTReflectionStyle::NoReflection".to_string(),
+            ASTReflectionStyle::Expansion => "ASTReflectionStyle::Expansion".to_string(),
+        };
+    }
+
+    // ret: 文字が文字クラスに含まれるかを判定するクロージャのコード
+    fn to_char_class_matcher_code(ranges: &[(char, char)]) -> String {
+        if ranges.is_empty() {
+            return "|_| false".to_string();
+        }
+
+        let patterns = ranges.iter().map(|(start_char, end_char)| {
+            if start_char == end_char {
+                format!("{:?}", start_char)
+            } else {
+                format!("{:?}..={:?}", start_char, end_char)
+            }
+        }).collect::<Vec<String>>();
+
+        return format!("|c| matches!(c, {})", patterns.join(" | "));
+    }
+
+    fn to_env_key(env: &ArgumentEnvironment<'a>) -> String {
+        return env.iter().map(|(each_arg_id, each_group)| format!("{}={}", each_arg_id, each_group.uuid)).collect::<Vec<String>>().join(",");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::*;
+
+    use crate::*;
+
+    const FCPEG_SOURCE: &str = "[Main]{\n    + start Syntax.Main,\n}\n\n[Syntax]{\n    Main <- Sum (\",\" Sum)*,\n    Sum <- Sum \"+\" Num : Num,\n    Num <- [0-9]+ : List<Num>,\n    List<$T> <- \"(\" $T (\",\" $T)* \")\",\n    Word <- JOIN<[a-z]+>,\n}\n";
+
+    fn generate_source() -> String {
+        let parser = FCPEGParser::load_from_str("test.fcpeg".to_string(), FCPEG_SOURCE.to_string(), String::new(), HashMap::new(), &MemorySourceResolver::new(), true).unwrap();
+        return parser.generate_source().unwrap();
+    }
+
+    #[test]
+    fn generated_source_is_stable_across_loads() {
+        let source = generate_source();
+
+        for _ in 0..8 {
+            assert!(generate_source() == source);
+        }
+    }
+
+    #[test]
+    fn only_reachable_rules_are_generated() {
+        let source = generate_source();
+
+        assert!(source.contains("pub const START_RULE_ID: &str = \".Syntax.Main\";"));
+        assert!(source.contains("// rule: .Syntax.Sum\n"));
+        assert!(source.contains("// rule: .Syntax.List\n"));
+        assert!(!source.contains("// rule: .Syntax.Word\n"));
+    }
+}
@@ -0,0 +1,179 @@
+use std::sync::Arc;
+
+use crate::*;
+use crate::parser::*;
+use crate::rule::*;
+use crate::tree::*;
+
+// spec: 入力の範囲 [start, end) を text に置き換える編集; 位置は CR を含む入力の文字単位
+// note: 複数の編集は順に適用し、各編集の位置は直前までの編集を適用した入力に対するものとする
+#[derive(Clone)]
+pub struct TextEdit {
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+}
+
+impl TextEdit {
+    pub fn new(start: usize, end: usize, text: String) -> TextEdit {
+        return TextEdit {
+            start: start,
+            end: end,
+            text: text,
+        };
+    }
+
+    // spec: 入力に編集を適用する
+    // ret: CR を除去した入力における置き換え範囲の開始位置・終了位置と挿入された文字数
+    pub fn apply_to(&self, src: &mut String) -> FCPEGResult<(usize, usize, usize)> {
+        let src_len = src.chars().count();
+
+        if self.start > self.end || self.end > src_len {
+            return Err(SyntaxParsingLog::InvalidTextEdit {
+                start: self.start,
+                end: self.end,
+                src_len: src_len,
+            }.into());
+        }
+
+        let mut start_byte_i = src.len();
+        let mut end_byte_i = src.len();
+        // note: SyntaxParser は CR を除去した入力を扱うため、範囲の手前及び範囲内の CR を数える
+        let mut cr_count_before_start = 0usize;
+        let mut cr_count_in_range = 0usize;
+
+        for (char_i, (byte_i, each_char)) in src.char_indices().enumerate() {
+            if char_i == self.start {
+                start_byte_i = byte_i;
+            }
+
+            if char_i == self.end {
+                end_byte_i = byte_i;
+                break;
+            }
+
+            if each_char == '\r' {
+                if char_i < self.start {
+                    cr_count_before_start += 1;
+                } else {
+                    cr_count_in_range += 1;
+                }
+            }
+        }
+
+        src.replace_range(start_byte_i..end_byte_i, &self.text);
+
+        let start_i = self.start - cr_count_before_start;
+        let end_i = self.end - cr_count_before_start - cr_count_in_range;
+        let inserted_len = self.text.chars().filter(|each_char| *each_char != '\r').count();
+        return Ok((start_i, end_i, inserted_len));
+    }
+}
+
+// spec: 再パースに用いる構文木; 構文木と合わせてパース時の入力及びメモ化結果を保持する
+pub struct IncrementalSyntaxTree {
+    // note: メモ化 ID は規則マップ毎に割り当てられるため、同じ規則マップでの再パースのみ結果を再利用する
+    rule_map: Arc<RuleMap>,
+    src_path: String,
+    // note: CR を含む入力
+    src: String,
+    tree: SyntaxTree,
+    // note: 再パース時に新しい構文木へ移される
+    memoized_map: Option<Box<MemoizationMap>>,
+}
+
+impl IncrementalSyntaxTree {
+    pub fn new(rule_map: Arc<RuleMap>, src_path: String, src: String, tree: SyntaxTree, memoized_map: Option<Box<MemoizationMap>>) -> IncrementalSyntaxTree {
+        return IncrementalSyntaxTree {
+            rule_map: rule_map,
+            src_path: src_path,
+            src: src,
+            tree: tree,
+            memoized_map: memoized_map,
+        };
+    }
+
+    pub fn get_tree(&self) -> &SyntaxTree {
+        return &self.tree;
+    }
+
+    pub fn into_tree(self) -> SyntaxTree {
+        return self.tree;
+    }
+
+    pub fn get_source(&self) -> &String {
+        return &self.src;
+    }
+
+    pub fn get_source_path(&self) -> &String {
+        return &self.src_path;
+    }
+
+    pub fn is_parsed_with(&self, rule_map: &Arc<RuleMap>) -> bool {
+        return Arc::ptr_eq(&self.rule_map, rule_map);
+    }
+
+    pub fn take_memoized_map(&mut self) -> Option<Box<MemoizationMap>> {
+        return self.memoized_map.take();
+    }
+
+    pub fn set_memoized_map(&mut self, memoized_map: Box<MemoizationMap>) {
+        self.memoized_map = Some(memoized_map);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    const FCPEG_SOURCE: &str = "[Main]{\n    Main <- Line* \"\\z\",\n    Line <- Key \"=\" Value \";\" \"\\n\",\n    Key <- [a-z]+,\n    Value <- List : Num,\n    List <- \"(\" Value (\",\" Value)* \")\",\n    Num <- [0-9]+,\n}\n";
+    const INPUT_SOURCE: &str = "a=1;\nlist=(1,(2,3),4);\nb=2;\n";
+
+    // spec: 編集後の入力を再パースした構文木が入力全体をパースした構文木と位置情報を含めて一致することを検査する
+    fn assert_reparse_equals_full_parse(src: &str, edits: Vec<TextEdit>) {
+        let parser = load_test_parser(FCPEG_SOURCE);
+        let mut prev_tree = parser.parse_from_str_into_incremental("input.txt".to_string(), src.to_string()).unwrap();
+        let tree = parser.reparse(&mut prev_tree, &edits).unwrap();
+
+        let mut edited_src = src.to_string();
+
+        for each_edit in &edits {
+            each_edit.apply_to(&mut edited_src).unwrap();
+        }
+
+        let expected_tree = parser.parse_from_str("input.txt".to_string(), edited_src.clone()).unwrap();
+        assert_eq!(tree.get_source(), &edited_src);
+        assert_eq!(tree.get_tree().to_sexpr(false), expected_tree.to_sexpr(false));
+    }
+
+    #[test]
+    fn reparse_after_insert_equals_full_parse() {
+        assert_reparse_equals_full_parse(INPUT_SOURCE, vec![TextEdit::new(2, 2, "0".to_string())]);
+        assert_reparse_equals_full_parse(INPUT_SOURCE, vec![TextEdit::new(5, 5, "c=(5,6);\n".to_string())]);
+    }
+
+    #[test]
+    fn reparse_after_delete_equals_full_parse() {
+        assert_reparse_equals_full_parse(INPUT_SOURCE, vec![TextEdit::new(0, 5, String::new())]);
+        assert_reparse_equals_full_parse(INPUT_SOURCE, vec![TextEdit::new(12, 18, String::new())]);
+    }
+
+    #[test]
+    fn reparse_after_replace_equals_full_parse() {
+        assert_reparse_equals_full_parse(INPUT_SOURCE, vec![TextEdit::new(13, 18, "7".to_string())]);
+        // note: 複数の編集及び CR を含む入力
+        assert_reparse_equals_full_parse("a=1;\r\nb=2;\r\n", vec![TextEdit::new(2, 3, "(1,2)".to_string()), TextEdit::new(12, 13, "33".to_string())]);
+    }
+
+    #[test]
+    fn reparse_moves_memoized_map_out_of_prev_tree() {
+        let parser = load_test_parser(FCPEG_SOURCE);
+        let mut prev_tree = parser.parse_from_str_into_incremental("input.txt".to_string(), INPUT_SOURCE.to_string()).unwrap();
+        let edits = vec![TextEdit::new(2, 3, "9".to_string())];
+        let tree = parser.reparse(&mut prev_tree, &edits).unwrap();
+
+        // note: 2 回目は prev_tree にメモ化結果が残っていないため入力全体をパースするが、結果は一致する
+        assert!(prev_tree.take_memoized_map().is_none());
+        assert_eq!(parser.reparse(&mut prev_tree, &edits).unwrap().get_tree().to_sexpr(false), tree.get_tree().to_sexpr(false));
+    }
+}
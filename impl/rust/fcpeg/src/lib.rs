@@ -1,54 +1,413 @@
+pub mod action;
+pub mod arena;
 pub mod block;
 pub mod config;
+pub mod deserializer;
 pub mod file;
+pub mod formatter;
+pub mod generator;
+pub mod incremental;
 pub mod parser;
 pub mod rule;
+pub mod serializer;
+pub mod source;
 pub mod tree;
+pub mod typed;
+pub mod visitor;
 
-use std::cell::RefCell;
+use std::any::type_name;
 use std::collections::*;
-use std::rc::Rc;
+use std::fmt::{Display, Formatter};
+use std::io::Read;
 use std::sync::Arc;
 
+use crate::action::*;
+use crate::arena::*;
 use crate::block::*;
+use crate::config::*;
+use crate::deserializer::*;
 use crate::file::*;
+use crate::generator::*;
+use crate::incremental::*;
 use crate::parser::*;
 use crate::rule::*;
 use crate::tree::*;
+use crate::typed::*;
 
 use rustnutlib::console::*;
-use rustnutlib::file::*;
 
+pub type FCPEGResult<T> = std::result::Result<T, FCPEGError>;
+
+// spec: 公開 API が返すエラー; コンソールへの出力は get_logs() を利用する
+#[derive(Clone, Debug)]
+// note: 大きなログは Box で保持して Result のサイズを抑える
+pub enum FCPEGError {
+    BlockParsing(Box<BlockParsingLog>),
+    Configuration(ConfigurationLog),
+    Deserialization(DeserializationLog),
+    File(FileLog),
+    // note: 複数のエラーが同時に検出された場合
+    Multiple(Vec<FCPEGError>),
+    SemanticAction(SemanticActionLog),
+    SyntaxParsing(Box<SyntaxParsingLog>),
+    Tree(TreeLog),
+}
+
+impl FCPEGError {
+    pub fn get_logs(&self) -> Vec<ConsoleLog> {
+        return match self {
+            FCPEGError::BlockParsing(log) => vec![log.get_log()],
+            FCPEGError::Configuration(log) => vec![log.get_log()],
+            FCPEGError::Deserialization(log) => vec![log.get_log()],
+            FCPEGError::File(log) => vec![log.get_log()],
+            FCPEGError::Multiple(errs) => errs.iter().flat_map(|each_err| each_err.get_logs()).collect(),
+            FCPEGError::SemanticAction(log) => vec![log.get_log()],
+            FCPEGError::SyntaxParsing(log) => vec![log.get_log()],
+            FCPEGError::Tree(log) => vec![log.get_log()],
+        };
+    }
+
+    // ret: エラーが位置情報を持たない場合は None
+    pub fn get_position(&self) -> Option<CharacterPosition> {
+        return match self {
+            FCPEGError::BlockParsing(log) => log.get_position(),
+            FCPEGError::Multiple(errs) => errs.iter().find_map(|each_err| each_err.get_position()),
+            FCPEGError::SyntaxParsing(log) => log.get_position(),
+            _ => None,
+        };
+    }
+}
+
+// spec: ログ毎にタイトルを出力し、説明を字下げした行として続ける
+impl Display for FCPEGError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        let mut lines = Vec::<String>::new();
+
+        for each_log in self.get_logs() {
+            lines.push(each_log.title.clone());
+
+            for each_desc in &each_log.descs {
+                lines.push(format!("    {}", each_desc.replace("\t", " ")));
+            }
+        }
+
+        return write!(f, "{}", lines.join("\n"));
+    }
+}
+
+impl std::error::Error for FCPEGError {}
+
+impl From<BlockParsingLog> for FCPEGError {
+    fn from(log: BlockParsingLog) -> FCPEGError {
+        return FCPEGError::BlockParsing(Box::new(log));
+    }
+}
+
+impl From<ConfigurationLog> for FCPEGError {
+    fn from(log: ConfigurationLog) -> FCPEGError {
+        return FCPEGError::Configuration(log);
+    }
+}
+
+impl From<DeserializationLog> for FCPEGError {
+    fn from(log: DeserializationLog) -> FCPEGError {
+        return FCPEGError::Deserialization(log);
+    }
+}
+
+impl From<FileLog> for FCPEGError {
+    fn from(log: FileLog) -> FCPEGError {
+        return FCPEGError::File(log);
+    }
+}
+
+impl From<SemanticActionLog> for FCPEGError {
+    fn from(log: SemanticActionLog) -> FCPEGError {
+        return FCPEGError::SemanticAction(log);
+    }
+}
+
+impl From<SyntaxParsingLog> for FCPEGError {
+    fn from(log: SyntaxParsingLog) -> FCPEGError {
+        return FCPEGError::SyntaxParsing(Box::new(log));
+    }
+}
+
+impl From<TreeLog> for FCPEGError {
+    fn from(log: TreeLog) -> FCPEGError {
+        return FCPEGError::Tree(log);
+    }
+}
+
+// spec: 一度読み込んだ規則マップをスレッド間で共有する; パース毎に独立した SyntaxParser を生成する
+#[derive(Clone)]
 pub struct FCPEGParser {
-    cons: Rc<RefCell<Console>>,
-    rule_map: Arc<Box<RuleMap>>,
+    rule_map: Arc<RuleMap>,
+    // note: ブロック解析時の警告
+    warnings: Vec<BlockParsingLog>,
     enable_memoization: bool,
+    action_map: Arc<SemanticActionMap>,
 }
 
 impl FCPEGParser {
-    pub fn load(cons: Rc<RefCell<Console>>, fcpeg_file_path: String, lib_fcpeg_file_map: HashMap<String, String>, enable_memoization: bool) -> ConsoleResult<FCPEGParser> {
-        let mut fcpeg_file_map = FCPEGFileMap::load(cons.clone(), fcpeg_file_path, lib_fcpeg_file_map)?;
-        let rule_map = BlockParser::get_rule_map(cons.clone(), &mut fcpeg_file_map, true)?;
+    pub fn load(fcpeg_file_path: String, lib_fcpeg_file_map: HashMap<String, String>, enable_memoization: bool) -> FCPEGResult<FCPEGParser> {
+        let mut fcpeg_file_map = FCPEGFileMap::load(fcpeg_file_path, lib_fcpeg_file_map)?;
+        return FCPEGParser::from_file_map(&mut fcpeg_file_map, enable_memoization);
+    }
+
+    // note: fcpeg_file_path は仮想ファイル名; サブファイル及び外部ファイルは resolver から取得する
+    pub fn load_from_str(fcpeg_file_path: String, fcpeg_source: String, config_source: String, lib_fcpeg_file_map: HashMap<String, String>, resolver: &dyn SourceResolver, enable_memoization: bool) -> FCPEGResult<FCPEGParser> {
+        let mut fcpeg_file_map = FCPEGFileMap::load_from_str(fcpeg_file_path, fcpeg_source, config_source, lib_fcpeg_file_map, resolver)?;
+        return FCPEGParser::from_file_map(&mut fcpeg_file_map, enable_memoization);
+    }
+
+    fn from_file_map(fcpeg_file_map: &mut FCPEGFileMap, enable_memoization: bool) -> FCPEGResult<FCPEGParser> {
+        let mut warnings = Vec::<BlockParsingLog>::new();
+        let rule_map = BlockParser::get_rule_map(fcpeg_file_map, true, &mut warnings)?;
 
         let parser = FCPEGParser {
-            cons: cons,
             rule_map: rule_map,
+            warnings: warnings,
             enable_memoization: enable_memoization,
+            action_map: Arc::new(HashMap::new()),
         };
 
         return Ok(parser);
     }
 
-    pub fn parse(&mut self, input_file_path: String) -> ConsoleResult<SyntaxTree> {
-        let input_file_content = match FileMan::read_all(&input_file_path) {
-            Ok(v) => Box::new(v),
-            Err(e) => {
-                self.cons.borrow_mut().append_log(e.get_log());
-                return Err(());
-            },
+    // spec: 構築済みの規則マップからパーサを生成する; fcpeg_macro の fcpeg! 及び include_fcpeg! が展開した規則マップを用いる
+    pub fn from_rule_map(rule_map: RuleMap, enable_memoization: bool) -> FCPEGParser {
+        return FCPEGParser {
+            rule_map: Arc::new(rule_map),
+            warnings: Vec::new(),
+            enable_memoization: enable_memoization,
+            action_map: Arc::new(HashMap::new()),
         };
+    }
+
+    pub fn get_warnings(&self) -> &Vec<BlockParsingLog> {
+        return &self.warnings;
+    }
+
+    // spec: 規則マップから再帰下降パーサの Rust コードを生成する; build.rs から利用すると実行時に FCPEG ファイルを読み込まずにパースできる
+    pub fn generate_source(&self) -> FCPEGResult<String> {
+        return FCPEGGenerator::generate(&self.rule_map);
+    }
+
+    // spec: 規則マップの反映名及び繰り返し範囲から構文木に対応する型定義と構文木からの変換コードを生成する
+    pub fn generate_typed_ast_source(&self) -> FCPEGResult<String> {
+        return TypedASTGenerator::generate(&self.rule_map);
+    }
+
+    // spec: 規則 ID に意味アクションを登録する; parse_from_path_with_actions() 及び parse_from_str_with_actions() で規則の成功時に呼び出す
+    // note: 同じ規則 ID に登録済みのアクションは置き換える
+    pub fn add_action<T: 'static, F: Fn(&SemanticActionArgs) -> T + Send + Sync + 'static>(&mut self, rule_id: &str, action: F) -> FCPEGResult<()> {
+        if !self.rule_map.rule_map.contains_key(rule_id) {
+            return Err(SemanticActionLog::UnknownRuleID {
+                rule_id: rule_id.to_string(),
+            }.into());
+        }
+
+        let action: SemanticAction = Arc::new(move |args| Box::new(action(args)));
+        Arc::make_mut(&mut self.action_map).insert(rule_id.to_string(), action);
+        return Ok(());
+    }
+
+    pub fn parse_from_path(&self, input_file_path: String) -> FCPEGResult<SyntaxTree> {
+        let input_file_content = FileSourceResolver::new().read_source(&input_file_path)?;
+        return self.parse_from_str(input_file_path, input_file_content);
+    }
 
-        let tree = SyntaxParser::parse(self.cons.clone(), self.rule_map.clone(), input_file_path, input_file_content, self.enable_memoization)?;
+    // note: input_file_path は CharacterPosition に設定される仮想ファイル名
+    pub fn parse_from_str(&self, input_file_path: String, input_source: String) -> FCPEGResult<SyntaxTree> {
+        let tree = SyntaxParser::parse(self.rule_map.clone(), input_file_path, Box::new(input_source), self.enable_memoization)?;
         return Ok(tree);
     }
+
+    // spec: 意味アクションを持つ規則の部分木を構築せずにアクションの値に置き換え、開始規則の値を返す
+    // note: 開始規則に意味アクションが登録されていない場合・値の型が T でない場合はエラー
+    pub fn parse_from_path_with_actions<T: 'static>(&self, input_file_path: String) -> FCPEGResult<T> {
+        let input_file_content = FileSourceResolver::new().read_source(&input_file_path)?;
+        return self.parse_from_str_with_actions(input_file_path, input_file_content);
+    }
+
+    pub fn parse_from_str_with_actions<T: 'static>(&self, input_file_path: String, input_source: String) -> FCPEGResult<T> {
+        let value = SyntaxParser::parse_with_actions(self.rule_map.clone(), self.action_map.clone(), input_file_path, Box::new(input_source), self.enable_memoization)?;
+
+        return match value.downcast::<T>() {
+            Ok(v) => Ok(*v),
+            Err(_) => Err(SemanticActionLog::UnexpectedValueType {
+                rule_id: self.rule_map.start_rule_id.clone(),
+                type_name: type_name::<T>().to_string(),
+            }.into()),
+        };
+    }
+
+    // spec: Read から入力を必要に応じて読み込みながらパースする; 巨大な入力を全体を読み込まずに扱う
+    // note: 切断点 (失敗時に戻りうる最も手前の位置) より手前の入力及びメモ化結果は破棄される
+    pub fn parse_from_reader<'a>(&self, input_file_path: String, reader: impl Read + 'a) -> FCPEGResult<SyntaxTree> {
+        return SyntaxParser::parse_from_reader(self.rule_map.clone(), input_file_path, Box::new(reader), self.enable_memoization);
+    }
+
+    // spec: 構文木をアリーナ表現に変換して返す
+    // note: パーサはリーフを直接アリーナに出力せず、通常の構文木を構築してから変換する; 解析中のメモリ使用量は通常のパースより小さくならない
+    pub fn parse_from_path_into_arena(&self, input_file_path: String) -> FCPEGResult<ArenaSyntaxTree> {
+        let input_file_content = FileSourceResolver::new().read_source(&input_file_path)?;
+        return self.parse_from_str_into_arena(input_file_path, input_file_content);
+    }
+
+    pub fn parse_from_str_into_arena(&self, input_file_path: String, input_source: String) -> FCPEGResult<ArenaSyntaxTree> {
+        // note: SyntaxParser と同様に CR を除去した入力をリーフの範囲の参照先とする
+        let src = Arc::<str>::from(input_source.replace("\r", ""));
+        let tree = SyntaxParser::parse(self.rule_map.clone(), input_file_path.clone(), Box::new(input_source), self.enable_memoization)?;
+        return Ok(ArenaSyntaxTree::from_tree(tree, src, Some(input_file_path)));
+    }
+
+    // spec: 再パースに用いるため、入力及びメモ化結果を構文木と合わせて保持する
+    pub fn parse_from_path_into_incremental(&self, input_file_path: String) -> FCPEGResult<IncrementalSyntaxTree> {
+        let input_file_content = FileSourceResolver::new().read_source(&input_file_path)?;
+        return self.parse_from_str_into_incremental(input_file_path, input_file_content);
+    }
+
+    pub fn parse_from_str_into_incremental(&self, input_file_path: String, input_source: String) -> FCPEGResult<IncrementalSyntaxTree> {
+        return SyntaxParser::parse_into_incremental(self.rule_map.clone(), input_file_path, Box::new(input_source), self.enable_memoization);
+    }
+
+    // spec: 前回の構文木の入力に編集を順に適用して再びパースする; 編集範囲の影響を受けないメモ化結果及び部分木を再利用する
+    // note: メモ化が無効であれば再利用する結果がないため入力全体をパースする
+    // note: 再利用したメモ化結果は prev_tree から新しい構文木へ移るため prev_tree を可変参照で受け取る; 移した後の prev_tree から再パースした場合は入力全体をパースする
+    // note: 失敗した場合はメモ化結果が prev_tree に戻るため、以降の編集を追加して prev_tree から再パースできる
+    pub fn reparse(&self, prev_tree: &mut IncrementalSyntaxTree, edits: &Vec<TextEdit>) -> FCPEGResult<IncrementalSyntaxTree> {
+        return SyntaxParser::parse_incrementally(self.rule_map.clone(), prev_tree, edits, self.enable_memoization);
+    }
+
+    // spec: 構成ファイルの Recovery で同期点が設定された規則についてエラー回復を行う
+    // ret: 部分的な構文木と回復したエラーの一覧
+    pub fn parse_from_path_with_recovery(&self, input_file_path: String) -> FCPEGResult<(SyntaxTree, Vec<FCPEGError>)> {
+        let input_file_content = FileSourceResolver::new().read_source(&input_file_path)?;
+        return self.parse_from_str_with_recovery(input_file_path, input_file_content);
+    }
+
+    pub fn parse_from_str_with_recovery(&self, input_file_path: String, input_source: String) -> FCPEGResult<(SyntaxTree, Vec<FCPEGError>)> {
+        return SyntaxParser::parse_with_recovery(self.rule_map.clone(), input_file_path, Box::new(input_source), self.enable_memoization);
+    }
+}
+
+// note: FCPEGParser が Send + Sync であることをコンパイル時に検査する
+#[allow(dead_code)]
+fn assert_parser_is_send_and_sync() {
+    fn assert_send_and_sync<T: Send + Sync>() {}
+    assert_send_and_sync::<FCPEGParser>();
+    assert_send_and_sync::<SyntaxTree>();
+    assert_send_and_sync::<ArenaSyntaxTree>();
+    assert_send_and_sync::<IncrementalSyntaxTree>();
+}
+
+// spec: 各モジュールの単体テストで用いる; 構成ファイル及び外部ファイルを持たない FCPEG ソースからメモ化を有効にしたパーサを読み込む
+#[cfg(test)]
+pub(crate) fn load_test_parser(fcpeg_source: &str) -> FCPEGParser {
+    return FCPEGParser::load_from_str("test.fcpeg".to_string(), fcpeg_source.to_string(), String::new(), HashMap::new(), &MemorySourceResolver::new(), true).unwrap();
+}
+
+// spec: 各モジュールの単体テストで用いる; 1 バイトずつ読み込ませ、UTF-8 の文字が読み込みの境界をまたぐようにする
+#[cfg(test)]
+pub(crate) struct ByteReader {
+    bytes: Vec<u8>,
+    byte_i: usize,
+}
+
+#[cfg(test)]
+impl ByteReader {
+    pub fn new(bytes: &[u8]) -> ByteReader {
+        return ByteReader {
+            bytes: bytes.to_vec(),
+            byte_i: 0,
+        };
+    }
+}
+
+#[cfg(test)]
+impl Read for ByteReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.byte_i >= self.bytes.len() || buf.is_empty() {
+            return Ok(0);
+        }
+
+        buf[0] = self.bytes[self.byte_i];
+        self.byte_i += 1;
+        return Ok(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use crate::*;
+
+    #[test]
+    fn error_is_displayed_with_log_title_and_descriptions() {
+        let parser = load_test_parser("[Main]{\n    Main <- \"a\",\n}\n");
+        let e = match parser.parse_from_str("input.txt".to_string(), "b".to_string()) {
+            Ok(_) => panic!("unexpected success"),
+            Err(e) => e,
+        };
+        let msg = e.to_string();
+
+        assert!(msg.starts_with("expected .Main.Main at input.txt:1:1, found `b`\n    "), "{}", msg);
+        assert!(!msg.contains('\t'), "{}", msg);
+        assert!(format!("{:?}", e).starts_with("SyntaxParsing(UnexpectedInput"));
+    }
+
+    #[test]
+    fn error_converts_into_boxed_error() {
+        fn parse() -> std::result::Result<SyntaxTree, Box<dyn Error>> {
+            let parser = load_test_parser("[Main]{\n    Main <- \"a\",\n}\n");
+            return Ok(parser.parse_from_str("input.txt".to_string(), "b".to_string())?);
+        }
+
+        assert!(parse().is_err());
+    }
+
+    // spec: 1 つのパーサを共有する複数のスレッドのパース結果が互いに影響しないことを検査する
+    #[test]
+    fn parser_is_shared_between_threads() {
+        let parser = load_test_parser("[Main]{\n    Main <- Num (\",\" Num)* \"\\z\",\n    Num <- [0-9]+,\n}\n");
+        let inputs = (0..8).map(|each_i| if each_i == 5 { "1,x".to_string() } else { (0..each_i + 1).map(|v| (v * 7).to_string()).collect::<Vec<String>>().join(",") }).collect::<Vec<String>>();
+        let expected_results = inputs.iter().map(|each_input| parser.parse_from_str("input.txt".to_string(), each_input.clone()).map(|v| v.to_sexpr(false))).collect::<Vec<FCPEGResult<String>>>();
+
+        let shared_parser = &parser;
+        let results = std::thread::scope(|scope| {
+            let workers = inputs.iter().map(|each_input| scope.spawn(move || shared_parser.parse_from_str("input.txt".to_string(), each_input.clone()).map(|v| v.to_sexpr(false)))).collect::<Vec<_>>();
+            return workers.into_iter().map(|each_worker| each_worker.join().unwrap()).collect::<Vec<FCPEGResult<String>>>();
+        });
+
+        for (each_result, each_expected_result) in results.iter().zip(&expected_results) {
+            match (each_result, each_expected_result) {
+                (Ok(result_sexpr), Ok(expected_sexpr)) => assert_eq!(result_sexpr, expected_sexpr),
+                (Err(e), Err(expected_e)) => assert_eq!(e.to_string(), expected_e.to_string()),
+                _ => panic!("result differs from serial parsing"),
+            }
+        }
+
+        assert!(results[5].is_err());
+        assert_ne!(results[0].as_ref().unwrap(), results[7].as_ref().unwrap());
+    }
+
+    // note: 1 バイトずつ読み込んだ場合も文字列からパースした場合と同じ結果になる
+    #[test]
+    fn parsing_from_reader_matches_parsing_from_str() {
+        let parser = load_test_parser("[Main]{\n    Main <- Line* \"\\z\",\n    Line <- Word (\",\" Word)* \"\\n\",\n    Word <- [a-zあ-ん]+,\n}\n");
+
+        for each_input in ["ab,いう\r\nc\n", "ab,\nいう,1\n"] {
+            let str_result = parser.parse_from_str("input.txt".to_string(), each_input.to_string());
+            let reader_result = parser.parse_from_reader("input.txt".to_string(), ByteReader::new(each_input.as_bytes()));
+
+            match (str_result, reader_result) {
+                (Ok(str_tree), Ok(reader_tree)) => assert_eq!(reader_tree.to_sexpr(false), str_tree.to_sexpr(false)),
+                (Err(str_err), Err(reader_err)) => assert_eq!(reader_err.to_string(), str_err.to_string()),
+                _ => panic!("results differ for {:?}", each_input),
+            }
+        }
+    }
 }
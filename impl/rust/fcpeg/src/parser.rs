@@ -1,28 +1,63 @@
-use std::cell::RefCell;
+use std::any::Any;
 use std::collections::*;
-use std::rc::Rc;
+use std::fmt::*;
+use std::io::Read;
 use std::sync::Arc;
 
+use crate::*;
+use crate::action::*;
 use crate::block::*;
+use crate::incremental::*;
 use crate::rule::*;
+use crate::source::*;
 use crate::tree::*;
 
 use colored::*;
 
-use regex::*;
-
 use rustnutlib::*;
 use rustnutlib::console::*;
 
 use uuid::Uuid;
 
+// spec: 入力位置で期待された要素
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExpectedElement {
+    CharClass(String),
+    Rule(String),
+    String(String),
+    Wildcard,
+}
+
+impl ExpectedElement {
+    pub fn is_rule(&self) -> bool {
+        return matches!(self, ExpectedElement::Rule(_));
+    }
+}
+
+impl Display for ExpectedElement {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let s = match self {
+            ExpectedElement::CharClass(value) => format!("`{}`", value),
+            ExpectedElement::Rule(rule_id) => rule_id.clone(),
+            ExpectedElement::String(value) if value == "\0" => "end of input".to_string(),
+            ExpectedElement::String(value) => format!("`{}`", value.replace("\n", "\\n").replace("\t", "\\t")),
+            ExpectedElement::Wildcard => "any character".to_string(),
+        };
+
+        return write!(f, "{}", s);
+    }
+}
+
+#[derive(Clone, Debug)]
 pub enum SyntaxParsingLog {
     InvalidCharClassFormat { value: String },
     InvalidGenericsArgumentLength { pos: CharacterPosition, expected_arg_len: usize },
     InvalidTemplateArgumentLength { pos: CharacterPosition, expected_arg_len: usize },
     InvalidLoopRange { msg: String },
     InvalidRuleElementStructure { uuid: Uuid, msg: String },
-    NoSucceededRule { pos: CharacterPosition, rule_id: String, rule_stack: Vec<(CharacterPosition, String)> },
+    InvalidTextEdit { start: usize, end: usize, src_len: usize },
+    // note: source_line は pos の行の内容
+    UnexpectedInput { pos: CharacterPosition, expected: Vec<ExpectedElement>, found: String, source_line: String },
     TooLongRepetition { loop_limit: usize },
     UncoveredPrimitiveRule { pos: CharacterPosition, rule_name: String },
     UnknownGenericsArgumentID { arg_id: String },
@@ -31,6 +66,20 @@ pub enum SyntaxParsingLog {
     UnknownRuleID { pos: CharacterPosition, rule_id: String },
 }
 
+impl SyntaxParsingLog {
+    // ret: ログが位置情報を持たない場合は None
+    pub fn get_position(&self) -> Option<CharacterPosition> {
+        return match self {
+            SyntaxParsingLog::InvalidGenericsArgumentLength { pos, expected_arg_len: _ } => Some(pos.clone()),
+            SyntaxParsingLog::InvalidTemplateArgumentLength { pos, expected_arg_len: _ } => Some(pos.clone()),
+            SyntaxParsingLog::UnexpectedInput { pos, expected: _, found: _, source_line: _ } => Some(pos.clone()),
+            SyntaxParsingLog::UncoveredPrimitiveRule { pos, rule_name: _ } => Some(pos.clone()),
+            SyntaxParsingLog::UnknownRuleID { pos, rule_id: _ } => Some(pos.clone()),
+            _ => None,
+        };
+    }
+}
+
 impl ConsoleLogger for SyntaxParsingLog {
     fn get_log(&self) -> ConsoleLog {
         return match self {
@@ -39,7 +88,24 @@ impl ConsoleLogger for SyntaxParsingLog {
             SyntaxParsingLog::InvalidTemplateArgumentLength { pos, expected_arg_len } => log!(Error, format!("invalid template argument length; expected {} argument(s)", expected_arg_len), format!("pos:\t{}", pos)),
             SyntaxParsingLog::InvalidLoopRange { msg } => log!(Error, format!("invalid loop range"), format!("{}", msg.bright_black())),
             SyntaxParsingLog::InvalidRuleElementStructure { uuid, msg } => log!(Error, format!("invalid rule element structure"), format!("uuid:\t{}", uuid), format!("{}", msg.bright_black())),
-            SyntaxParsingLog::NoSucceededRule { pos, rule_id, rule_stack } => log!(Error, format!("no succeeded rule '{}'", rule_id), format!("at:\t{}", pos), format!("rule stack:\t{}", rule_stack.iter().map(|(each_pos, each_rule_id)| format!("\n\t\t{} at {}", each_rule_id, each_pos)).collect::<Vec<String>>().join(""))),
+            SyntaxParsingLog::InvalidTextEdit { start, end, src_len } => log!(Error, format!("invalid text edit range {}..{}", start, end), format!("source length:\t{}", src_len)),
+            SyntaxParsingLog::UnexpectedInput { pos, expected, found, source_line } => {
+                let expected_text = match expected.len() {
+                    0 => "nothing".to_string(),
+                    1 => expected.first().unwrap().to_string(),
+                    _ => format!("one of {}", expected.iter().map(|each_elem| each_elem.to_string()).collect::<Vec<String>>().join(", ")),
+                };
+
+                let line_num_text = (pos.line + 1).to_string();
+                // note: タブ文字の幅を揃えるため列までの文字を空白に置換する
+                let caret_indent = source_line.chars().take(pos.column).map(|each_char| if each_char == '\t' { '\t' } else { ' ' }).collect::<String>();
+
+                log!(Error,
+                    format!("expected {} at {}, found {}", expected_text, pos, found),
+                    format!("{} | {}", line_num_text, source_line),
+                    format!("{} | {}{}", " ".repeat(line_num_text.len()), caret_indent, "^".bright_red())
+                )
+            },
             SyntaxParsingLog::TooLongRepetition { loop_limit } => log!(Error, format!("too long repetition over {}", loop_limit)),
             SyntaxParsingLog::UncoveredPrimitiveRule { pos, rule_name } => log!(Error, format!("uncovered primitive rule '{}'", rule_name), format!("pos:\t{}", pos)),
             SyntaxParsingLog::UnknownGenericsArgumentID { arg_id } => log!(Error, format!("unknown generics argument id '{}'", arg_id)),
@@ -64,163 +130,816 @@ impl ArgumentMap {
     }
 }
 
+impl Default for ArgumentMap {
+    fn default() -> ArgumentMap {
+        return ArgumentMap::new();
+    }
+}
+
+// note: 左再帰の種を参照するリーフの名前; 値は種の ID で、パース中にのみ現れる
+const SEED_REFERENCE_LEAF_NAME: &str = "#SeedRef";
+
+// spec: 左再帰の検出と種の成長に用いる規則呼び出し毎のデータ
+pub struct LeftRecursionSeed {
+    // note: 種を参照するリーフの値に用いる
+    pub id: usize,
+    // note: 規則呼び出しのネスト数; 種を参照した結果がどの呼び出しに依存するかの判定に用いる
+    pub depth: usize,
+    pub src_len: usize,
+    pub result: Option<SyntaxNodeElement>,
+    pub is_detected: bool,
+}
+
+// note: 失敗した結果は result が None で src_len が 0
+pub struct MemoizedResult {
+    pub memo_id: usize,
+    pub src_len: usize,
+    // note: 検査時に参照した入力の長さ; 先読みや失敗した要素の参照も含む
+    pub examined_len: usize,
+    // note: 再パース時に結果を移した入力位置の差; 移した場合の結果の位置情報は移す前のもの
+    pub src_i_delta: Option<isize>,
+    pub result: Option<Vec<SyntaxNodeElement>>,
+}
+
 pub struct MemoizationMap {
-    // note: HashMap<(group_uuid, src_i), (src_len, result)>
-    map: HashMap<(Uuid, usize), (usize, Option<Vec<SyntaxNodeElement>>)>,
+    // spec: 入力位置を添字とする packrat 表; 各行はその位置で検査したグループの結果を保持する
+    // note: 1 つの位置で検査されるグループ数は少ないため行内は線形探索する
+    rows: Vec<Vec<MemoizedResult>>,
+    // note: rows[0] の入力位置; 切断点より手前の行は破棄される
+    row_offset: usize,
+    result_count: usize,
+    // note: 結果の件数が上限に達した場合は以降の結果をメモ化しない
+    limit: Option<usize>,
+    // note: HashMap<(規則グループのメモ化 ID, src_i), seed>; 検査中の規則呼び出しのみを保持する
+    seed_map: HashMap<(usize, usize), LeftRecursionSeed>,
+    seed_count: usize,
 }
 
 impl MemoizationMap {
-    pub fn new() -> MemoizationMap {
+    pub fn new(src_len: usize, limit: Option<usize>) -> MemoizationMap {
+        let mut rows = Vec::<Vec<MemoizedResult>>::new();
+        rows.resize_with(src_len + 1, Vec::new);
+
         return MemoizationMap {
-            map: HashMap::new(),
+            rows: rows,
+            row_offset: 0,
+            result_count: 0,
+            limit: limit,
+            seed_map: HashMap::new(),
+            seed_count: 0,
         };
     }
 
-    pub fn push(&mut self, group_uuid: Uuid, src_i: usize, src_len: usize, result: Option<Vec<SyntaxNodeElement>>) {
-        self.map.insert((group_uuid, src_i), (src_len, result));
+    pub fn push_seed(&mut self, memo_id: usize, src_i: usize, depth: usize) {
+        let seed = LeftRecursionSeed {
+            id: self.seed_count,
+            depth: depth,
+            src_len: 0,
+            result: None,
+            is_detected: false,
+        };
+
+        self.seed_count += 1;
+        self.seed_map.insert((memo_id, src_i), seed);
     }
 
-    pub fn find(&self, pattern: &Uuid, src_i: usize) -> Option<(usize, Option<Vec<SyntaxNodeElement>>)> {
-        return match self.map.get(&(*pattern, src_i)) {
-            Some((src_len, result)) => Some((*src_len, result.clone())),
-            None => None,
-        };
+    pub fn find_seed_mut(&mut self, memo_id: usize, src_i: usize) -> Option<&mut LeftRecursionSeed> {
+        return self.seed_map.get_mut(&(memo_id, src_i));
+    }
+
+    // note: 検査中の規則呼び出しの数のみを走査する
+    pub fn find_seed_by_id(&self, seed_id: usize) -> Option<&LeftRecursionSeed> {
+        return self.seed_map.values().find(|each_seed| each_seed.id == seed_id);
+    }
+
+    pub fn remove_seed(&mut self, memo_id: usize, src_i: usize) -> Option<LeftRecursionSeed> {
+        return self.seed_map.remove(&(memo_id, src_i));
+    }
+
+    pub fn push(&mut self, memo_id: usize, src_i: usize, src_len: usize, examined_len: usize, result: Option<Vec<SyntaxNodeElement>>) {
+        if src_i < self.row_offset {
+            return;
+        }
+
+        let row_i = src_i - self.row_offset;
+
+        // note: 入力を読み込みながらパースする場合は表を拡張する
+        if row_i >= self.rows.len() {
+            self.rows.resize_with(row_i + 1, Vec::new);
+        }
+
+        let row = &mut self.rows[row_i];
+
+        match row.iter_mut().find(|each_result| each_result.memo_id == memo_id) {
+            Some(memoized_result) => {
+                memoized_result.src_len = src_len;
+                memoized_result.examined_len = examined_len;
+                memoized_result.src_i_delta = None;
+                memoized_result.result = result;
+                return;
+            },
+            None => (),
+        }
+
+        match self.limit {
+            Some(v) if self.result_count >= v => return,
+            _ => (),
+        }
+
+        row.push(MemoizedResult {
+            memo_id: memo_id,
+            src_len: src_len,
+            examined_len: examined_len,
+            src_i_delta: None,
+            result: result,
+        });
+
+        self.result_count += 1;
+    }
+
+    pub fn find(&self, memo_id: usize, src_i: usize) -> Option<&MemoizedResult> {
+        if src_i < self.row_offset {
+            return None;
+        }
+
+        let row = self.rows.get(src_i - self.row_offset)?;
+        return row.iter().find(|each_result| each_result.memo_id == memo_id);
+    }
+
+    pub fn discard_before(&mut self, src_i: usize) {
+        if src_i <= self.row_offset {
+            return;
+        }
+
+        let discarded_len = (src_i - self.row_offset).min(self.rows.len());
+
+        for each_row in self.rows.drain(..discarded_len) {
+            self.result_count -= each_row.len();
+        }
+
+        self.row_offset += discarded_len;
+    }
+
+    // spec: 入力の範囲 [start_i, end_i) が長さ inserted_len の文字列に置き換えられた場合に結果を移す
+    // note: 置き換えられた範囲を参照した結果は破棄し、範囲より後ろの結果は入力位置のずれを記録して移す
+    pub fn apply_edit(&mut self, start_i: usize, end_i: usize, inserted_len: usize) {
+        let src_i_delta = inserted_len as isize - (end_i - start_i) as isize;
+        let mut new_rows = Vec::<Vec<MemoizedResult>>::new();
+        new_rows.resize_with(self.rows.len() + inserted_len, Vec::new);
+        self.result_count = 0;
+
+        for (row_i, mut each_row) in self.rows.drain(..).enumerate() {
+            let src_i = self.row_offset + row_i;
+
+            let new_src_i = if src_i >= end_i {
+                // note: 入力位置の差が 0 でも手前の行が変化しうるため、移した結果は常に位置情報を付け直す
+                for each_result in each_row.iter_mut() {
+                    each_result.src_i_delta = match each_result.src_i_delta {
+                        Some(v) => Some(v + src_i_delta),
+                        None => Some(src_i_delta),
+                    };
+                }
+
+                src_i + inserted_len - (end_i - start_i)
+            } else if src_i < start_i {
+                each_row.retain(|each_result| src_i + each_result.examined_len <= start_i);
+                src_i
+            } else {
+                continue;
+            };
+
+            self.result_count += each_row.len();
+            new_rows[new_src_i - self.row_offset] = each_row;
+        }
+
+        self.rows = new_rows;
+    }
+
+    // ret: 左再帰が検出された検査中の規則呼び出しのうち最も手前の開始位置
+    pub fn get_min_detected_seed_src_i(&self) -> Option<usize> {
+        return self.seed_map.iter().filter(|(_, each_seed)| each_seed.is_detected).map(|((_, each_src_i), _)| *each_src_i).min();
     }
 }
 
-pub struct SyntaxParser {
-    cons: Rc<RefCell<Console>>,
-    rule_map: Arc<Box<RuleMap>>,
+pub struct SyntaxParser<'a> {
+    rule_map: Arc<RuleMap>,
     src_i: usize,
-    src_line: usize,
-    src_latest_line_i: usize,
     src_path: String,
-    src_content: Box<String>,
+    // note: 入力位置は文字単位
+    src: SourceBuffer<'a>,
     loop_limit: usize,
-    arg_maps: Box<Vec<ArgumentMap>>,
-    rule_stack: Box<Vec<(CharacterPosition, String)>>,
-    regex_map: Box<HashMap<String, Regex>>,
+    arg_maps: Vec<ArgumentMap>,
     memoized_map: Box<MemoizationMap>,
     enable_memoization: bool,
+    // note: 現在の規則呼び出しのネスト数
+    rule_depth: usize,
+    // note: 検査中に参照された左再帰の種のうち最も浅いもののネスト数
+    min_seed_depth: Option<usize>,
+    // note: 検査に失敗した最も遠い入力位置とその位置で期待された要素
+    farthest_src_i: usize,
+    farthest_expected_elems: Vec<ExpectedElement>,
+    // note: 先読み中の失敗は期待要素に含めない
+    lookahead_depth: usize,
+    enable_recovery: bool,
+    // note: <エラーノードの UUID, エラー>; 最終的な構文木に残ったエラーノードのみを報告する
+    recovered_error_map: HashMap<Uuid, SyntaxParsingLog>,
+    // spec: 失敗時に入力位置を戻して再び検査しうる位置のスタック; None を除いて下から昇順に並ぶ
+    // note: 選択の残りの候補・先読み・繰り返しの各回・順不同グループの開始位置; 切断演算子により無効化された位置は None
+    backtrack_src_indexes: Vec<Option<usize>>,
+    // note: 切断点より手前の入力及びメモ化結果を破棄するかどうか
+    enable_discarding: bool,
+    // note: 最も内側の選択の候補の開始時の backtrack_src_indexes の長さ; 規則の開始時及び先読み中は None
+    cut_base_backtrack_len: Option<usize>,
+    // note: 切断演算子に到達した後に失敗したかどうか; 最も内側の選択まで失敗を伝播させる
+    has_cut_failure: bool,
+    action_map: Arc<SemanticActionMap>,
+    // note: <値のノードの UUID, 意味アクションの値>; バックトラックで破棄された結果の値は破棄する
+    action_value_map: HashMap<Uuid, Box<dyn Any>>,
+    // note: 値のノードの UUID を生成順に並べたもの; 結果の破棄時に、検査開始後に生成された値を特定する
+    action_value_ids: Vec<Uuid>,
 }
 
-impl SyntaxParser {
-    pub fn parse(cons: Rc<RefCell<Console>>, rule_map: Arc<Box<RuleMap>>, src_path: String, src_content: Box<String>, enable_memoization: bool) -> ConsoleResult<SyntaxTree> {
+impl<'a> SyntaxParser<'a> {
+    pub fn parse(rule_map: Arc<RuleMap>, src_path: String, src_content: Box<String>, enable_memoization: bool) -> FCPEGResult<SyntaxTree> {
+        let src = SourceBuffer::from_string(src_path.clone(), src_content);
+        let (tree, _) = SyntaxParser::parse_source(rule_map, src_path, src, enable_memoization, false)?;
+        return Ok(tree);
+    }
+
+    // spec: Read から入力を必要に応じて読み込みながらパースする; 切断点より手前の入力及びメモ化結果を破棄する
+    pub fn parse_from_reader(rule_map: Arc<RuleMap>, src_path: String, reader: Box<dyn Read + 'a>, enable_memoization: bool) -> FCPEGResult<SyntaxTree> {
+        let src = SourceBuffer::from_reader(src_path.clone(), reader);
+        let (tree, _) = SyntaxParser::parse_source(rule_map, src_path, src, enable_memoization, false)?;
+        return Ok(tree);
+    }
+
+    // spec: 同期点が設定された規則の失敗時に入力を読み飛ばしてエラーノードを挿入し、パースを継続する
+    // ret: 部分的な構文木と回復したエラーの一覧
+    pub fn parse_with_recovery(rule_map: Arc<RuleMap>, src_path: String, src_content: Box<String>, enable_memoization: bool) -> FCPEGResult<(SyntaxTree, Vec<FCPEGError>)> {
+        let src = SourceBuffer::from_string(src_path.clone(), src_content);
+        return SyntaxParser::parse_source(rule_map, src_path, src, enable_memoization, true);
+    }
+
+    // spec: 意味アクションを持つ規則の成功時にアクションを呼び出し、規則の部分木の代わりに値を保持する
+    // ret: 開始規則の意味アクションの値
+    pub fn parse_with_actions(rule_map: Arc<RuleMap>, action_map: Arc<SemanticActionMap>, src_path: String, src_content: Box<String>, enable_memoization: bool) -> FCPEGResult<Box<dyn Any>> {
+        let src = SourceBuffer::from_string(src_path.clone(), src_content);
+        let mut parser = SyntaxParser::new(rule_map.clone(), src_path, src, enable_memoization, false);
+        parser.action_map = action_map;
+        let (tree, _) = parser.parse_start_rule()?;
+
+        let value = match tree.get_child_ref() {
+            SyntaxNodeElement::Node(node) => {
+                match node.sub_elems.as_slice() {
+                    [SyntaxNodeElement::Node(value_node)] if value_node.is_action_value_node() => parser.action_value_map.remove(&value_node.uuid),
+                    _ => None,
+                }
+            },
+            SyntaxNodeElement::Leaf(_) => None,
+        };
+
+        return match value {
+            Some(v) => Ok(v),
+            None => Err(SemanticActionLog::StartRuleValueNotFound {
+                rule_id: rule_map.start_rule_id.clone(),
+            }.into()),
+        };
+    }
+
+    // spec: 再パースに用いるため、入力及びメモ化結果を構文木と合わせて返す
+    pub fn parse_into_incremental(rule_map: Arc<RuleMap>, src_path: String, src_content: Box<String>, enable_memoization: bool) -> FCPEGResult<IncrementalSyntaxTree> {
+        let src = SourceBuffer::from_string(src_path.clone(), src_content.clone());
+        let mut parser = SyntaxParser::new(rule_map.clone(), src_path.clone(), src, enable_memoization, false);
+        let (tree, _) = parser.parse_start_rule()?;
+        return Ok(IncrementalSyntaxTree::new(rule_map, src_path, *src_content, tree, Some(parser.memoized_map)));
+    }
+
+    // spec: 前回の構文木の入力に編集を順に適用して再びパースする; 編集範囲を参照していないメモ化結果を再利用する
+    // note: 再利用したメモ化結果は新しい構文木に移る; 失敗した場合は編集前の位置に戻して prev_tree に返す
+    // note: 再利用した結果は期待要素を記録し直さないため、失敗した場合はメモ化結果を再利用せずにパースし直してエラーを生成する
+    pub fn parse_incrementally(rule_map: Arc<RuleMap>, prev_tree: &mut IncrementalSyntaxTree, edits: &Vec<TextEdit>, enable_memoization: bool) -> FCPEGResult<IncrementalSyntaxTree> {
+        let src_path = prev_tree.get_source_path().clone();
+        let mut src_content = prev_tree.get_source().clone();
+        let mut applied_edits = Vec::<(usize, usize, usize)>::new();
+
+        for each_edit in edits {
+            applied_edits.push(each_edit.apply_to(&mut src_content)?);
+        }
+
+        // note: 規則マップが異なる場合または prev_tree から既に再パースしている場合はメモ化結果を再利用せずにパースする
+        let prev_memoized_map = if prev_tree.is_parsed_with(&rule_map) {
+            prev_tree.take_memoized_map()
+        } else {
+            None
+        };
+
+        let mut memoized_map = match prev_memoized_map {
+            Some(v) => v,
+            None => return SyntaxParser::parse_into_incremental(rule_map, src_path, Box::new(src_content), enable_memoization),
+        };
+
+        for (start_i, end_i, inserted_len) in &applied_edits {
+            memoized_map.apply_edit(*start_i, *end_i, *inserted_len);
+        }
+
+        let src = SourceBuffer::from_string(src_path.clone(), Box::new(src_content.clone()));
+        let mut parser = SyntaxParser::new(rule_map.clone(), src_path.clone(), src, enable_memoization, false);
+        parser.memoized_map = memoized_map;
+
+        match parser.parse_start_rule() {
+            Ok((tree, _)) => return Ok(IncrementalSyntaxTree::new(rule_map, src_path, src_content, tree, Some(parser.memoized_map))),
+            Err(_) => {
+                let mut memoized_map = parser.memoized_map;
+
+                for (start_i, end_i, inserted_len) in applied_edits.iter().rev() {
+                    memoized_map.apply_edit(*start_i, *start_i + *inserted_len, *end_i - *start_i);
+                }
+
+                prev_tree.set_memoized_map(memoized_map);
+                return SyntaxParser::parse_into_incremental(rule_map, src_path, Box::new(src_content), enable_memoization);
+            },
+        }
+    }
+
+    // spec: 生成されたパーサの検査関数でパースする; start_rule は開始規則を検査する
+    // note: 入力全体を読み込んでパースする; エラー回復は行わない
+    pub fn parse_generated<F: FnMut(&mut SyntaxParser<'a>) -> FCPEGResult<Option<SyntaxNodeElement>>>(src_path: String, src_content: Box<String>, enable_memoization: bool, memoization_limit: Option<usize>, start_rule_id: &str, start_rule: F) -> FCPEGResult<SyntaxTree> {
+        let rule_map = Arc::new(RuleMap::from_start_rule_id(start_rule_id.to_string(), memoization_limit));
+        let src = SourceBuffer::from_string(src_path.clone(), src_content);
+        let mut parser = SyntaxParser::new(rule_map, src_path, src, enable_memoization, false);
+        let (tree, _) = parser.parse_start_rule_with(start_rule)?;
+        return Ok(tree);
+    }
+
+    fn parse_source(rule_map: Arc<RuleMap>, src_path: String, src: SourceBuffer<'a>, enable_memoization: bool, enable_recovery: bool) -> FCPEGResult<(SyntaxTree, Vec<FCPEGError>)> {
+        let mut parser = SyntaxParser::new(rule_map, src_path, src, enable_memoization, enable_recovery);
+        return parser.parse_start_rule();
+    }
+
+    fn new(rule_map: Arc<RuleMap>, src_path: String, src: SourceBuffer<'a>, enable_memoization: bool, enable_recovery: bool) -> SyntaxParser<'a> {
+        // note: エラー回復時は規則の開始位置から読み飛ばすため入力を破棄しない
+        let enable_discarding = src.is_streaming() && !enable_recovery;
+
         let mut parser = SyntaxParser {
-            cons: cons,
             rule_map: rule_map,
             src_i: 0,
-            src_line: 0,
-            src_latest_line_i: 0,
             src_path: src_path,
-            src_content: src_content,
+            src: src,
             loop_limit: 65536,
-            arg_maps: Box::new(Vec::new()),
-            rule_stack: Box::new(Vec::new()),
-            regex_map: Box::new(HashMap::new()),
-            memoized_map: Box::new(MemoizationMap::new()),
+            arg_maps: Vec::new(),
+            memoized_map: Box::new(MemoizationMap::new(0, None)),
             enable_memoization: enable_memoization,
+            rule_depth: 0,
+            min_seed_depth: None,
+            farthest_src_i: 0,
+            farthest_expected_elems: Vec::new(),
+            lookahead_depth: 0,
+            enable_recovery: enable_recovery,
+            recovered_error_map: HashMap::new(),
+            backtrack_src_indexes: Vec::new(),
+            enable_discarding: enable_discarding,
+            cut_base_backtrack_len: None,
+            has_cut_failure: false,
+            action_map: Arc::new(HashMap::new()),
+            action_value_map: HashMap::new(),
+            action_value_ids: Vec::new(),
         };
 
-        // note: 余分な改行コード 0x0d を排除する
-        loop {
-            match parser.src_content.find(0x0d as char) {
-                Some(v) => {
-                    let _ = parser.src_content.remove(v);
-                },
-                None => break,
-            }
+        // note: Read から読み込む場合は入力の長さが未確定のため、メモ化表は読み込みに応じて拡張する
+        parser.memoized_map = Box::new(MemoizationMap::new(parser.src.get_loaded_len(), parser.rule_map.memoization_limit));
+
+        // note: 繰り返し回数の上限は入力の長さ以上とする; 巨大な入力での正常な繰り返しを打ち切らない
+        if parser.loop_limit < parser.src.get_loaded_len() {
+            parser.loop_limit = parser.src.get_loaded_len();
         }
 
-        // EOF 用のヌル文字
-        *parser.src_content += "\0";
+        return parser;
+    }
+
+    fn parse_start_rule(&mut self) -> FCPEGResult<(SyntaxTree, Vec<FCPEGError>)> {
+        let start_rule_id = self.rule_map.start_rule_id.clone();
+        let start_rule_pos = self.rule_map.start_rule_pos.clone();
+        return self.parse_start_rule_with(|p| p.parse_rule(&start_rule_id, &start_rule_pos));
+    }
+
+    // note: f は開始規則を検査する
+    fn parse_start_rule_with<F: FnMut(&mut SyntaxParser<'a>) -> FCPEGResult<Option<SyntaxNodeElement>>>(&mut self, mut f: F) -> FCPEGResult<(SyntaxTree, Vec<FCPEGError>)> {
+        if !self.src.has_char_at(0) {
+            return Ok((SyntaxTree::from_node_args(Vec::new(), ASTReflectionStyle::Reflection(String::new())), Vec::new()));
+        }
 
-        let start_rule_id = parser.rule_map.start_rule_id.clone();
+        let result = f(self);
 
-        if parser.src_content.chars().count() == 0 {
-            return Ok(SyntaxTree::from_node_args(Vec::new(), ASTReflectionStyle::Reflection(String::new())));
+        // note: 読み込み時のエラーは構文エラーより優先する
+        match self.src.take_error() {
+            Some(e) => return Err(e),
+            None => (),
         }
 
-        let start_rule_pos = parser.rule_map.start_rule_pos.clone();
-        let mut root_node = match parser.parse_rule(&start_rule_id, &start_rule_pos)? {
+        let mut root_node = match result? {
             Some(v) => v,
-            None => {
-                parser.cons.borrow_mut().append_log(SyntaxParsingLog::NoSucceededRule {
-                    rule_id: start_rule_id.clone(),
-                    pos: parser.get_char_position(),
-                    rule_stack: *parser.rule_stack.clone(),
-                }.get_log());
-
-                return Err(());
-            },
+            None => return Err(self.get_unrecovered_error()),
         };
 
         // note: ルートは常に Reflectable
-        root_node.set_ast_reflection_style(ASTReflectionStyle::Reflection(start_rule_id.clone()));
+        root_node.set_ast_reflection_style(ASTReflectionStyle::Reflection(self.rule_map.start_rule_id.clone()));
 
         // note: 入力位置が length を超えると失敗
-        if parser.src_i < parser.src_content.chars().count() {
-            parser.cons.borrow_mut().append_log(SyntaxParsingLog::NoSucceededRule {
-                rule_id: start_rule_id.clone(),
-                pos: parser.get_char_position(),
-                rule_stack: *parser.rule_stack.clone(),
-            }.get_log());
+        if self.src.has_char_at(self.src_i) {
+            return Err(self.get_unrecovered_error());
+        }
+
+        let mut recovered_errs = Vec::<FCPEGError>::new();
+        self.collect_recovered_errors(&root_node, &mut recovered_errs);
+
+        return Ok((SyntaxTree::from_node(root_node), recovered_errs));
+    }
+
+    // ret: 回復したエラーがあれば最終的なエラーと合わせて返す
+    fn get_unrecovered_error(&mut self) -> FCPEGError {
+        let last_err = self.get_unexpected_input_log().into();
 
-            return Err(());
+        if self.recovered_error_map.is_empty() {
+            return last_err;
         }
 
-        return Ok(SyntaxTree::from_node(root_node));
+        let mut recovered_logs = self.recovered_error_map.drain().map(|(_, log)| log).collect::<Vec<SyntaxParsingLog>>();
+        recovered_logs.sort_by_key(|each_log| each_log.get_position().unwrap().index);
+
+        let mut errs = recovered_logs.into_iter().map(|each_log| each_log.into()).collect::<Vec<FCPEGError>>();
+        errs.push(last_err);
+        return FCPEGError::Multiple(errs);
     }
 
-    fn parse_rule(&mut self, rule_id: &String, pos: &CharacterPosition) -> ConsoleResult<Option<SyntaxNodeElement>> {
-        let rule_group = match self.rule_map.rule_map.get(rule_id) {
-            Some(rule) => rule.group.clone(),
+    fn collect_recovered_errors(&self, elem: &SyntaxNodeElement, errs: &mut Vec<FCPEGError>) {
+        match elem {
+            SyntaxNodeElement::Node(node) => {
+                if node.is_error_node() {
+                    match self.recovered_error_map.get(&node.uuid) {
+                        Some(log) => errs.push(log.clone().into()),
+                        None => (),
+                    }
+                }
+
+                for each_elem in &node.sub_elems {
+                    self.collect_recovered_errors(each_elem, errs);
+                }
+            },
+            SyntaxNodeElement::Leaf(_) => (),
+        }
+    }
+
+    fn parse_rule(&mut self, rule_id: &String, pos: &CharacterPosition) -> FCPEGResult<Option<SyntaxNodeElement>> {
+        // note: 規則グループを複製せずに参照するため規則マップの Arc を保持する
+        let rule_map = self.rule_map.clone();
+        let rule_group = match rule_map.rule_map.get(rule_id) {
+            Some(rule) => &rule.group,
             None => {
-                self.cons.borrow_mut().append_log(SyntaxParsingLog::UnknownRuleID {
+                return Err(SyntaxParsingLog::UnknownRuleID {
                     pos: pos.clone(),
                     rule_id: rule_id.clone(),
-                }.get_log());
-
-                return Err(());
+                }.into());
             },
         };
 
-        self.rule_stack.push((self.get_char_position(), rule_id.clone()));
+        let is_memoized = rule_map.memoized_group_flags[rule_group.memo_id];
+        let ast_reflection_style = SyntaxParser::get_rule_ast_reflection_style(rule_group);
 
-        return match self.parse_group(&rule_group.elem_order, &rule_group)? {
-            Some(v) => {
-                let mut ast_reflection_style = match &rule_group.sub_elems.get(0) {
-                    Some(v) => {
-                        match v {
-                            RuleElement::Group(sub_choice) => sub_choice.ast_reflection_style.clone(),
-                            RuleElement::Expression(_) => rule_group.ast_reflection_style.clone(),
-                        }
+        return self.parse_rule_with(rule_id, rule_group.memo_id, is_memoized, ast_reflection_style, |p| p.parse_group(&rule_group.elem_order, rule_group));
+    }
+
+    // spec: 規則のノードの反映スタイル; 規則グループの最初の子要素が選択であればその反映スタイルとする
+    pub fn get_rule_ast_reflection_style(rule_group: &RuleGroup) -> &ASTReflectionStyle {
+        return match rule_group.sub_elems.first() {
+            Some(RuleElement::Group(sub_choice)) => &sub_choice.ast_reflection_style,
+            _ => &rule_group.ast_reflection_style,
+        };
+    }
+
+    // spec: 規則を検査する; 左再帰の種の成長・成長後の結果のメモ化・期待要素の置き換え・エラー回復を行う
+    // note: memo_id は規則グループのメモ化 ID; 反映名が空の ast_reflection_style は規則 ID を反映名とする
+    // note: f は規則グループを検査する; 規則マップの解釈及び生成されたパーサの双方から呼び出される
+    pub fn parse_rule_with<F: FnMut(&mut SyntaxParser<'a>) -> FCPEGResult<Option<Vec<SyntaxNodeElement>>>>(&mut self, rule_id: &str, memo_id: usize, is_memoized: bool, ast_reflection_style: &ASTReflectionStyle, mut f: F) -> FCPEGResult<Option<SyntaxNodeElement>> {
+        let start_src_i = self.src_i;
+
+        // note: 検査中の同規則が同位置で呼び出された場合は左再帰として種を返す
+        match self.memoized_map.find_seed_mut(memo_id, start_src_i) {
+            Some(seed) => {
+                seed.is_detected = true;
+
+                let seed_depth = seed.depth;
+                let seed_src_len = seed.src_len;
+
+                // spec: 種を複製せず、種を参照するリーフのみを子要素とするノードを返す; 参照は種の成長時に種の子要素に置き換える
+                let seed_result = match &seed.result {
+                    Some(SyntaxNodeElement::Node(node)) => {
+                        let seed_ref_leaf = SyntaxNodeElement::from_leaf_args(CharacterPosition::get_empty(), seed.id.to_string(), ASTReflectionStyle::Reflection(SEED_REFERENCE_LEAF_NAME.to_string()));
+                        Some(SyntaxNodeElement::from_node_args(vec![seed_ref_leaf], node.ast_reflection_style.clone()))
                     },
-                    _ => rule_group.ast_reflection_style.clone(),
+                    _ => seed.result.clone(),
                 };
 
-                match &ast_reflection_style {
-                    ASTReflectionStyle::Reflection(elem_name) if *elem_name == String::new() => {
-                        // todo: 構成ファイルを ASTReflection に反映
-                        ast_reflection_style = ASTReflectionStyle::from_config(false, true, rule_id.clone());
-                    },
-                    _ => (),
+                self.min_seed_depth = match self.min_seed_depth {
+                    Some(v) if v <= seed_depth => Some(v),
+                    _ => Some(seed_depth),
+                };
+
+                self.src_i += seed_src_len;
+                return Ok(seed_result);
+            },
+            None => (),
+        }
+
+        let farthest_src_i_before = self.farthest_src_i;
+        let farthest_expected_elem_len_before = self.farthest_expected_elems.len();
+
+        let depth = self.rule_depth;
+        let outer_min_seed_depth = self.min_seed_depth;
+        self.min_seed_depth = None;
+        self.rule_depth += 1;
+
+        // note: 切断演算子の効果は規則内に限る
+        let outer_cut_base_backtrack_len = self.cut_base_backtrack_len;
+        self.cut_base_backtrack_len = None;
+        let outer_examined_end_i = self.src.get_examined_end_i();
+        self.src.set_examined_end_i(start_src_i);
+        self.memoized_map.push_seed(memo_id, start_src_i, depth);
+
+        let start_action_value_len = self.action_value_ids.len();
+        let mut attempt_action_value_len = start_action_value_len;
+        let mut result = self.parse_rule_group(rule_id, ast_reflection_style, &mut f);
+
+        // spec: 左再帰が検出された場合は入力位置が進まなくなるまで種を成長させる
+        loop {
+            let seed = self.memoized_map.find_seed_mut(memo_id, start_src_i).unwrap();
+
+            if !seed.is_detected {
+                break;
+            }
+
+            match result {
+                // note: 直前の種は新しい種の中の参照に移す
+                Ok(Some(mut v)) if self.src_i > start_src_i + seed.src_len => {
+                    SyntaxParser::replace_seed_refs(&mut v, seed.id, seed.result.take());
+                    seed.src_len = self.src_i - start_src_i;
+                    seed.result = Some(v);
+                },
+                // note: 成長が止まった時点で直前の種を結果とする; 最後の検査で生成された値は破棄する
+                Ok(_) => {
+                    self.src_i = start_src_i + seed.src_len;
+                    result = Ok(seed.result.take());
+                    self.discard_action_values_after(attempt_action_value_len);
+                    break;
+                },
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                },
+            }
+
+            self.src_i = start_src_i;
+            attempt_action_value_len = self.action_value_ids.len();
+            result = self.parse_rule_group(rule_id, ast_reflection_style, &mut f);
+        }
+
+        let seed = self.memoized_map.remove_seed(memo_id, start_src_i).unwrap();
+        self.rule_depth -= 1;
+        self.cut_base_backtrack_len = outer_cut_base_backtrack_len;
+        self.has_cut_failure = false;
+
+        let examined_end_i = self.src.get_examined_end_i();
+        self.src.set_examined_end_i(outer_examined_end_i.max(examined_end_i));
+
+        // note: 自身より外側の種に依存しない場合のみ成長後の結果をメモ化する
+        let depends_on_outer_seed = match self.min_seed_depth {
+            Some(v) => v < depth,
+            None => false,
+        };
+
+        // note: 意味アクションを持つ規則の成長後の結果は値のノードに置き換わっており、規則グループの結果として再利用できないためメモ化しない
+        let has_action = self.action_map.contains_key(rule_id);
+
+        if seed.is_detected && self.enable_memoization && is_memoized && !depends_on_outer_seed && !has_action && self.src_i != start_src_i {
+            match &result {
+                Ok(Some(SyntaxNodeElement::Node(node))) => self.memoized_map.push(memo_id, start_src_i, self.src_i - start_src_i, examined_end_i + 1 - start_src_i, Some(node.sub_elems.clone())),
+                _ => (),
+            }
+        }
+
+        self.min_seed_depth = match (outer_min_seed_depth, self.min_seed_depth) {
+            (Some(outer), Some(inner)) if inner < depth => Some(outer.min(inner)),
+            (Some(outer), _) => Some(outer),
+            (None, Some(inner)) if inner < depth => Some(inner),
+            (None, _) => None,
+        };
+
+        let result = result?;
+
+        if result.is_none() {
+            self.discard_action_values_after(start_action_value_len);
+        }
+
+        // spec: 規則が開始位置で失敗した場合、規則内で追加された期待要素が字句のみであればそれらを規則 ID に置き換える
+        if result.is_none() && self.lookahead_depth == 0 && self.farthest_src_i == start_src_i {
+            let added_elem_start_i = if farthest_src_i_before == start_src_i {
+                farthest_expected_elem_len_before
+            } else {
+                0
+            };
+
+            let added_elems = self.farthest_expected_elems.split_off(added_elem_start_i);
+
+            if !added_elems.is_empty() && added_elems.iter().all(|each_elem| !each_elem.is_rule()) {
+                self.add_expected_elem(ExpectedElement::Rule(rule_id.to_string()));
+            } else {
+                self.farthest_expected_elems.extend(added_elems);
+            }
+        }
+
+        // note: 規則内で最も遠い失敗位置が更新された場合のみ途中まで一致したとみなす
+        let is_partially_matched = self.farthest_src_i > start_src_i && (self.farthest_src_i > farthest_src_i_before || self.farthest_expected_elems.len() > farthest_expected_elem_len_before);
+
+        if result.is_none() && self.enable_recovery && self.lookahead_depth == 0 && is_partially_matched {
+            return Ok(self.recover_rule(rule_id, start_src_i));
+        }
+
+        return Ok(result);
+    }
+
+    // spec: 規則が途中まで一致して失敗した場合、失敗位置以降の同期点までを読み飛ばしてエラーノードを生成する
+    // note: 同期点が見つからない場合は入力の終端までを読み飛ばす
+    fn recover_rule(&mut self, rule_id: &str, start_src_i: usize) -> Option<SyntaxNodeElement> {
+        let sync_strings = match self.rule_map.sync_point_map.get(rule_id) {
+            Some(v) => v.clone(),
+            None => return None,
+        };
+
+        let err = self.get_unexpected_input_log();
+        let mut skip_end_i = self.farthest_src_i;
+
+        // note: 入力の終端まで読み込まないよう、同期点が見つかるまで 1 文字ずつ読み進める; EOF 用のヌル文字は読み飛ばさない
+        while self.src.has_char_at(skip_end_i + 1) {
+            let matched_sync_len = sync_strings.iter().find_map(|each_sync_string| self.match_string_at(skip_end_i, each_sync_string));
+
+            match matched_sync_len {
+                Some(v) => {
+                    skip_end_i += v;
+                    break;
+                },
+                None => skip_end_i += 1,
+            }
+        }
+
+        // note: ヌル文字を消費した後に失敗した場合はヌル文字の位置までとする
+        if !self.src.has_char_at(skip_end_i) {
+            skip_end_i -= 1;
+        }
+
+        self.src_i = start_src_i;
+        let skipped_str = self.src.substring(start_src_i, skip_end_i - start_src_i);
+        let skipped_leaf = SyntaxNodeElement::from_leaf_args(self.get_char_position(), skipped_str.clone(), ASTReflectionStyle::Reflection(String::new()));
+        self.add_source_index_by_string(&skipped_str);
+
+        let err_node = SyntaxNodeElement::from_node_args(vec![skipped_leaf], ASTReflectionStyle::Reflection(ERROR_NODE_NAME.to_string()));
+
+        match &err_node {
+            SyntaxNodeElement::Node(node) => {
+                self.recovered_error_map.insert(node.uuid, err);
+            },
+            SyntaxNodeElement::Leaf(_) => (),
+        }
+
+        // note: 読み飛ばした範囲の期待要素は報告済み
+        self.farthest_src_i = self.src_i;
+        self.farthest_expected_elems.clear();
+
+        return Some(SyntaxNodeElement::from_node_args(vec![err_node], ASTReflectionStyle::Reflection(rule_id.to_string())));
+    }
+
+    fn parse_rule_group<F: FnMut(&mut SyntaxParser<'a>) -> FCPEGResult<Option<Vec<SyntaxNodeElement>>>>(&mut self, rule_id: &str, ast_reflection_style: &ASTReflectionStyle, f: &mut F) -> FCPEGResult<Option<SyntaxNodeElement>> {
+        let start_src_i = self.src_i;
+
+        return match f(self)? {
+            Some(v) => {
+                let ast_reflection_style = match ast_reflection_style {
+                    // todo: 構成ファイルを ASTReflection に反映
+                    ASTReflectionStyle::Reflection(elem_name) if *elem_name == String::new() => ASTReflectionStyle::from_config(false, true, rule_id.to_string()),
+                    _ => ast_reflection_style.clone(),
                 };
 
-                self.rule_stack.pop().unwrap();
                 let new_node = SyntaxNodeElement::from_node_args(v, ast_reflection_style);
-                Ok(Some(new_node))
+
+                match self.action_map.get(rule_id).cloned() {
+                    Some(action) => Ok(Some(self.apply_action(rule_id, &action, start_src_i, new_node))),
+                    None => Ok(Some(new_node)),
+                }
             },
             None => {
                 Ok(None)
             },
+        };
+    }
+
+    // spec: 規則のノードを意味アクションに渡し、ノードの子要素を値のノードに置き換える
+    // note: 値のノードは規則の展開及び空のノードの除去で失われないよう、位置情報を持つ非反映のリーフを子要素とする
+    fn apply_action(&mut self, rule_id: &str, action: &SemanticAction, start_src_i: usize, node_elem: SyntaxNodeElement) -> SyntaxNodeElement {
+        let mut node = match node_elem {
+            SyntaxNodeElement::Node(v) => v,
+            SyntaxNodeElement::Leaf(_) => return node_elem,
+        };
+
+        // note: 意味アクションには種を参照するリーフを置き換えたノードを渡す
+        self.resolve_seed_refs(&mut node.sub_elems);
+
+        let rule_id = rule_id.to_string();
+        let pos = self.get_char_position_at(start_src_i);
+        let value = action(&SemanticActionArgs::new(&rule_id, pos.clone(), &node, &self.action_value_map));
+
+        let pos_leaf = SyntaxNodeElement::from_leaf_args(pos, String::new(), ASTReflectionStyle::NoReflection);
+        let value_node = SyntaxNodeElement::from_node_args(vec![pos_leaf], ASTReflectionStyle::Reflection(ACTION_VALUE_NODE_NAME.to_string()));
+
+        match &value_node {
+            SyntaxNodeElement::Node(v) => {
+                self.action_value_map.insert(v.uuid, value);
+                self.action_value_ids.push(v.uuid);
+            },
+            SyntaxNodeElement::Leaf(_) => (),
+        }
+
+        node.sub_elems = vec![value_node];
+        return SyntaxNodeElement::Node(node);
+    }
+
+    // spec: 結果の破棄時に、指定した数より後に生成された意味アクションの値を破棄する
+    // note: 破棄された値を参照するメモ化結果は再利用時に検出して検査し直す
+    fn discard_action_values_after(&mut self, action_value_len: usize) {
+        if action_value_len >= self.action_value_ids.len() {
+            return;
+        }
+
+        for each_uuid in self.action_value_ids.drain(action_value_len..) {
+            self.action_value_map.remove(&each_uuid);
         }
     }
 
-    fn parse_group(&mut self, parent_elem_order: &RuleElementOrder, group: &Box<RuleGroup>) -> ConsoleResult<Option<Vec<SyntaxNodeElement>>> {
-        if self.enable_memoization {
-            match self.memoized_map.find(&group.uuid, self.src_i) {
-                Some((src_len, result)) => {
+    // ret: 破棄された意味アクションの値を参照する値のノードを含むかどうか
+    fn refers_discarded_action_value(&self, elems: &Vec<SyntaxNodeElement>) -> bool {
+        for each_elem in elems {
+            match each_elem {
+                SyntaxNodeElement::Node(node) => {
+                    if node.is_action_value_node() {
+                        if !self.action_value_map.contains_key(&node.uuid) {
+                            return true;
+                        }
+                    } else if self.refers_discarded_action_value(&node.sub_elems) {
+                        return true;
+                    }
+                },
+                SyntaxNodeElement::Leaf(_) => (),
+            }
+        }
+
+        return false;
+    }
+
+    fn parse_group(&mut self, parent_elem_order: &RuleElementOrder, group: &RuleGroup) -> FCPEGResult<Option<Vec<SyntaxNodeElement>>> {
+        let is_memoized = self.rule_map.memoized_group_flags[group.memo_id];
+        return self.parse_group_with(group.memo_id, is_memoized, |p| p.parse_lookahead_group(parent_elem_order, group));
+    }
+
+    // spec: グループを検査する; メモ化された結果があれば再利用し、なければ検査してその結果をメモ化する
+    // note: f は先読み・繰り返しを含むグループを検査する
+    pub fn parse_group_with<F: FnMut(&mut SyntaxParser<'a>) -> FCPEGResult<Option<Vec<SyntaxNodeElement>>>>(&mut self, memo_id: usize, is_memoized: bool, mut f: F) -> FCPEGResult<Option<Vec<SyntaxNodeElement>>> {
+        let is_memoized = self.enable_memoization && is_memoized;
+
+        if is_memoized {
+            // note: 破棄された意味アクションの値を参照する結果は再利用せず、検査し直した結果で置き換える
+            let memoized_result = match self.memoized_map.find(memo_id, self.src_i) {
+                Some(v) if !self.action_map.is_empty() => match &v.result {
+                    Some(elems) if self.refers_discarded_action_value(elems) => None,
+                    _ => Some(v),
+                },
+                v => v,
+            };
+
+            match memoized_result {
+                Some(memoized_result) => {
+                    let src_len = memoized_result.src_len;
+                    let examined_len = memoized_result.examined_len;
+                    let src_i_delta = memoized_result.src_i_delta;
+                    let mut result = memoized_result.result.clone();
+
+                    // note: 再パース時に移された結果は位置情報を現在の入力に合わせる
+                    match (src_i_delta, &mut result) {
+                        (Some(delta), Some(elems)) => {
+                            for each_elem in elems.iter_mut() {
+                                self.relocate_elem(each_elem, delta);
+                            }
+                        },
+                        _ => (),
+                    }
+
+                    self.src.mark_examined(self.src_i + examined_len - 1);
                     self.src_i += src_len;
                     return Ok(result);
                 },
@@ -229,364 +948,530 @@ impl SyntaxParser {
         }
 
         let tmp_i = self.src_i;
-        let result = self.parse_lookahead_group(parent_elem_order, group)?;
+        let outer_min_seed_depth = self.min_seed_depth;
+        self.min_seed_depth = None;
+        let outer_examined_end_i = self.src.get_examined_end_i();
+        self.src.set_examined_end_i(tmp_i);
+
+        let result = f(self);
+
+        let examined_end_i = self.src.get_examined_end_i();
+        self.src.set_examined_end_i(outer_examined_end_i.max(examined_end_i));
+        let examined_len = examined_end_i + 1 - tmp_i;
+
+        // note: 検査中の規則の種を参照した結果は成長途中のものであるためメモ化しない
+        let depends_on_seed = match self.min_seed_depth {
+            Some(v) => v < self.rule_depth,
+            None => false,
+        };
+
+        self.min_seed_depth = match (outer_min_seed_depth, self.min_seed_depth) {
+            (Some(outer), Some(inner)) => Some(outer.min(inner)),
+            (Some(outer), None) => Some(outer),
+            (None, inner) => inner,
+        };
+
+        let result = result?;
 
-        if self.enable_memoization {
-            if self.src_i != tmp_i {
-                self.memoized_map.push(group.uuid.clone(), tmp_i, self.src_i - tmp_i, result.clone());
+        if is_memoized && !depends_on_seed {
+            match &result {
+                Some(_) => self.memoized_map.push(memo_id, tmp_i, self.src_i - tmp_i, examined_len, result.clone()),
+                // note: 先読み中の失敗は期待要素を記録していないためメモ化しない
+                // note: エラー回復時は失敗位置の更新から回復の可否を判定するため失敗をメモ化しない
+                // note: 切断演算子に到達した後の失敗は再利用時に選択へ伝播できないためメモ化しない
+                None if self.lookahead_depth == 0 && !self.enable_recovery && !self.has_cut_failure => self.memoized_map.push(memo_id, tmp_i, 0, examined_len, None),
+                None => (),
             }
         }
 
         return Ok(result);
     }
 
-    fn parse_lookahead_group(&mut self, parent_elem_order: &RuleElementOrder, group: &Box<RuleGroup>) -> ConsoleResult<Option<Vec<SyntaxNodeElement>>> {
+    fn parse_lookahead_group(&mut self, parent_elem_order: &RuleElementOrder, group: &RuleGroup) -> FCPEGResult<Option<Vec<SyntaxNodeElement>>> {
         return if group.lookahead_kind.is_none() {
             self.parse_loop_group(parent_elem_order, group)
         } else {
-            let start_src_i = self.src_i;
-            let is_lookahead_positive = group.lookahead_kind == RuleElementLookaheadKind::Positive;
-
-            let result = self.parse_loop_group(parent_elem_order, group)?;
-            self.src_i = start_src_i;
+            self.parse_lookahead_with(group.lookahead_kind == RuleElementLookaheadKind::Positive, |p| p.parse_loop_group(parent_elem_order, group))
+        };
+    }
 
-            if result.is_some() == is_lookahead_positive {
-                Ok(Some(Vec::new()))
-            } else {
-                Ok(None)
-            }
+    // spec: 先読みを検査する; 入力位置を戻し、結果が先読みの種類と一致すれば空の結果を返す
+    pub fn parse_lookahead_with<F: FnMut(&mut SyntaxParser<'a>) -> FCPEGResult<Option<Vec<SyntaxNodeElement>>>>(&mut self, is_lookahead_positive: bool, mut f: F) -> FCPEGResult<Option<Vec<SyntaxNodeElement>>> {
+        let start_src_i = self.src_i;
+
+        let outer_cut_base_backtrack_len = self.cut_base_backtrack_len;
+        self.cut_base_backtrack_len = None;
+        let start_action_value_len = self.action_value_ids.len();
+        self.lookahead_depth += 1;
+        self.backtrack_src_indexes.push(Some(start_src_i));
+        let result = f(self);
+        self.backtrack_src_indexes.pop();
+        self.lookahead_depth -= 1;
+        // note: 先読みの結果は構文木に含めないため、先読み中に生成された値を破棄する
+        self.discard_action_values_after(start_action_value_len);
+        self.cut_base_backtrack_len = outer_cut_base_backtrack_len;
+        // note: 先読みの結果は反転しうるため切断演算子による失敗を外側に伝播させない
+        self.has_cut_failure = false;
+
+        let result = result?;
+        self.src_i = start_src_i;
+
+        return if result.is_some() == is_lookahead_positive {
+            Ok(Some(Vec::new()))
+        } else {
+            Ok(None)
         };
     }
 
-    fn parse_loop_group(&mut self, parent_elem_order: &RuleElementOrder, group: &Box<RuleGroup>) -> ConsoleResult<Option<Vec<SyntaxNodeElement>>> {
+    fn parse_loop_group(&mut self, parent_elem_order: &RuleElementOrder, group: &RuleGroup) -> FCPEGResult<Option<Vec<SyntaxNodeElement>>> {
         let (min_count, max_count) = group.loop_range.to_tuple();
+        return self.parse_loop_with(min_count, max_count, |p| p.parse_element_order_group(parent_elem_order, group));
+    }
 
+    // spec: 繰り返しを検査する; max_count が -1 であれば上限なし
+    // note: f は繰り返しの各回を検査する
+    pub fn parse_loop_with<F: FnMut(&mut SyntaxParser<'a>) -> FCPEGResult<Option<Vec<SyntaxNodeElement>>>>(&mut self, min_count: usize, max_count: isize, mut f: F) -> FCPEGResult<Option<Vec<SyntaxNodeElement>>> {
         if max_count != -1 && min_count as isize > max_count {
-            self.cons.borrow_mut().append_log(SyntaxParsingLog::InvalidLoopRange {
+            return Err(SyntaxParsingLog::InvalidLoopRange {
                 msg: format!("invalid loop range {{{},{}}} was detected", min_count, max_count),
-            }.get_log());
-
-            return Err(());
+            }.into());
         }
 
         let mut children = Vec::<SyntaxNodeElement>::new();
-        let mut loop_count = 0isize;
+        let mut loop_count = 0usize;
 
-        while self.src_i < self.src_content.chars().count() {
-            if loop_count > self.loop_limit as isize {
-                self.cons.borrow_mut().append_log(SyntaxParsingLog::TooLongRepetition {
-                    loop_limit: self.loop_limit as usize,
-                }.get_log());
+        // note: 入力の終端より後でも必要な繰り返し回数に達するまでは検査し、失敗した要素を期待要素として追加する
+        while loop_count < min_count || self.src.has_char_at(self.src_i) {
+            if loop_count > self.get_loop_limit() {
+                return Err(SyntaxParsingLog::TooLongRepetition {
+                    loop_limit: self.get_loop_limit(),
+                }.into());
+            }
+
+            self.discard_behind_cut_point();
 
-                return Err(());
+            // note: 必要な繰り返し回数に達している場合のみ、失敗時にこの回の開始位置から検査を続ける
+            let is_backtrackable = loop_count >= min_count;
+
+            if is_backtrackable {
+                self.backtrack_src_indexes.push(Some(self.src_i));
             }
 
-            match self.parse_element_order_group(parent_elem_order, group)? {
-                Some(node_elems) => {
-                    for each_elem in node_elems {
-                        match &each_elem {
-                            SyntaxNodeElement::Node(node) => {
-                                if node.sub_elems.len() != 0 {
-                                    children.push(each_elem);
-                                }
-                            },
-                            _ => children.push(each_elem),
-                        }
-                    }
+            let iteration_action_value_len = self.action_value_ids.len();
+            let result = f(self);
+
+            if is_backtrackable {
+                self.backtrack_src_indexes.pop();
+            }
 
+            match result? {
+                Some(node_elems) => {
+                    SyntaxParser::push_node_elems(&mut children, node_elems);
                     loop_count += 1;
 
-                    if max_count != -1 && loop_count == max_count {
+                    if max_count != -1 && loop_count as isize == max_count {
                         return Ok(Some(children));
                     }
                 },
                 None => {
-                    if loop_count >= min_count as isize && (max_count == -1 || loop_count <= max_count) {
-                        return Ok(Some(children));
-                    } else {
+                    self.discard_action_values_after(iteration_action_value_len);
+
+                    // note: 切断演算子に到達した後の失敗では繰り返しを打ち切らずに失敗する
+                    if self.has_cut_failure {
                         return Ok(None);
                     }
+
+                    break;
                 },
             }
         }
 
-        if loop_count >= min_count as isize && (max_count == -1 || loop_count <= max_count) {
-            return Ok(Some(children));
+        return if loop_count >= min_count && (max_count == -1 || loop_count as isize <= max_count) {
+            Ok(Some(children))
         } else {
-            return Ok(None);
-        }
+            Ok(None)
+        };
     }
 
-    fn parse_element_order_group(&mut self, parent_elem_order: &RuleElementOrder, group: &Box<RuleGroup>) -> ConsoleResult<Option<Vec<SyntaxNodeElement>>> {
-        let mut children = Vec::<SyntaxNodeElement>::new();
-
+    fn parse_element_order_group(&mut self, parent_elem_order: &RuleElementOrder, group: &RuleGroup) -> FCPEGResult<Option<Vec<SyntaxNodeElement>>> {
         return match parent_elem_order {
             RuleElementOrder::Random(random_order_loop_range) => {
-                let tar_elems = match group.sub_elems.get(0) {
+                let tar_elems = match group.sub_elems.first() {
                     Some(tar_parent_elem) => {
                         match tar_parent_elem {
                             RuleElement::Group(tar_parent_group) => &tar_parent_group.sub_elems,
                             _ => {
-                                self.cons.borrow_mut().append_log(SyntaxParsingLog::InvalidRuleElementStructure {
-                                    uuid: group.uuid.clone(),
+                                return Err(SyntaxParsingLog::InvalidRuleElementStructure {
+                                    uuid: group.uuid,
                                     msg: "child element of random order group must be a group".to_string(),
-                                }.get_log());
-
-                                return Err(());
+                                }.into());
                             },
                         }
                     },
                     None => {
-                        self.cons.borrow_mut().append_log(SyntaxParsingLog::InvalidRuleElementStructure {
-                            uuid: group.uuid.clone(),
+                        return Err(SyntaxParsingLog::InvalidRuleElementStructure {
+                            uuid: group.uuid,
                             msg: "random order group must have a child group".to_string(),
-                        }.get_log());
-
-                        return Err(());
+                        }.into());
                     },
                 };
 
-                let random_order_start_src_i = self.src_i;
-                let mut is_each_subgroup_matched = vec![false; tar_elems.len()];
-                let mut subgroup_i = 0usize;
-
-                for _ in 0..tar_elems.len() {
-                    let elem_start_src_i = self.src_i;
-                    for subelem in tar_elems {
-                        match subelem {
-                            RuleElement::Group(subgroup) => {
-                                let mut conved_subgroup = subgroup.clone();
-                                conved_subgroup.loop_range = random_order_loop_range.clone();
-
-                                match self.parse_group(&RuleElementOrder::Sequential, &conved_subgroup)? {
-                                    Some(node_elems) => {
-                                        if is_each_subgroup_matched[subgroup_i] {
-                                            subgroup_i += 1;
-                                            continue;
-                                        }
+                self.parse_random_order_with(tar_elems.len(), |p, subgroup_i| {
+                    return match &tar_elems[subgroup_i] {
+                        RuleElement::Group(subgroup) => {
+                            let mut conved_subgroup = subgroup.clone();
+                            conved_subgroup.loop_range = random_order_loop_range.clone();
+                            p.parse_group(&RuleElementOrder::Sequential, &conved_subgroup)
+                        },
+                        _ => Ok(None),
+                    };
+                })
+            },
+            RuleElementOrder::Sequential => self.parse_raw_group(group),
+        };
+    }
 
-                                        for each_elem in node_elems {
-                                            match &each_elem {
-                                                SyntaxNodeElement::Node(node) => {
-                                                    if node.sub_elems.len() != 0 {
-                                                        children.push(each_elem);
-                                                    }
-                                                },
-                                                _ => children.push(each_elem),
-                                            }
-                                        }
+    // spec: 順不同グループを検査する; 全ての子グループが順不同に一度ずつ一致すれば成功する
+    // note: f は子グループの添字を受け取り、その子グループを検査する
+    pub fn parse_random_order_with<F: FnMut(&mut SyntaxParser<'a>, usize) -> FCPEGResult<Option<Vec<SyntaxNodeElement>>>>(&mut self, subgroup_len: usize, mut f: F) -> FCPEGResult<Option<Vec<SyntaxNodeElement>>> {
+        let mut children = Vec::<SyntaxNodeElement>::new();
+        let random_order_start_src_i = self.src_i;
+        let mut is_each_subgroup_matched = vec![false; subgroup_len];
 
-                                        is_each_subgroup_matched[subgroup_i] = true;
-                                        break;
-                                    },
-                                    None => self.src_i = elem_start_src_i,
-                                }
-                            },
-                            _ => (),
-                        }
+        self.backtrack_src_indexes.push(Some(random_order_start_src_i));
 
-                        subgroup_i += 1;
-                    }
+        let random_order_action_value_len = self.action_value_ids.len();
 
-                    if is_each_subgroup_matched.iter().find(|v| !**v).is_none() {
-                        return Ok(Some(children));
-                    }
+        for _ in 0..subgroup_len {
+            let elem_start_src_i = self.src_i;
+
+            for (subgroup_i, is_subgroup_matched) in is_each_subgroup_matched.iter_mut().enumerate() {
+                let elem_action_value_len = self.action_value_ids.len();
+
+                match f(self, subgroup_i)? {
+                    Some(node_elems) => {
+                        if *is_subgroup_matched {
+                            self.discard_action_values_after(elem_action_value_len);
+                            continue;
+                        }
 
-                    subgroup_i = 0;
+                        SyntaxParser::push_node_elems(&mut children, node_elems);
+                        *is_subgroup_matched = true;
+                        break;
+                    },
+                    None => {
+                        self.src_i = elem_start_src_i;
+                        self.discard_action_values_after(elem_action_value_len);
+
+                        if self.has_cut_failure {
+                            self.backtrack_src_indexes.pop();
+                            self.src_i = random_order_start_src_i;
+                            self.discard_action_values_after(random_order_action_value_len);
+                            return Ok(None);
+                        }
+                    },
                 }
+            }
 
-                self.src_i = random_order_start_src_i;
-                Ok(None)
-            },
-            RuleElementOrder::Sequential => self.parse_raw_group(group),
-        };
+            if is_each_subgroup_matched.iter().find(|v| !**v).is_none() {
+                self.backtrack_src_indexes.pop();
+                return Ok(Some(children));
+            }
+        }
+
+        self.backtrack_src_indexes.pop();
+        self.src_i = random_order_start_src_i;
+        self.discard_action_values_after(random_order_action_value_len);
+        return Ok(None);
     }
 
-    fn parse_raw_group(&mut self, group: &Box<RuleGroup>) -> ConsoleResult<Option<Vec<SyntaxNodeElement>>> {
+    fn parse_raw_group(&mut self, group: &RuleGroup) -> FCPEGResult<Option<Vec<SyntaxNodeElement>>> {
         let mut children = Vec::<SyntaxNodeElement>::new();
+        // note: 切断演算子に到達した後の要素の失敗は外側の選択まで伝播させる
+        let mut is_cut = false;
+        let is_single_elem = group.sub_elems.len() == 1;
 
         for each_elem in &group.sub_elems {
-            let start_src_i = self.src_i;
-
-            match each_elem {
+            let is_matched = match each_elem {
                 RuleElement::Group(each_group) => {
                     match each_group.kind {
                         RuleGroupKind::Choice => {
-                            let mut is_successful = false;
-
-                            for each_sub_elem in &each_group.sub_elems {
-                                match each_sub_elem {
+                            self.parse_choice_with(&mut children, is_cut, is_single_elem, each_group.sub_elems.len(), |p, choice_i| {
+                                return match &each_group.sub_elems[choice_i] {
                                     RuleElement::Group(each_sub_group) => {
-                                        match self.parse_group(&each_group.elem_order, each_sub_group)? {
-                                            Some(v) => {
-                                                if group.sub_elems.len() != 1 {
-                                                    let new_child = SyntaxNodeElement::from_node_args(v, each_sub_group.ast_reflection_style.clone());
-
-                                                    match new_child {
-                                                        SyntaxNodeElement::Node(node) if node.sub_elems.len() == 0 => (),
-                                                        _ => {
-                                                            match new_child {
-                                                                SyntaxNodeElement::Node(new_node) if new_node.ast_reflection_style.is_expandable() => {
-                                                                    children.append(&mut new_node.sub_elems.clone());
-                                                                },
-                                                                _ => children.push(new_child),
-                                                            }
-                                                        },
-                                                    }
-                                                } else {
-                                                    children = v;
-                                                }
-
-                                                is_successful = true;
-                                                break;
-                                            },
-                                            None => {
-                                                self.src_i = start_src_i;
-                                            },
+                                        match p.parse_group(&each_group.elem_order, each_sub_group)? {
+                                            Some(v) => Ok(Some((v, each_sub_group.ast_reflection_style.clone()))),
+                                            None => Ok(None),
                                         }
                                     },
-                                    _ => (),
-                                }
-                            }
-
-                            if !is_successful {
-                                return Ok(None);
-                            }
-                        },
-                        RuleGroupKind::Sequence => {
-                            match self.parse_group(&each_group.elem_order, each_group)? {
-                                Some(v) => {
-                                    if group.sub_elems.len() != 1 {
-                                        let new_child = SyntaxNodeElement::from_node_args(v, each_group.ast_reflection_style.clone());
-
-                                        match new_child {
-                                            SyntaxNodeElement::Node(node) if node.sub_elems.len() == 0 => (),
-                                            _ => {
-                                                match new_child {
-                                                    SyntaxNodeElement::Node(new_node) if new_node.ast_reflection_style.is_expandable() => {
-                                                        children.append(&mut new_node.sub_elems.clone());
-                                                    },
-                                                    _ => children.push(new_child),
-                                                }
-                                            },
-                                        }
-                                    } else {
-                                        children = v;
-                                    }
-
-                                    continue;
-                                },
-                                None => {
-                                    self.src_i = start_src_i;
-                                    return Ok(None);
-                                },
-                            }
+                                    _ => Ok(None),
+                                };
+                            })?
                         },
+                        RuleGroupKind::Sequence => self.parse_sequence_group_with(&mut children, is_cut, is_single_elem, &each_group.ast_reflection_style, |p| p.parse_group(&each_group.elem_order, each_group))?,
                     }
                 },
-                RuleElement::Expression(each_expr) => {
-                    match self.parse_expr(each_expr)? {
-                        Some(node_elems) => {
-                            for each_elem in node_elems {
-                                match each_elem {
-                                    SyntaxNodeElement::Node(node) if node.sub_elems.len() == 0 => (),
-                                    _ => children.push(each_elem),
-                                }
-                            }
+                RuleElement::Expression(each_expr) => self.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_expr(each_expr))?,
+                RuleElement::Cut(_) => {
+                    is_cut = true;
+                    self.cut_backtrack_src_indexes();
+                    continue;
+                },
+            };
 
-                            continue;
-                        },
-                        None => {
-                            self.src_i = start_src_i;
-                            return Ok(None);
-                        },
-                    }
-                }
+            if !is_matched {
+                return Ok(None);
             }
         }
 
         return Ok(Some(children));
     }
 
-    fn parse_expr(&mut self, expr: &Box<RuleExpression>) -> ConsoleResult<Option<Vec<SyntaxNodeElement>>> {
-        return self.parse_lookahead_expr(expr);
+    // spec: 連接の子要素である選択を検査する; 候補を順に検査し、一致した候補の結果を children に追加する
+    // note: f は候補の添字を受け取り、候補の結果とその反映スタイルを返す
+    // note: is_cut は連接内で切断演算子に到達したかどうか; is_single_elem は連接の子要素が 1 つのみであるかどうか
+    // ret: 一致したかどうか
+    pub fn parse_choice_with<F: FnMut(&mut SyntaxParser<'a>, usize) -> FCPEGResult<Option<(Vec<SyntaxNodeElement>, ASTReflectionStyle)>>>(&mut self, children: &mut Vec<SyntaxNodeElement>, is_cut: bool, is_single_elem: bool, choice_len: usize, mut f: F) -> FCPEGResult<bool> {
+        let start_src_i = self.src_i;
+
+        for choice_i in 0..choice_len {
+            // note: 最後の候補が失敗した場合は外側で入力位置を戻すため、残りの候補がある場合のみ開始位置を保持する
+            let has_next_choice = choice_i + 1 < choice_len;
+            let outer_cut_base_backtrack_len = self.cut_base_backtrack_len;
+            self.cut_base_backtrack_len = Some(self.backtrack_src_indexes.len());
+
+            if has_next_choice {
+                self.backtrack_src_indexes.push(Some(start_src_i));
+            }
+
+            let choice_action_value_len = self.action_value_ids.len();
+            let result = f(self, choice_i);
+
+            if has_next_choice {
+                self.backtrack_src_indexes.pop();
+            }
+
+            self.cut_base_backtrack_len = outer_cut_base_backtrack_len;
+
+            match result? {
+                Some((v, ast_reflection_style)) => {
+                    SyntaxParser::push_group_result(children, is_single_elem, v, ast_reflection_style);
+                    return Ok(true);
+                },
+                None => {
+                    self.src_i = start_src_i;
+                    self.discard_action_values_after(choice_action_value_len);
+
+                    // note: 候補内で切断演算子に到達していれば残りの候補を検査しない
+                    if self.has_cut_failure {
+                        self.has_cut_failure = false;
+                        break;
+                    }
+                },
+            }
+        }
+
+        self.has_cut_failure |= is_cut;
+        return Ok(false);
     }
 
-    fn parse_lookahead_expr(&mut self, expr: &Box<RuleExpression>) -> ConsoleResult<Option<Vec<SyntaxNodeElement>>> {
-        return if expr.lookahead_kind.is_none() {
-            self.parse_loop_expr(expr)
-        } else {
-            let start_src_i = self.src_i;
-            let is_lookahead_positive = expr.lookahead_kind == RuleElementLookaheadKind::Positive;
+    // spec: 連接の子要素である連接を検査する
+    // ret: 一致したかどうか
+    pub fn parse_sequence_group_with<F: FnMut(&mut SyntaxParser<'a>) -> FCPEGResult<Option<Vec<SyntaxNodeElement>>>>(&mut self, children: &mut Vec<SyntaxNodeElement>, is_cut: bool, is_single_elem: bool, ast_reflection_style: &ASTReflectionStyle, mut f: F) -> FCPEGResult<bool> {
+        let start_src_i = self.src_i;
 
-            let result = self.parse_loop_expr(expr)?;
-            self.src_i = start_src_i;
+        return match f(self)? {
+            Some(v) => {
+                SyntaxParser::push_group_result(children, is_single_elem, v, ast_reflection_style.clone());
+                Ok(true)
+            },
+            None => {
+                self.src_i = start_src_i;
+                self.has_cut_failure |= is_cut;
+                Ok(false)
+            },
+        };
+    }
 
-            if result.is_some() == is_lookahead_positive {
-                Ok(Some(Vec::new()))
-            } else {
-                Ok(None)
+    // spec: 連接の子要素である式を検査する
+    // ret: 一致したかどうか
+    pub fn parse_sequence_expr_with<F: FnMut(&mut SyntaxParser<'a>) -> FCPEGResult<Option<Vec<SyntaxNodeElement>>>>(&mut self, children: &mut Vec<SyntaxNodeElement>, is_cut: bool, mut f: F) -> FCPEGResult<bool> {
+        let start_src_i = self.src_i;
+
+        return match f(self)? {
+            Some(node_elems) => {
+                SyntaxParser::push_node_elems(children, node_elems);
+                Ok(true)
+            },
+            None => {
+                self.src_i = start_src_i;
+                self.has_cut_failure |= is_cut;
+                Ok(false)
+            },
+        };
+    }
+
+    // note: 連接の子要素が 1 つのみであれば結果をそのまま子要素とする; 空のノードは追加せず、展開されるノードは子要素を追加する
+    fn push_group_result(children: &mut Vec<SyntaxNodeElement>, is_single_elem: bool, result: Vec<SyntaxNodeElement>, ast_reflection_style: ASTReflectionStyle) {
+        if is_single_elem {
+            *children = result;
+            return;
+        }
+
+        match SyntaxNodeElement::from_node_args(result, ast_reflection_style) {
+            SyntaxNodeElement::Node(node) if node.sub_elems.is_empty() => (),
+            SyntaxNodeElement::Node(mut node) if node.ast_reflection_style.is_expandable() => children.append(&mut node.sub_elems),
+            new_child => children.push(new_child),
+        }
+    }
+
+    // note: 空のノードは追加しない
+    fn push_node_elems(children: &mut Vec<SyntaxNodeElement>, node_elems: Vec<SyntaxNodeElement>) {
+        for each_elem in node_elems {
+            match &each_elem {
+                SyntaxNodeElement::Node(node) if node.sub_elems.is_empty() => (),
+                _ => children.push(each_elem),
             }
         }
     }
 
-    fn parse_loop_expr(&mut self, expr: &Box<RuleExpression>) -> ConsoleResult<Option<Vec<SyntaxNodeElement>>> {
-        let (min_count, max_count) = expr.loop_range.to_tuple();
+    // ret: 要素が種を参照するリーフであればその種の ID
+    fn get_seed_ref_id(elem: &SyntaxNodeElement) -> Option<usize> {
+        return match elem {
+            SyntaxNodeElement::Leaf(leaf) => {
+                match &leaf.ast_reflection_style {
+                    ASTReflectionStyle::Reflection(elem_name) if elem_name == SEED_REFERENCE_LEAF_NAME => leaf.value.parse::<usize>().ok(),
+                    _ => None,
+                }
+            },
+            SyntaxNodeElement::Node(_) => None,
+        };
+    }
 
-        if max_count != -1 && min_count as isize > max_count {
-            self.cons.borrow_mut().append_log(SyntaxParsingLog::InvalidLoopRange {
-                msg: format!("invalid loop range {{{},{}}} was detected", min_count, max_count),
-            }.get_log());
+    // spec: 成長した種の中で直前の種 seed_id を参照するリーフを直前の種の子要素に置き換える
+    // note: 最後に置き換えるリーフには直前の種の子要素を複製せずに移す
+    fn replace_seed_refs(elem: &mut SyntaxNodeElement, seed_id: usize, seed_result: Option<SyntaxNodeElement>) {
+        let node = match elem {
+            SyntaxNodeElement::Node(node) => node,
+            SyntaxNodeElement::Leaf(_) => return,
+        };
+
+        let mut seed_elems = match seed_result {
+            Some(SyntaxNodeElement::Node(seed_node)) => seed_node.sub_elems,
+            Some(seed_leaf) => vec![seed_leaf],
+            None => return,
+        };
+
+        let mut ref_count = SyntaxParser::count_seed_refs(&node.sub_elems, seed_id);
+        SyntaxParser::replace_seed_refs_in(&mut node.sub_elems, seed_id, &mut seed_elems, &mut ref_count);
+    }
+
+    fn count_seed_refs(elems: &[SyntaxNodeElement], seed_id: usize) -> usize {
+        let mut ref_count = 0usize;
 
-            return Err(());
+        for each_elem in elems {
+            match each_elem {
+                SyntaxNodeElement::Node(node) => ref_count += SyntaxParser::count_seed_refs(&node.sub_elems, seed_id),
+                SyntaxNodeElement::Leaf(_) if SyntaxParser::get_seed_ref_id(each_elem) == Some(seed_id) => ref_count += 1,
+                SyntaxNodeElement::Leaf(_) => (),
+            }
         }
 
-        let mut children = Vec::<SyntaxNodeElement>::new();
-        let mut loop_count = 0usize;
+        return ref_count;
+    }
+
+    fn replace_seed_refs_in(elems: &mut Vec<SyntaxNodeElement>, seed_id: usize, seed_elems: &mut Vec<SyntaxNodeElement>, ref_count: &mut usize) {
+        let mut elem_i = 0usize;
 
-        while self.src_i < self.src_content.chars().count() {
-            if loop_count > self.loop_limit {
-                self.cons.borrow_mut().append_log(SyntaxParsingLog::TooLongRepetition {
-                    loop_limit: self.loop_limit as usize
-                }.get_log());
+        while elem_i < elems.len() && *ref_count != 0 {
+            if SyntaxParser::get_seed_ref_id(&elems[elem_i]) != Some(seed_id) {
+                match &mut elems[elem_i] {
+                    SyntaxNodeElement::Node(node) => SyntaxParser::replace_seed_refs_in(&mut node.sub_elems, seed_id, seed_elems, ref_count),
+                    SyntaxNodeElement::Leaf(_) => (),
+                }
 
-                return Err(());
+                elem_i += 1;
+                continue;
             }
 
-            match self.parse_raw_expr(expr)? {
-                Some(node) => {
-                    for each_node in node {
-                        match each_node {
-                            SyntaxNodeElement::Node(node) if node.sub_elems.len() == 0 => (),
-                            _ => children.push(each_node),
-                        }
-                    }
+            *ref_count -= 1;
+
+            let new_elems = if *ref_count == 0 {
+                std::mem::take(seed_elems)
+            } else {
+                seed_elems.clone()
+            };
+
+            let new_elem_len = new_elems.len();
+            elems.splice(elem_i..elem_i + 1, new_elems);
+            elem_i += new_elem_len;
+        }
+    }
 
-                    loop_count += 1;
+    // spec: 種を参照するリーフを検査中の種の子要素の複製に置き換える
+    // note: 規則の結果を利用者に渡す意味アクション及び子要素の値を参照する式で用いる
+    fn resolve_seed_refs(&self, elems: &mut Vec<SyntaxNodeElement>) {
+        let mut elem_i = 0usize;
 
-                    if max_count != -1 && loop_count as isize == max_count {
-                        return Ok(Some(children));
-                    }
-                },
+        while elem_i < elems.len() {
+            let seed_id = match SyntaxParser::get_seed_ref_id(&elems[elem_i]) {
+                Some(v) => v,
                 None => {
-                    return if loop_count >= min_count && (max_count == -1 || loop_count as isize <= max_count) {
-                        Ok(Some(children))
-                    } else {
-                        Ok(None)
+                    match &mut elems[elem_i] {
+                        SyntaxNodeElement::Node(node) => self.resolve_seed_refs(&mut node.sub_elems),
+                        SyntaxNodeElement::Leaf(_) => (),
                     }
+
+                    elem_i += 1;
+                    continue;
                 },
-            }
+            };
+
+            let seed_result = match self.memoized_map.find_seed_by_id(seed_id) {
+                Some(seed) => &seed.result,
+                None => &None,
+            };
+
+            let mut new_elems = match seed_result {
+                Some(SyntaxNodeElement::Node(seed_node)) => seed_node.sub_elems.clone(),
+                Some(seed_leaf) => vec![seed_leaf.clone()],
+                None => Vec::new(),
+            };
+
+            // note: 種の子要素は外側の種を参照しうる
+            self.resolve_seed_refs(&mut new_elems);
+
+            let new_elem_len = new_elems.len();
+            elems.splice(elem_i..elem_i + 1, new_elems);
+            elem_i += new_elem_len;
         }
+    }
 
-        return if loop_count >= min_count && (max_count == -1 || loop_count as isize <= max_count) {
-            Ok(Some(children))
+    fn parse_expr(&mut self, expr: &RuleExpression) -> FCPEGResult<Option<Vec<SyntaxNodeElement>>> {
+        return self.parse_lookahead_expr(expr);
+    }
+
+    fn parse_lookahead_expr(&mut self, expr: &RuleExpression) -> FCPEGResult<Option<Vec<SyntaxNodeElement>>> {
+        return if expr.lookahead_kind.is_none() {
+            self.parse_loop_expr(expr)
         } else {
-            Ok(None)
-        }
+            self.parse_lookahead_with(expr.lookahead_kind == RuleElementLookaheadKind::Positive, |p| p.parse_loop_expr(expr))
+        };
+    }
+
+    fn parse_loop_expr(&mut self, expr: &RuleExpression) -> FCPEGResult<Option<Vec<SyntaxNodeElement>>> {
+        let (min_count, max_count) = expr.loop_range.to_tuple();
+        return self.parse_loop_with(min_count, max_count, |p| p.parse_raw_expr(expr));
     }
 
-    fn parse_raw_expr(&mut self, expr: &Box<RuleExpression>) -> ConsoleResult<Option<Vec<SyntaxNodeElement>>> {
-        if self.src_i >= self.src_content.chars().count() {
+    fn parse_raw_expr(&mut self, expr: &RuleExpression) -> FCPEGResult<Option<Vec<SyntaxNodeElement>>> {
+        // note: 入力の終端 (ヌル文字) より後ではすべての式が失敗する; エラーの期待要素を示すため式を期待要素として追加する
+        if !self.src.has_char_at(self.src_i) {
+            match SyntaxParser::get_expected_elem(expr) {
+                Some(v) => self.add_expected_elem(v),
+                None => (),
+            }
+
             return Ok(None);
         }
 
@@ -594,7 +1479,7 @@ impl SyntaxParser {
             RuleExpressionKind::ArgId => {
                 let mut generics_group = Option::<Box<RuleGroup>>::None;
 
-                for each_arg_map in &*self.arg_maps {
+                for each_arg_map in &self.arg_maps {
                     match each_arg_map.generics_group.get(&expr.value) {
                         Some(v) => {
                             generics_group = Some(v.clone());
@@ -604,75 +1489,27 @@ impl SyntaxParser {
                     };
                 }
 
-                let result = match &generics_group {
-                    Some(v) => self.parse_group(&RuleElementOrder::Sequential, &v),
+                return match &generics_group {
+                    Some(v) => self.parse_arg_id_with(&expr.ast_reflection_style, |p| p.parse_group(&RuleElementOrder::Sequential, v)),
                     None => {
-                        self.cons.borrow_mut().append_log(SyntaxParsingLog::UnknownGenericsArgumentID {
+                        Err(SyntaxParsingLog::UnknownGenericsArgumentID {
                             arg_id: expr.value.clone(),
-                        }.get_log());
-
-                        return Err(());
+                        }.into())
                     },
                 };
-
-                return if !expr.ast_reflection_style.is_reflectable() {
-                    match &result {
-                        Ok(v) => {
-                            match v {
-                                Some(node_elems) => {
-                                    match node_elems.get(0) {
-                                        Some(each_node_elem) => {
-                                            let mut new_node_elem = each_node_elem.clone();
-                                            new_node_elem.set_ast_reflection_style(expr.ast_reflection_style.clone());
-                                            Ok(Some(vec![new_node_elem]))
-                                        },
-                                        _ => result,
-                                    }
-                                },
-                                None => result,
-                            }
-                        },
-                        Err(()) => result,
-                    }
-                } else {
-                    result
-                };
             },
             RuleExpressionKind::CharClass => {
-                if self.src_content.chars().count() < self.src_i + 1 {
-                    return Ok(None);
-                }
-
-                // note: Regex パターンが見つからない場合は新しく追加する
-                let pattern = match self.regex_map.get(&expr.value) {
+                // note: 文字クラスは規則マップの構築時にコンパイルされる
+                let char_class = match &expr.char_class {
                     Some(v) => v,
                     None => {
-                        let pattern = match Regex::new(&expr.value.clone()) {
-                            Ok(v) => v,
-                            Err(_) => {
-                                self.cons.borrow_mut().append_log(SyntaxParsingLog::InvalidCharClassFormat {
-                                    value: expr.to_string(),
-                                }.get_log());
-
-                                return Err(());
-                            },
-                        };
-
-                        self.regex_map.insert(expr.value.clone(), pattern);
-                        self.regex_map.get(&expr.value).unwrap()
+                        return Err(SyntaxParsingLog::InvalidCharClassFormat {
+                            value: expr.to_string(),
+                        }.into());
                     },
                 };
 
-                let tar_char = self.substring_src_content(self.src_i, 1);
-
-                if pattern.is_match(&tar_char) {
-                    let new_leaf = SyntaxNodeElement::from_leaf_args(self.get_char_position(), tar_char.clone(), expr.ast_reflection_style.clone());
-                    self.add_source_index_by_string(&tar_char);
-
-                    return Ok(Some(vec![new_leaf]));
-                } else {
-                    return Ok(None);
-                }
+                return self.parse_char_class_with(&expr.value, |c| char_class.contains(c), &expr.ast_reflection_style);
             },
             RuleExpressionKind::Id => self.parse_id_expr(expr),
             RuleExpressionKind::IdWithArgs { generics_args, template_args } => {
@@ -681,53 +1518,31 @@ impl SyntaxParser {
 
                 match rule_id.as_str() {
                     "JOIN" => {
-                        match generics_args.get(0) {
+                        match generics_args.first() {
                             Some(tar_arg) if generics_args.len() == 1 => {
-                                if template_args.len() != 0 {
-                                    self.cons.borrow_mut().append_log(SyntaxParsingLog::InvalidTemplateArgumentLength {
+                                if !template_args.is_empty() {
+                                    return Err(SyntaxParsingLog::InvalidTemplateArgumentLength {
                                         pos: expr.pos.clone(),
                                         expected_arg_len: 0,
-                                    }.get_log());
-
-                                    return Err(());
+                                    }.into());
                                 }
 
-                                return match self.parse_group(&RuleElementOrder::Sequential, tar_arg)? {
-                                    Some(result_elems) => {
-                                        let mut joined_str = String::new();
-
-                                        for each_elem in result_elems {
-                                            match each_elem {
-                                                SyntaxNodeElement::Node(node) if node.is_reflectable() => joined_str += &node.join_child_leaf_values(),
-                                                SyntaxNodeElement::Leaf(leaf) if leaf.is_reflectable() => joined_str += &leaf.value,
-                                                _ => (),
-                                            }
-                                        }
-
-                                        let new_leaf = SyntaxNodeElement::from_leaf_args(self.get_char_position(), joined_str, expr.ast_reflection_style.clone());
-                                        Ok(Some(vec![new_leaf]))
-                                    },
-                                    None => Ok(None),
-                                };
+                                return self.parse_join_with(&expr.ast_reflection_style, |p| p.parse_group(&RuleElementOrder::Sequential, tar_arg));
                             },
                             _ => {
-                                self.cons.borrow_mut().append_log(SyntaxParsingLog::InvalidGenericsArgumentLength {
+                                return Err(SyntaxParsingLog::InvalidGenericsArgumentLength {
                                     pos: expr.pos.clone(),
                                     expected_arg_len: 1,
-                                }.get_log());
-
-                                return Err(());
+                                }.into());
                             },
                         }
                     },
                     _ => {
                         if PRIMITIVE_RULE_NAMES.contains(&rule_id.as_str()) {
-                            self.cons.borrow_mut().append_log(SyntaxParsingLog::UncoveredPrimitiveRule {
+                            return Err(SyntaxParsingLog::UncoveredPrimitiveRule {
                                 pos: expr.pos.clone(),
                                 rule_name: rule_id.clone(),
-                            }.get_log());
-
-                            return Err(());
+                            }.into());
                         }
                     },
                 }
@@ -735,53 +1550,43 @@ impl SyntaxParser {
                 let (generics_arg_ids, template_arg_ids) = match self.rule_map.rule_map.get(rule_id) {
                     Some(rule) => (&rule.generics_arg_ids, &rule.template_arg_ids),
                     None => {
-                        self.cons.borrow_mut().append_log(SyntaxParsingLog::UnknownRuleID {
+                        return Err(SyntaxParsingLog::UnknownRuleID {
                             pos: expr.pos.clone(),
                             rule_id: rule_id.clone(),
-                        }.get_log());
-
-                        return Err(());
+                        }.into());
                     },
                 };
 
                 if generics_args.len() != generics_arg_ids.len() {
-                    self.cons.borrow_mut().append_log(SyntaxParsingLog::InvalidGenericsArgumentLength {
+                    return Err(SyntaxParsingLog::InvalidGenericsArgumentLength {
                         pos: expr.pos.clone(),
                         expected_arg_len: generics_arg_ids.len(),
-                    }.get_log());
-
-                    return Err(());
+                    }.into());
                 }
 
                 if template_args.len() != template_arg_ids.len() {
-                    self.cons.borrow_mut().append_log(SyntaxParsingLog::InvalidTemplateArgumentLength {
+                    return Err(SyntaxParsingLog::InvalidTemplateArgumentLength {
                         pos: expr.pos.clone(),
                         expected_arg_len: template_arg_ids.len(),
-                    }.get_log());
-
-                    return Err(());
+                    }.into());
                 }
 
                 for i in 0..generics_arg_ids.len() {
                     let new_arg_id = match generics_arg_ids.get(i) {
                         Some(v) => v,
                         None => {
-                            self.cons.borrow_mut().append_log(SyntaxParsingLog::UnknownGenericsArgumentID {
+                            return Err(SyntaxParsingLog::UnknownGenericsArgumentID {
                                 arg_id: format!("[{}]", i),
-                            }.get_log());
-
-                            return Err(());
+                            }.into());
                         },
                     };
 
                     let new_arg_group = match generics_args.get(i) {
                         Some(v) => v,
                         None => {
-                            self.cons.borrow_mut().append_log(SyntaxParsingLog::UnknownGenericsArgumentID {
+                            return Err(SyntaxParsingLog::UnknownGenericsArgumentID {
                                 arg_id: format!("[{}]", i),
-                            }.get_log());
-
-                            return Err(());
+                            }.into());
                         }
                     };
 
@@ -792,22 +1597,18 @@ impl SyntaxParser {
                     let new_arg_id = match template_arg_ids.get(i) {
                         Some(v) => v,
                         None => {
-                            self.cons.borrow_mut().append_log(SyntaxParsingLog::UnknownTemplateArgumentID {
+                            return Err(SyntaxParsingLog::UnknownTemplateArgumentID {
                                 arg_id: format!("[{}]", i),
-                            }.get_log());
-
-                            return Err(());
+                            }.into());
                         },
                     };
 
                     let new_arg_group = match template_args.get(i) {
                         Some(v) => v,
                         None => {
-                            self.cons.borrow_mut().append_log(SyntaxParsingLog::UnknownTemplateArgumentID {
+                            return Err(SyntaxParsingLog::UnknownTemplateArgumentID {
                                 arg_id: format!("[{}]", i),
-                            }.get_log());
-
-                            return Err(());
+                            }.into());
                         }
                     };
 
@@ -819,115 +1620,586 @@ impl SyntaxParser {
                 self.arg_maps.pop();
                 return result;
             },
-            RuleExpressionKind::String => {
-                if self.src_content.chars().count() < self.src_i + expr.value.chars().count() {
-                    return Ok(None);
-                }
-
-                if self.substring_src_content(self.src_i, expr.value.chars().count()) == expr.value {
-                    let new_leaf = SyntaxNodeElement::from_leaf_args(self.get_char_position(), expr.value.clone(), expr.ast_reflection_style.clone());
-                    self.add_source_index_by_string(&expr.value);
+            RuleExpressionKind::String => self.parse_string(&expr.value, &expr.ast_reflection_style),
+            RuleExpressionKind::Wildcard => self.parse_wildcard(&expr.ast_reflection_style),
+        }
+    }
 
-                    return Ok(Some(vec![new_leaf]));
-                } else {
-                    return Ok(None);
-                }
-            },
-            RuleExpressionKind::Wildcard => {
-                if self.src_content.chars().count() < self.src_i + 1 {
-                    return Ok(None);
-                }
+    // spec: 引数 ID 式を検査する; 非反映的な式であれば結果の最初の要素の反映スタイルを置き換える
+    // note: f は引数のグループを検査する
+    pub fn parse_arg_id_with<F: FnMut(&mut SyntaxParser<'a>) -> FCPEGResult<Option<Vec<SyntaxNodeElement>>>>(&mut self, ast_reflection_style: &ASTReflectionStyle, mut f: F) -> FCPEGResult<Option<Vec<SyntaxNodeElement>>> {
+        let result = f(self)?;
 
-                let expr_value = self.substring_src_content(self.src_i, 1);
-                let new_leaf = SyntaxNodeElement::from_leaf_args(self.get_char_position(), expr_value.clone(), expr.ast_reflection_style.clone());
-                self.add_source_index_by_string(&expr_value);
+        if ast_reflection_style.is_reflectable() {
+            return Ok(result);
+        }
 
-                return Ok(Some(vec![new_leaf]));
+        return match result {
+            Some(mut node_elems) if !node_elems.is_empty() => {
+                self.resolve_seed_refs(&mut node_elems);
+                let mut new_node_elem = node_elems[0].clone();
+                new_node_elem.set_ast_reflection_style(ast_reflection_style.clone());
+                Ok(Some(vec![new_node_elem]))
             },
-        }
+            _ => Ok(result),
+        };
     }
 
-    fn parse_id_expr(&mut self, expr: &Box<RuleExpression>) -> ConsoleResult<Option<Vec<SyntaxNodeElement>>> {
-        match self.parse_rule(&expr.value, &expr.pos)? {
-            Some(node_elem) => {
-                let conv_node_elems = match &node_elem {
-                    SyntaxNodeElement::Node(node) => {
-                        let sub_ast_reflection_style = match &expr.ast_reflection_style {
-                            ASTReflectionStyle::Reflection(elem_name) => {
-                                let conv_elem_name = if elem_name == "" {
-                                    expr.value.clone()
-                                } else {
-                                    elem_name.clone()
-                                };
+    // note: value は期待要素に用いる文字クラスの表記; f は文字が文字クラスに含まれるかを判定する
+    pub fn parse_char_class_with<F: FnOnce(char) -> bool>(&mut self, value: &str, f: F, ast_reflection_style: &ASTReflectionStyle) -> FCPEGResult<Option<Vec<SyntaxNodeElement>>> {
+        let tar_char = match self.src.get(self.src_i) {
+            Some(v) => v,
+            None => {
+                self.add_expected_elem(ExpectedElement::CharClass(value.to_string()));
+                return Ok(None);
+            },
+        };
 
-                                ASTReflectionStyle::Reflection(conv_elem_name)
-                            },
-                            _ => expr.ast_reflection_style.clone(),
-                        };
+        if f(tar_char) {
+            let new_leaf = SyntaxNodeElement::from_leaf_args(self.get_char_position(), tar_char.to_string(), ast_reflection_style.clone());
+            self.src_i += 1;
+
+            return Ok(Some(vec![new_leaf]));
+        } else {
+            self.add_expected_elem(ExpectedElement::CharClass(value.to_string()));
+            return Ok(None);
+        }
+    }
 
-                        let node = SyntaxNodeElement::from_node_args(node.sub_elems.clone(), sub_ast_reflection_style);
+    pub fn parse_string(&mut self, value: &str, ast_reflection_style: &ASTReflectionStyle) -> FCPEGResult<Option<Vec<SyntaxNodeElement>>> {
+        if self.match_string_at(self.src_i, value).is_some() {
+            let new_leaf = SyntaxNodeElement::from_leaf_args(self.get_char_position(), value.to_string(), ast_reflection_style.clone());
+            self.add_source_index_by_string(value);
 
-                        if expr.ast_reflection_style.is_expandable() {
-                            match node {
-                                SyntaxNodeElement::Node(node) => node.sub_elems,
-                                _ => vec![node],
-                            }
-                        } else {
-                            vec![node]
-                        }
-                    },
-                    SyntaxNodeElement::Leaf(_) => vec![node_elem],
-                };
+            return Ok(Some(vec![new_leaf]));
+        } else {
+            self.add_expected_elem(ExpectedElement::String(value.to_string()));
+            return Ok(None);
+        }
+    }
 
-                return Ok(Some(conv_node_elems));
-            },
+    pub fn parse_wildcard(&mut self, ast_reflection_style: &ASTReflectionStyle) -> FCPEGResult<Option<Vec<SyntaxNodeElement>>> {
+        let expr_value = match self.src.get(self.src_i) {
+            Some(v) => v.to_string(),
             None => {
+                self.add_expected_elem(ExpectedElement::Wildcard);
                 return Ok(None);
             },
         };
-    }
+        let new_leaf = SyntaxNodeElement::from_leaf_args(self.get_char_position(), expr_value.clone(), ast_reflection_style.clone());
+        self.add_source_index_by_string(&expr_value);
 
-    fn substring_src_content(&self, start_i: usize, len: usize) -> String {
-        return self.src_content.chars().skip(start_i).take(len).collect::<String>();
+        return Ok(Some(vec![new_leaf]));
     }
 
-    fn add_source_index_by_string(&mut self, expr_str: &String) {
-        let mut new_line_indexes = Vec::<usize>::new();
-        let mut char_i = 0usize;
+    // spec: JOIN<...> を検査する; 結果の反映的なリーフの値を結合した 1 つのリーフを返す
+    // note: f は引数のグループを検査する
+    pub fn parse_join_with<F: FnMut(&mut SyntaxParser<'a>) -> FCPEGResult<Option<Vec<SyntaxNodeElement>>>>(&mut self, ast_reflection_style: &ASTReflectionStyle, mut f: F) -> FCPEGResult<Option<Vec<SyntaxNodeElement>>> {
+        // note: 結合したリーフの位置は引数の開始位置とする
+        let start_pos = self.get_char_position();
+
+        return match f(self)? {
+            Some(mut result_elems) => {
+                self.resolve_seed_refs(&mut result_elems);
+                let mut joined_str = String::new();
+
+                for each_elem in result_elems {
+                    match each_elem {
+                        SyntaxNodeElement::Node(node) if node.is_reflectable() => joined_str += &node.join_child_leaf_values(),
+                        SyntaxNodeElement::Leaf(leaf) if leaf.is_reflectable() => joined_str += &leaf.value,
+                        _ => (),
+                    }
+                }
 
-        for each_char in expr_str.chars().rev() {
-            if each_char == '\n' {
-                new_line_indexes.push(char_i);
+                let new_leaf = SyntaxNodeElement::from_leaf_args(start_pos, joined_str, ast_reflection_style.clone());
+                Ok(Some(vec![new_leaf]))
+            },
+            None => Ok(None),
+        };
+    }
 
-                if new_line_indexes.len() >= 2 {
-                    break;
-                }
-            }
+    fn parse_id_expr(&mut self, expr: &RuleExpression) -> FCPEGResult<Option<Vec<SyntaxNodeElement>>> {
+        return self.parse_id_with(&expr.value, &expr.ast_reflection_style, |p| p.parse_rule(&expr.value, &expr.pos));
+    }
 
-            char_i += 1;
-        }
+    // spec: 規則 ID 式を検査する; 規則のノードを式の反映スタイルのノードに置き換え、展開する式であれば子要素を返す
+    // note: 反映名が空であれば規則 ID を反映名とする; f は規則を検査する
+    pub fn parse_id_with<F: FnMut(&mut SyntaxParser<'a>) -> FCPEGResult<Option<SyntaxNodeElement>>>(&mut self, rule_id: &str, ast_reflection_style: &ASTReflectionStyle, mut f: F) -> FCPEGResult<Option<Vec<SyntaxNodeElement>>> {
+        let node_elem = match f(self)? {
+            Some(v) => v,
+            None => return Ok(None),
+        };
 
-        match new_line_indexes.pop() {
-            Some(latest_new_line_i) => {
-                self.src_line += expr_str.match_indices("\n").count();
-                self.src_latest_line_i = match new_line_indexes.last() {
-                    Some(second_latest_new_line_i) => self.src_i + latest_new_line_i - second_latest_new_line_i + 1,
-                    None => self.src_i + latest_new_line_i + 1,
+        return match node_elem {
+            SyntaxNodeElement::Node(node) if ast_reflection_style.is_expandable() => Ok(Some(node.sub_elems)),
+            SyntaxNodeElement::Node(node) => {
+                let sub_ast_reflection_style = match ast_reflection_style {
+                    ASTReflectionStyle::Reflection(elem_name) if elem_name.is_empty() => ASTReflectionStyle::Reflection(rule_id.to_string()),
+                    _ => ast_reflection_style.clone(),
                 };
+
+                Ok(Some(vec![SyntaxNodeElement::from_node_args(node.sub_elems, sub_ast_reflection_style)]))
             },
-            None => (),
+            SyntaxNodeElement::Leaf(_) => Ok(Some(vec![node_elem])),
+        };
+    }
+
+    // ret: 入力位置から文字列が一致すればその文字数
+    fn match_string_at(&mut self, src_i: usize, s: &str) -> Option<usize> {
+        let mut char_i = src_i;
+
+        for each_char in s.chars() {
+            match self.src.get(char_i) {
+                Some(v) if v == each_char => char_i += 1,
+                _ => return None,
+            }
         }
 
+        return Some(char_i - src_i);
+    }
+
+    fn add_source_index_by_string(&mut self, expr_str: &str) {
         self.src_i += expr_str.chars().count();
     }
 
-    fn get_char_position(&self) -> CharacterPosition {
-        // note: 検査に失敗すると src_i < src_latest_line_i になる; その場合は src_latest_line_i の値を使用する
-        let column = match self.src_i.checked_sub(self.src_latest_line_i) {
+    // note: 再パース時に移されたメモ化結果の位置情報を入力位置のずれから付け直す
+    fn relocate_elem(&self, elem: &mut SyntaxNodeElement, src_i_delta: isize) {
+        match elem {
+            SyntaxNodeElement::Node(node) => {
+                for each_elem in node.sub_elems.iter_mut() {
+                    self.relocate_elem(each_elem, src_i_delta);
+                }
+            },
+            SyntaxNodeElement::Leaf(leaf) => {
+                leaf.pos = self.get_char_position_at((leaf.pos.index as isize + src_i_delta) as usize);
+            },
+        }
+    }
+
+    // note: 読み込み済みの入力が増えた場合は繰り返し回数の上限も引き上げる
+    fn get_loop_limit(&self) -> usize {
+        return self.loop_limit.max(self.src.get_loaded_len());
+    }
+
+    // spec: 再び検査しうる最も手前の位置を切断点とし、それより手前の入力及びメモ化結果を破棄する
+    // note: 左再帰が検出された規則は開始位置から種を成長させるため、その開始位置も考慮する
+    fn discard_behind_cut_point(&mut self) {
+        if !self.enable_discarding || self.src_i < self.src.get_offset() + SOURCE_CHUNK_SIZE {
+            return;
+        }
+
+        let mut cut_src_i = self.src_i;
+
+        match self.backtrack_src_indexes.iter().find_map(|each_src_i| *each_src_i) {
+            Some(v) if v < cut_src_i => cut_src_i = v,
+            _ => (),
+        }
+
+        match self.memoized_map.get_min_detected_seed_src_i() {
+            Some(v) if v < cut_src_i => cut_src_i = v,
+            _ => (),
+        }
+
+        self.src.discard_before(cut_src_i);
+        self.memoized_map.discard_before(cut_src_i);
+    }
+
+    // spec: 最も内側の選択の候補の開始以降に積まれた位置を無効化し、切断点を進める
+    // note: 切断演算子に到達した後の失敗ではそれらの位置に戻らない
+    pub fn cut_backtrack_src_indexes(&mut self) {
+        let base_len = match self.cut_base_backtrack_len {
             Some(v) => v,
-            None => self.src_latest_line_i,
+            None => return,
+        };
+
+        for each_src_i in self.backtrack_src_indexes.iter_mut().skip(base_len) {
+            *each_src_i = None;
+        }
+
+        self.discard_behind_cut_point();
+    }
+
+    // note: 先読み中または最も遠い位置より手前での失敗は無視する
+    // ret: 式が失敗した場合に期待要素として示すもの; 引数 ID 及び JOIN は引数によって異なるため None
+    fn get_expected_elem(expr: &RuleExpression) -> Option<ExpectedElement> {
+        return match &expr.kind {
+            RuleExpressionKind::ArgId => None,
+            RuleExpressionKind::CharClass => Some(ExpectedElement::CharClass(expr.value.clone())),
+            RuleExpressionKind::Id => Some(ExpectedElement::Rule(expr.value.clone())),
+            RuleExpressionKind::IdWithArgs { generics_args: _, template_args: _ } if PRIMITIVE_RULE_NAMES.contains(&expr.value.as_str()) => None,
+            RuleExpressionKind::IdWithArgs { generics_args: _, template_args: _ } => Some(ExpectedElement::Rule(expr.value.clone())),
+            RuleExpressionKind::String => Some(ExpectedElement::String(expr.value.clone())),
+            RuleExpressionKind::Wildcard => Some(ExpectedElement::Wildcard),
+        };
+    }
+
+    fn add_expected_elem(&mut self, elem: ExpectedElement) {
+        if self.lookahead_depth != 0 || self.src_i < self.farthest_src_i {
+            return;
+        }
+
+        if self.src_i > self.farthest_src_i {
+            self.farthest_src_i = self.src_i;
+            self.farthest_expected_elems.clear();
+        }
+
+        if !self.farthest_expected_elems.contains(&elem) {
+            self.farthest_expected_elems.push(elem);
+        }
+    }
+
+    // note: 最も遠い失敗位置もしくは入力の読み残し位置のうち遠い方でエラーを生成する
+    fn get_unexpected_input_log(&mut self) -> SyntaxParsingLog {
+        let (src_i, expected) = if self.farthest_src_i >= self.src_i {
+            (self.farthest_src_i, self.farthest_expected_elems.clone())
+        } else {
+            (self.src_i, Vec::new())
+        };
+
+        // note: 入力の終端 (ヌル文字) を消費した後の位置は終端の位置として示す
+        let src_i = src_i.min(self.src.get_loaded_len() - 1);
+
+        let found = match self.src.get(src_i) {
+            Some('\0') | None => "end of input".to_string(),
+            Some(v) => format!("`{}`", v.to_string().replace("\n", "\\n").replace("\t", "\\t")),
+        };
+
+        let (pos, source_line) = self.get_char_position_and_line_at(src_i);
+
+        return SyntaxParsingLog::UnexpectedInput {
+            pos: pos,
+            expected: expected,
+            found: found,
+            source_line: source_line,
+        };
+    }
+
+    // ret: 入力位置の CharacterPosition とその行の内容
+    fn get_char_position_and_line_at(&mut self, src_i: usize) -> (CharacterPosition, String) {
+        let pos = self.get_char_position_at(src_i);
+        let source_line = self.src.get_line_content_at(src_i);
+        return (pos, source_line);
+    }
+
+    fn get_char_position(&self) -> CharacterPosition {
+        return self.get_char_position_at(self.src_i);
+    }
+
+    fn get_char_position_at(&self, src_i: usize) -> CharacterPosition {
+        let (line, line_start_i) = self.src.get_line_at(src_i);
+        return CharacterPosition::new(Some(self.src_path.clone()), src_i, line, src_i - line_start_i);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::*;
+
+    const FCPEG_SOURCE: &str = "[Main]{\n    Main <- Sum \"\\z\"#,\n    Sum <- Sum \"+\" Num : Num,\n    Num <- [0-9]+,\n}\n";
+
+    // note: 選択の候補が Pair の後もしくは Pair の途中で失敗し、次の候補で検査し直す
+    const BACKTRACKING_FCPEG_SOURCE: &str = "[Main]{\n    Main <- Item+ \"\\z\"#,\n    Item <- Pair \"+\" : Pair \";\" : Num \";\",\n    Pair <- Num \",\" Num,\n    Num <- [0-9]+,\n}\n";
+
+    fn add_sum_actions(parser: &mut FCPEGParser) {
+        parser.add_action(".Main.Main", |args| args.get_values::<u64>().into_iter().sum::<u64>()).unwrap();
+        parser.add_action(".Main.Item", |args| args.get_values::<u64>().into_iter().sum::<u64>()).unwrap();
+        parser.add_action(".Main.Num", |args| args.get_string().parse::<u64>().unwrap()).unwrap();
+    }
+
+    // ret: 最初の子要素を辿った左再帰の規則のノードの数
+    fn get_left_recursion_depth(elem: &SyntaxNodeElement) -> usize {
+        return match elem {
+            SyntaxNodeElement::Node(node) if node.ast_reflection_style == ASTReflectionStyle::Reflection(".Main.Sum".to_string()) => 1 + get_left_recursion_depth(&node.sub_elems[0]),
+            _ => 0,
+        };
+    }
+
+    #[test]
+    fn left_recursion_nests_grown_seeds() {
+        let input = (0..300).map(|i| i.to_string()).collect::<Vec<String>>().join("+");
+        let tree = load_test_parser(FCPEG_SOURCE).parse_from_str("input.txt".to_string(), input.clone()).unwrap();
+        let root = tree.get_child_ref().get_node().unwrap();
+
+        assert_eq!(root.join_child_leaf_values(), input);
+        assert_eq!(get_left_recursion_depth(&root.sub_elems[0]), 300);
+    }
+
+    #[test]
+    fn left_recursion_passes_seed_values_to_actions() {
+        let mut parser = load_test_parser(FCPEG_SOURCE);
+        parser.add_action(".Main.Main", |args| *args.get_values::<u64>()[0]).unwrap();
+        parser.add_action(".Main.Sum", |args| args.get_values::<u64>().into_iter().sum::<u64>()).unwrap();
+        parser.add_action(".Main.Num", |args| args.get_string().parse::<u64>().unwrap()).unwrap();
+
+        let value = parser.parse_from_str_with_actions::<u64>("input.txt".to_string(), "1+2+3+40".to_string()).unwrap();
+        assert_eq!(value, 46);
+    }
+
+    #[test]
+    fn backtracked_action_values_are_discarded() {
+        let mut parser = load_test_parser(BACKTRACKING_FCPEG_SOURCE);
+        add_sum_actions(&mut parser);
+
+        let src = SourceBuffer::from_string("input.txt".to_string(), Box::new("1,2;3,4+5,6;7;".to_string()));
+        let mut syntax_parser = SyntaxParser::new(parser.rule_map.clone(), "input.txt".to_string(), src, true, false);
+        syntax_parser.action_map = parser.action_map.clone();
+        syntax_parser.parse_start_rule().unwrap();
+
+        // note: Main 1 個・Item 4 個・成功した候補の Num 7 個; 失敗した候補の Num の値は残らない
+        assert_eq!(syntax_parser.action_value_map.len(), 12);
+        assert_eq!(syntax_parser.action_value_ids.len(), 12);
+    }
+
+    #[test]
+    fn memoized_results_referring_discarded_values_are_not_reused() {
+        let mut parser = load_test_parser(BACKTRACKING_FCPEG_SOURCE);
+        add_sum_actions(&mut parser);
+
+        let value = parser.parse_from_str_with_actions::<u64>("input.txt".to_string(), "1,2;3,4+5,6;7;".to_string()).unwrap();
+        assert_eq!(value, 28);
+    }
+
+    // ret: 構文エラーの位置 (行, 列)・期待要素の表記・見つかった入力
+    fn get_unexpected_input(fcpeg_source: &str, input: &str) -> ((usize, usize), Vec<String>, String) {
+        return match load_test_parser(fcpeg_source).parse_from_str("input.txt".to_string(), input.to_string()) {
+            Ok(_) => panic!("unexpected success"),
+            Err(FCPEGError::SyntaxParsing(log)) => match *log {
+                SyntaxParsingLog::UnexpectedInput { pos, expected, found, source_line: _ } => ((pos.line, pos.column), expected.iter().map(|each_elem| each_elem.to_string()).collect(), found),
+                v => panic!("unexpected log {:?}", v),
+            },
+            Err(e) => panic!("unexpected error {:?}", e),
         };
+    }
+
+    #[test]
+    fn expected_elems_are_collected_at_farthest_position() {
+        let unexpected_input = get_unexpected_input("[Main]{\n    Main <- \"a\" (\"b\" : \"c\" : [0-9]) \"\\z\",\n}\n", "ax");
+        assert_eq!(unexpected_input, ((0, 1), vec!["`b`".to_string(), "`c`".to_string(), "`[0-9]`".to_string()], "`x`".to_string()));
+    }
+
+    #[test]
+    fn expected_elems_after_end_of_input_are_reported_at_end() {
+        let unexpected_input = get_unexpected_input("[Main]{\n    Main <- \"a\" \"\\z\" \"b\",\n}\n", "a");
+        assert_eq!(unexpected_input, ((0, 1), vec!["`b`".to_string()], "end of input".to_string()));
+
+        let unexpected_input = get_unexpected_input("[Main]{\n    Main <- \"a\" \"\\z\" Tail,\n    Tail <- \"b\",\n}\n", "a");
+        assert_eq!(unexpected_input, ((0, 1), vec![".Main.Tail".to_string()], "end of input".to_string()));
+
+        // note: ワイルドカードは終端のヌル文字にも一致するため、続く終端の検査が失敗する
+        let unexpected_input = get_unexpected_input("[Main]{\n    Main <- .+ \"\\z\",\n}\n", "ab");
+        assert_eq!(unexpected_input, ((0, 2), vec!["end of input".to_string()], "end of input".to_string()));
+    }
+
+    #[test]
+    fn lexical_expected_elems_are_collapsed_into_rule_id() {
+        let fcpeg_source = "[Main]{\n    Main <- Item+ \"\\z\",\n    Item <- Word \"\\n\",\n    Word <- [a-z]+,\n}\n";
+
+        // note: 開始位置で失敗した規則は規則 ID として示す
+        let unexpected_input = get_unexpected_input(fcpeg_source, "ab\nx\n1");
+        assert_eq!(unexpected_input, ((2, 0), vec![".Main.Word".to_string(), "end of input".to_string()], "`1`".to_string()));
+
+        // note: 途中まで一致した規則は字句の期待要素を示す
+        let unexpected_input = get_unexpected_input(fcpeg_source, "ab\nx1\n");
+        assert_eq!(unexpected_input, ((1, 1), vec!["`[a-z]`".to_string(), "`\\n`".to_string()], "`1`".to_string()));
+    }
+
+    // note: 文の途中で失敗した場合に ";" まで読み飛ばして回復する
+    const RECOVERY_FCPEG_SOURCE: &str = "[Main]{\n    Main <- Statement* \"\\z\",\n    Statement <- Id \"=\" Num \";\",\n    Id <- [a-z]+,\n    Num <- [0-9]+,\n}\n";
+    const RECOVERY_CONFIG_SOURCE: &str = "Recovery:\n|| Main:\n|||| Statement: ;,\n";
+
+    fn parse_with_recovery(input: &str) -> FCPEGResult<(SyntaxTree, Vec<FCPEGError>)> {
+        let parser = FCPEGParser::load_from_str("test.fcpeg".to_string(), RECOVERY_FCPEG_SOURCE.to_string(), RECOVERY_CONFIG_SOURCE.to_string(), HashMap::new(), &MemorySourceResolver::new(), true).unwrap();
+        return parser.parse_from_str_with_recovery("input.txt".to_string(), input.to_string());
+    }
+
+    // ret: 各エラーの位置 (行, 列)
+    fn get_error_positions(errs: &[FCPEGError]) -> Vec<(usize, usize)> {
+        return errs.iter().map(|each_err| {
+            let pos = each_err.get_position().unwrap();
+            (pos.line, pos.column)
+        }).collect();
+    }
+
+    // ret: 各文のエラーノードで読み飛ばされた文字列
+    fn get_skipped_strings(tree: &SyntaxTree) -> Vec<String> {
+        let root_node = tree.get_child_ref().get_node().unwrap();
+        return root_node.find_child_nodes(vec![".Main.Statement"]).iter().filter_map(|each_node| each_node.find_first_child_node(vec![ERROR_NODE_NAME])).map(|each_node| each_node.join_child_leaf_values()).collect();
+    }
+
+    #[test]
+    fn errors_are_recovered_at_each_sync_point() {
+        let (tree, errs) = parse_with_recovery("a=1;b=x;c=2;d=y;").unwrap();
+        assert_eq!(get_skipped_strings(&tree), vec!["b=x;".to_string(), "d=y;".to_string()]);
+        // note: 回復したエラーは入力位置の順に並ぶ
+        assert_eq!(get_error_positions(&errs), vec![(0, 6), (0, 14)]);
+    }
+
+    #[test]
+    fn input_is_skipped_to_end_without_sync_point() {
+        let (tree, errs) = parse_with_recovery("a=1;b=x").unwrap();
+        // note: 終端のヌル文字は読み飛ばさない
+        assert_eq!(get_skipped_strings(&tree), vec!["b=x".to_string()]);
+        assert_eq!(get_error_positions(&errs), vec![(0, 6)]);
+    }
+
+    #[test]
+    fn recovered_errors_are_returned_with_final_error() {
+        match parse_with_recovery("a=1;b=x;1") {
+            Err(FCPEGError::Multiple(errs)) => assert_eq!(get_error_positions(&errs), vec![(0, 6), (0, 8)]),
+            v => panic!("unexpected result {:?}", v.err()),
+        }
+    }
+
+    #[test]
+    fn rule_failed_at_start_position_is_not_recovered() {
+        match parse_with_recovery("1;") {
+            Err(FCPEGError::SyntaxParsing(log)) => assert_eq!(log.get_position().map(|pos| (pos.line, pos.column)), Some((0, 0))),
+            v => panic!("unexpected result {:?}", v.err()),
+        }
+    }
+
+    // note: Item の 1 つ目及び 2 つ目の候補で Word が同じ位置で失敗する
+    const MEMOIZATION_FCPEG_SOURCE: &str = "[Main]{\n    Main <- Item \"\\z\",\n    Item <- Word \"1\" : Word \"2\" : \"a\" \"3\",\n    Word <- \"a\" \"b\",\n}\n";
+
+    fn load_rule_map(config_source: &str) -> Arc<RuleMap> {
+        let parser = FCPEGParser::load_from_str("test.fcpeg".to_string(), MEMOIZATION_FCPEG_SOURCE.to_string(), config_source.to_string(), HashMap::new(), &MemorySourceResolver::new(), true).unwrap();
+        return parser.rule_map.clone();
+    }
+
+    fn new_memoizing_parser<'a>(rule_map: &Arc<RuleMap>, input: &str) -> SyntaxParser<'a> {
+        let src = SourceBuffer::from_string("input.txt".to_string(), Box::new(input.to_string()));
+        return SyntaxParser::new(rule_map.clone(), "input.txt".to_string(), src, true, false);
+    }
+
+    // ret: 規則グループのメモ化 ID
+    fn get_rule_memo_id(rule_map: &RuleMap, rule_id: &str) -> usize {
+        return rule_map.rule_memo_id_map.get(rule_id).unwrap()[0];
+    }
+
+    #[test]
+    fn failed_results_are_memoized_and_reused() {
+        let rule_map = load_rule_map("");
+        let word_memo_id = get_rule_memo_id(&rule_map, ".Main.Word");
+
+        let mut parser = new_memoizing_parser(&rule_map, "a3");
+        assert!(parser.parse_start_rule().is_ok());
+
+        let memoized_result = parser.memoized_map.find(word_memo_id, 0).unwrap();
+        assert!(memoized_result.result.is_none());
+        assert_eq!((memoized_result.src_len, memoized_result.examined_len), (0, 2));
+
+        // note: メモ化された失敗を再利用する場合は Word を検査し直さないため、Word が一致する入力でも失敗する
+        let mut parser = new_memoizing_parser(&rule_map, "ab1");
+        parser.memoized_map.push(word_memo_id, 0, 0, 1, None);
+        assert!(parser.parse_start_rule().is_err());
+    }
+
+    #[test]
+    fn memoization_limit_stops_pushing_results() {
+        let mut parser = new_memoizing_parser(&load_rule_map(""), "a3");
+        assert!(parser.parse_start_rule().is_ok());
+        assert!(parser.memoized_map.result_count > 1);
+
+        let mut parser = new_memoizing_parser(&load_rule_map("Memoization:\n|| Limit: 1,\n"), "a3");
+        assert!(parser.parse_start_rule().is_ok());
+        assert_eq!(parser.memoized_map.result_count, 1);
+    }
+
+    #[test]
+    fn rule_memoization_settings_are_applied() {
+        let rule_map = load_rule_map("Memoization:\n|| Default: off,\n|| Rules:\n|||| Main:\n|||||| Word: on,\n");
+        let mut parser = new_memoizing_parser(&rule_map, "a3");
+        assert!(parser.parse_start_rule().is_ok());
+        assert!(parser.memoized_map.find(get_rule_memo_id(&rule_map, ".Main.Word"), 0).is_some());
+        assert!(parser.memoized_map.find(get_rule_memo_id(&rule_map, ".Main.Item"), 0).is_none());
+
+        let rule_map = load_rule_map("Memoization:\n|| Rules:\n|||| Main:\n|||||| Word: off,\n");
+        let mut parser = new_memoizing_parser(&rule_map, "a3");
+        assert!(parser.parse_start_rule().is_ok());
+        assert!(parser.memoized_map.find(get_rule_memo_id(&rule_map, ".Main.Word"), 0).is_none());
+        assert!(parser.memoized_map.find(get_rule_memo_id(&rule_map, ".Main.Item"), 0).is_some());
+    }
+
+    // note: 切断演算子を持たない文法でも繰り返しの各回の開始位置より手前の入力を破棄する
+    #[test]
+    fn input_is_discarded_while_parsing_from_reader() {
+        let rule_map = load_test_parser("[Main]{\n    Main <- Line* \"\\z\",\n    Line <- [a-z]+ \"\\n\",\n}\n").rule_map.clone();
+        let input = "abc\n".repeat(SOURCE_CHUNK_SIZE / 2) + "1\n";
+        let src = SourceBuffer::from_reader("input.txt".to_string(), Box::new(std::io::Cursor::new(input.into_bytes())));
+        let mut parser = SyntaxParser::new(rule_map, "input.txt".to_string(), src, true, false);
+
+        // note: 破棄した後も行番号は入力全体におけるもの
+        match parser.parse_start_rule() {
+            Err(FCPEGError::SyntaxParsing(log)) => assert_eq!(log.get_position().map(|pos| (pos.index, pos.line, pos.column)), Some((SOURCE_CHUNK_SIZE * 2, SOURCE_CHUNK_SIZE / 2, 0))),
+            v => panic!("unexpected result {:?}", v.err()),
+        }
+
+        assert!(parser.src.get_offset() > 0);
+    }
+
+    fn parse_str(fcpeg_source: &str, input: &str) -> FCPEGResult<SyntaxTree> {
+        return load_test_parser(fcpeg_source).parse_from_str("input.txt".to_string(), input.to_string());
+    }
+
+    #[test]
+    fn cut_commits_to_alternative_in_innermost_choice() {
+        assert!(parse_str("[Main]{\n    Main <- (\"a\" ~ \"b\" : \"a\" \"c\") \"\\z\",\n}\n", "ab").is_ok());
+        assert!(parse_str("[Main]{\n    Main <- (\"a\" ~ \"b\" : \"a\" \"c\") \"\\z\",\n}\n", "ac").is_err());
+        // note: 外側の選択の残りの候補は検査する
+        assert!(parse_str("[Main]{\n    Main <- (\"a\" ~ \"b\" : \"a\" \"c\") \"\\z\" : \"a\" \"c\" \"\\z\",\n}\n", "ac").is_ok());
+    }
+
+    #[test]
+    fn cut_does_not_affect_choice_in_caller_rule() {
+        assert!(parse_str("[Main]{\n    Main <- Item \"\\z\" : \"a\" \"c\" \"\\z\",\n    Item <- \"a\" ~ \"b\",\n}\n", "ac").is_ok());
+    }
+
+    #[test]
+    fn cut_failure_in_loop_fails_without_ending_loop() {
+        assert!(parse_str("[Main]{\n    Main <- (\"a\" \"b\")* \"a\" \"c\" \"\\z\",\n}\n", "abac").is_ok());
+        assert!(parse_str("[Main]{\n    Main <- (\"a\" ~ \"b\")* \"a\" \"c\" \"\\z\",\n}\n", "abac").is_err());
+    }
+
+    #[test]
+    fn cut_in_lookahead_does_not_affect_outer_choice() {
+        assert!(parse_str("[Main]{\n    Main <- \"a\" ~ \"b\" \"\\z\" : \"a\" \"c\" \"\\z\",\n}\n", "ac").is_err());
+        assert!(parse_str("[Main]{\n    Main <- &(\"a\" ~ \"b\") \"ab\" \"\\z\" : \"a\" \"c\" \"\\z\",\n}\n", "ac").is_ok());
+    }
+
+    // ret: 切断演算子を直接含むグループのメモ化 ID
+    fn find_cut_group_memo_id(group: &RuleGroup) -> Option<usize> {
+        if group.sub_elems.iter().any(|each_elem| matches!(each_elem, RuleElement::Cut(_))) {
+            return Some(group.memo_id);
+        }
+
+        return group.sub_elems.iter().find_map(|each_elem| match each_elem {
+            RuleElement::Group(each_group) => find_cut_group_memo_id(each_group),
+            _ => None,
+        });
+    }
+
+    // note: 再利用時に選択へ失敗を伝播できないため、切断演算子に到達した後の失敗はメモ化しない
+    #[test]
+    fn cut_failure_is_not_memoized() {
+        let rule_map = load_test_parser("[Main]{\n    Main <- (\"a\" ~ \"b\" : \"a\" \"c\") \"\\z\" : \"a\" \"c\" \"\\z\",\n}\n").rule_map.clone();
+        let cut_group_memo_id = find_cut_group_memo_id(&rule_map.rule_map[".Main.Main"].group).unwrap();
+
+        let mut parser = new_memoizing_parser(&rule_map, "ac");
+        assert!(parser.parse_start_rule().is_ok());
+        assert!(parser.memoized_map.find(cut_group_memo_id, 0).is_none());
+
+        let mut parser = new_memoizing_parser(&rule_map, "ab");
+        assert!(parser.parse_start_rule().is_ok());
+        assert!(parser.memoized_map.find(cut_group_memo_id, 0).is_some());
+    }
+
+    // note: 確定した候補内の失敗位置がエラーとして報告される
+    #[test]
+    fn cut_reports_error_in_committed_alternative() {
+        let unexpected_input = get_unexpected_input("[Main]{\n    Main <- (\"a\" \"b\" : [a-z]+ \"!\") \"\\z\",\n}\n", "acd?");
+        assert_eq!((unexpected_input.0, unexpected_input.1), ((0, 3), vec!["`[a-z]`".to_string(), "`!`".to_string()]));
 
-        return CharacterPosition::new(Some(self.src_path.clone()), self.src_i, self.src_line, column);
+        let unexpected_input = get_unexpected_input("[Main]{\n    Main <- (\"a\" ~ \"b\" : [a-z]+ \"!\") \"\\z\",\n}\n", "acd?");
+        assert_eq!((unexpected_input.0, unexpected_input.1), ((0, 1), vec!["`b`".to_string()]));
     }
 }
@@ -1,10 +1,13 @@
+use std::cmp::Ordering;
 use std::collections::*;
 use std::fmt::*;
+use std::sync::Arc;
 
+use crate::*;
 use crate::block::*;
 use crate::tree::*;
 
-use rustnutlib::console::ConsoleResult;
+use regex_syntax::hir::{Class, HirKind, Literal};
 
 use uuid::Uuid;
 
@@ -13,11 +16,45 @@ pub struct RuleMap {
     pub rule_map: HashMap<String, Box<Rule>>,
     pub start_rule_pos: CharacterPosition,
     pub start_rule_id: String,
+    // spec: エラー回復モードで用いる同期点
+    // note: <rule_id, sync_strings>
+    pub sync_point_map: HashMap<String, Vec<String>>,
+    // spec: 規則マップの構築時に各グループへ割り当てたメモ化 ID の総数
+    pub group_count: usize,
+    // note: メモ化 ID 毎にメモ化を行うかどうか
+    pub memoized_group_flags: Vec<bool>,
+    // note: <rule_id, memo_ids>
+    pub rule_memo_id_map: HashMap<String, Vec<usize>>,
+    // spec: メモ化する結果の最大件数; None であれば制限しない
+    pub memoization_limit: Option<usize>,
 }
 
 impl RuleMap {
-    pub fn new(block_map: Vec<BlockMap>, start_rule_id: String) -> ConsoleResult<RuleMap> {
-        let raw_rule_map = RuleMap::to_rule_map(block_map)?;
+    pub fn new(block_map: Vec<BlockMap>, start_rule_id: String) -> FCPEGResult<RuleMap> {
+        let mut raw_rule_map = RuleMap::to_rule_map(block_map)?;
+        let mut group_count = 0usize;
+        let mut rule_memo_id_map = HashMap::<String, Vec<usize>>::new();
+        // note: <文字クラスの値, コンパイル済みの文字クラス>
+        let mut char_class_map = HashMap::<String, Arc<CharacterClass>>::new();
+        let mut errs = Vec::<FCPEGError>::new();
+
+        // note: 同じ文法から常に同じメモ化 ID を割り当てるため、規則 ID の昇順に走査する
+        let mut rule_ids = raw_rule_map.keys().cloned().collect::<Vec<String>>();
+        rule_ids.sort();
+
+        for each_rule_id in rule_ids {
+            let each_rule = raw_rule_map.get_mut(&each_rule_id).unwrap();
+            let mut memo_ids = Vec::<usize>::new();
+            each_rule.group.assign_memo_ids(&mut group_count, &mut memo_ids);
+            each_rule.group.compile_char_classes(&mut char_class_map, &mut errs);
+            rule_memo_id_map.insert(each_rule_id, memo_ids);
+        }
+
+        if errs.len() == 1 {
+            return Err(errs.remove(0));
+        } else if !errs.is_empty() {
+            return Err(FCPEGError::Multiple(errs));
+        }
 
         let start_rule_pos = match raw_rule_map.get(&start_rule_id) {
             Some(v) => v.pos.clone(),
@@ -28,12 +65,31 @@ impl RuleMap {
             rule_map: raw_rule_map,
             start_rule_pos: start_rule_pos,
             start_rule_id: start_rule_id,
+            sync_point_map: HashMap::new(),
+            group_count: group_count,
+            memoized_group_flags: vec![true; group_count],
+            rule_memo_id_map: rule_memo_id_map,
+            memoization_limit: None,
         };
 
         return Ok(rule_map);
     }
 
-    fn to_rule_map(block_maps: Vec<BlockMap>) -> ConsoleResult<HashMap<String, Box<Rule>>> {
+    // spec: 規則を持たない規則マップ; 生成されたパーサの検査関数が規則を保持するため開始規則 ID 及びメモ化の設定のみを持つ
+    pub fn from_start_rule_id(start_rule_id: String, memoization_limit: Option<usize>) -> RuleMap {
+        return RuleMap {
+            rule_map: HashMap::new(),
+            start_rule_pos: CharacterPosition::get_empty(),
+            start_rule_id: start_rule_id,
+            sync_point_map: HashMap::new(),
+            group_count: 0,
+            memoized_group_flags: Vec::new(),
+            rule_memo_id_map: HashMap::new(),
+            memoization_limit: memoization_limit,
+        };
+    }
+
+    fn to_rule_map(block_maps: Vec<BlockMap>) -> FCPEGResult<HashMap<String, Box<Rule>>> {
         let mut rule_map = HashMap::<String, Box<Rule>>::new();
 
         for each_block_map in block_maps {
@@ -51,6 +107,246 @@ impl RuleMap {
 
         return Ok(rule_map);
     }
+
+    // ret: 規則が存在しなければ false
+    pub fn set_rule_memoization(&mut self, rule_id: &String, is_memoized: bool) -> bool {
+        let memo_ids = match self.rule_memo_id_map.get(rule_id) {
+            Some(v) => v,
+            None => return false,
+        };
+
+        for each_memo_id in memo_ids {
+            self.memoized_group_flags[*each_memo_id] = is_memoized;
+        }
+
+        return true;
+    }
+
+    pub fn set_all_memoization(&mut self, is_memoized: bool) {
+        for each_flag in self.memoized_group_flags.iter_mut() {
+            *each_flag = is_memoized;
+        }
+    }
+
+    // spec: 入力を消費せずに成功しうる規則 ID の集合を不動点計算で求める
+    pub fn get_nullable_rule_ids(&self) -> HashSet<String> {
+        let mut nullable_rule_ids = HashSet::<String>::new();
+
+        loop {
+            let mut is_changed = false;
+
+            for (each_rule_id, each_rule) in &self.rule_map {
+                if !nullable_rule_ids.contains(each_rule_id) && each_rule.group.is_nullable(&nullable_rule_ids) {
+                    nullable_rule_ids.insert(each_rule_id.clone());
+                    is_changed = true;
+                }
+            }
+
+            if !is_changed {
+                return nullable_rule_ids;
+            }
+        }
+    }
+
+    // spec: 生成規則の引数のうち入力を消費せずに呼び出されうるもののインデックスを不動点計算で求める
+    // note: <rule_id, 生成引数のインデックス>
+    fn get_left_call_arg_map(&self, nullable_rule_ids: &HashSet<String>) -> HashMap<String, Vec<usize>> {
+        let mut left_call_arg_map = HashMap::<String, Vec<usize>>::new();
+
+        loop {
+            let mut is_changed = false;
+
+            for (each_rule_id, each_rule) in &self.rule_map {
+                if each_rule.generics_arg_ids.is_empty() {
+                    continue;
+                }
+
+                let mut left_call_rule_ids = Vec::<String>::new();
+                each_rule.group.collect_left_call_rule_ids(nullable_rule_ids, &left_call_arg_map, &mut left_call_rule_ids);
+
+                let arg_indexes = (0..each_rule.generics_arg_ids.len()).filter(|each_arg_i| left_call_rule_ids.contains(&format!("${}", each_rule.generics_arg_ids[*each_arg_i]))).collect::<Vec<usize>>();
+
+                if left_call_arg_map.get(each_rule_id) != Some(&arg_indexes) {
+                    left_call_arg_map.insert(each_rule_id.clone(), arg_indexes);
+                    is_changed = true;
+                }
+            }
+
+            if !is_changed {
+                return left_call_arg_map;
+            }
+        }
+    }
+
+    // ret: 規則 ID の昇順; 各循環は同じ規則 ID で始まり同じ規則 ID で終わる
+    // note: 生成規則の引数に渡した規則が入力を消費せずに呼び出される場合は、呼び出し元から引数の規則への呼び出しとみなす
+    pub fn get_left_recursive_cycles(&self, nullable_rule_ids: &HashSet<String>) -> Vec<Vec<String>> {
+        let left_call_arg_map = self.get_left_call_arg_map(nullable_rule_ids);
+        // note: <rule_id, 入力を消費せずに呼び出されうる規則 ID>
+        let mut left_call_map = HashMap::<String, Vec<String>>::new();
+
+        for (each_rule_id, each_rule) in &self.rule_map {
+            let mut left_call_rule_ids = Vec::<String>::new();
+            each_rule.group.collect_left_call_rule_ids(nullable_rule_ids, &left_call_arg_map, &mut left_call_rule_ids);
+            left_call_map.insert(each_rule_id.clone(), left_call_rule_ids);
+        }
+
+        let mut rule_ids = self.rule_map.keys().cloned().collect::<Vec<String>>();
+        rule_ids.sort();
+
+        let mut cycles = Vec::<Vec<String>>::new();
+        let mut cycle_rule_ids = HashSet::<String>::new();
+
+        for each_rule_id in &rule_ids {
+            if cycle_rule_ids.contains(each_rule_id) {
+                continue;
+            }
+
+            // note: 幅優先探索で自身に戻る最短の経路を求める
+            // note: <rule_id, 直前の rule_id>
+            let mut prev_map = HashMap::<String, String>::new();
+            let mut queue = VecDeque::<String>::new();
+            queue.push_back(each_rule_id.clone());
+
+            'search: while let Some(current_rule_id) = queue.pop_front() {
+                let next_rule_ids = match left_call_map.get(&current_rule_id) {
+                    Some(v) => v,
+                    None => continue,
+                };
+
+                for each_next_rule_id in next_rule_ids {
+                    if each_next_rule_id == each_rule_id {
+                        let mut cycle = vec![each_rule_id.clone()];
+                        let mut tracing_rule_id = current_rule_id.clone();
+
+                        while tracing_rule_id != *each_rule_id {
+                            cycle.push(tracing_rule_id.clone());
+                            tracing_rule_id = prev_map.get(&tracing_rule_id).unwrap().clone();
+                        }
+
+                        cycle.push(each_rule_id.clone());
+                        cycle.reverse();
+
+                        for each_cycle_rule_id in &cycle {
+                            cycle_rule_ids.insert(each_cycle_rule_id.clone());
+                        }
+
+                        cycles.push(cycle);
+                        break 'search;
+                    }
+
+                    if !prev_map.contains_key(each_next_rule_id) {
+                        prev_map.insert(each_next_rule_id.clone(), current_rule_id.clone());
+                        queue.push_back(each_next_rule_id.clone());
+                    }
+                }
+            }
+        }
+
+        return cycles;
+    }
+
+    // ret: (規則 ID, 繰り返し要素の位置) の一覧; 規則 ID の昇順
+    pub fn get_nullable_repetitions(&self, nullable_rule_ids: &HashSet<String>) -> Vec<(String, CharacterPosition)> {
+        let mut rule_ids = self.rule_map.keys().cloned().collect::<Vec<String>>();
+        rule_ids.sort();
+
+        let mut repetitions = Vec::<(String, CharacterPosition)>::new();
+
+        for each_rule_id in &rule_ids {
+            let each_rule = self.rule_map.get(each_rule_id).unwrap();
+            let mut positions = Vec::<CharacterPosition>::new();
+            each_rule.group.collect_nullable_repetitions(nullable_rule_ids, &each_rule.pos, &mut positions);
+
+            for each_pos in positions {
+                repetitions.push((each_rule_id.clone(), each_pos));
+            }
+        }
+
+        return repetitions;
+    }
+
+    // spec: 開始規則から呼び出されうる規則 ID の集合を求める
+    // note: 開始規則が存在しなければ空集合
+    pub fn get_reachable_rule_ids(&self) -> HashSet<String> {
+        let mut reachable_rule_ids = HashSet::<String>::new();
+        let mut rule_id_stack = vec![self.start_rule_id.clone()];
+
+        while let Some(each_rule_id) = rule_id_stack.pop() {
+            if reachable_rule_ids.contains(&each_rule_id) {
+                continue;
+            }
+
+            let each_rule = match self.rule_map.get(&each_rule_id) {
+                Some(v) => v,
+                None => continue,
+            };
+
+            each_rule.group.collect_called_rule_ids(&mut rule_id_stack);
+            reachable_rule_ids.insert(each_rule_id);
+        }
+
+        return reachable_rule_ids;
+    }
+
+    // spec: 選択の候補のうち先の候補と同一のものを求める
+    // ret: (規則 ID, 候補の位置, 先の候補の位置) の一覧; 規則 ID の昇順
+    pub fn get_duplicate_alternatives(&self) -> Vec<(String, CharacterPosition, CharacterPosition)> {
+        return self.get_redundant_alternatives(true);
+    }
+
+    // spec: 選択の候補のうち先の候補が常に先に成功するため選ばれないものを求める; 同一の候補は含まない
+    // ret: (規則 ID, 候補の位置, 先の候補の位置) の一覧; 規則 ID の昇順
+    pub fn get_shadowed_alternatives(&self) -> Vec<(String, CharacterPosition, CharacterPosition)> {
+        return self.get_redundant_alternatives(false);
+    }
+
+    // note: 各候補について最初に見つかった先の候補のみを対象とする
+    fn get_redundant_alternatives(&self, is_duplicate: bool) -> Vec<(String, CharacterPosition, CharacterPosition)> {
+        let mut rule_ids = self.rule_map.keys().cloned().collect::<Vec<String>>();
+        rule_ids.sort();
+
+        let mut alternatives = Vec::<(String, CharacterPosition, CharacterPosition)>::new();
+
+        for each_rule_id in &rule_ids {
+            let each_rule = self.rule_map.get(each_rule_id).unwrap();
+            let mut choice_groups = Vec::<&RuleGroup>::new();
+            each_rule.group.collect_choice_groups(&mut choice_groups);
+
+            for each_group in choice_groups {
+                let alternative_texts = each_group.sub_elems.iter().map(|each_elem| each_elem.to_string()).collect::<Vec<String>>();
+
+                for (alternative_i, each_alternative) in each_group.sub_elems.iter().enumerate() {
+                    for prev_alternative_i in 0..alternative_i {
+                        let prev_alternative = &each_group.sub_elems[prev_alternative_i];
+                        let is_same = alternative_texts[prev_alternative_i] == alternative_texts[alternative_i];
+
+                        if !is_same && !prev_alternative.shadows(each_alternative) {
+                            continue;
+                        }
+
+                        if is_same == is_duplicate {
+                            let pos = match each_alternative.get_first_position() {
+                                Some(v) => v,
+                                None => each_rule.pos.clone(),
+                            };
+
+                            let prev_pos = match prev_alternative.get_first_position() {
+                                Some(v) => v,
+                                None => each_rule.pos.clone(),
+                            };
+
+                            alternatives.push((each_rule_id.clone(), pos, prev_pos));
+                        }
+
+                        break;
+                    }
+                }
+            }
+        }
+
+        return alternatives;
+    }
 }
 
 impl Display for RuleMap {
@@ -124,7 +420,7 @@ impl Rule {
 
 impl Display for Rule {
     fn fmt(&self, f: &mut Formatter) -> Result {
-        let generics_arg_id_text = if self.generics_arg_ids.len() == 0 {
+        let generics_arg_id_text = if self.generics_arg_ids.is_empty() {
             String::new()
         } else {
             format!("({})", self.generics_arg_ids.iter().map(|s| format!("${}", s)).collect::<Vec<String>>().join(", "))
@@ -248,8 +544,8 @@ impl RuleElementOrder {
 impl Display for RuleElementOrder {
     fn fmt(&self, f: &mut Formatter) -> Result {
         let s = match self {
-            RuleElementOrder::Random(loop_range) => format!("{}", loop_range.to_string(false, "^", "[", "-", "]")),
-            RuleElementOrder::Sequential => format!(""),
+            RuleElementOrder::Random(loop_range) => loop_range.to_string(false, "^", "[", "-", "]"),
+            RuleElementOrder::Sequential => String::new(),
         };
 
         return write!(f, "{}", s);
@@ -260,6 +556,115 @@ impl Display for RuleElementOrder {
 pub enum RuleElement {
     Group(Box<RuleGroup>),
     Expression(Box<RuleExpression>),
+    // note: 切断演算子; 連接内で到達した後の失敗は外側の選択の残りの候補を検査しない
+    Cut(CharacterPosition),
+}
+
+impl RuleElement {
+    pub fn is_nullable(&self, nullable_rule_ids: &HashSet<String>) -> bool {
+        return match self {
+            RuleElement::Group(group) => group.is_nullable(nullable_rule_ids),
+            RuleElement::Expression(expr) => expr.is_nullable(nullable_rule_ids),
+            RuleElement::Cut(_) => true,
+        };
+    }
+
+    fn collect_left_call_rule_ids(&self, nullable_rule_ids: &HashSet<String>, left_call_arg_map: &HashMap<String, Vec<usize>>, left_call_rule_ids: &mut Vec<String>) {
+        match self {
+            RuleElement::Group(group) => group.collect_left_call_rule_ids(nullable_rule_ids, left_call_arg_map, left_call_rule_ids),
+            RuleElement::Expression(expr) => expr.collect_left_call_rule_ids(nullable_rule_ids, left_call_arg_map, left_call_rule_ids),
+            RuleElement::Cut(_) => (),
+        }
+    }
+
+    fn collect_nullable_repetitions(&self, nullable_rule_ids: &HashSet<String>, rule_pos: &CharacterPosition, positions: &mut Vec<CharacterPosition>) {
+        match self {
+            RuleElement::Group(group) => group.collect_nullable_repetitions(nullable_rule_ids, rule_pos, positions),
+            RuleElement::Expression(expr) => expr.collect_nullable_repetitions(nullable_rule_ids, positions),
+            RuleElement::Cut(_) => (),
+        }
+    }
+
+    fn collect_called_rule_ids(&self, called_rule_ids: &mut Vec<String>) {
+        match self {
+            RuleElement::Group(group) => group.collect_called_rule_ids(called_rule_ids),
+            RuleElement::Expression(expr) => expr.collect_called_rule_ids(called_rule_ids),
+            RuleElement::Cut(_) => (),
+        }
+    }
+
+    fn collect_choice_groups<'a>(&'a self, choice_groups: &mut Vec<&'a RuleGroup>) {
+        match self {
+            RuleElement::Group(group) => group.collect_choice_groups(choice_groups),
+            RuleElement::Expression(expr) => expr.collect_choice_groups(choice_groups),
+            RuleElement::Cut(_) => (),
+        }
+    }
+
+    // spec: 入力に関わらず成功するか; 規則の呼び出しは辿らない
+    fn is_always_successful(&self) -> bool {
+        return match self {
+            RuleElement::Group(group) => group.is_always_successful(),
+            RuleElement::Expression(expr) => expr.lookahead_kind.is_none() && (expr.loop_range.min == 0 || expr.is_literal() && expr.value.is_empty()),
+            RuleElement::Cut(_) => true,
+        };
+    }
+
+    // ret: 先頭から連続する文字列を連結したものと、要素全体が文字列のみから成るかどうか
+    fn get_literal_prefix(&self) -> (String, bool) {
+        return match self {
+            RuleElement::Group(group) => group.get_literal_prefix(),
+            RuleElement::Expression(expr) => {
+                if expr.is_literal() {
+                    (expr.value.clone(), true)
+                } else {
+                    (String::new(), false)
+                }
+            },
+            RuleElement::Cut(_) => (String::new(), true),
+        };
+    }
+
+    // spec: 選択の候補として、other が成功する入力では常に自身が先に成功するか
+    // note: 自身が常に成功する場合、または自身が文字列のみから成り other の先頭の文字列がそれで始まる場合 (例: "a" : "ab")
+    fn shadows(&self, other: &RuleElement) -> bool {
+        if self.is_always_successful() {
+            return true;
+        }
+
+        let (prefix, is_literal) = self.get_literal_prefix();
+
+        if !is_literal {
+            return false;
+        }
+
+        let (other_prefix, _) = other.get_literal_prefix();
+        return other_prefix.starts_with(&prefix);
+    }
+
+    fn get_first_position(&self) -> Option<CharacterPosition> {
+        return match self {
+            RuleElement::Group(group) => group.get_first_position(),
+            RuleElement::Expression(expr) => Some(expr.pos.clone()),
+            RuleElement::Cut(pos) => Some(pos.clone()),
+        };
+    }
+
+    fn assign_memo_ids(&mut self, next_memo_id: &mut usize, memo_ids: &mut Vec<usize>) {
+        match self {
+            RuleElement::Group(group) => group.assign_memo_ids(next_memo_id, memo_ids),
+            RuleElement::Expression(expr) => expr.assign_memo_ids(next_memo_id, memo_ids),
+            RuleElement::Cut(_) => (),
+        }
+    }
+
+    fn compile_char_classes(&mut self, char_class_map: &mut HashMap<String, Arc<CharacterClass>>, errs: &mut Vec<FCPEGError>) {
+        match self {
+            RuleElement::Group(group) => group.compile_char_classes(char_class_map, errs),
+            RuleElement::Expression(expr) => expr.compile_char_classes(char_class_map, errs),
+            RuleElement::Cut(_) => (),
+        }
+    }
 }
 
 impl Display for RuleElement {
@@ -267,6 +672,7 @@ impl Display for RuleElement {
         return match self {
             RuleElement::Group(group) => write!(f, "{}", group),
             RuleElement::Expression(expr) => write!(f, "{}", expr),
+            RuleElement::Cut(_) => write!(f, "~"),
         }
     }
 }
@@ -291,6 +697,8 @@ impl Display for RuleGroupKind {
 #[derive(Clone)]
 pub struct RuleGroup {
     pub uuid: Uuid,
+    // note: 規則マップの構築時に割り当てられる連番; メモ化表の添字に用いる
+    pub memo_id: usize,
     pub kind: RuleGroupKind,
     pub sub_elems: Vec<RuleElement>,
     pub ast_reflection_style: ASTReflectionStyle,
@@ -303,6 +711,7 @@ impl RuleGroup {
     pub fn new(kind: RuleGroupKind) -> RuleGroup {
         return RuleGroup {
             uuid: Uuid::new_v4(),
+            memo_id: 0,
             kind: kind,
             sub_elems: Vec::new(),
             lookahead_kind: RuleElementLookaheadKind::None,
@@ -311,6 +720,162 @@ impl RuleGroup {
             elem_order: RuleElementOrder::Sequential,
         };
     }
+
+    // note: 先読み及び繰り返しを考慮する
+    pub fn is_nullable(&self, nullable_rule_ids: &HashSet<String>) -> bool {
+        return !self.lookahead_kind.is_none() || self.loop_range.min == 0 || self.is_nullable_once(nullable_rule_ids);
+    }
+
+    // note: 先読み及び繰り返しを考慮せずに 1 回分の検査が入力を消費せずに成功しうるか
+    fn is_nullable_once(&self, nullable_rule_ids: &HashSet<String>) -> bool {
+        match &self.elem_order {
+            RuleElementOrder::Random(random_order_loop_range) => {
+                if random_order_loop_range.min == 0 {
+                    return true;
+                }
+
+                // note: 順不同ではすべての対象要素が出現する必要がある
+                return match self.sub_elems.first() {
+                    Some(RuleElement::Group(tar_parent_group)) => tar_parent_group.sub_elems.iter().all(|each_elem| each_elem.is_nullable(nullable_rule_ids)),
+                    _ => self.sub_elems.iter().all(|each_elem| each_elem.is_nullable(nullable_rule_ids)),
+                };
+            },
+            RuleElementOrder::Sequential => (),
+        }
+
+        return match self.kind {
+            RuleGroupKind::Choice => self.sub_elems.iter().any(|each_elem| each_elem.is_nullable(nullable_rule_ids)),
+            RuleGroupKind::Sequence => self.sub_elems.iter().all(|each_elem| each_elem.is_nullable(nullable_rule_ids)),
+        };
+    }
+
+    fn collect_left_call_rule_ids(&self, nullable_rule_ids: &HashSet<String>, left_call_arg_map: &HashMap<String, Vec<usize>>, left_call_rule_ids: &mut Vec<String>) {
+        match self.kind {
+            RuleGroupKind::Choice => {
+                for each_elem in &self.sub_elems {
+                    each_elem.collect_left_call_rule_ids(nullable_rule_ids, left_call_arg_map, left_call_rule_ids);
+                }
+            },
+            RuleGroupKind::Sequence => {
+                // note: 順不同ではいずれの要素も先頭に来うる
+                let is_random = self.elem_order.is_random();
+
+                for each_elem in &self.sub_elems {
+                    each_elem.collect_left_call_rule_ids(nullable_rule_ids, left_call_arg_map, left_call_rule_ids);
+
+                    if !is_random && !each_elem.is_nullable(nullable_rule_ids) {
+                        break;
+                    }
+                }
+            },
+        }
+    }
+
+    fn collect_nullable_repetitions(&self, nullable_rule_ids: &HashSet<String>, rule_pos: &CharacterPosition, positions: &mut Vec<CharacterPosition>) {
+        if self.lookahead_kind.is_none() && self.loop_range.max.is_infinite() && self.is_nullable_once(nullable_rule_ids) {
+            let pos = match self.get_first_position() {
+                Some(v) => v,
+                None => rule_pos.clone(),
+            };
+
+            positions.push(pos);
+        }
+
+        for each_elem in &self.sub_elems {
+            each_elem.collect_nullable_repetitions(nullable_rule_ids, rule_pos, positions);
+        }
+    }
+
+    fn collect_called_rule_ids(&self, called_rule_ids: &mut Vec<String>) {
+        for each_elem in &self.sub_elems {
+            each_elem.collect_called_rule_ids(called_rule_ids);
+        }
+    }
+
+    fn collect_choice_groups<'a>(&'a self, choice_groups: &mut Vec<&'a RuleGroup>) {
+        // note: 順不同の対象要素を囲むグループは順序付き選択ではないため対象外とする
+        if self.elem_order.is_random() {
+            for each_elem in &self.sub_elems {
+                match each_elem {
+                    RuleElement::Group(tar_parent_group) => {
+                        for each_tar_elem in &tar_parent_group.sub_elems {
+                            each_tar_elem.collect_choice_groups(choice_groups);
+                        }
+                    },
+                    _ => each_elem.collect_choice_groups(choice_groups),
+                }
+            }
+
+            return;
+        }
+
+        if self.kind == RuleGroupKind::Choice {
+            choice_groups.push(self);
+        }
+
+        for each_elem in &self.sub_elems {
+            each_elem.collect_choice_groups(choice_groups);
+        }
+    }
+
+    fn is_always_successful(&self) -> bool {
+        if !self.lookahead_kind.is_none() {
+            return false;
+        }
+
+        if self.loop_range.min == 0 {
+            return true;
+        }
+
+        if self.elem_order.is_random() {
+            return false;
+        }
+
+        return match self.kind {
+            RuleGroupKind::Choice => self.sub_elems.iter().any(|each_elem| each_elem.is_always_successful()),
+            RuleGroupKind::Sequence => self.sub_elems.iter().all(|each_elem| each_elem.is_always_successful()),
+        };
+    }
+
+    // note: 先読み・繰り返し・順不同を伴わない連接のみ要素を辿る
+    fn get_literal_prefix(&self) -> (String, bool) {
+        if !self.lookahead_kind.is_none() || !self.loop_range.is_single_loop() || self.elem_order.is_random() || self.kind == RuleGroupKind::Choice {
+            return (String::new(), false);
+        }
+
+        let mut prefix = String::new();
+
+        for each_elem in &self.sub_elems {
+            let (each_prefix, is_literal) = each_elem.get_literal_prefix();
+            prefix += &each_prefix;
+
+            if !is_literal {
+                return (prefix, false);
+            }
+        }
+
+        return (prefix, true);
+    }
+
+    fn get_first_position(&self) -> Option<CharacterPosition> {
+        return self.sub_elems.iter().find_map(|each_elem| each_elem.get_first_position());
+    }
+
+    fn assign_memo_ids(&mut self, next_memo_id: &mut usize, memo_ids: &mut Vec<usize>) {
+        self.memo_id = *next_memo_id;
+        memo_ids.push(*next_memo_id);
+        *next_memo_id += 1;
+
+        for each_elem in self.sub_elems.iter_mut() {
+            each_elem.assign_memo_ids(next_memo_id, memo_ids);
+        }
+    }
+
+    fn compile_char_classes(&mut self, char_class_map: &mut HashMap<String, Arc<CharacterClass>>, errs: &mut Vec<FCPEGError>) {
+        for each_elem in self.sub_elems.iter_mut() {
+            each_elem.compile_char_classes(char_class_map, errs);
+        }
+    }
 }
 
 impl Display for RuleGroup {
@@ -325,6 +890,9 @@ impl Display for RuleGroup {
                 RuleElement::Expression(each_expr) => {
                     seq_text.push(format!("{}", each_expr));
                 },
+                RuleElement::Cut(_) => {
+                    seq_text.push("~".to_string());
+                },
             }
         }
 
@@ -340,7 +908,7 @@ impl Display for RuleGroup {
 
         let loop_text = self.loop_range.to_string(true, "", "{", ",", "}");
 
-        return write!(f, "{}", format!("{}({}){}{}{}", self.lookahead_kind, seq_text.join(separator), loop_text, self.elem_order, self.ast_reflection_style));
+        return write!(f, "{}({}){}{}{}", self.lookahead_kind, seq_text.join(separator), loop_text, self.elem_order, self.ast_reflection_style);
     }
 }
 
@@ -377,6 +945,8 @@ pub struct RuleExpression {
     pub ast_reflection_style: ASTReflectionStyle,
     pub lookahead_kind: RuleElementLookaheadKind,
     pub loop_range: RuleElementLoopRange,
+    // note: 文字クラスであれば規則マップの構築時に設定される
+    pub char_class: Option<Arc<CharacterClass>>,
 }
 
 impl RuleExpression {
@@ -388,6 +958,155 @@ impl RuleExpression {
             ast_reflection_style: ASTReflectionStyle::NoReflection,
             lookahead_kind: RuleElementLookaheadKind::None,
             loop_range: RuleElementLoopRange::get_single_loop(),
+            char_class: None,
+        }
+    }
+
+    // note: 先読み及び繰り返しを考慮する
+    pub fn is_nullable(&self, nullable_rule_ids: &HashSet<String>) -> bool {
+        return !self.lookahead_kind.is_none() || self.loop_range.min == 0 || self.is_nullable_once(nullable_rule_ids);
+    }
+
+    // note: 引数 ID は引数によって異なるため入力を消費するものとみなす
+    fn is_nullable_once(&self, nullable_rule_ids: &HashSet<String>) -> bool {
+        return match &self.kind {
+            RuleExpressionKind::ArgId => false,
+            RuleExpressionKind::CharClass => false,
+            RuleExpressionKind::Id => nullable_rule_ids.contains(&self.value),
+            RuleExpressionKind::IdWithArgs { generics_args, template_args: _ } => {
+                if PRIMITIVE_RULE_NAMES.contains(&self.value.as_str()) {
+                    generics_args.iter().all(|each_arg| each_arg.is_nullable(nullable_rule_ids))
+                } else {
+                    nullable_rule_ids.contains(&self.value)
+                }
+            },
+            RuleExpressionKind::String => self.value.is_empty(),
+            RuleExpressionKind::Wildcard => false,
+        };
+    }
+
+    fn collect_left_call_rule_ids(&self, nullable_rule_ids: &HashSet<String>, left_call_arg_map: &HashMap<String, Vec<usize>>, left_call_rule_ids: &mut Vec<String>) {
+        match &self.kind {
+            // note: 引数 ID は規則 ID と区別するため先頭に $ を付ける
+            RuleExpressionKind::ArgId => left_call_rule_ids.push(format!("${}", self.value)),
+            RuleExpressionKind::Id => left_call_rule_ids.push(self.value.clone()),
+            RuleExpressionKind::IdWithArgs { generics_args, template_args: _ } => {
+                if PRIMITIVE_RULE_NAMES.contains(&self.value.as_str()) {
+                    for each_arg in generics_args {
+                        each_arg.collect_left_call_rule_ids(nullable_rule_ids, left_call_arg_map, left_call_rule_ids);
+                    }
+                } else {
+                    left_call_rule_ids.push(self.value.clone());
+
+                    // note: 呼び出し先で入力を消費せずに呼び出されうる生成引数は、呼び出し元からも入力を消費せずに呼び出されうる
+                    match left_call_arg_map.get(&self.value) {
+                        Some(arg_indexes) => {
+                            for each_arg_i in arg_indexes {
+                                match generics_args.get(*each_arg_i) {
+                                    Some(v) => v.collect_left_call_rule_ids(nullable_rule_ids, left_call_arg_map, left_call_rule_ids),
+                                    None => (),
+                                }
+                            }
+                        },
+                        None => (),
+                    }
+                }
+            },
+            _ => (),
+        }
+    }
+
+    fn collect_nullable_repetitions(&self, nullable_rule_ids: &HashSet<String>, positions: &mut Vec<CharacterPosition>) {
+        if self.lookahead_kind.is_none() && self.loop_range.max.is_infinite() && self.is_nullable_once(nullable_rule_ids) {
+            positions.push(self.pos.clone());
+        }
+
+        match &self.kind {
+            RuleExpressionKind::IdWithArgs { generics_args, template_args } => {
+                for each_arg in generics_args.iter().chain(template_args.iter()) {
+                    each_arg.collect_nullable_repetitions(nullable_rule_ids, &self.pos, positions);
+                }
+            },
+            _ => (),
+        }
+    }
+
+    fn collect_called_rule_ids(&self, called_rule_ids: &mut Vec<String>) {
+        match &self.kind {
+            RuleExpressionKind::Id => called_rule_ids.push(self.value.clone()),
+            RuleExpressionKind::IdWithArgs { generics_args, template_args } => {
+                if !PRIMITIVE_RULE_NAMES.contains(&self.value.as_str()) {
+                    called_rule_ids.push(self.value.clone());
+                }
+
+                for each_arg in generics_args.iter().chain(template_args.iter()) {
+                    each_arg.collect_called_rule_ids(called_rule_ids);
+                }
+            },
+            _ => (),
+        }
+    }
+
+    fn collect_choice_groups<'a>(&'a self, choice_groups: &mut Vec<&'a RuleGroup>) {
+        match &self.kind {
+            RuleExpressionKind::IdWithArgs { generics_args, template_args } => {
+                for each_arg in generics_args.iter().chain(template_args.iter()) {
+                    each_arg.collect_choice_groups(choice_groups);
+                }
+            },
+            _ => (),
+        }
+    }
+
+    // ret: 先読み及び繰り返しを伴わない文字列であるか
+    fn is_literal(&self) -> bool {
+        return match self.kind {
+            RuleExpressionKind::String => self.lookahead_kind.is_none() && self.loop_range.is_single_loop(),
+            _ => false,
+        };
+    }
+
+    // note: 引数のグループも呼び出し元の規則に属するものとして扱う
+    fn assign_memo_ids(&mut self, next_memo_id: &mut usize, memo_ids: &mut Vec<usize>) {
+        match &mut self.kind {
+            RuleExpressionKind::IdWithArgs { generics_args, template_args } => {
+                for each_arg in generics_args.iter_mut().chain(template_args.iter_mut()) {
+                    each_arg.assign_memo_ids(next_memo_id, memo_ids);
+                }
+            },
+            _ => (),
+        }
+    }
+
+    // note: 同じ値の文字クラスはコンパイル結果を共有する
+    fn compile_char_classes(&mut self, char_class_map: &mut HashMap<String, Arc<CharacterClass>>, errs: &mut Vec<FCPEGError>) {
+        match &mut self.kind {
+            RuleExpressionKind::CharClass => {
+                match char_class_map.get(&self.value) {
+                    Some(v) => self.char_class = Some(v.clone()),
+                    None => {
+                        match CharacterClass::compile(&self.value) {
+                            Some(v) => {
+                                let char_class = Arc::new(v);
+                                char_class_map.insert(self.value.clone(), char_class.clone());
+                                self.char_class = Some(char_class);
+                            },
+                            None => {
+                                errs.push(BlockParsingLog::InvalidCharacterClass {
+                                    pos: self.pos.clone(),
+                                    value: self.value.clone(),
+                                }.into());
+                            },
+                        }
+                    },
+                }
+            },
+            RuleExpressionKind::IdWithArgs { generics_args, template_args } => {
+                for each_arg in generics_args.iter_mut().chain(template_args.iter_mut()) {
+                    each_arg.compile_char_classes(char_class_map, errs);
+                }
+            },
+            _ => (),
         }
     }
 }
@@ -401,7 +1120,7 @@ impl Display for RuleExpression {
             RuleExpressionKind::Id => self.value.clone(),
             RuleExpressionKind::IdWithArgs { generics_args, template_args } => {
                 let generics_text = {
-                    if generics_args.len() != 0 {
+                    if !generics_args.is_empty() {
                         let generics_arg_text = generics_args.iter().map(|v| v.to_string()).collect::<Vec<String>>();
                         format!("{}<{}>", self.value, generics_arg_text.join(", "))
                     } else {
@@ -410,7 +1129,7 @@ impl Display for RuleExpression {
                 };
 
                 let template_text = {
-                    if template_args.len() != 0 {
+                    if !template_args.is_empty() {
                         let template_arg_text = template_args.iter().map(|v| v.to_string()).collect::<Vec<String>>();
                         format!("{}({})", self.value, template_arg_text.join(", "))
                     } else {
@@ -427,3 +1146,148 @@ impl Display for RuleExpression {
         return write!(f, "{}{}{}{}", self.lookahead_kind, value_text, loop_text, self.ast_reflection_style);
     }
 }
+
+// spec: 規則マップの構築時にコンパイルされる文字クラス; 照合時にメモリを確保しない
+// note: ASCII 文字はビットマップで、それ以外は昇順に並べた文字範囲の二分探索で判定する
+#[derive(Clone)]
+pub struct CharacterClass {
+    ascii_bitmap: u128,
+    // note: (開始文字, 終了文字) のいずれも含む範囲; 互いに重ならない
+    ranges: Vec<(char, char)>,
+}
+
+impl CharacterClass {
+    // spec: 値は角括弧を含む正規表現の文字クラス構文; \p{L} などの Unicode カテゴリを使用できる
+    // ret: 単一の文字クラスとして解釈できなければ None
+    pub fn compile(value: &str) -> Option<CharacterClass> {
+        let hir = match regex_syntax::Parser::new().parse(value) {
+            Ok(v) => v,
+            Err(_) => return None,
+        };
+
+        let ranges = match hir.kind() {
+            HirKind::Class(Class::Unicode(class)) => class.iter().map(|each_range| (each_range.start(), each_range.end())).collect::<Vec<(char, char)>>(),
+            HirKind::Literal(Literal::Unicode(c)) => vec![(*c, *c)],
+            _ => return None,
+        };
+
+        return Some(CharacterClass::from_ranges(ranges));
+    }
+
+    // note: ranges は昇順に並べた互いに重ならない文字範囲
+    pub fn from_ranges(ranges: Vec<(char, char)>) -> CharacterClass {
+        let mut ascii_bitmap = 0u128;
+
+        for (start_char, end_char) in &ranges {
+            for each_code in (*start_char as u32)..=(*end_char as u32).min(127) {
+                ascii_bitmap |= 1u128 << each_code;
+            }
+        }
+
+        return CharacterClass {
+            ascii_bitmap: ascii_bitmap,
+            ranges: ranges,
+        };
+    }
+
+    pub fn get_ranges(&self) -> &Vec<(char, char)> {
+        return &self.ranges;
+    }
+
+    pub fn contains(&self, c: char) -> bool {
+        if (c as u32) < 128 {
+            return self.ascii_bitmap & (1u128 << (c as u32)) != 0;
+        }
+
+        let result = self.ranges.binary_search_by(|(start_char, end_char)| {
+            if *end_char < c {
+                Ordering::Less
+            } else if *start_char > c {
+                Ordering::Greater
+            } else {
+                Ordering::Equal
+            }
+        });
+
+        return result.is_ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::*;
+
+    use crate::block::*;
+    use crate::file::*;
+    use crate::rule::*;
+
+    const FCPEG_SOURCE: &str = "[Main]{\n    + start Syntax.Main,\n}\n\n[Syntax]{\n    Main <- (A : B : C : D)*,\n    A <- \"a\" (\"1\" : \"2\"),\n    B <- \"b\"+,\n    C <- \"c\" D?,\n    D <- [0-9] (\",\" [0-9])*,\n}\n";
+
+    fn load_rule_map() -> RuleMap {
+        let mut fcpeg_file_map = FCPEGFileMap::load_from_str("test.fcpeg".to_string(), FCPEG_SOURCE.to_string(), String::new(), HashMap::new(), &MemorySourceResolver::new()).unwrap();
+        let rule_map = BlockParser::get_rule_map(&mut fcpeg_file_map, true, &mut Vec::new()).unwrap();
+        return (*rule_map).clone();
+    }
+
+    #[test]
+    fn memo_ids_are_assigned_in_rule_id_order() {
+        let rule_map = load_rule_map();
+        let mut rule_ids = rule_map.rule_memo_id_map.keys().collect::<Vec<&String>>();
+        rule_ids.sort();
+
+        let memo_ids = rule_ids.iter().flat_map(|each_rule_id| rule_map.rule_memo_id_map[*each_rule_id].clone()).collect::<Vec<usize>>();
+        assert_eq!(memo_ids, (0..rule_map.group_count).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn memo_ids_are_stable_across_builds() {
+        let rule_map = load_rule_map();
+
+        for _ in 0..8 {
+            let other_rule_map = load_rule_map();
+            assert_eq!(rule_map.rule_memo_id_map, other_rule_map.rule_memo_id_map);
+
+            for (each_rule_id, each_rule) in &rule_map.rule_map {
+                assert_eq!(each_rule.group.memo_id, other_rule_map.rule_map[each_rule_id].group.memo_id);
+            }
+        }
+    }
+
+    // ret: 文字列の各文字が文字クラスに含まれるかどうか
+    fn get_contained_flags(value: &str, chars: &str) -> Vec<bool> {
+        let char_class = CharacterClass::compile(value).unwrap();
+        return chars.chars().map(|each_char| char_class.contains(each_char)).collect();
+    }
+
+    #[test]
+    fn char_class_ranges_are_matched() {
+        assert_eq!(get_contained_flags("[a-cx]", "`abcdx"), vec![false, true, true, true, false, true]);
+        assert_eq!(get_contained_flags("[^0-9]", "a05\0あ"), vec![true, false, false, true, true]);
+    }
+
+    #[test]
+    fn non_ascii_chars_are_matched_by_ranges() {
+        assert_eq!(get_contained_flags("[α-γぁ-ん]", "βδかア"), vec![true, false, true, false]);
+        assert_eq!(get_contained_flags("[\\p{L}]", "aéあ1。"), vec![true, true, true, false, false]);
+    }
+
+    // note: 127 までは ASCII のビット列、128 以降は範囲の二分探索で判定する
+    #[test]
+    fn char_class_range_across_ascii_boundary_is_matched() {
+        assert_eq!(get_contained_flags("[\\x7E-\\x{80}]", "}\u{7e}\u{7f}\u{80}\u{81}"), vec![false, true, true, true, false]);
+        assert_eq!(get_contained_flags("[^\\x{80}]", "\u{7f}\u{80}\u{81}"), vec![true, false, true]);
+    }
+
+    #[test]
+    fn invalid_char_class_fails_to_load() {
+        let mut fcpeg_file_map = FCPEGFileMap::load_from_str("test.fcpeg".to_string(), "[Main]{\n    Main <- [z-a],\n}\n".to_string(), String::new(), HashMap::new(), &MemorySourceResolver::new()).unwrap();
+
+        match BlockParser::get_rule_map(&mut fcpeg_file_map, true, &mut Vec::new()) {
+            Err(FCPEGError::BlockParsing(log)) => match *log {
+                BlockParsingLog::InvalidCharacterClass { pos, value } => assert_eq!((pos.line, pos.column, value), (1, 12, "[z-a]".to_string())),
+                v => panic!("unexpected log {:?}", v),
+            },
+            v => panic!("unexpected result {:?}", v.err()),
+        }
+    }
+}
@@ -0,0 +1,319 @@
+use crate::tree::*;
+
+// spec: 構文木を JSON 文字列に変換する
+// note: 位置情報の行数・列数は 0 始まり; 子要素を持たないノードの範囲は null
+pub struct JSONSerializer {
+    ignore_hidden_elems: bool,
+    output: String,
+}
+
+impl JSONSerializer {
+    pub fn serialize(tree: &SyntaxTree, ignore_hidden_elems: bool) -> String {
+        let mut serializer = JSONSerializer {
+            ignore_hidden_elems: ignore_hidden_elems,
+            output: String::new(),
+        };
+
+        serializer.write_elem(tree.get_child_ref(), 0);
+        serializer.output += "\n";
+        return serializer.output;
+    }
+
+    fn write_elem(&mut self, elem: &SyntaxNodeElement, nest: usize) {
+        match elem {
+            SyntaxNodeElement::Node(node) => self.write_node(node, nest),
+            SyntaxNodeElement::Leaf(leaf) => self.write_leaf(leaf, nest),
+        }
+    }
+
+    fn write_node(&mut self, node: &SyntaxNode, nest: usize) {
+        let indent = "    ".repeat(nest + 1);
+        let sub_elems = node.sub_elems.iter().filter(|each_elem| !self.ignore_hidden_elems || each_elem.is_reflectable()).collect::<Vec<&SyntaxNodeElement>>();
+
+        self.output += "{\n";
+        self.output += &format!("{}\"kind\": \"node\",\n", indent);
+        self.write_ast_reflection_style(&node.ast_reflection_style, &indent);
+        self.output += &format!("{}\"uuid\": \"{}\",\n", indent, node.uuid);
+
+        let span = match node.get_span() {
+            Some((start_pos, end_pos)) => JSONSerializer::to_span_value(&start_pos, &end_pos),
+            None => "null".to_string(),
+        };
+
+        self.output += &format!("{}\"span\": {},\n", indent, span);
+
+        if sub_elems.is_empty() {
+            self.output += &format!("{}\"children\": []\n", indent);
+        } else {
+            self.output += &format!("{}\"children\": [\n", indent);
+
+            for (elem_i, each_elem) in sub_elems.iter().enumerate() {
+                self.output += &"    ".repeat(nest + 2);
+                self.write_elem(each_elem, nest + 2);
+
+                if elem_i + 1 != sub_elems.len() {
+                    self.output += ",";
+                }
+
+                self.output += "\n";
+            }
+
+            self.output += &format!("{}]\n", indent);
+        }
+
+        self.output += &format!("{}}}", "    ".repeat(nest));
+    }
+
+    fn write_leaf(&mut self, leaf: &SyntaxLeaf, nest: usize) {
+        let indent = "    ".repeat(nest + 1);
+
+        self.output += "{\n";
+        self.output += &format!("{}\"kind\": \"leaf\",\n", indent);
+        self.write_ast_reflection_style(&leaf.ast_reflection_style, &indent);
+        self.output += &format!("{}\"uuid\": \"{}\",\n", indent, leaf.uuid);
+        self.output += &format!("{}\"span\": {},\n", indent, JSONSerializer::to_span_value(&leaf.pos, &leaf.get_end_position()));
+        self.output += &format!("{}\"value\": {}\n", indent, JSONSerializer::to_string_value(&leaf.value));
+        self.output += &format!("{}}}", "    ".repeat(nest));
+    }
+
+    fn write_ast_reflection_style(&mut self, ast_reflection_style: &ASTReflectionStyle, indent: &String) {
+        let (reflection, name) = match ast_reflection_style {
+            ASTReflectionStyle::Reflection(elem_name) => ("reflection", JSONSerializer::to_string_value(elem_name)),
+            ASTReflectionStyle::NoReflection => ("noReflection", "null".to_string()),
+            ASTReflectionStyle::Expansion => ("expansion", "null".to_string()),
+        };
+
+        self.output += &format!("{}\"reflection\": \"{}\",\n", indent, reflection);
+        self.output += &format!("{}\"name\": {},\n", indent, name);
+    }
+
+    fn to_span_value(start_pos: &CharacterPosition, end_pos: &CharacterPosition) -> String {
+        return format!("{{ \"start\": {}, \"end\": {} }}", JSONSerializer::to_position_value(start_pos), JSONSerializer::to_position_value(end_pos));
+    }
+
+    fn to_position_value(pos: &CharacterPosition) -> String {
+        let file_path = match &pos.file_path {
+            Some(v) => JSONSerializer::to_string_value(v),
+            None => "null".to_string(),
+        };
+
+        return format!("{{ \"file_path\": {}, \"index\": {}, \"line\": {}, \"column\": {} }}", file_path, pos.index, pos.line, pos.column);
+    }
+
+    fn to_string_value(s: &str) -> String {
+        let mut value = String::new();
+
+        for each_char in s.chars() {
+            match each_char {
+                '"' => value += "\\\"",
+                '\\' => value += "\\\\",
+                '\n' => value += "\\n",
+                '\r' => value += "\\r",
+                '\t' => value += "\\t",
+                c if (c as u32) < 0x20 => value += &format!("\\u{:04x}", c as u32),
+                c => value.push(c),
+            }
+        }
+
+        return format!("\"{}\"", value);
+    }
+}
+
+// spec: 構文木を S 式文字列に変換する; UUID を出力しないため同じ入力に対して常に同じ結果となる
+// note: ノードは (反映方式 子要素...)、リーフは (反映方式 "値" 入力位置:行数:列数) の形式
+// note: 反映方式は反映的であれば #反映名, 展開的であれば ##, 非反映的であれば !
+pub struct SExpressionSerializer {
+    ignore_hidden_elems: bool,
+    output: String,
+}
+
+impl SExpressionSerializer {
+    pub fn serialize(tree: &SyntaxTree, ignore_hidden_elems: bool) -> String {
+        let mut serializer = SExpressionSerializer {
+            ignore_hidden_elems: ignore_hidden_elems,
+            output: String::new(),
+        };
+
+        serializer.write_elem(tree.get_child_ref(), 0);
+        serializer.output += "\n";
+        return serializer.output;
+    }
+
+    fn write_elem(&mut self, elem: &SyntaxNodeElement, nest: usize) {
+        match elem {
+            SyntaxNodeElement::Node(node) => {
+                self.output += &format!("({}", SExpressionSerializer::to_ast_reflection_atom(&node.ast_reflection_style));
+
+                for each_elem in &node.sub_elems {
+                    if self.ignore_hidden_elems && !each_elem.is_reflectable() {
+                        continue;
+                    }
+
+                    self.output += &format!("\n{}", "    ".repeat(nest + 1));
+                    self.write_elem(each_elem, nest + 1);
+                }
+
+                self.output += ")";
+            },
+            SyntaxNodeElement::Leaf(leaf) => {
+                let ast_reflection_atom = SExpressionSerializer::to_ast_reflection_atom(&leaf.ast_reflection_style);
+                let pos_atom = format!("{}:{}:{}", leaf.pos.index, leaf.pos.line, leaf.pos.column);
+                self.output += &format!("({} {} {})", ast_reflection_atom, SExpressionSerializer::to_string_atom(&leaf.value), pos_atom);
+            },
+        }
+    }
+
+    fn to_ast_reflection_atom(ast_reflection_style: &ASTReflectionStyle) -> String {
+        return match ast_reflection_style {
+            ASTReflectionStyle::Reflection(elem_name) => format!("#{}", SExpressionSerializer::escape(elem_name, false)),
+            ASTReflectionStyle::NoReflection => "!".to_string(),
+            ASTReflectionStyle::Expansion => "##".to_string(),
+        };
+    }
+
+    fn to_string_atom(s: &str) -> String {
+        return format!("\"{}\"", SExpressionSerializer::escape(s, true));
+    }
+
+    // note: 引用符の外では空白・括弧もエスケープし、アトムを 1 トークンとして読めるようにする
+    fn escape(s: &str, is_quoted: bool) -> String {
+        let mut value = String::new();
+
+        for each_char in s.chars() {
+            match each_char {
+                '"' => value += "\\\"",
+                '\\' => value += "\\\\",
+                '\n' => value += "\\n",
+                '\r' => value += "\\r",
+                '\t' => value += "\\t",
+                ' ' if !is_quoted => value += "\\s",
+                '(' if !is_quoted => value += "\\(",
+                ')' if !is_quoted => value += "\\)",
+                c if (c as u32) < 0x20 => value += &format!("\\u{:04x}", c as u32),
+                c => value.push(c),
+            }
+        }
+
+        return value;
+    }
+}
+
+// spec: 構文木を XML 文字列に変換する
+// note: XML 1.0 で使用できない制御文字は U+FFFD に置換する
+pub struct XMLSerializer {
+    ignore_hidden_elems: bool,
+    output: String,
+}
+
+impl XMLSerializer {
+    pub fn serialize(tree: &SyntaxTree, ignore_hidden_elems: bool) -> String {
+        let mut serializer = XMLSerializer {
+            ignore_hidden_elems: ignore_hidden_elems,
+            output: String::new(),
+        };
+
+        serializer.output += "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n";
+        serializer.output += "<tree>\n";
+        serializer.write_elem(tree.get_child_ref(), 1);
+        serializer.output += "</tree>\n";
+        return serializer.output;
+    }
+
+    fn write_elem(&mut self, elem: &SyntaxNodeElement, nest: usize) {
+        let indent = "    ".repeat(nest);
+
+        match elem {
+            SyntaxNodeElement::Node(node) => {
+                let sub_elems = node.sub_elems.iter().filter(|each_elem| !self.ignore_hidden_elems || each_elem.is_reflectable()).collect::<Vec<&SyntaxNodeElement>>();
+                let attrs = format!("{} uuid=\"{}\"", XMLSerializer::to_ast_reflection_attrs(&node.ast_reflection_style), node.uuid);
+
+                if sub_elems.is_empty() {
+                    self.output += &format!("{}<node {}/>\n", indent, attrs);
+                } else {
+                    self.output += &format!("{}<node {}>\n", indent, attrs);
+
+                    for each_elem in sub_elems {
+                        self.write_elem(each_elem, nest + 1);
+                    }
+
+                    self.output += &format!("{}</node>\n", indent);
+                }
+            },
+            SyntaxNodeElement::Leaf(leaf) => {
+                let file_path_attr = match &leaf.pos.file_path {
+                    Some(v) => format!(" file=\"{}\"", XMLSerializer::escape(v)),
+                    None => String::new(),
+                };
+
+                let pos_attrs = format!("{} index=\"{}\" line=\"{}\" column=\"{}\"", file_path_attr, leaf.pos.index, leaf.pos.line, leaf.pos.column);
+                let attrs = format!("{} uuid=\"{}\"{}", XMLSerializer::to_ast_reflection_attrs(&leaf.ast_reflection_style), leaf.uuid, pos_attrs);
+                self.output += &format!("{}<leaf {}>{}</leaf>\n", indent, attrs, XMLSerializer::escape(&leaf.value));
+            },
+        }
+    }
+
+    fn to_ast_reflection_attrs(ast_reflection_style: &ASTReflectionStyle) -> String {
+        return match ast_reflection_style {
+            ASTReflectionStyle::Reflection(elem_name) => format!("reflection=\"reflection\" name=\"{}\"", XMLSerializer::escape(elem_name)),
+            ASTReflectionStyle::NoReflection => "reflection=\"noReflection\"".to_string(),
+            ASTReflectionStyle::Expansion => "reflection=\"expansion\"".to_string(),
+        };
+    }
+
+    fn escape(s: &str) -> String {
+        let mut value = String::new();
+
+        for each_char in s.chars() {
+            match each_char {
+                '&' => value += "&amp;",
+                '<' => value += "&lt;",
+                '>' => value += "&gt;",
+                '"' => value += "&quot;",
+                '\'' => value += "&apos;",
+                '\n' => value += "&#xA;",
+                '\r' => value += "&#xD;",
+                '\t' => value += "&#x9;",
+                c if (c as u32) < 0x20 => value.push('\u{FFFD}'),
+                c => value.push(c),
+            }
+        }
+
+        return value;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::serializer::*;
+
+    fn build_tree() -> SyntaxTree {
+        let value_leaf = SyntaxNodeElement::from_leaf_args(CharacterPosition::new(None, 0, 0, 0), "<\"a\"\n\u{1}>".to_string(), ASTReflectionStyle::Reflection("Value".to_string()));
+        let hidden_leaf = SyntaxNodeElement::from_leaf_args(CharacterPosition::new(None, 6, 1, 2), ";".to_string(), ASTReflectionStyle::NoReflection);
+        return SyntaxTree::from_node_args(vec![value_leaf, hidden_leaf], ASTReflectionStyle::Reflection("A (b)".to_string()));
+    }
+
+    #[test]
+    fn json_escapes_string_values() {
+        let json = build_tree().to_json(true);
+
+        assert!(json.contains("\"name\": \"A (b)\",\n"));
+        assert!(json.contains("\"value\": \"<\\\"a\\\"\\n\\u0001>\"\n"));
+        assert!(!json.contains("\"value\": \";\""));
+    }
+
+    #[test]
+    fn sexpr_escapes_atoms_and_strings() {
+        assert_eq!(build_tree().to_sexpr(false), "(#A\\s\\(b\\)\n    (#Value \"<\\\"a\\\"\\n\\u0001>\" 0:0:0)\n    (! \";\" 6:1:2))\n");
+        assert_eq!(build_tree().to_sexpr(true), "(#A\\s\\(b\\)\n    (#Value \"<\\\"a\\\"\\n\\u0001>\" 0:0:0))\n");
+    }
+
+    #[test]
+    fn xml_escapes_values_and_replaces_control_chars() {
+        let xml = build_tree().to_xml(true);
+
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<tree>\n"));
+        assert!(xml.contains("reflection=\"reflection\" name=\"A (b)\""));
+        assert!(xml.contains(">&lt;&quot;a&quot;&#xA;\u{FFFD}&gt;</leaf>\n"));
+        assert!(!xml.contains("reflection=\"noReflection\""));
+    }
+}
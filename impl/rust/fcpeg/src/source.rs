@@ -0,0 +1,325 @@
+use std::io::Read;
+
+use crate::*;
+use crate::file::*;
+
+// note: Read から一度に読み込むバイト数
+pub const SOURCE_CHUNK_SIZE: usize = 64 * 1024;
+
+// spec: 構文解析の入力を文字単位で保持する; Read から読み込む場合は要求された位置まで必要に応じて読み込む
+// note: 余分な改行コード 0x0d は読み込み時に排除し、入力の終端に EOF 用のヌル文字を追加する
+pub struct SourceBuffer<'a> {
+    src_path: String,
+    reader: Option<Box<dyn Read + 'a>>,
+    // note: UTF-8 の途中で読み込みが区切られた場合の未変換のバイト列
+    pending_bytes: Vec<u8>,
+    // note: chars[0] の入力位置; 切断点より手前の文字は破棄される
+    offset: usize,
+    chars: Vec<char>,
+    // note: 各行の先頭の入力位置; line_start_indexes[0] は chars[0] を含む行
+    line_start_indexes: Vec<usize>,
+    // note: 破棄された行の数; line_start_indexes[0] の行番号
+    discarded_line_count: usize,
+    is_eof: bool,
+    // note: 参照された最も遠い入力位置; メモ化結果が検査した範囲の記録に用いる
+    examined_end_i: usize,
+    // note: 読み込み時のエラー; 以降の入力は終端とみなす
+    err: Option<FCPEGError>,
+}
+
+impl<'a> SourceBuffer<'a> {
+    pub fn from_string(src_path: String, src_content: Box<String>) -> SourceBuffer<'a> {
+        let mut buffer = SourceBuffer::new(src_path, None);
+        buffer.push_str(&src_content);
+        buffer.push_eof();
+        return buffer;
+    }
+
+    pub fn from_reader(src_path: String, reader: Box<dyn Read + 'a>) -> SourceBuffer<'a> {
+        return SourceBuffer::new(src_path, Some(reader));
+    }
+
+    fn new(src_path: String, reader: Option<Box<dyn Read + 'a>>) -> SourceBuffer<'a> {
+        return SourceBuffer {
+            src_path: src_path,
+            reader: reader,
+            pending_bytes: Vec::new(),
+            offset: 0,
+            chars: Vec::new(),
+            line_start_indexes: vec![0],
+            discarded_line_count: 0,
+            is_eof: false,
+            examined_end_i: 0,
+            err: None,
+        };
+    }
+
+    pub fn is_streaming(&self) -> bool {
+        return self.reader.is_some();
+    }
+
+    // ret: 入力位置の文字; 入力の範囲外または破棄された位置であれば None
+    pub fn get(&mut self, src_i: usize) -> Option<char> {
+        self.mark_examined(src_i);
+
+        if src_i < self.offset {
+            return None;
+        }
+
+        while src_i >= self.offset + self.chars.len() && !self.is_eof {
+            self.read_chunk();
+        }
+
+        return self.chars.get(src_i - self.offset).cloned();
+    }
+
+    pub fn has_char_at(&mut self, src_i: usize) -> bool {
+        return self.get(src_i).is_some();
+    }
+
+    // note: 入力の終端まで読み込むため、Read から読み込む場合はすべての入力を保持する
+    pub fn get_len(&mut self) -> usize {
+        while !self.is_eof {
+            self.read_chunk();
+        }
+
+        return self.offset + self.chars.len();
+    }
+
+    // note: 現在までに読み込んだ入力の長さ
+    pub fn get_loaded_len(&self) -> usize {
+        return self.offset + self.chars.len();
+    }
+
+    pub fn get_offset(&self) -> usize {
+        return self.offset;
+    }
+
+    // ret: 読み込み済みの範囲の部分文字列; 破棄された範囲は含まない
+    pub fn substring(&mut self, start_i: usize, len: usize) -> String {
+        self.get(start_i + len);
+
+        let end_i = (start_i + len).max(self.offset) - self.offset;
+        let start_i = start_i.max(self.offset) - self.offset;
+        return self.chars[start_i..end_i.min(self.chars.len())].iter().collect::<String>();
+    }
+
+    // ret: 行番号とその行の先頭の入力位置
+    // note: 破棄された行の位置は保持しないため、破棄された範囲の入力位置は保持している最初の行の先頭として扱う
+    pub fn get_line_at(&self, src_i: usize) -> (usize, usize) {
+        // note: src_i 以下で最大の行頭位置を持つ行
+        let line = match self.line_start_indexes.binary_search(&src_i) {
+            Ok(v) => v,
+            Err(0) => return (self.discarded_line_count, src_i),
+            Err(v) => v - 1,
+        };
+
+        return (self.discarded_line_count + line, self.line_start_indexes[line]);
+    }
+
+    // ret: 入力位置を含む行の内容; 破棄された範囲は含まない
+    pub fn get_line_content_at(&mut self, src_i: usize) -> String {
+        let (_, line_start_i) = self.get_line_at(src_i);
+        let mut content = String::new();
+        let mut char_i = line_start_i.max(self.offset);
+
+        loop {
+            match self.get(char_i) {
+                Some('\n') | Some('\0') | None => return content,
+                Some(v) => content.push(v),
+            }
+
+            char_i += 1;
+        }
+    }
+
+    // spec: 切断点より手前の文字を破棄する; 破棄する文字数が読み込み単位に満たない場合は何もしない
+    pub fn discard_before(&mut self, src_i: usize) {
+        if src_i < self.offset + SOURCE_CHUNK_SIZE {
+            return;
+        }
+
+        let discarded_len = (src_i - self.offset).min(self.chars.len());
+        self.chars.drain(..discarded_len);
+        self.offset += discarded_len;
+
+        // note: 先頭の文字を含む行より手前の行頭位置を破棄する
+        let discarded_line_len = match self.line_start_indexes.binary_search(&self.offset) {
+            Ok(v) => v,
+            Err(v) => v - 1,
+        };
+
+        self.line_start_indexes.drain(..discarded_line_len);
+        self.discarded_line_count += discarded_line_len;
+    }
+
+    pub fn get_examined_end_i(&self) -> usize {
+        return self.examined_end_i;
+    }
+
+    pub fn set_examined_end_i(&mut self, src_i: usize) {
+        self.examined_end_i = src_i;
+    }
+
+    // note: メモ化結果を再利用した場合はその結果が検査した範囲を参照済みとする
+    pub fn mark_examined(&mut self, src_i: usize) {
+        if src_i > self.examined_end_i {
+            self.examined_end_i = src_i;
+        }
+    }
+
+    pub fn take_error(&mut self) -> Option<FCPEGError> {
+        return self.err.take();
+    }
+
+    fn read_chunk(&mut self) {
+        let reader = match &mut self.reader {
+            Some(v) => v,
+            None => {
+                self.push_eof();
+                return;
+            },
+        };
+
+        let mut chunk = vec![0u8; SOURCE_CHUNK_SIZE];
+
+        let read_len = match reader.read(&mut chunk) {
+            Ok(v) => v,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => return,
+            Err(_) => {
+                self.set_error();
+                return;
+            },
+        };
+
+        if read_len == 0 {
+            if !self.pending_bytes.is_empty() {
+                self.set_error();
+            } else {
+                self.push_eof();
+            }
+
+            return;
+        }
+
+        self.pending_bytes.extend_from_slice(&chunk[..read_len]);
+
+        // note: 末尾の不完全な UTF-8 のバイト列は次の読み込みまで保持する
+        let valid_len = match std::str::from_utf8(&self.pending_bytes) {
+            Ok(v) => v.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(_) => {
+                self.set_error();
+                return;
+            },
+        };
+
+        let pending_bytes = self.pending_bytes.split_off(valid_len);
+        let valid_bytes = std::mem::replace(&mut self.pending_bytes, pending_bytes);
+        self.push_str(std::str::from_utf8(&valid_bytes).unwrap());
+    }
+
+    fn push_str(&mut self, s: &str) {
+        for each_char in s.chars() {
+            if each_char == 0x0d as char {
+                continue;
+            }
+
+            self.chars.push(each_char);
+
+            if each_char == '\n' {
+                self.line_start_indexes.push(self.offset + self.chars.len());
+            }
+        }
+    }
+
+    fn push_eof(&mut self) {
+        if self.is_eof {
+            return;
+        }
+
+        // EOF 用のヌル文字
+        self.chars.push('\0');
+        self.is_eof = true;
+    }
+
+    fn set_error(&mut self) {
+        self.err = Some(FileLog::FailedToReadSource {
+            file_path: self.src_path.clone(),
+        }.into());
+
+        self.pending_bytes.clear();
+        self.push_eof();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::source::*;
+
+    fn from_bytes<'a>(bytes: &[u8]) -> SourceBuffer<'a> {
+        return SourceBuffer::from_reader("input.txt".to_string(), Box::new(ByteReader::new(bytes)));
+    }
+
+    #[test]
+    fn reader_joins_split_multibyte_chars() {
+        let mut buffer = from_bytes("aあ\r\nい".as_bytes());
+
+        assert_eq!(buffer.get(1), Some('あ'));
+        assert_eq!(buffer.get_loaded_len(), 2);
+        assert_eq!(buffer.get_len(), 5);
+        assert_eq!(buffer.substring(0, 5), "aあ\nい\0");
+        assert_eq!(buffer.get_line_at(3), (1, 3));
+        assert_eq!(buffer.get_line_content_at(0), "aあ");
+        assert!(buffer.take_error().is_none());
+    }
+
+    #[test]
+    fn invalid_utf8_is_read_as_end_of_source() {
+        for each_bytes in [vec![0x61, 0xff, 0x62], vec![0x61, 0xe3, 0x81]] {
+            let mut buffer = from_bytes(&each_bytes);
+
+            assert_eq!(buffer.get(0), Some('a'));
+            assert_eq!(buffer.get(1), Some('\0'));
+            assert_eq!(buffer.get(2), None);
+
+            match buffer.take_error() {
+                Some(FCPEGError::File(FileLog::FailedToReadSource { file_path })) => assert_eq!(file_path, "input.txt"),
+                _ => panic!("unexpected error"),
+            }
+        }
+    }
+
+    #[test]
+    fn chars_before_cut_point_are_discarded() {
+        let src = "a".repeat(SOURCE_CHUNK_SIZE * 2);
+        let mut buffer = SourceBuffer::from_reader("input.txt".to_string(), Box::new(Cursor::new(src.into_bytes())));
+        buffer.get(SOURCE_CHUNK_SIZE + 10);
+
+        // note: 読み込み単位に満たない文字数は破棄しない
+        buffer.discard_before(SOURCE_CHUNK_SIZE - 1);
+        assert_eq!(buffer.get_offset(), 0);
+
+        buffer.discard_before(SOURCE_CHUNK_SIZE + 5);
+        assert_eq!(buffer.get_offset(), SOURCE_CHUNK_SIZE + 5);
+        assert_eq!(buffer.get(0), None);
+        assert_eq!(buffer.substring(SOURCE_CHUNK_SIZE + 3, 4), "aa");
+        assert_eq!(buffer.get_len(), SOURCE_CHUNK_SIZE * 2 + 1);
+    }
+
+    #[test]
+    fn line_starts_before_cut_point_are_discarded() {
+        let src = "a\n".repeat(SOURCE_CHUNK_SIZE);
+        let mut buffer = SourceBuffer::from_reader("input.txt".to_string(), Box::new(Cursor::new(src.into_bytes())));
+        buffer.get(SOURCE_CHUNK_SIZE + 10);
+        buffer.discard_before(SOURCE_CHUNK_SIZE + 5);
+
+        // note: 先頭の文字を含む行以降の行頭位置のみを保持する
+        assert_eq!(buffer.line_start_indexes[0], SOURCE_CHUNK_SIZE + 4);
+        assert_eq!(buffer.discarded_line_count, SOURCE_CHUNK_SIZE / 2 + 2);
+        assert_eq!(buffer.get_line_at(SOURCE_CHUNK_SIZE + 5), (SOURCE_CHUNK_SIZE / 2 + 2, SOURCE_CHUNK_SIZE + 4));
+        assert_eq!(buffer.get_line_at(SOURCE_CHUNK_SIZE + 6), (SOURCE_CHUNK_SIZE / 2 + 3, SOURCE_CHUNK_SIZE + 6));
+    }
+}
@@ -1,16 +1,19 @@
-use std::cell::RefCell;
 use std::fmt::*;
 use std::io::*;
 use std::io::Write;
-use std::rc::Rc;
 
+use crate::*;
+use crate::deserializer::*;
 use crate::rule::*;
+use crate::serializer::*;
+use crate::visitor::*;
 
 use rustnutlib::*;
 use rustnutlib::console::*;
 
 use uuid::Uuid;
 
+#[derive(Clone, Debug)]
 pub enum TreeLog {
     Unknown {},
     CharacterPositionNotFound { uuid: Uuid },
@@ -18,6 +21,11 @@ pub enum TreeLog {
     ElementNotLeaf { uuid: Uuid },
     NodeChildNotFound { parent_uuid: Uuid, index: usize },
     ReflectableChildNotFound { parent_uuid: Uuid, index: usize },
+    ArenaElementNotNode { id: usize },
+    ArenaElementNotLeaf { id: usize },
+    ArenaReflectableChildNotFound { parent_id: usize, index: usize },
+    // note: 生成された型に構文木のノードを変換できない場合
+    UnexpectedNodeStructure { uuid: Uuid, type_name: String },
 }
 
 impl ConsoleLogger for TreeLog {
@@ -29,11 +37,15 @@ impl ConsoleLogger for TreeLog {
             TreeLog::ElementNotLeaf { uuid } => log!(Error, "element not leaf", format!("uuid:\t{}", uuid)),
             TreeLog::NodeChildNotFound { parent_uuid, index } => log!(Error, "node child not found", format!("parent:\t{}", parent_uuid), format!("index:\t{}", index)),
             TreeLog::ReflectableChildNotFound { parent_uuid, index } => log!(Error, "reflectable child not found", format!("parent:\t{}", parent_uuid), format!("index:\t{}", index)),
+            TreeLog::ArenaElementNotNode { id } => log!(Error, "element not node", format!("id:\t{}", id)),
+            TreeLog::ArenaElementNotLeaf { id } => log!(Error, "element not leaf", format!("id:\t{}", id)),
+            TreeLog::ArenaReflectableChildNotFound { parent_id, index } => log!(Error, "reflectable child not found", format!("parent id:\t{}", parent_id), format!("index:\t{}", index)),
+            TreeLog::UnexpectedNodeStructure { uuid, type_name } => log!(Error, "unexpected node structure", format!("uuid:\t{}", uuid), format!("type:\t{}", type_name)),
         };
     }
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct CharacterPosition {
     pub file_path: Option<String>,
     pub index: usize,
@@ -135,37 +147,30 @@ impl SyntaxNodeElement {
         return SyntaxNodeElement::Leaf(Box::new(SyntaxLeaf::new(pos, value, ast_reflection, Uuid::new_v4())));
     }
 
-    pub fn get_node(&self, cons: &Rc<RefCell<Console>>) -> ConsoleResult<&SyntaxNode> {
+    pub fn get_node(&self) -> FCPEGResult<&SyntaxNode> {
         return match self {
             SyntaxNodeElement::Node(node) => Ok(node),
             SyntaxNodeElement::Leaf(leaf) => {
-                cons.borrow_mut().append_log(TreeLog::ElementNotNode {
-                    uuid: leaf.uuid.clone(),
-                }.get_log());
-
-                return Err(());
+                return Err(TreeLog::ElementNotNode {
+                    uuid: leaf.uuid,
+                }.into());
             },
         };
     }
 
-    pub fn get_leaf(&self, cons: &Rc<RefCell<Console>>) -> ConsoleResult<&SyntaxLeaf> {
+    pub fn get_leaf(&self) -> FCPEGResult<&SyntaxLeaf> {
         return match self {
             SyntaxNodeElement::Node(node) => {
-                cons.borrow_mut().append_log(TreeLog::ElementNotLeaf {
-                    uuid: node.uuid.clone(),
-                }.get_log());
-
-                return Err(());
+                return Err(TreeLog::ElementNotLeaf {
+                    uuid: node.uuid,
+                }.into());
             },
             SyntaxNodeElement::Leaf(leaf) => Ok(leaf),
         };
     }
 
     pub fn is_node(&self) -> bool {
-        return match self {
-            SyntaxNodeElement::Node(_) => true,
-            _ => false,
-        };
+        return matches!(self, SyntaxNodeElement::Node(_));
     }
 
     pub fn is_reflectable(&self) -> bool {
@@ -189,6 +194,39 @@ impl SyntaxNodeElement {
         }
     }
 
+    // ret: UUID を除いて構造・AST 反映方式・位置・値が一致するかどうか
+    pub fn is_equivalent_to(&self, other: &SyntaxNodeElement) -> bool {
+        return match (self, other) {
+            (SyntaxNodeElement::Node(node), SyntaxNodeElement::Node(other_node)) => {
+                node.ast_reflection_style == other_node.ast_reflection_style
+                    && node.sub_elems.len() == other_node.sub_elems.len()
+                    && node.sub_elems.iter().zip(other_node.sub_elems.iter()).all(|(each_elem, each_other_elem)| each_elem.is_equivalent_to(each_other_elem))
+            },
+            (SyntaxNodeElement::Leaf(leaf), SyntaxNodeElement::Leaf(other_leaf)) => {
+                leaf.ast_reflection_style == other_leaf.ast_reflection_style
+                    && leaf.pos == other_leaf.pos
+                    && leaf.value == other_leaf.value
+            },
+            _ => false,
+        };
+    }
+
+    pub fn walk<V: Visitor>(&self, visitor: &mut V) {
+        TreeWalker::walk(self, visitor);
+    }
+
+    pub fn walk_mut<V: VisitorMut>(&mut self, visitor: &mut V) {
+        TreeWalker::walk_mut(self, visitor);
+    }
+
+    pub fn iter_depth_first(&self) -> DepthFirstIter<'_> {
+        return DepthFirstIter::new(self);
+    }
+
+    pub fn iter_breadth_first(&self) -> BreadthFirstIter<'_> {
+        return BreadthFirstIter::new(self);
+    }
+
     pub fn print(&self, ignore_hidden_elems: bool) {
         self.print_with_details(0, &mut BufWriter::new(stdout().lock()), ignore_hidden_elems)
     }
@@ -219,6 +257,15 @@ impl SyntaxTree {
         };
     }
 
+    pub fn from_json(source: &str) -> FCPEGResult<SyntaxTree> {
+        return JSONDeserializer::deserialize(source);
+    }
+
+    // note: S 式はファイルパスを持たないため、復元したリーフの位置には file_path を設定する
+    pub fn from_sexpr(source: &str, file_path: Option<String>) -> FCPEGResult<SyntaxTree> {
+        return SExpressionDeserializer::deserialize(source, file_path);
+    }
+
     pub fn print(&self, ignore_hidden_elems: bool) {
         self.child.print(ignore_hidden_elems)
     }
@@ -226,8 +273,68 @@ impl SyntaxTree {
     pub fn get_child_ref(&self) -> &SyntaxNodeElement {
         return &self.child;
     }
+
+    pub fn into_child(self) -> SyntaxNodeElement {
+        return self.child;
+    }
+
+    pub fn is_equivalent_to(&self, other: &SyntaxTree) -> bool {
+        return self.child.is_equivalent_to(&other.child);
+    }
+
+    // spec: ルートから深さ優先で走査し、各要素の訪問前後に visitor を呼び出す
+    pub fn walk<V: Visitor>(&self, visitor: &mut V) {
+        self.child.walk(visitor);
+    }
+
+    // spec: ルートから深さ優先で走査し、visitor の結果に応じて要素を置き換えもしくは削除する
+    pub fn walk_mut<V: VisitorMut>(&mut self, visitor: &mut V) {
+        self.child.walk_mut(visitor);
+    }
+
+    pub fn iter_depth_first(&self) -> DepthFirstIter<'_> {
+        return self.child.iter_depth_first();
+    }
+
+    pub fn iter_breadth_first(&self) -> BreadthFirstIter<'_> {
+        return self.child.iter_breadth_first();
+    }
+
+    // ret: 指定した UUID の要素の親ノード; ルートの要素もしくは要素が存在しなければ None
+    pub fn find_parent(&self, uuid: &Uuid) -> Option<&SyntaxNode> {
+        for each_visited_elem in self.iter_depth_first() {
+            let each_uuid = match each_visited_elem.elem {
+                SyntaxNodeElement::Node(node) => &node.uuid,
+                SyntaxNodeElement::Leaf(leaf) => &leaf.uuid,
+            };
+
+            if each_uuid == uuid {
+                return each_visited_elem.parent;
+            }
+        }
+
+        return None;
+    }
+
+    // note: ignore_hidden_elems が真であれば非反映的な要素を出力しない
+    pub fn to_json(&self, ignore_hidden_elems: bool) -> String {
+        return JSONSerializer::serialize(self, ignore_hidden_elems);
+    }
+
+    pub fn to_sexpr(&self, ignore_hidden_elems: bool) -> String {
+        return SExpressionSerializer::serialize(self, ignore_hidden_elems);
+    }
+
+    pub fn to_xml(&self, ignore_hidden_elems: bool) -> String {
+        return XMLSerializer::serialize(self, ignore_hidden_elems);
+    }
 }
 
+// note: エラー回復時に挿入されるノードの名前; 規則 ID や要素名と衝突しない
+pub const ERROR_NODE_NAME: &str = "#Error";
+// note: 意味アクションの値を参照するノードの名前; 値は UUID をキーとして保持される
+pub const ACTION_VALUE_NODE_NAME: &str = "#Value";
+
 #[derive(Clone)]
 pub struct SyntaxNode {
     pub sub_elems: Vec<SyntaxNodeElement>,
@@ -301,7 +408,7 @@ impl SyntaxNode {
     }
 
     // todo: 最初に出現したリーフの位置を返す; Unreflectable なリーフも対象にする
-    pub fn get_position(&self, cons: &Rc<RefCell<Console>>) -> ConsoleResult<CharacterPosition> {
+    pub fn get_position(&self) -> FCPEGResult<CharacterPosition> {
         for each_child in self.get_children() {
             match each_child {
                 SyntaxNodeElement::Leaf(each_leaf) => return Ok(each_leaf.pos.clone()),
@@ -309,63 +416,91 @@ impl SyntaxNode {
             }
         };
 
-        cons.borrow_mut().append_log(TreeLog::CharacterPositionNotFound {
-            uuid: self.uuid.clone(),
-        }.get_log());
+        return Err(TreeLog::CharacterPositionNotFound {
+            uuid: self.uuid,
+        }.into());
+    }
+
+    // ret: 子孫リーフの先頭位置と末尾位置; 非反映的なリーフも対象にする; リーフが存在しなければ None
+    pub fn get_span(&self) -> Option<(CharacterPosition, CharacterPosition)> {
+        let mut start_pos = None;
+        let mut end_pos = None;
+
+        for each_elem in &self.sub_elems {
+            let each_span = match each_elem {
+                SyntaxNodeElement::Node(node) => node.get_span(),
+                SyntaxNodeElement::Leaf(leaf) => Some((leaf.pos.clone(), leaf.get_end_position())),
+            };
+
+            match each_span {
+                Some((each_start_pos, each_end_pos)) => {
+                    if start_pos.is_none() {
+                        start_pos = Some(each_start_pos);
+                    }
+
+                    end_pos = Some(each_end_pos);
+                },
+                None => (),
+            }
+        }
 
-        return Err(());
+        return match (start_pos, end_pos) {
+            (Some(start_pos), Some(end_pos)) => Some((start_pos, end_pos)),
+            _ => None,
+        };
     }
 
     pub fn get_children(&self) -> &Vec<SyntaxNodeElement> {
         return &self.sub_elems;
     }
 
-    pub fn get_child_at(&self, cons: &Rc<RefCell<Console>>, index: usize) -> ConsoleResult<&SyntaxNodeElement> {
-        let mut elem_i = 0;
+    pub fn get_child_at(&self, index: usize) -> FCPEGResult<&SyntaxNodeElement> {
         let mut reflectable_elem_i = 0;
 
-        for each_elem in &self.sub_elems {
+        for (elem_i, each_elem) in self.sub_elems.iter().enumerate() {
             if each_elem.is_reflectable() {
                 if reflectable_elem_i == index {
                     return match self.sub_elems.get(elem_i) {
-                        Some(v) => Ok(&v),
+                        Some(v) => Ok(v),
                         None => {
-                            cons.borrow_mut().append_log(TreeLog::NodeChildNotFound {
-                                parent_uuid: self.uuid.clone(),
+                            return Err(TreeLog::NodeChildNotFound {
+                                parent_uuid: self.uuid,
                                 index: index,
-                            }.get_log());
-
-                            return Err(());
+                            }.into());
                         },
                     };
                 }
 
                 reflectable_elem_i += 1;
             }
-
-            elem_i += 1;
         }
 
-        cons.borrow_mut().append_log(TreeLog::ReflectableChildNotFound {
+        return Err(TreeLog::ReflectableChildNotFound {
             parent_uuid: self.uuid,
             index: index,
-        }.get_log());
-
-        return Err(());
+        }.into());
     }
 
-    pub fn get_node_child_at(&self, cons: &Rc<RefCell<Console>>, index: usize) -> ConsoleResult<&SyntaxNode> {
-        return self.get_child_at(cons, index)?.get_node(cons);
+    pub fn get_node_child_at(&self, index: usize) -> FCPEGResult<&SyntaxNode> {
+        return self.get_child_at(index)?.get_node();
     }
 
-    pub fn get_leaf_child_at(&self, cons: &Rc<RefCell<Console>>, index: usize) -> ConsoleResult<&SyntaxLeaf> {
-        return self.get_child_at(cons, index)?.get_leaf(cons);
+    pub fn get_leaf_child_at(&self, index: usize) -> FCPEGResult<&SyntaxLeaf> {
+        return self.get_child_at(index)?.get_leaf();
     }
 
     pub fn is_reflectable(&self) -> bool {
         return self.ast_reflection_style.is_reflectable();
     }
 
+    pub fn is_error_node(&self) -> bool {
+        return self.ast_reflection_style == ASTReflectionStyle::Reflection(ERROR_NODE_NAME.to_string());
+    }
+
+    pub fn is_action_value_node(&self) -> bool {
+        return self.ast_reflection_style == ASTReflectionStyle::Reflection(ACTION_VALUE_NODE_NAME.to_string());
+    }
+
     // note: Reflectable な子孫ノードの値をすべて結合して返す
     pub fn join_child_leaf_values(&self) -> String {
         let mut s = String::new();
@@ -398,7 +533,7 @@ impl SyntaxNode {
 
         let display_name = match &self.ast_reflection_style {
             ASTReflectionStyle::Reflection(elem_name) => {
-                if elem_name == "" {
+                if elem_name.is_empty() {
                     "[noname]".to_string()
                 } else {
                     elem_name.clone()
@@ -440,6 +575,24 @@ impl SyntaxLeaf {
         return self.ast_reflection_style.is_reflectable();
     }
 
+    // ret: トークン末尾の直後の位置
+    pub fn get_end_position(&self) -> CharacterPosition {
+        let mut end_pos = self.pos.clone();
+
+        for each_char in self.value.chars() {
+            end_pos.index += 1;
+
+            if each_char == '\n' {
+                end_pos.line += 1;
+                end_pos.column = 0;
+            } else {
+                end_pos.column += 1;
+            }
+        }
+
+        return end_pos;
+    }
+
     pub fn print(&self, ignore_hidden_elems: bool) {
         self.print_with_details(0, &mut BufWriter::new(stdout().lock()), ignore_hidden_elems);
     }
@@ -0,0 +1,1177 @@
+use std::any::Any;
+use std::collections::*;
+
+use crate::*;
+use crate::block::*;
+use crate::generator::*;
+use crate::parser::*;
+use crate::rule::*;
+use crate::tree::*;
+
+// note: 生成されたコードが用いる型名; 生成する型名と衝突させない
+pub const RESERVED_TYPE_NAMES: &[&str] = &["Box", "FCPEGResult", "None", "Option", "Self", "Some", "String", "SyntaxLeaf", "SyntaxNode", "SyntaxTree", "TypedNodeReader", "Vec"];
+
+// note: フィールド名に用いる場合は生識別子にする
+pub const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if",
+    "impl", "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "static", "struct", "trait", "true", "try",
+    "type", "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+// note: 生識別子にできないためフィールド名の末尾に "_" を付加する; r は read() の引数名
+pub const UNRAW_FIELD_NAMES: &[&str] = &["crate", "r", "self", "super"];
+
+// spec: 規則の要素がノードの反映的な子要素として生成するもの
+// note: loop_range は (最小回数, 最大回数); 最大回数が -1 であれば上限なし
+enum TypedElement {
+    // note: value は文字列リテラルの値; 選択の候補の判別に用いる
+    Leaf { ast_reflection_style: ASTReflectionStyle, value: Option<String>, loop_range: (usize, isize) },
+    // note: ジェネリクス引数を持たない規則の規則ノード
+    RuleNode { name: String, rule_id: String, loop_range: (usize, isize) },
+    // note: 展開されたジェネリクス引数を持たない規則の子要素
+    ExpandedRule { rule_id: String, loop_range: (usize, isize) },
+    // note: グループ及びジェネリクス引数を持つ規則のノード; 子要素が空であれば構文木に追加されない
+    Node { name: String, content: Box<TypedContent>, loop_range: (usize, isize) },
+    // note: 展開されたグループ及びジェネリクス引数を持つ規則の子要素
+    Expansion(Box<TypedContent>),
+}
+
+enum TypedContent {
+    Sequence(Vec<TypedElement>),
+    Choice(Vec<TypedContent>),
+    Repetition(Box<TypedContent>, usize, isize),
+}
+
+// spec: 要素を読み込むコード
+struct TypedCode {
+    // note: フィールド名及びヴァリアント名の元となる名前
+    name: String,
+    // note: None であれば値を持たない
+    type_name: Option<String>,
+    // note: Option<型> (値を持たなければ Option<()>) を返す式
+    read_code: String,
+}
+
+// spec: 規則マップの反映名及び繰り返し範囲から構文木に対応する Rust の型定義と構文木からの変換コードを生成する
+// note: 繰り返し範囲が {0,1} であれば Option、それ以外の繰り返しは Vec、選択は enum とする
+// note: ジェネリクス引数を持たない規則は規則毎に型を生成する; ジェネリクス引数を持つ規則は呼び出し毎に展開する
+pub struct TypedASTGenerator<'a> {
+    rule_map: &'a RuleMap,
+    nullable_rule_ids: HashSet<String>,
+    // note: 左再帰の循環に含まれる規則 ID
+    left_recursive_rule_ids: HashSet<String>,
+    // note: <規則 ID, 型名>
+    rule_type_name_map: HashMap<String, String>,
+    // note: 型を生成していない規則 ID
+    pending_rule_ids: VecDeque<String>,
+    type_names: HashSet<String>,
+    // note: 展開中のジェネリクス引数を持つ規則 ID; 再帰的な展開を検出する
+    generics_rule_id_stack: Vec<String>,
+    type_sources: Vec<String>,
+}
+
+impl<'a> TypedASTGenerator<'a> {
+    pub fn generate(rule_map: &'a RuleMap) -> FCPEGResult<String> {
+        let nullable_rule_ids = rule_map.get_nullable_rule_ids();
+        let left_recursive_rule_ids = rule_map.get_left_recursive_cycles(&nullable_rule_ids).into_iter().flatten().collect();
+
+        let mut generator = TypedASTGenerator {
+            rule_map: rule_map,
+            nullable_rule_ids: nullable_rule_ids,
+            left_recursive_rule_ids: left_recursive_rule_ids,
+            rule_type_name_map: HashMap::new(),
+            pending_rule_ids: VecDeque::new(),
+            type_names: RESERVED_TYPE_NAMES.iter().map(|s| s.to_string()).collect(),
+            generics_rule_id_stack: Vec::new(),
+            type_sources: Vec::new(),
+        };
+
+        if !rule_map.rule_map.contains_key(&rule_map.start_rule_id) {
+            return Err(SyntaxParsingLog::UnknownRuleID {
+                pos: rule_map.start_rule_pos.clone(),
+                rule_id: rule_map.start_rule_id.clone(),
+            }.into());
+        }
+
+        let start_type_name = generator.get_rule_type_name(&rule_map.start_rule_id);
+
+        while let Some(each_rule_id) = generator.pending_rule_ids.pop_front() {
+            generator.generate_rule_type(&each_rule_id)?;
+        }
+
+        let mut s = String::new();
+        s += "// This file was generated by FCPEG. Do not edit it manually.\n\n";
+        s += "use fcpeg::FCPEGResult;\n";
+        s += "use fcpeg::tree::{SyntaxLeaf, SyntaxNode, SyntaxTree};\n";
+        s += "use fcpeg::typed::TypedNodeReader;\n\n";
+        s += &format!("pub const START_RULE_ID: &str = {:?};\n\n", rule_map.start_rule_id);
+        s += &format!("pub fn from_tree(tree: &SyntaxTree) -> FCPEGResult<{}> {{\n", start_type_name);
+        s += &format!("    return {}::from_node(tree.get_child_ref().get_node()?);\n", start_type_name);
+        s += "}\n";
+
+        for each_source in &generator.type_sources {
+            s += "\n";
+            s += each_source;
+        }
+
+        return Ok(s);
+    }
+
+    // note: 型を生成していない規則であれば生成待ちに追加する
+    fn get_rule_type_name(&mut self, rule_id: &String) -> String {
+        match self.rule_type_name_map.get(rule_id) {
+            Some(v) => return v.clone(),
+            None => (),
+        }
+
+        let hint = rule_id.split('.').filter(|s| !s.is_empty()).map(TypedASTGenerator::to_camel_case).collect::<Vec<String>>().join("");
+        let type_name = self.allocate_type_name(&hint);
+        self.rule_type_name_map.insert(rule_id.clone(), type_name.clone());
+        self.pending_rule_ids.push_back(rule_id.clone());
+        return type_name;
+    }
+
+    fn allocate_type_name(&mut self, hint: &String) -> String {
+        let mut type_name = hint.clone();
+        let mut suffix_num = 2;
+
+        while self.type_names.contains(&type_name) {
+            type_name = format!("{}{}", hint, suffix_num);
+            suffix_num += 1;
+        }
+
+        self.type_names.insert(type_name.clone());
+        return type_name;
+    }
+
+    fn generate_rule_type(&mut self, rule_id: &String) -> FCPEGResult<()> {
+        let rule = match self.rule_map.rule_map.get(rule_id) {
+            Some(v) => v,
+            None => {
+                return Err(SyntaxParsingLog::UnknownRuleID {
+                    pos: CharacterPosition::get_empty(),
+                    rule_id: rule_id.clone(),
+                }.into());
+            },
+        };
+
+        let type_name = self.get_rule_type_name(rule_id);
+        let content = self.to_group_content(&rule.group, &rule.group.elem_order, None, &ArgumentEnvironment::new())?;
+
+        return match content {
+            TypedContent::Choice(alternatives) => self.generate_enum(&type_name, &alternatives, true),
+            TypedContent::Sequence(elems) => {
+                let codes = self.to_sequence_codes(&elems, &type_name)?;
+                self.generate_struct(&type_name, &codes, true);
+                Ok(())
+            },
+            _ => {
+                let code = self.to_content_code(&content, &format!("{}Group", type_name))?;
+                self.generate_struct(&type_name, &vec![code], true);
+                Ok(())
+            },
+        };
+    }
+
+    // spec: FCPEGGenerator::generate_group と同様に、先読み・繰り返し・要素の順序を含むグループの子要素を求める
+    // note: loop_range は順不同グループの子グループの繰り返し範囲を置き換える場合に指定する
+    fn to_group_content(&mut self, group: &'a RuleGroup, parent_elem_order: &RuleElementOrder, loop_range: Option<&RuleElementLoopRange>, env: &ArgumentEnvironment<'a>) -> FCPEGResult<TypedContent> {
+        if !group.lookahead_kind.is_none() {
+            return Ok(TypedContent::Sequence(Vec::new()));
+        }
+
+        let loop_range = match loop_range {
+            Some(v) => v,
+            None => &group.loop_range,
+        };
+
+        let content = match parent_elem_order {
+            RuleElementOrder::Random(random_order_loop_range) => {
+                let tar_elems = match group.sub_elems.first() {
+                    Some(RuleElement::Group(tar_parent_group)) => &tar_parent_group.sub_elems,
+                    _ => {
+                        return Err(SyntaxParsingLog::InvalidRuleElementStructure {
+                            uuid: group.uuid,
+                            msg: "random order group must have a child group".to_string(),
+                        }.into());
+                    },
+                };
+
+                let mut subgroup_contents = Vec::<TypedContent>::new();
+
+                for each_elem in tar_elems {
+                    match each_elem {
+                        RuleElement::Group(subgroup) => subgroup_contents.push(self.to_group_content(subgroup, &RuleElementOrder::Sequential, Some(random_order_loop_range), env)?),
+                        _ => (),
+                    }
+                }
+
+                // note: 一致した順に各子グループの子要素が追加されるため、子グループの選択の繰り返しとして扱う
+                let subgroup_len = subgroup_contents.len();
+                TypedContent::Repetition(Box::new(TypedASTGenerator::to_choice_content(subgroup_contents)), subgroup_len, subgroup_len as isize)
+            },
+            RuleElementOrder::Sequential => self.to_elems_content(group, env)?,
+        };
+
+        return Ok(TypedASTGenerator::to_repetition_content(content, loop_range.to_tuple()));
+    }
+
+    // spec: FCPEGGenerator::generate_group_elems と同様に、グループの子要素を順に求める
+    fn to_elems_content(&mut self, group: &'a RuleGroup, env: &ArgumentEnvironment<'a>) -> FCPEGResult<TypedContent> {
+        let is_single_elem = group.sub_elems.len() == 1;
+        let mut elems = Vec::<TypedElement>::new();
+
+        for each_elem in &group.sub_elems {
+            let new_elem = match each_elem {
+                RuleElement::Group(each_group) => {
+                    match each_group.kind {
+                        RuleGroupKind::Choice => {
+                            let mut alternatives = Vec::<TypedContent>::new();
+
+                            for each_sub_elem in &each_group.sub_elems {
+                                match each_sub_elem {
+                                    RuleElement::Group(each_sub_group) => {
+                                        let sub_content = self.to_group_content(each_sub_group, &each_group.elem_order, None, env)?;
+                                        let pushed_elems = TypedASTGenerator::to_pushed_elem(is_single_elem, sub_content, &each_sub_group.ast_reflection_style).into_iter().collect();
+                                        alternatives.push(TypedASTGenerator::to_sequence_content(pushed_elems));
+                                    },
+                                    _ => (),
+                                }
+                            }
+
+                            Some(TypedElement::Expansion(Box::new(TypedASTGenerator::to_choice_content(alternatives))))
+                        },
+                        RuleGroupKind::Sequence => {
+                            let sub_content = self.to_group_content(each_group, &each_group.elem_order, None, env)?;
+                            TypedASTGenerator::to_pushed_elem(is_single_elem, sub_content, &each_group.ast_reflection_style)
+                        },
+                    }
+                },
+                RuleElement::Expression(each_expr) => self.to_expr_elem(each_expr, env)?,
+                RuleElement::Cut(_) => None,
+            };
+
+            match new_elem {
+                Some(v) => elems.push(v),
+                None => (),
+            }
+        }
+
+        return Ok(TypedASTGenerator::to_sequence_content(elems));
+    }
+
+    // note: 先読みを含む式及び非反映的な式は要素を生成しない
+    fn to_expr_elem(&mut self, expr: &'a RuleExpression, env: &ArgumentEnvironment<'a>) -> FCPEGResult<Option<TypedElement>> {
+        if !expr.lookahead_kind.is_none() {
+            return Ok(None);
+        }
+
+        let loop_range = expr.loop_range.to_tuple();
+
+        let elem = match &expr.kind {
+            RuleExpressionKind::ArgId => {
+                let arg_group = match env.get(&expr.value) {
+                    Some(v) => *v,
+                    None => {
+                        return Err(SyntaxParsingLog::UnknownGenericsArgumentID {
+                            arg_id: expr.value.clone(),
+                        }.into());
+                    },
+                };
+
+                // note: 非反映的な引数は先頭の要素のみが非反映的になるが、引数の要素は 1 つであるものとして要素を生成しない
+                if !expr.ast_reflection_style.is_reflectable() {
+                    return Ok(None);
+                }
+
+                let arg_content = self.to_group_content(arg_group, &RuleElementOrder::Sequential, None, env)?;
+                TypedElement::Expansion(Box::new(TypedASTGenerator::to_repetition_content(arg_content, loop_range)))
+            },
+            RuleExpressionKind::CharClass | RuleExpressionKind::Wildcard => {
+                if !expr.ast_reflection_style.is_reflectable() {
+                    return Ok(None);
+                }
+
+                TypedElement::Leaf {
+                    ast_reflection_style: expr.ast_reflection_style.clone(),
+                    value: None,
+                    loop_range: loop_range,
+                }
+            },
+            RuleExpressionKind::String => {
+                if !expr.ast_reflection_style.is_reflectable() {
+                    return Ok(None);
+                }
+
+                TypedElement::Leaf {
+                    ast_reflection_style: expr.ast_reflection_style.clone(),
+                    value: Some(expr.value.clone()),
+                    loop_range: loop_range,
+                }
+            },
+            RuleExpressionKind::Id => {
+                let rule = match self.rule_map.rule_map.get(&expr.value) {
+                    Some(v) => v,
+                    None => {
+                        return Err(SyntaxParsingLog::UnknownRuleID {
+                            pos: expr.pos.clone(),
+                            rule_id: expr.value.clone(),
+                        }.into());
+                    },
+                };
+
+                // note: ジェネリクス引数を持たない規則の呼び出しでは引数を引き継がない
+                let callee_env = if !rule.generics_arg_ids.is_empty() {
+                    env.clone()
+                } else {
+                    ArgumentEnvironment::new()
+                };
+
+                return self.to_id_elem(expr, rule, &callee_env);
+            },
+            RuleExpressionKind::IdWithArgs { generics_args, template_args } => {
+                let rule_id = &expr.value;
+
+                if rule_id == "JOIN" {
+                    if !expr.ast_reflection_style.is_reflectable() {
+                        return Ok(None);
+                    }
+
+                    TypedElement::Leaf {
+                        ast_reflection_style: expr.ast_reflection_style.clone(),
+                        value: None,
+                        loop_range: loop_range,
+                    }
+                } else {
+                    if PRIMITIVE_RULE_NAMES.contains(&rule_id.as_str()) {
+                        return Err(SyntaxParsingLog::UncoveredPrimitiveRule {
+                            pos: expr.pos.clone(),
+                            rule_name: rule_id.clone(),
+                        }.into());
+                    }
+
+                    let rule = match self.rule_map.rule_map.get(rule_id) {
+                        Some(v) => v,
+                        None => {
+                            return Err(SyntaxParsingLog::UnknownRuleID {
+                                pos: expr.pos.clone(),
+                                rule_id: rule_id.clone(),
+                            }.into());
+                        },
+                    };
+
+                    if generics_args.len() != rule.generics_arg_ids.len() {
+                        return Err(SyntaxParsingLog::InvalidGenericsArgumentLength {
+                            pos: expr.pos.clone(),
+                            expected_arg_len: rule.generics_arg_ids.len(),
+                        }.into());
+                    }
+
+                    if template_args.len() != rule.template_arg_ids.len() {
+                        return Err(SyntaxParsingLog::InvalidTemplateArgumentLength {
+                            pos: expr.pos.clone(),
+                            expected_arg_len: rule.template_arg_ids.len(),
+                        }.into());
+                    }
+
+                    let mut callee_env = env.clone();
+
+                    for (each_arg_id, each_arg_group) in rule.generics_arg_ids.iter().zip(generics_args.iter()) {
+                        callee_env.entry(each_arg_id.clone()).or_insert(each_arg_group);
+                    }
+
+                    return self.to_id_elem(expr, rule, &callee_env);
+                }
+            },
+        };
+
+        return Ok(Some(elem));
+    }
+
+    // note: SyntaxParser::parse_id_expr と同様に、反映名が省略された場合は規則 ID を反映名とする
+    fn to_id_elem(&mut self, expr: &'a RuleExpression, rule: &'a Rule, callee_env: &ArgumentEnvironment<'a>) -> FCPEGResult<Option<TypedElement>> {
+        let loop_range = expr.loop_range.to_tuple();
+        let is_generics_rule = !rule.generics_arg_ids.is_empty();
+
+        let elem = match &expr.ast_reflection_style {
+            ASTReflectionStyle::Reflection(elem_name) => {
+                let name = if elem_name.is_empty() {
+                    expr.value.clone()
+                } else {
+                    elem_name.clone()
+                };
+
+                if is_generics_rule {
+                    TypedElement::Node {
+                        name: name,
+                        content: Box::new(self.to_generics_rule_content(rule, callee_env)?),
+                        loop_range: loop_range,
+                    }
+                } else {
+                    TypedElement::RuleNode {
+                        name: name,
+                        rule_id: expr.value.clone(),
+                        loop_range: loop_range,
+                    }
+                }
+            },
+            ASTReflectionStyle::NoReflection => return Ok(None),
+            ASTReflectionStyle::Expansion => {
+                if is_generics_rule {
+                    let content = self.to_generics_rule_content(rule, callee_env)?;
+                    TypedElement::Expansion(Box::new(TypedASTGenerator::to_repetition_content(content, loop_range)))
+                } else {
+                    TypedElement::ExpandedRule {
+                        rule_id: expr.value.clone(),
+                        loop_range: loop_range,
+                    }
+                }
+            },
+        };
+
+        return Ok(Some(elem));
+    }
+
+    fn to_generics_rule_content(&mut self, rule: &'a Rule, callee_env: &ArgumentEnvironment<'a>) -> FCPEGResult<TypedContent> {
+        if self.generics_rule_id_stack.contains(&rule.id) {
+            return Err(SyntaxParsingLog::InvalidRuleElementStructure {
+                uuid: rule.group.uuid,
+                msg: format!("recursive generics rule '{}' cannot be typed", rule.id),
+            }.into());
+        }
+
+        self.generics_rule_id_stack.push(rule.id.clone());
+        let content = self.to_group_content(&rule.group, &rule.group.elem_order, None, callee_env);
+        self.generics_rule_id_stack.pop();
+
+        return content;
+    }
+
+    // spec: SyntaxParser::push_group_result と同様に、連接に追加される要素を求める
+    fn to_pushed_elem(is_single_elem: bool, content: TypedContent, ast_reflection_style: &ASTReflectionStyle) -> Option<TypedElement> {
+        if is_single_elem {
+            return Some(TypedElement::Expansion(Box::new(content)));
+        }
+
+        return match ast_reflection_style {
+            ASTReflectionStyle::Reflection(elem_name) => Some(TypedElement::Node {
+                name: elem_name.clone(),
+                content: Box::new(content),
+                loop_range: (1, 1),
+            }),
+            ASTReflectionStyle::NoReflection => None,
+            ASTReflectionStyle::Expansion => Some(TypedElement::Expansion(Box::new(content))),
+        };
+    }
+
+    fn to_sequence_content(mut elems: Vec<TypedElement>) -> TypedContent {
+        if elems.len() == 1 {
+            match elems.remove(0) {
+                TypedElement::Expansion(content) => return *content,
+                elem => elems.push(elem),
+            }
+        }
+
+        return TypedContent::Sequence(elems);
+    }
+
+    fn to_choice_content(mut alternatives: Vec<TypedContent>) -> TypedContent {
+        return if alternatives.len() == 1 {
+            alternatives.remove(0)
+        } else {
+            TypedContent::Choice(alternatives)
+        };
+    }
+
+    // note: 繰り返し範囲を持たない要素 1 つの連接であれば要素の繰り返し範囲とする
+    fn to_repetition_content(content: TypedContent, loop_range: (usize, isize)) -> TypedContent {
+        if loop_range == (1, 1) {
+            return content;
+        }
+
+        return match content {
+            TypedContent::Sequence(mut elems) if elems.len() == 1 => {
+                match elems.remove(0) {
+                    TypedElement::Leaf { ast_reflection_style, value, loop_range: (1, 1) } => TypedContent::Sequence(vec![TypedElement::Leaf { ast_reflection_style: ast_reflection_style, value: value, loop_range: loop_range }]),
+                    TypedElement::RuleNode { name, rule_id, loop_range: (1, 1) } => TypedContent::Sequence(vec![TypedElement::RuleNode { name: name, rule_id: rule_id, loop_range: loop_range }]),
+                    TypedElement::ExpandedRule { rule_id, loop_range: (1, 1) } => TypedContent::Sequence(vec![TypedElement::ExpandedRule { rule_id: rule_id, loop_range: loop_range }]),
+                    TypedElement::Node { name, content, loop_range: (1, 1) } => TypedContent::Sequence(vec![TypedElement::Node { name: name, content: content, loop_range: loop_range }]),
+                    elem => TypedContent::Repetition(Box::new(TypedContent::Sequence(vec![elem])), loop_range.0, loop_range.1),
+                }
+            },
+            _ => TypedContent::Repetition(Box::new(content), loop_range.0, loop_range.1),
+        };
+    }
+
+    // note: 展開された連接の要素は外側の連接の要素として扱う
+    fn to_sequence_codes(&mut self, elems: &Vec<TypedElement>, type_name: &String) -> FCPEGResult<Vec<TypedCode>> {
+        let mut codes = Vec::<TypedCode>::new();
+
+        for each_elem in elems {
+            match each_elem {
+                TypedElement::Expansion(content) => {
+                    match content.as_ref() {
+                        TypedContent::Sequence(sub_elems) => codes.append(&mut self.to_sequence_codes(sub_elems, type_name)?),
+                        _ => codes.push(self.to_content_code(content, &format!("{}{}", type_name, TypedASTGenerator::get_content_hint(content)))?),
+                    }
+                },
+                _ => codes.push(self.to_elem_code(each_elem, type_name)?),
+            }
+        }
+
+        return Ok(codes);
+    }
+
+    // note: type_name は要素を含む型の名前
+    fn to_elem_code(&mut self, elem: &TypedElement, type_name: &String) -> FCPEGResult<TypedCode> {
+        let code = match elem {
+            TypedElement::Leaf { ast_reflection_style, value, loop_range } => {
+                let value_code = match value {
+                    Some(v) => format!("Some({:?})", v),
+                    None => "None".to_string(),
+                };
+
+                let (name, read_code) = match ast_reflection_style {
+                    ASTReflectionStyle::Reflection(elem_name) if !elem_name.is_empty() => (elem_name.clone(), format!("r.read_leaf({:?}, {})", elem_name, value_code)),
+                    ASTReflectionStyle::Reflection(_) => ("leaf".to_string(), format!("r.read_leaf(\"\", {})", value_code)),
+                    _ => ("leaf".to_string(), format!("r.read_expanded_leaf({})", value_code)),
+                };
+
+                TypedASTGenerator::to_loop_code(name, "SyntaxLeaf".to_string(), read_code.clone(), read_code, *loop_range)
+            },
+            TypedElement::RuleNode { name, rule_id, loop_range } => {
+                let rule_type_name = self.get_rule_type_name(rule_id);
+
+                // note: 空のノードは入力を消費せずに成功しうる規則でのみ生じる; 左再帰の規則を空の子要素から変換し続けないようにする
+                let read_method_name = if self.nullable_rule_ids.contains(rule_id) {
+                    "read_node_or_empty"
+                } else {
+                    "read_node"
+                };
+
+                match loop_range {
+                    (1, 1) => TypedCode {
+                        name: TypedASTGenerator::get_last_id(name),
+                        type_name: Some(format!("Box<{}>", rule_type_name)),
+                        read_code: format!("r.{}({:?}, {}::read).map(Box::new)", read_method_name, name, rule_type_name),
+                    },
+                    (0, 1) => TypedCode {
+                        name: TypedASTGenerator::get_last_id(name),
+                        type_name: Some(format!("Option<Box<{}>>", rule_type_name)),
+                        read_code: format!("r.read_optional(|r| r.read_node({:?}, {}::read).map(Box::new))", name, rule_type_name),
+                    },
+                    _ => TypedASTGenerator::to_loop_code(TypedASTGenerator::get_last_id(name), rule_type_name.clone(), String::new(), format!("r.read_node({:?}, {}::read)", name, rule_type_name), *loop_range),
+                }
+            },
+            TypedElement::ExpandedRule { rule_id, loop_range } => {
+                let rule_type_name = self.get_rule_type_name(rule_id);
+
+                // note: 展開された左再帰の規則は同じ読み込み位置で再帰するため、種を成長させて読み込む
+                let read_code = if self.left_recursive_rule_ids.contains(rule_id) {
+                    format!("r.read_left_recursive_rule({:?}, {}::read)", rule_type_name, rule_type_name)
+                } else {
+                    format!("{}::read(r)", rule_type_name)
+                };
+
+                match loop_range {
+                    (1, 1) | (0, 1) => TypedASTGenerator::to_loop_code(TypedASTGenerator::get_last_id(rule_id), format!("Box<{}>", rule_type_name), format!("{}.map(Box::new)", read_code), format!("{}.map(Box::new)", read_code), *loop_range),
+                    _ => TypedASTGenerator::to_loop_code(TypedASTGenerator::get_last_id(rule_id), rule_type_name, String::new(), read_code, *loop_range),
+                }
+            },
+            TypedElement::Node { name, content, loop_range } => {
+                let hint = if name.is_empty() {
+                    TypedASTGenerator::get_content_hint(content)
+                } else {
+                    TypedASTGenerator::to_camel_case(&TypedASTGenerator::get_last_id(name))
+                };
+
+                let content_code = self.to_content_code(content, &format!("{}{}", type_name, hint))?;
+
+                let code_name = if name.is_empty() {
+                    content_code.name.clone()
+                } else {
+                    TypedASTGenerator::get_last_id(name)
+                };
+
+                let closure_code = TypedASTGenerator::to_closure_code(&content_code.read_code);
+                let single_read_code = format!("r.read_node_or_empty({:?}, {})", name, closure_code);
+                let read_code = format!("r.read_node({:?}, {})", name, closure_code);
+
+                match content_code.type_name {
+                    Some(content_type_name) => TypedASTGenerator::to_loop_code(code_name, content_type_name, single_read_code, read_code, *loop_range),
+                    None => TypedASTGenerator::to_unit_loop_code(code_name, single_read_code, read_code, *loop_range),
+                }
+            },
+            TypedElement::Expansion(content) => self.to_content_code(content, &format!("{}{}", type_name, TypedASTGenerator::get_content_hint(content)))?,
+        };
+
+        return Ok(code);
+    }
+
+    // note: type_name は子要素が複数であれば生成する型の名前
+    fn to_content_code(&mut self, content: &TypedContent, type_name: &String) -> FCPEGResult<TypedCode> {
+        let code = match content {
+            TypedContent::Sequence(elems) => {
+                let mut codes = self.to_sequence_codes(elems, type_name)?;
+
+                match codes.len() {
+                    0 => TypedCode {
+                        name: "group".to_string(),
+                        type_name: None,
+                        read_code: "Some(())".to_string(),
+                    },
+                    1 => codes.remove(0),
+                    _ => {
+                        let new_type_name = self.allocate_type_name(type_name);
+                        self.generate_struct(&new_type_name, &codes, false);
+
+                        TypedCode {
+                            name: "group".to_string(),
+                            type_name: Some(new_type_name.clone()),
+                            read_code: format!("{}::read(r)", new_type_name),
+                        }
+                    },
+                }
+            },
+            TypedContent::Choice(alternatives) => {
+                let new_type_name = self.allocate_type_name(type_name);
+                self.generate_enum(&new_type_name, alternatives, false)?;
+
+                TypedCode {
+                    name: "choice".to_string(),
+                    type_name: Some(new_type_name.clone()),
+                    read_code: format!("{}::read(r)", new_type_name),
+                }
+            },
+            TypedContent::Repetition(sub_content, min_count, max_count) => {
+                let sub_code = self.to_content_code(sub_content, type_name)?;
+
+                match sub_code.type_name {
+                    Some(sub_type_name) => TypedASTGenerator::to_loop_code(sub_code.name, sub_type_name, sub_code.read_code.clone(), sub_code.read_code, (*min_count, *max_count)),
+                    None => TypedASTGenerator::to_unit_loop_code(sub_code.name, sub_code.read_code.clone(), sub_code.read_code, (*min_count, *max_count)),
+                }
+            },
+        };
+
+        return Ok(code);
+    }
+
+    // note: single_read_code は繰り返し範囲が {1,1} の場合に用いる
+    fn to_loop_code(name: String, type_name: String, single_read_code: String, read_code: String, loop_range: (usize, isize)) -> TypedCode {
+        let (type_name, read_code) = match loop_range {
+            (1, 1) => (type_name, single_read_code),
+            (0, 1) => (format!("Option<{}>", type_name), format!("r.read_optional(|r| {})", read_code)),
+            (min_count, max_count) => (format!("Vec<{}>", type_name), format!("r.read_repetition({}, {}, |r| {})", min_count, max_count, read_code)),
+        };
+
+        return TypedCode {
+            name: name,
+            type_name: Some(type_name),
+            read_code: read_code,
+        };
+    }
+
+    fn to_unit_loop_code(name: String, single_read_code: String, read_code: String, loop_range: (usize, isize)) -> TypedCode {
+        let read_code = match loop_range {
+            _ if read_code == "Some(())" => read_code,
+            (1, 1) => single_read_code,
+            (0, 1) => format!("r.read_optional(|r| {}).map(|_| ())", read_code),
+            (min_count, max_count) => format!("r.read_repetition({}, {}, |r| {}).map(|_| ())", min_count, max_count, read_code),
+        };
+
+        return TypedCode {
+            name: name,
+            type_name: None,
+            read_code: read_code,
+        };
+    }
+
+    fn generate_struct(&mut self, type_name: &String, codes: &Vec<TypedCode>, is_rule_type: bool) {
+        let field_names = TypedASTGenerator::to_field_names(codes);
+        let mut fields = String::new();
+        let mut reads = String::new();
+        let mut inits = String::new();
+
+        for (each_code, each_field_name) in codes.iter().zip(field_names.iter()) {
+            match &each_code.type_name {
+                Some(field_type_name) => {
+                    fields += &format!("    pub {}: {},\n", each_field_name, field_type_name);
+                    reads += &format!("        let {} = {}?;\n", each_field_name, each_code.read_code);
+                    inits += &format!("            {}: {},\n", each_field_name, each_field_name);
+                },
+                None => reads += &format!("        {}?;\n", each_code.read_code),
+            }
+        }
+
+        let mut s = "#[derive(Clone)]\n".to_string();
+
+        if fields.is_empty() {
+            s += &format!("pub struct {} {{}}\n\n", type_name);
+        } else {
+            s += &format!("pub struct {} {{\n{}}}\n\n", type_name, fields);
+        }
+
+        s += &format!("impl {} {{\n", type_name);
+
+        if is_rule_type {
+            s += &TypedASTGenerator::to_from_node_code(type_name);
+        }
+
+        if reads.is_empty() {
+            s += &format!("    pub fn read(_: &mut TypedNodeReader) -> Option<{}> {{\n", type_name);
+        } else {
+            s += &format!("    pub fn read(r: &mut TypedNodeReader) -> Option<{}> {{\n{}", type_name, reads);
+        }
+
+        if inits.is_empty() {
+            s += &format!("        return Some({} {{}});\n", type_name);
+        } else {
+            s += &format!("        return Some({} {{\n{}        }});\n", type_name, inits);
+        }
+
+        s += "    }\n";
+        s += "}\n";
+
+        self.type_sources.push(s);
+    }
+
+    // note: 候補は記述順に読み込み、最初に一致したものとする
+    fn generate_enum(&mut self, type_name: &String, alternatives: &[TypedContent], is_rule_type: bool) -> FCPEGResult<()> {
+        let mut variant_names = Vec::<String>::new();
+
+        for (alternative_i, each_alternative) in alternatives.iter().enumerate() {
+            let mut variant_name = match TypedASTGenerator::get_content_name(each_alternative) {
+                Some(v) => TypedASTGenerator::to_camel_case(&v),
+                None => format!("Choice{}", alternative_i),
+            };
+
+            if variant_name == "Self" || variant_names.contains(&variant_name) {
+                variant_name = format!("{}{}", variant_name, alternative_i);
+            }
+
+            variant_names.push(variant_name);
+        }
+
+        let mut variants = String::new();
+        let mut reads = Vec::<String>::new();
+
+        for (each_alternative, each_variant_name) in alternatives.iter().zip(variant_names.iter()) {
+            let code = self.to_content_code(each_alternative, &format!("{}{}", type_name, each_variant_name))?;
+
+            let read_code = match &code.type_name {
+                Some(variant_type_name) => {
+                    variants += &format!("    {}({}),\n", each_variant_name, variant_type_name);
+                    format!("r.read_try(|r| {}.map({}::{}))", code.read_code, type_name, each_variant_name)
+                },
+                None => {
+                    variants += &format!("    {},\n", each_variant_name);
+
+                    if code.read_code == "Some(())" {
+                        format!("r.read_try(|_| Some({}::{}))", type_name, each_variant_name)
+                    } else {
+                        format!("r.read_try(|r| {}.map(|_| {}::{}))", code.read_code, type_name, each_variant_name)
+                    }
+                },
+            };
+
+            reads.push(format!("        match {} {{\n            Some(v) => return Some(v),\n            None => (),\n        }}\n", read_code));
+        }
+
+        let mut s = "#[derive(Clone)]\n".to_string();
+        s += &format!("pub enum {} {{\n{}}}\n\n", type_name, variants);
+        s += &format!("impl {} {{\n", type_name);
+
+        if is_rule_type {
+            s += &TypedASTGenerator::to_from_node_code(type_name);
+        }
+
+        s += &format!("    pub fn read(r: &mut TypedNodeReader) -> Option<{}> {{\n", type_name);
+        s += &reads.join("\n");
+        s += "\n        return None;\n";
+        s += "    }\n";
+        s += "}\n";
+
+        self.type_sources.push(s);
+        return Ok(());
+    }
+
+    // note: 子要素を読み込まない場合は引数を用いない
+    fn to_closure_code(read_code: &String) -> String {
+        return if read_code == "Some(())" {
+            "|_| Some(())".to_string()
+        } else {
+            format!("|r| {}", read_code)
+        };
+    }
+
+    fn to_from_node_code(type_name: &String) -> String {
+        let mut s = format!("    pub fn from_node(node: &SyntaxNode) -> FCPEGResult<{}> {{\n", type_name);
+        s += &format!("        return TypedNodeReader::read_root(node, {:?}, {}::read);\n", type_name, type_name);
+        s += "    }\n\n";
+        return s;
+    }
+
+    // note: 同名のフィールドには 2 から始まる連番を付加する
+    fn to_field_names(codes: &Vec<TypedCode>) -> Vec<String> {
+        let mut field_names = Vec::<String>::new();
+        let mut used_names = HashSet::<String>::new();
+
+        for each_code in codes {
+            let base_name = TypedASTGenerator::to_snake_case(&each_code.name);
+            let mut field_name = base_name.clone();
+            let mut suffix_num = 2;
+
+            while used_names.contains(&field_name) {
+                field_name = format!("{}_{}", base_name, suffix_num);
+                suffix_num += 1;
+            }
+
+            used_names.insert(field_name.clone());
+
+            if RUST_KEYWORDS.contains(&field_name.as_str()) {
+                field_names.push(format!("r#{}", field_name));
+            } else if UNRAW_FIELD_NAMES.contains(&field_name.as_str()) {
+                field_names.push(format!("{}_", field_name));
+            } else {
+                field_names.push(field_name);
+            }
+        }
+
+        return field_names;
+    }
+
+    // ret: 展開された子要素から生成する型の名前の接尾辞
+    fn get_content_hint(content: &TypedContent) -> String {
+        return match content {
+            TypedContent::Choice(_) => "Choice".to_string(),
+            _ => "Group".to_string(),
+        };
+    }
+
+    // ret: 要素 1 つの連接であれば要素の名前
+    fn get_content_name(content: &TypedContent) -> Option<String> {
+        let elem = match content {
+            TypedContent::Sequence(elems) if elems.len() == 1 => &elems[0],
+            _ => return None,
+        };
+
+        let name = match elem {
+            TypedElement::Leaf { ast_reflection_style: ASTReflectionStyle::Reflection(elem_name), value: _, loop_range: _ } => elem_name.clone(),
+            TypedElement::RuleNode { name, rule_id: _, loop_range: _ } => TypedASTGenerator::get_last_id(name),
+            TypedElement::ExpandedRule { rule_id, loop_range: _ } => TypedASTGenerator::get_last_id(rule_id),
+            TypedElement::Node { name, content: _, loop_range: _ } => TypedASTGenerator::get_last_id(name),
+            _ => String::new(),
+        };
+
+        return if name.is_empty() {
+            None
+        } else {
+            Some(name)
+        };
+    }
+
+    // note: ".Block.Rule" 形式の ID であれば末尾の ID を返す
+    fn get_last_id(name: &str) -> String {
+        return match name.split('.').rfind(|s| !s.is_empty()) {
+            Some(v) => v.to_string(),
+            None => String::new(),
+        };
+    }
+
+    // note: "_" 及び英数字以外の文字で区切り、各部分の先頭を大文字にする
+    fn to_camel_case(name: &str) -> String {
+        let mut s = String::new();
+
+        for each_part in name.split(|c: char| c == '_' || !c.is_alphanumeric()) {
+            let mut chars = each_part.chars();
+
+            match chars.next() {
+                Some(first_char) => {
+                    s += &first_char.to_uppercase().to_string();
+                    s += chars.as_str();
+                },
+                None => (),
+            }
+        }
+
+        if s.starts_with(|c: char| c.is_numeric()) {
+            s = format!("_{}", s);
+        }
+
+        return s;
+    }
+
+    // note: 大文字の前に "_" を挿入する; 連続する大文字は次の文字が小文字であれば区切る (SingleID → single_id, ASTStyle → ast_style)
+    fn to_snake_case(name: &str) -> String {
+        let chars = name.chars().collect::<Vec<char>>();
+        let mut s = String::new();
+
+        for (char_i, each_char) in chars.iter().enumerate() {
+            if !each_char.is_alphanumeric() {
+                if !s.ends_with('_') {
+                    s.push('_');
+                }
+
+                continue;
+            }
+
+            if each_char.is_uppercase() && char_i != 0 && !s.ends_with('_') {
+                let prev_char = chars[char_i - 1];
+                let is_next_lowercase = match chars.get(char_i + 1) {
+                    Some(v) => v.is_lowercase(),
+                    None => false,
+                };
+
+                if prev_char.is_lowercase() || prev_char.is_numeric() || (prev_char.is_uppercase() && is_next_lowercase) {
+                    s.push('_');
+                }
+            }
+
+            s += &each_char.to_lowercase().to_string();
+        }
+
+        let s = s.trim_matches('_').to_string();
+
+        return if s.is_empty() {
+            "field".to_string()
+        } else if s.starts_with(|c: char| c.is_numeric()) {
+            format!("_{}", s)
+        } else {
+            s
+        };
+    }
+}
+
+// note: (左再帰の種, 種の読み込み終了位置)
+type TypedSeed = (Option<Box<dyn Any>>, usize);
+
+// spec: 生成された型がノードの反映的な子要素を先頭から順に読み込む
+// note: 各読み込みメソッドは一致しなければ None を返す; 読み込み位置は read_try() 及び繰り返しのメソッドが元に戻す
+pub struct TypedNodeReader<'a> {
+    children: Vec<&'a SyntaxNodeElement>,
+    child_i: usize,
+    // note: <(型名, 読み込み開始位置), (左再帰の種, 種の読み込み終了位置)>
+    seed_map: HashMap<(&'static str, usize), TypedSeed>,
+}
+
+impl<'a> TypedNodeReader<'a> {
+    pub fn new(node: &'a SyntaxNode) -> TypedNodeReader<'a> {
+        return TypedNodeReader {
+            children: node.get_reflectable_children(),
+            child_i: 0,
+            seed_map: HashMap::new(),
+        };
+    }
+
+    // spec: ノードの反映的な子要素をすべて読み込んで型に変換する
+    pub fn read_root<T, F: FnOnce(&mut TypedNodeReader<'a>) -> Option<T>>(node: &'a SyntaxNode, type_name: &str, f: F) -> FCPEGResult<T> {
+        let mut reader = TypedNodeReader::new(node);
+
+        return match f(&mut reader) {
+            Some(v) if reader.is_end() => Ok(v),
+            _ => {
+                Err(TreeLog::UnexpectedNodeStructure {
+                    uuid: node.uuid,
+                    type_name: type_name.to_string(),
+                }.into())
+            },
+        };
+    }
+
+    pub fn is_end(&self) -> bool {
+        return self.child_i == self.children.len();
+    }
+
+    // note: 一致しなければ読み込み位置を元に戻す
+    pub fn read_try<T, F: FnOnce(&mut TypedNodeReader<'a>) -> Option<T>>(&mut self, f: F) -> Option<T> {
+        let start_child_i = self.child_i;
+        let result = f(self);
+
+        if result.is_none() {
+            self.child_i = start_child_i;
+        }
+
+        return result;
+    }
+
+    // ret: 常に Some; 一致しなければ Some(None)
+    pub fn read_optional<T, F: FnOnce(&mut TypedNodeReader<'a>) -> Option<T>>(&mut self, f: F) -> Option<Option<T>> {
+        return Some(self.read_try(f));
+    }
+
+    // note: max_count が -1 であれば上限なし
+    // note: 子要素を読み込まずに一致した場合は、非反映的な要素の繰り返しであるため最小回数に関わらず打ち切る
+    pub fn read_repetition<T, F: FnMut(&mut TypedNodeReader<'a>) -> Option<T>>(&mut self, min_count: usize, max_count: isize, mut f: F) -> Option<Vec<T>> {
+        let mut elems = Vec::<T>::new();
+
+        while max_count == -1 || (elems.len() as isize) < max_count {
+            let start_child_i = self.child_i;
+
+            match self.read_try(&mut f) {
+                Some(_) if self.child_i == start_child_i => return Some(elems),
+                Some(v) => elems.push(v),
+                None => break,
+            }
+        }
+
+        return if elems.len() >= min_count {
+            Some(elems)
+        } else {
+            None
+        };
+    }
+
+    // note: name は反映名; value を指定すればリーフの値も比較する
+    pub fn read_leaf(&mut self, name: &str, value: Option<&str>) -> Option<SyntaxLeaf> {
+        return self.read_styled_leaf(ASTReflectionStyle::Reflection(name.to_string()), value);
+    }
+
+    pub fn read_expanded_leaf(&mut self, value: Option<&str>) -> Option<SyntaxLeaf> {
+        return self.read_styled_leaf(ASTReflectionStyle::Expansion, value);
+    }
+
+    fn read_styled_leaf(&mut self, ast_reflection_style: ASTReflectionStyle, value: Option<&str>) -> Option<SyntaxLeaf> {
+        let leaf = match self.children.get(self.child_i) {
+            Some(SyntaxNodeElement::Leaf(leaf)) => leaf,
+            _ => return None,
+        };
+
+        if leaf.ast_reflection_style != ast_reflection_style {
+            return None;
+        }
+
+        match value {
+            Some(v) if leaf.value != v => return None,
+            _ => (),
+        }
+
+        self.child_i += 1;
+        return Some(leaf.as_ref().clone());
+    }
+
+    // spec: 反映名が一致するノードの子要素をすべて読み込んで型に変換する
+    pub fn read_node<T, F: FnOnce(&mut TypedNodeReader<'a>) -> Option<T>>(&mut self, name: &str, f: F) -> Option<T> {
+        let node = self.get_named_node(name)?;
+        let mut node_reader = TypedNodeReader::new(node);
+        let result = f(&mut node_reader)?;
+
+        if !node_reader.is_end() {
+            return None;
+        }
+
+        self.child_i += 1;
+        return Some(result);
+    }
+
+    // note: 子要素が空のノードは構文木に追加されないため、ノードが存在しなければ空の子要素から変換する
+    pub fn read_node_or_empty<T, F: FnOnce(&mut TypedNodeReader<'a>) -> Option<T>>(&mut self, name: &str, f: F) -> Option<T> {
+        if self.get_named_node(name).is_some() {
+            return self.read_node(name, f);
+        }
+
+        let mut empty_reader = TypedNodeReader {
+            children: Vec::new(),
+            child_i: 0,
+            seed_map: HashMap::new(),
+        };
+
+        return f(&mut empty_reader);
+    }
+
+    // spec: 展開された左再帰の規則を読み込む; 同じ位置で再帰した場合は種を返し、読み込み位置が進まなくなるまで種を成長させる
+    pub fn read_left_recursive_rule<T: Clone + 'static, F: FnMut(&mut TypedNodeReader<'a>) -> Option<T>>(&mut self, type_name: &'static str, mut f: F) -> Option<T> {
+        let start_child_i = self.child_i;
+        let key = (type_name, start_child_i);
+
+        match self.seed_map.get(&key) {
+            Some((Some(seed), seed_end_child_i)) => {
+                self.child_i = *seed_end_child_i;
+                return seed.downcast_ref::<T>().cloned();
+            },
+            Some((None, _)) => return None,
+            None => (),
+        }
+
+        self.seed_map.insert(key, (None, start_child_i));
+
+        let mut result = None;
+        let mut end_child_i = start_child_i;
+
+        loop {
+            self.child_i = start_child_i;
+
+            match f(self) {
+                Some(v) if result.is_none() || self.child_i > end_child_i => {
+                    end_child_i = self.child_i;
+                    self.seed_map.insert(key, (Some(Box::new(v.clone())), end_child_i));
+                    result = Some(v);
+                },
+                _ => break,
+            }
+        }
+
+        self.seed_map.remove(&key);
+        self.child_i = end_child_i;
+        return result;
+    }
+
+    fn get_named_node(&self, name: &str) -> Option<&'a SyntaxNode> {
+        let elem: &'a SyntaxNodeElement = self.children.get(self.child_i)?;
+
+        return match elem {
+            SyntaxNodeElement::Node(node) => {
+                match &node.ast_reflection_style {
+                    ASTReflectionStyle::Reflection(node_name) if node_name == name => Some(node),
+                    _ => None,
+                }
+            },
+            SyntaxNodeElement::Leaf(_) => None,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::typed::*;
+
+    const FCPEG_SOURCE: &str = "[Main]{\n    Main <- Item+ \"\\z\",\n    Item <- Key#type \"=\" Value (\";\" : \",\")?,\n    Key <- [a-z]+,\n    Value <- Num : Str#text,\n    Num <- [0-9]+,\n    Str <- \"'\" [a-z]*#self \"'\",\n}\n";
+
+    #[test]
+    fn types_follow_reflection_names_and_loop_ranges() {
+        let source = load_test_parser(FCPEG_SOURCE).generate_typed_ast_source().unwrap();
+
+        assert!(source.contains("pub const START_RULE_ID: &str = \".Main.Main\";"));
+        assert!(source.contains("pub struct MainMain {\n    pub item: Vec<MainItem>,\n    pub leaf: SyntaxLeaf,\n}"));
+        assert!(source.contains("pub struct MainItem {\n    pub r#type: Box<MainKey>,\n    pub leaf: SyntaxLeaf,\n    pub value: Box<MainValue>,\n    pub choice: Option<MainItemGroup>,\n}"));
+        assert!(source.contains("pub enum MainValue {\n    Num(Box<MainNum>),\n    Text(Box<MainStr>),\n}"));
+        assert!(source.contains("pub struct MainStr {\n    pub leaf: SyntaxLeaf,\n    pub self_: Vec<SyntaxLeaf>,\n    pub leaf_2: SyntaxLeaf,\n}"));
+    }
+
+    #[test]
+    fn recursive_generics_rule_is_rejected() {
+        let parser = load_test_parser("[Main]{\n    Main <- List<\"a\">,\n    List<$T> <- $T List<$T> : $T,\n}\n");
+        assert!(parser.generate_typed_ast_source().is_err());
+    }
+
+    #[test]
+    fn node_reader_rewinds_failed_reads() {
+        let parser = load_test_parser("[Main]{\n    Main <- Pair+ \"\\z\",\n    Pair <- [a-z] [0-9],\n}\n");
+        let tree = parser.parse_from_str("input.txt".to_string(), "a1b2".to_string()).unwrap();
+        let mut r = TypedNodeReader::new(tree.get_child_ref().get_node().unwrap());
+
+        fn read_pair(r: &mut TypedNodeReader) -> Option<(SyntaxLeaf, SyntaxLeaf)> {
+            return Some((r.read_leaf("", None)?, r.read_leaf("", None)?));
+        }
+
+        // note: 1 つ目の Pair を読み込んだ後に失敗するため、読み込み位置は先頭に戻る
+        assert!(r.read_try(|r| {
+            r.read_node(".Main.Pair", read_pair)?;
+            return r.read_leaf("", Some("x"));
+        }).is_none());
+
+        let pairs = r.read_repetition(1, -1, |r| r.read_node(".Main.Pair", read_pair)).unwrap();
+        assert_eq!(pairs.iter().map(|(key, value)| format!("{}{}", key.value, value.value)).collect::<Vec<String>>(), vec!["a1", "b2"]);
+        assert!(r.read_leaf("", Some("\0")).is_some());
+        assert!(r.is_end());
+    }
+}
@@ -0,0 +1,362 @@
+use std::collections::*;
+
+use crate::tree::*;
+
+use uuid::Uuid;
+
+// spec: Visitor の各コールバックの後の走査の方法
+#[derive(Clone, PartialEq)]
+pub enum VisitAction {
+    Continue,
+    // note: enter_node() で返した場合は子要素を訪問せずに leave_node() を呼び出す
+    SkipChildren,
+    Stop,
+}
+
+// spec: VisitorMut の各コールバックの後の走査の方法; 訪問中の要素を置き換えもしくは削除できる
+#[derive(Clone)]
+pub enum VisitMutAction {
+    Continue,
+    // note: enter_node() で返した場合は子要素を訪問せずに leave_node() を呼び出す
+    SkipChildren,
+    Stop,
+    // note: enter_*() で返した場合は置き換えた要素の子要素及び leave_*() を訪問しない
+    Replace(SyntaxNodeElement),
+    // note: ルートの要素は削除できないため無視する
+    Remove,
+}
+
+// spec: 構文木を深さ優先で走査する; parents はルートから親ノードまでの祖先ノードの列
+pub trait Visitor {
+    fn enter_node(&mut self, _node: &SyntaxNode, _parents: &Vec<&SyntaxNode>) -> VisitAction {
+        return VisitAction::Continue;
+    }
+
+    fn leave_node(&mut self, _node: &SyntaxNode, _parents: &Vec<&SyntaxNode>) -> VisitAction {
+        return VisitAction::Continue;
+    }
+
+    fn enter_leaf(&mut self, _leaf: &SyntaxLeaf, _parents: &Vec<&SyntaxNode>) -> VisitAction {
+        return VisitAction::Continue;
+    }
+
+    fn leave_leaf(&mut self, _leaf: &SyntaxLeaf, _parents: &Vec<&SyntaxNode>) -> VisitAction {
+        return VisitAction::Continue;
+    }
+}
+
+// spec: 構文木を深さ優先で走査し、要素を変更・置換・削除する; parent_uuids はルートから親ノードまでの祖先ノードの UUID の列
+pub trait VisitorMut {
+    fn enter_node(&mut self, _node: &mut SyntaxNode, _parent_uuids: &Vec<Uuid>) -> VisitMutAction {
+        return VisitMutAction::Continue;
+    }
+
+    fn leave_node(&mut self, _node: &mut SyntaxNode, _parent_uuids: &Vec<Uuid>) -> VisitMutAction {
+        return VisitMutAction::Continue;
+    }
+
+    fn enter_leaf(&mut self, _leaf: &mut SyntaxLeaf, _parent_uuids: &Vec<Uuid>) -> VisitMutAction {
+        return VisitMutAction::Continue;
+    }
+
+    fn leave_leaf(&mut self, _leaf: &mut SyntaxLeaf, _parent_uuids: &Vec<Uuid>) -> VisitMutAction {
+        return VisitMutAction::Continue;
+    }
+}
+
+pub struct TreeWalker {}
+
+impl TreeWalker {
+    pub fn walk<V: Visitor>(elem: &SyntaxNodeElement, visitor: &mut V) {
+        TreeWalker::walk_elem(elem, visitor, &mut Vec::new());
+    }
+
+    pub fn walk_mut<V: VisitorMut>(elem: &mut SyntaxNodeElement, visitor: &mut V) {
+        TreeWalker::walk_elem_mut(elem, visitor, &mut Vec::new());
+    }
+
+    // ret: 走査を続けるか
+    fn walk_elem<'a, V: Visitor>(elem: &'a SyntaxNodeElement, visitor: &mut V, parents: &mut Vec<&'a SyntaxNode>) -> bool {
+        return match elem {
+            SyntaxNodeElement::Node(node) => {
+                match visitor.enter_node(node, parents) {
+                    VisitAction::Continue => {
+                        parents.push(node);
+
+                        for each_elem in &node.sub_elems {
+                            if !TreeWalker::walk_elem(each_elem, visitor, parents) {
+                                parents.pop();
+                                return false;
+                            }
+                        }
+
+                        parents.pop();
+                    },
+                    VisitAction::SkipChildren => (),
+                    VisitAction::Stop => return false,
+                }
+
+                visitor.leave_node(node, parents) != VisitAction::Stop
+            },
+            SyntaxNodeElement::Leaf(leaf) => {
+                if visitor.enter_leaf(leaf, parents) == VisitAction::Stop {
+                    return false;
+                }
+
+                visitor.leave_leaf(leaf, parents) != VisitAction::Stop
+            },
+        };
+    }
+
+    // ret: (走査を続けるか, 親ノードから要素を削除するか)
+    fn walk_elem_mut<V: VisitorMut>(elem: &mut SyntaxNodeElement, visitor: &mut V, parent_uuids: &mut Vec<Uuid>) -> (bool, bool) {
+        let enter_action = match elem {
+            SyntaxNodeElement::Node(node) => visitor.enter_node(node, parent_uuids),
+            SyntaxNodeElement::Leaf(leaf) => visitor.enter_leaf(leaf, parent_uuids),
+        };
+
+        let visits_children = match enter_action {
+            VisitMutAction::Continue => true,
+            VisitMutAction::SkipChildren => false,
+            VisitMutAction::Stop => return (false, false),
+            VisitMutAction::Replace(new_elem) => {
+                *elem = new_elem;
+                return (true, false);
+            },
+            VisitMutAction::Remove => return (true, !parent_uuids.is_empty()),
+        };
+
+        let leave_action = match elem {
+            SyntaxNodeElement::Node(node) => {
+                if visits_children {
+                    parent_uuids.push(node.uuid);
+                    let mut elem_i = 0;
+
+                    // note: 削除した子要素の位置には次の子要素が詰められるため、削除時は添字を進めない
+                    while elem_i < node.sub_elems.len() {
+                        let (continues, removes) = TreeWalker::walk_elem_mut(&mut node.sub_elems[elem_i], visitor, parent_uuids);
+
+                        if removes {
+                            node.sub_elems.remove(elem_i);
+                        } else {
+                            elem_i += 1;
+                        }
+
+                        if !continues {
+                            parent_uuids.pop();
+                            return (false, false);
+                        }
+                    }
+
+                    parent_uuids.pop();
+                }
+
+                visitor.leave_node(node, parent_uuids)
+            },
+            SyntaxNodeElement::Leaf(leaf) => visitor.leave_leaf(leaf, parent_uuids),
+        };
+
+        return match leave_action {
+            VisitMutAction::Continue | VisitMutAction::SkipChildren => (true, false),
+            VisitMutAction::Stop => (false, false),
+            VisitMutAction::Replace(new_elem) => {
+                *elem = new_elem;
+                (true, false)
+            },
+            VisitMutAction::Remove => (true, !parent_uuids.is_empty()),
+        };
+    }
+}
+
+// spec: 走査で得られた要素; parent はルートの要素であれば None
+pub struct VisitedElement<'a> {
+    pub elem: &'a SyntaxNodeElement,
+    pub parent: Option<&'a SyntaxNode>,
+    pub depth: usize,
+}
+
+// spec: 要素を行きがけ順に列挙する; 非反映的な要素も含む
+pub struct DepthFirstIter<'a> {
+    stack: Vec<VisitedElement<'a>>,
+}
+
+impl<'a> DepthFirstIter<'a> {
+    pub fn new(root: &'a SyntaxNodeElement) -> DepthFirstIter<'a> {
+        return DepthFirstIter {
+            stack: vec![VisitedElement { elem: root, parent: None, depth: 0 }],
+        };
+    }
+}
+
+impl<'a> Iterator for DepthFirstIter<'a> {
+    type Item = VisitedElement<'a>;
+
+    fn next(&mut self) -> Option<VisitedElement<'a>> {
+        let visited_elem = self.stack.pop()?;
+
+        match visited_elem.elem {
+            SyntaxNodeElement::Node(node) => {
+                for each_elem in node.sub_elems.iter().rev() {
+                    self.stack.push(VisitedElement {
+                        elem: each_elem,
+                        parent: Some(node),
+                        depth: visited_elem.depth + 1,
+                    });
+                }
+            },
+            SyntaxNodeElement::Leaf(_) => (),
+        }
+
+        return Some(visited_elem);
+    }
+}
+
+// spec: 要素を浅い順に列挙する; 同じ深さの要素は左から順に列挙する; 非反映的な要素も含む
+pub struct BreadthFirstIter<'a> {
+    queue: VecDeque<VisitedElement<'a>>,
+}
+
+impl<'a> BreadthFirstIter<'a> {
+    pub fn new(root: &'a SyntaxNodeElement) -> BreadthFirstIter<'a> {
+        return BreadthFirstIter {
+            queue: VecDeque::from(vec![VisitedElement { elem: root, parent: None, depth: 0 }]),
+        };
+    }
+}
+
+impl<'a> Iterator for BreadthFirstIter<'a> {
+    type Item = VisitedElement<'a>;
+
+    fn next(&mut self) -> Option<VisitedElement<'a>> {
+        let visited_elem = self.queue.pop_front()?;
+
+        match visited_elem.elem {
+            SyntaxNodeElement::Node(node) => {
+                for each_elem in &node.sub_elems {
+                    self.queue.push_back(VisitedElement {
+                        elem: each_elem,
+                        parent: Some(node),
+                        depth: visited_elem.depth + 1,
+                    });
+                }
+            },
+            SyntaxNodeElement::Leaf(_) => (),
+        }
+
+        return Some(visited_elem);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::visitor::*;
+
+    fn new_node(name: &str, sub_elems: Vec<SyntaxNodeElement>) -> SyntaxNodeElement {
+        return SyntaxNodeElement::from_node_args(sub_elems, ASTReflectionStyle::Reflection(name.to_string()));
+    }
+
+    fn new_leaf(value: &str) -> SyntaxNodeElement {
+        return SyntaxNodeElement::from_leaf_args(CharacterPosition::get_empty(), value.to_string(), ASTReflectionStyle::NoReflection);
+    }
+
+    // note: (A (B x y) z)
+    fn build_tree() -> SyntaxNodeElement {
+        return new_node("A", vec![new_node("B", vec![new_leaf("x"), new_leaf("y")]), new_leaf("z")]);
+    }
+
+    fn to_name(node: &SyntaxNode) -> String {
+        return match &node.ast_reflection_style {
+            ASTReflectionStyle::Reflection(name) => name.clone(),
+            _ => String::new(),
+        };
+    }
+
+    fn to_label(elem: &SyntaxNodeElement) -> String {
+        return match elem {
+            SyntaxNodeElement::Node(node) => to_name(node),
+            SyntaxNodeElement::Leaf(leaf) => leaf.value.clone(),
+        };
+    }
+
+    struct Recorder {
+        skipped_node_name: String,
+        stop_leaf_value: String,
+        events: Vec<String>,
+    }
+
+    impl Visitor for Recorder {
+        fn enter_node(&mut self, node: &SyntaxNode, parents: &Vec<&SyntaxNode>) -> VisitAction {
+            let name = to_name(node);
+            self.events.push(format!("enter {} {}", name, parents.len()));
+
+            return if name == self.skipped_node_name { VisitAction::SkipChildren } else { VisitAction::Continue };
+        }
+
+        fn leave_node(&mut self, node: &SyntaxNode, _parents: &Vec<&SyntaxNode>) -> VisitAction {
+            self.events.push(format!("leave {}", to_name(node)));
+            return VisitAction::Continue;
+        }
+
+        fn enter_leaf(&mut self, leaf: &SyntaxLeaf, parents: &Vec<&SyntaxNode>) -> VisitAction {
+            self.events.push(format!("enter {} {}", leaf.value, parents.len()));
+            return if leaf.value == self.stop_leaf_value { VisitAction::Stop } else { VisitAction::Continue };
+        }
+    }
+
+    fn record(skipped_node_name: &str, stop_leaf_value: &str) -> Vec<String> {
+        let mut recorder = Recorder {
+            skipped_node_name: skipped_node_name.to_string(),
+            stop_leaf_value: stop_leaf_value.to_string(),
+            events: Vec::new(),
+        };
+
+        TreeWalker::walk(&build_tree(), &mut recorder);
+        return recorder.events;
+    }
+
+    #[test]
+    fn walker_follows_visit_actions() {
+        assert_eq!(record("", ""), vec!["enter A 0", "enter B 1", "enter x 2", "enter y 2", "leave B", "enter z 1", "leave A"]);
+        assert_eq!(record("B", ""), vec!["enter A 0", "enter B 1", "leave B", "enter z 1", "leave A"]);
+        assert_eq!(record("", "x"), vec!["enter A 0", "enter B 1", "enter x 2"]);
+    }
+
+    struct Editor {}
+
+    impl VisitorMut for Editor {
+        fn leave_node(&mut self, _node: &mut SyntaxNode, parent_uuids: &Vec<Uuid>) -> VisitMutAction {
+            // note: ルートの削除は無視される
+            return if parent_uuids.is_empty() { VisitMutAction::Remove } else { VisitMutAction::Continue };
+        }
+
+        fn enter_leaf(&mut self, leaf: &mut SyntaxLeaf, _parent_uuids: &Vec<Uuid>) -> VisitMutAction {
+            return match leaf.value.as_str() {
+                "x" => VisitMutAction::Remove,
+                "z" => VisitMutAction::Replace(new_leaf("w")),
+                _ => {
+                    leaf.value = leaf.value.to_uppercase();
+                    VisitMutAction::Continue
+                },
+            };
+        }
+    }
+
+    #[test]
+    fn mutable_walker_edits_tree() {
+        let mut tree = build_tree();
+        TreeWalker::walk_mut(&mut tree, &mut Editor {});
+
+        let labels = DepthFirstIter::new(&tree).map(|each_elem| to_label(each_elem.elem)).collect::<Vec<String>>();
+        assert_eq!(labels, vec!["A", "B", "Y", "w"]);
+    }
+
+    #[test]
+    fn iterators_enumerate_in_order() {
+        let tree = build_tree();
+        let depth_first = DepthFirstIter::new(&tree).map(|each_elem| format!("{}{}", to_label(each_elem.elem), each_elem.depth)).collect::<Vec<String>>();
+        let breadth_first = BreadthFirstIter::new(&tree).map(|each_elem| to_label(each_elem.elem)).collect::<Vec<String>>();
+
+        assert_eq!(depth_first, vec!["A0", "B1", "x2", "y2", "z1"]);
+        assert_eq!(breadth_first, vec!["A", "B", "z", "x", "y"]);
+    }
+}
@@ -0,0 +1,706 @@
+// This file was generated by FCPEG. Do not edit it manually.
+
+use fcpeg::FCPEGResult;
+use fcpeg::generator::*;
+use fcpeg::parser::SyntaxParser;
+use fcpeg::tree::*;
+
+pub const START_RULE_ID: &str = ".Main.Main";
+
+pub fn parse(src_path: String, src_content: String, enable_memoization: bool) -> FCPEGResult<SyntaxTree> {
+    return SyntaxParser::parse_generated(src_path, Box::new(src_content), enable_memoization, None, START_RULE_ID, parse_rule_0);
+}
+
+fn parse_group_16_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, -1, |p| p.parse_char_class_with("[0-9]", |c| matches!(c, '0'..='9'), &ASTReflectionStyle::Reflection("".to_string()))))? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_16(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(24, true, |p| p.parse_loop_with(1, 1, |p| parse_group_16_elems(p)));
+}
+
+fn parse_group_15_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_group_with(&mut children, is_cut, true, &ASTReflectionStyle::Reflection("".to_string()), parse_group_16)? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_15(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(23, true, |p| p.parse_loop_with(1, 1, |p| parse_group_15_elems(p)));
+}
+
+fn parse_group_14_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_group_with(&mut children, is_cut, true, &ASTReflectionStyle::Reflection("".to_string()), parse_group_15)? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_14(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(22, true, |p| p.parse_loop_with(1, 1, |p| parse_group_14_elems(p)));
+}
+
+// rule: .Main.Num
+fn parse_rule_13(p: &mut SyntaxParser) -> FCPEGResult<Option<SyntaxNodeElement>> {
+    return p.parse_rule_with(".Main.Num", 22, true, &ASTReflectionStyle::Reflection("".to_string()), parse_group_14);
+}
+
+fn parse_group_12_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, 1, |p| p.parse_id_with(".Main.Sum", &ASTReflectionStyle::Reflection("".to_string()), parse_rule_10)))? {
+        return Ok(None);
+    }
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, 1, |p| p.parse_string("+", &ASTReflectionStyle::NoReflection)))? {
+        return Ok(None);
+    }
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, 1, |p| p.parse_id_with(".Main.Num", &ASTReflectionStyle::Reflection("".to_string()), parse_rule_13)))? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_12(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(36, true, |p| p.parse_loop_with(1, 1, |p| parse_group_12_elems(p)));
+}
+
+fn parse_group_17_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, 1, |p| p.parse_id_with(".Main.Sum", &ASTReflectionStyle::Reflection("".to_string()), parse_rule_10)))? {
+        return Ok(None);
+    }
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, 1, |p| p.parse_string("-", &ASTReflectionStyle::NoReflection)))? {
+        return Ok(None);
+    }
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, 1, |p| p.parse_id_with(".Main.Num", &ASTReflectionStyle::Reflection("".to_string()), parse_rule_13)))? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_17(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(37, true, |p| p.parse_loop_with(1, 1, |p| parse_group_17_elems(p)));
+}
+
+fn parse_group_18_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, 1, |p| p.parse_id_with(".Main.Num", &ASTReflectionStyle::Reflection("".to_string()), parse_rule_13)))? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_18(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(38, true, |p| p.parse_loop_with(1, 1, |p| parse_group_18_elems(p)));
+}
+
+fn parse_group_11_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_choice_with(&mut children, is_cut, true, 3, |p, choice_i| match choice_i { 0 => match parse_group_12(p)? { Some(v) => Ok(Some((v, ASTReflectionStyle::Reflection("".to_string())))), None => Ok(None) }, 1 => match parse_group_17(p)? { Some(v) => Ok(Some((v, ASTReflectionStyle::Reflection("".to_string())))), None => Ok(None) }, 2 => match parse_group_18(p)? { Some(v) => Ok(Some((v, ASTReflectionStyle::Reflection("".to_string())))), None => Ok(None) }, _ => Ok(None) })? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_11(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(34, true, |p| p.parse_loop_with(1, 1, |p| parse_group_11_elems(p)));
+}
+
+// rule: .Main.Sum
+fn parse_rule_10(p: &mut SyntaxParser) -> FCPEGResult<Option<SyntaxNodeElement>> {
+    return p.parse_rule_with(".Main.Sum", 34, true, &ASTReflectionStyle::Reflection("".to_string()), parse_group_11);
+}
+
+fn parse_group_9_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, 1, |p| p.parse_id_with(".Main.Sum", &ASTReflectionStyle::Reflection("".to_string()), parse_rule_10)))? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_9(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(30, true, |p| p.parse_loop_with(1, 1, |p| parse_group_9_elems(p)));
+}
+
+#[allow(unused_assignments)]
+fn parse_group_22_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let mut is_cut = false;
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, 1, |p| p.parse_string("if", &ASTReflectionStyle::NoReflection)))? {
+        return Ok(None);
+    }
+
+    is_cut = true;
+    p.cut_backtrack_src_indexes();
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, 1, |p| p.parse_string(" ", &ASTReflectionStyle::NoReflection)))? {
+        return Ok(None);
+    }
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, 1, |p| p.parse_id_with(".Main.Num", &ASTReflectionStyle::Reflection("".to_string()), parse_rule_13)))? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_22(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(14, true, |p| p.parse_loop_with(1, 1, |p| parse_group_22_elems(p)));
+}
+
+fn parse_group_23_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, 1, |p| p.parse_string("if", &ASTReflectionStyle::NoReflection)))? {
+        return Ok(None);
+    }
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, 1, |p| p.parse_string("!", &ASTReflectionStyle::NoReflection)))? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_23(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(15, true, |p| p.parse_loop_with(1, 1, |p| parse_group_23_elems(p)));
+}
+
+fn parse_group_21_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_choice_with(&mut children, is_cut, true, 2, |p, choice_i| match choice_i { 0 => match parse_group_22(p)? { Some(v) => Ok(Some((v, ASTReflectionStyle::Reflection("".to_string())))), None => Ok(None) }, 1 => match parse_group_23(p)? { Some(v) => Ok(Some((v, ASTReflectionStyle::Reflection("".to_string())))), None => Ok(None) }, _ => Ok(None) })? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_21(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(12, true, |p| p.parse_loop_with(1, 1, |p| parse_group_21_elems(p)));
+}
+
+// rule: .Main.Kw
+fn parse_rule_20(p: &mut SyntaxParser) -> FCPEGResult<Option<SyntaxNodeElement>> {
+    return p.parse_rule_with(".Main.Kw", 12, true, &ASTReflectionStyle::Reflection("".to_string()), parse_group_21);
+}
+
+fn parse_group_19_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, 1, |p| p.parse_id_with(".Main.Kw", &ASTReflectionStyle::Reflection("".to_string()), parse_rule_20)))? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_19(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(31, true, |p| p.parse_loop_with(1, 1, |p| parse_group_19_elems(p)));
+}
+
+fn parse_group_33_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, 1, |p| p.parse_string("x", &ASTReflectionStyle::Reflection("".to_string()))))? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_33(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(3, true, |p| p.parse_loop_with(1, 1, |p| parse_group_33_elems(p)));
+}
+
+fn parse_group_38_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, -1, |p| p.parse_char_class_with("[0-9]", |c| matches!(c, '0'..='9'), &ASTReflectionStyle::Reflection("".to_string()))))? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_38(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(24, true, |p| p.parse_loop_with(1, 1, |p| parse_group_38_elems(p)));
+}
+
+fn parse_group_37_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_group_with(&mut children, is_cut, true, &ASTReflectionStyle::Reflection("".to_string()), parse_group_38)? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_37(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(23, true, |p| p.parse_loop_with(1, 1, |p| parse_group_37_elems(p)));
+}
+
+fn parse_group_36_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_group_with(&mut children, is_cut, true, &ASTReflectionStyle::Reflection("".to_string()), parse_group_37)? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_36(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(22, true, |p| p.parse_loop_with(1, 1, |p| parse_group_36_elems(p)));
+}
+
+// rule: .Main.Num
+fn parse_rule_35(p: &mut SyntaxParser) -> FCPEGResult<Option<SyntaxNodeElement>> {
+    return p.parse_rule_with(".Main.Num", 22, true, &ASTReflectionStyle::Reflection("".to_string()), parse_group_36);
+}
+
+fn parse_group_34_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, 1, |p| p.parse_id_with(".Main.Num", &ASTReflectionStyle::Reflection("".to_string()), parse_rule_35)))? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_34(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(4, true, |p| p.parse_loop_with(1, 1, |p| parse_group_34_elems(p)));
+}
+
+fn parse_group_32_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, 1, |p| p.parse_arg_id_with(&ASTReflectionStyle::Reflection("".to_string()), parse_group_33)))? {
+        return Ok(None);
+    }
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, 1, |p| p.parse_string("=", &ASTReflectionStyle::Reflection("".to_string()))))? {
+        return Ok(None);
+    }
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, 1, |p| p.parse_arg_id_with(&ASTReflectionStyle::Reflection("Val".to_string()), parse_group_34)))? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_32(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(27, true, |p| p.parse_loop_with(1, 1, |p| parse_group_32_elems(p)));
+}
+
+fn parse_group_31_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_group_with(&mut children, is_cut, true, &ASTReflectionStyle::Reflection("".to_string()), parse_group_32)? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_31(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(26, true, |p| p.parse_loop_with(1, 1, |p| parse_group_31_elems(p)));
+}
+
+fn parse_group_30_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_group_with(&mut children, is_cut, true, &ASTReflectionStyle::Reflection("".to_string()), parse_group_31)? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_30(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(25, true, |p| p.parse_loop_with(1, 1, |p| parse_group_30_elems(p)));
+}
+
+// rule: .Main.Pair
+fn parse_rule_29(p: &mut SyntaxParser) -> FCPEGResult<Option<SyntaxNodeElement>> {
+    return p.parse_rule_with(".Main.Pair", 25, true, &ASTReflectionStyle::Reflection("".to_string()), parse_group_30);
+}
+
+fn parse_group_28_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, 1, |p| p.parse_string("g:", &ASTReflectionStyle::Reflection("".to_string()))))? {
+        return Ok(None);
+    }
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, 1, |p| p.parse_id_with(".Main.Pair", &ASTReflectionStyle::Reflection("".to_string()), parse_rule_29)))? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_28(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(2, true, |p| p.parse_loop_with(1, 1, |p| parse_group_28_elems(p)));
+}
+
+fn parse_group_27_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_group_with(&mut children, is_cut, true, &ASTReflectionStyle::Reflection("".to_string()), parse_group_28)? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_27(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(1, true, |p| p.parse_loop_with(1, 1, |p| parse_group_27_elems(p)));
+}
+
+fn parse_group_26_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_group_with(&mut children, is_cut, true, &ASTReflectionStyle::Reflection("".to_string()), parse_group_27)? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_26(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(0, true, |p| p.parse_loop_with(1, 1, |p| parse_group_26_elems(p)));
+}
+
+// rule: .Main.Gen
+fn parse_rule_25(p: &mut SyntaxParser) -> FCPEGResult<Option<SyntaxNodeElement>> {
+    return p.parse_rule_with(".Main.Gen", 0, true, &ASTReflectionStyle::Reflection("".to_string()), parse_group_26);
+}
+
+fn parse_group_24_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, 1, |p| p.parse_id_with(".Main.Gen", &ASTReflectionStyle::Reflection("".to_string()), parse_rule_25)))? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_24(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(32, true, |p| p.parse_loop_with(1, 1, |p| parse_group_24_elems(p)));
+}
+
+fn parse_group_47_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, 1, |p| p.parse_string("-", &ASTReflectionStyle::Reflection("".to_string()))))? {
+        return Ok(None);
+    }
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, -1, |p| p.parse_char_class_with("[a-z]", |c| matches!(c, 'a'..='z'), &ASTReflectionStyle::Reflection("".to_string()))))? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_47(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(11, true, |p| p.parse_loop_with(1, 1, |p| parse_group_47_elems(p)));
+}
+
+fn parse_group_46_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_group_with(&mut children, is_cut, true, &ASTReflectionStyle::Reflection("".to_string()), parse_group_47)? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_46(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(10, true, |p| p.parse_loop_with(1, 1, |p| parse_group_46_elems(p)));
+}
+
+fn parse_group_45_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_group_with(&mut children, is_cut, true, &ASTReflectionStyle::Reflection("".to_string()), parse_group_46)? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_45(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(9, true, |p| p.parse_loop_with(0, -1, |p| parse_group_45_elems(p)));
+}
+
+fn parse_group_44_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, -1, |p| p.parse_char_class_with("[a-z]", |c| matches!(c, 'a'..='z'), &ASTReflectionStyle::Reflection("".to_string()))))? {
+        return Ok(None);
+    }
+
+    if !p.parse_sequence_group_with(&mut children, is_cut, false, &ASTReflectionStyle::Reflection("".to_string()), parse_group_45)? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_44(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(8, true, |p| p.parse_loop_with(1, 1, |p| parse_group_44_elems(p)));
+}
+
+fn parse_group_43_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, 1, |p| p.parse_string("j:", &ASTReflectionStyle::Reflection("".to_string()))))? {
+        return Ok(None);
+    }
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, 1, |p| p.parse_join_with(&ASTReflectionStyle::Reflection("Word".to_string()), parse_group_44)))? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_43(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(7, true, |p| p.parse_loop_with(1, 1, |p| parse_group_43_elems(p)));
+}
+
+fn parse_group_42_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_group_with(&mut children, is_cut, true, &ASTReflectionStyle::Reflection("".to_string()), parse_group_43)? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_42(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(6, true, |p| p.parse_loop_with(1, 1, |p| parse_group_42_elems(p)));
+}
+
+fn parse_group_41_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_group_with(&mut children, is_cut, true, &ASTReflectionStyle::Reflection("".to_string()), parse_group_42)? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_41(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(5, true, |p| p.parse_loop_with(1, 1, |p| parse_group_41_elems(p)));
+}
+
+// rule: .Main.Joined
+fn parse_rule_40(p: &mut SyntaxParser) -> FCPEGResult<Option<SyntaxNodeElement>> {
+    return p.parse_rule_with(".Main.Joined", 5, true, &ASTReflectionStyle::Reflection("".to_string()), parse_group_41);
+}
+
+fn parse_group_39_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, 1, |p| p.parse_id_with(".Main.Joined", &ASTReflectionStyle::Reflection("".to_string()), parse_rule_40)))? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_39(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(33, true, |p| p.parse_loop_with(1, 1, |p| parse_group_39_elems(p)));
+}
+
+fn parse_group_8_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_choice_with(&mut children, is_cut, true, 4, |p, choice_i| match choice_i { 0 => match parse_group_9(p)? { Some(v) => Ok(Some((v, ASTReflectionStyle::Reflection("".to_string())))), None => Ok(None) }, 1 => match parse_group_19(p)? { Some(v) => Ok(Some((v, ASTReflectionStyle::Reflection("".to_string())))), None => Ok(None) }, 2 => match parse_group_24(p)? { Some(v) => Ok(Some((v, ASTReflectionStyle::Reflection("".to_string())))), None => Ok(None) }, 3 => match parse_group_39(p)? { Some(v) => Ok(Some((v, ASTReflectionStyle::Reflection("".to_string())))), None => Ok(None) }, _ => Ok(None) })? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_8(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(28, true, |p| p.parse_loop_with(1, 1, |p| parse_group_8_elems(p)));
+}
+
+// rule: .Main.Stmt
+fn parse_rule_7(p: &mut SyntaxParser) -> FCPEGResult<Option<SyntaxNodeElement>> {
+    return p.parse_rule_with(".Main.Stmt", 28, true, &ASTReflectionStyle::Reflection("".to_string()), parse_group_8);
+}
+
+fn parse_group_6_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, 1, |p| p.parse_id_with(".Main.Stmt", &ASTReflectionStyle::Reflection("".to_string()), parse_rule_7)))? {
+        return Ok(None);
+    }
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, 1, |p| p.parse_string("\n", &ASTReflectionStyle::NoReflection)))? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_6(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(21, true, |p| p.parse_loop_with(1, 1, |p| parse_group_6_elems(p)));
+}
+
+fn parse_group_5_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_group_with(&mut children, is_cut, true, &ASTReflectionStyle::Reflection("".to_string()), parse_group_6)? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_5(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(20, true, |p| p.parse_loop_with(1, 1, |p| parse_group_5_elems(p)));
+}
+
+fn parse_group_4_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_group_with(&mut children, is_cut, true, &ASTReflectionStyle::Reflection("".to_string()), parse_group_5)? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_4(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(19, true, |p| p.parse_loop_with(0, -1, |p| parse_group_4_elems(p)));
+}
+
+fn parse_group_3_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_group_with(&mut children, is_cut, false, &ASTReflectionStyle::Reflection("".to_string()), parse_group_4)? {
+        return Ok(None);
+    }
+
+    if !p.parse_sequence_expr_with(&mut children, is_cut, |p| p.parse_loop_with(1, 1, |p| p.parse_string("\0", &ASTReflectionStyle::NoReflection)))? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_3(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(18, true, |p| p.parse_loop_with(1, 1, |p| parse_group_3_elems(p)));
+}
+
+fn parse_group_2_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_group_with(&mut children, is_cut, true, &ASTReflectionStyle::Reflection("".to_string()), parse_group_3)? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_2(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(17, true, |p| p.parse_loop_with(1, 1, |p| parse_group_2_elems(p)));
+}
+
+fn parse_group_1_elems(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    let mut children = Vec::<SyntaxNodeElement>::new();
+    let is_cut = false;
+
+    if !p.parse_sequence_group_with(&mut children, is_cut, true, &ASTReflectionStyle::Reflection("".to_string()), parse_group_2)? {
+        return Ok(None);
+    }
+
+    return Ok(Some(children));
+}
+
+fn parse_group_1(p: &mut SyntaxParser) -> GeneratedGroupResult {
+    return p.parse_group_with(16, true, |p| p.parse_loop_with(1, 1, |p| parse_group_1_elems(p)));
+}
+
+// rule: .Main.Main
+fn parse_rule_0(p: &mut SyntaxParser) -> FCPEGResult<Option<SyntaxNodeElement>> {
+    return p.parse_rule_with(".Main.Main", 16, true, &ASTReflectionStyle::Reflection("".to_string()), parse_group_1);
+}
@@ -0,0 +1,77 @@
+// spec: 生成したパーサと規則マップを解釈するパーサが同じ構文木及びエラーを返すことを検査する
+// note: generated/calc.rs は generated/calc.fcpeg から FCPEGParser::generate_source() で生成したもの; 文法または生成器を変更した場合は再生成する
+
+use std::collections::HashMap;
+
+use fcpeg::*;
+use fcpeg::file::*;
+
+// note: 生成したコードは Clippy の検査対象としない
+#[allow(clippy::all)]
+mod calc {
+    include!("generated/calc.rs");
+}
+
+const CALC_FCPEG_SOURCE: &str = include_str!("generated/calc.fcpeg");
+const CALC_GENERATED_SOURCE: &str = include_str!("generated/calc.rs");
+
+fn load_calc_parser() -> FCPEGParser {
+    return FCPEGParser::load_from_str("calc.fcpeg".to_string(), CALC_FCPEG_SOURCE.to_string(), String::new(), HashMap::new(), &MemorySourceResolver::new(), true).unwrap();
+}
+
+// ret: 各ログの見出し及び説明
+fn to_log_texts(e: &FCPEGError) -> Vec<(String, Vec<String>)> {
+    return e.get_logs().iter().map(|each_log| (each_log.title.clone(), each_log.descs.clone())).collect();
+}
+
+// note: is_success は解釈するパーサがパースに成功すべきかどうか
+fn assert_same_result(input: &str, is_success: bool) {
+    let parser = load_calc_parser();
+
+    for enable_memoization in [true, false] {
+        let interpreted = parser.parse_from_str("input.txt".to_string(), input.to_string());
+        let generated = calc::parse("input.txt".to_string(), input.to_string(), enable_memoization);
+        assert_eq!(interpreted.is_ok(), is_success, "input {:?}", input);
+
+        match (interpreted, generated) {
+            (Ok(interpreted_tree), Ok(generated_tree)) => assert_eq!(generated_tree.to_sexpr(false), interpreted_tree.to_sexpr(false), "input {:?}", input),
+            (Err(interpreted_err), Err(generated_err)) => assert_eq!(to_log_texts(&generated_err), to_log_texts(&interpreted_err), "input {:?}", input),
+            (Ok(_), Err(e)) => panic!("generated parser failed on input {:?}: {:?}", input, to_log_texts(&e)),
+            (Err(e), Ok(_)) => panic!("generated parser succeeded on input {:?} but interpreter failed: {:?}", input, to_log_texts(&e)),
+        }
+    }
+}
+
+#[test]
+fn generated_source_is_up_to_date() {
+    assert_eq!(load_calc_parser().generate_source().unwrap(), CALC_GENERATED_SOURCE);
+}
+
+#[test]
+fn generated_parser_matches_interpreter_on_left_recursion() {
+    assert_same_result("1+2-3+45\n", true);
+}
+
+#[test]
+fn generated_parser_matches_interpreter_on_generics() {
+    assert_same_result("g:x=99\n", true);
+}
+
+#[test]
+fn generated_parser_matches_interpreter_on_join() {
+    assert_same_result("j:ab-cd-ef\n", true);
+}
+
+// note: 切断演算子により "if" の後の候補は選ばれない
+#[test]
+fn generated_parser_matches_interpreter_on_cut() {
+    assert_same_result("if 12\n", true);
+    assert_same_result("if!\n", false);
+}
+
+#[test]
+fn generated_parser_matches_interpreter_on_failures() {
+    for each_input in ["1+\n", "g:y=1\n", "j:ab-\n", "if x\n", "1+2"] {
+        assert_same_result(each_input, false);
+    }
+}
@@ -0,0 +1,497 @@
+use std::collections::*;
+use std::error::Error;
+use std::sync::Arc;
+
+use fcpeg::*;
+use fcpeg::block::*;
+use fcpeg::file::*;
+use fcpeg::rule::*;
+use fcpeg::tree::*;
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, RequestId, Response};
+use lsp_types::*;
+
+use rustnutlib::console::*;
+use rustnutlib::file::*;
+
+type LSPResult<T> = std::result::Result<T, Box<dyn Error + Sync + Send>>;
+
+fn main() {
+    let (connection, io_threads) = Connection::stdio();
+
+    match run(connection) {
+        Ok(()) => (),
+        Err(e) => {
+            eprintln!("[err] language server stopped: {}", e);
+            return;
+        },
+    }
+
+    match io_threads.join() {
+        Ok(()) => (),
+        Err(e) => eprintln!("[err] language server stopped: {}", e),
+    }
+}
+
+fn run(connection: Connection) -> LSPResult<()> {
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        definition_provider: Some(OneOf::Left(true)),
+        references_provider: Some(OneOf::Left(true)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        ..ServerCapabilities::default()
+    };
+
+    connection.initialize(serde_json::to_value(capabilities)?)?;
+
+    let mut server = GrammarServer::new(connection);
+    return server.run();
+}
+
+// spec: エディタで開かれた FCPEG ファイル
+struct GrammarDocument {
+    uri: Url,
+    content: String,
+    symbol_table: BlockSymbolTable,
+    // note: 最後にブロック解析に成功した時の規則マップ; 規則の表示に用いる
+    rule_map: Option<Arc<RuleMap>>,
+}
+
+// spec: 開かれた文書を優先し、それ以外はディスク上のファイルからソースを取得する
+struct DocumentSourceResolver<'a> {
+    documents: &'a HashMap<String, GrammarDocument>,
+    file_resolver: FileSourceResolver,
+}
+
+impl<'a> SourceResolver for DocumentSourceResolver<'a> {
+    fn read_source(&self, file_path: &str) -> std::result::Result<String, FileLog> {
+        return match self.documents.get(file_path) {
+            Some(v) => Ok(v.content.clone()),
+            None => self.file_resolver.read_source(file_path),
+        };
+    }
+
+    fn is_same_source(&self, file_path: &str, other_file_path: &str) -> std::result::Result<bool, FileLog> {
+        return self.file_resolver.is_same_source(file_path, other_file_path);
+    }
+}
+
+// spec: 標準入出力で LSP を話す言語サーバ; 文書の変更毎にブロック解析を行って診断を送信する
+struct GrammarServer {
+    connection: Connection,
+    // note: <ファイルパス, 文書>
+    documents: HashMap<String, GrammarDocument>,
+}
+
+impl GrammarServer {
+    fn new(connection: Connection) -> GrammarServer {
+        return GrammarServer {
+            connection: connection,
+            documents: HashMap::new(),
+        };
+    }
+
+    fn run(&mut self) -> LSPResult<()> {
+        loop {
+            let msg = match self.connection.receiver.recv() {
+                Ok(v) => v,
+                Err(_) => return Ok(()),
+            };
+
+            match msg {
+                Message::Request(req) => {
+                    if self.connection.handle_shutdown(&req)? {
+                        return Ok(());
+                    }
+
+                    self.handle_request(req)?;
+                },
+                Message::Notification(notification) => self.handle_notification(notification)?,
+                Message::Response(_) => (),
+            }
+        }
+    }
+
+    fn handle_request(&mut self, req: Request) -> LSPResult<()> {
+        let id = req.id.clone();
+
+        let result = match req.method.as_str() {
+            "textDocument/definition" => match serde_json::from_value::<GotoDefinitionParams>(req.params) {
+                Ok(params) => serde_json::to_value(self.find_definition(&params.text_document_position_params))?,
+                Err(e) => return self.respond_error(id, ErrorCode::InvalidParams, e.to_string()),
+            },
+            "textDocument/hover" => match serde_json::from_value::<HoverParams>(req.params) {
+                Ok(params) => serde_json::to_value(self.hover(&params.text_document_position_params))?,
+                Err(e) => return self.respond_error(id, ErrorCode::InvalidParams, e.to_string()),
+            },
+            "textDocument/references" => match serde_json::from_value::<ReferenceParams>(req.params) {
+                Ok(params) => serde_json::to_value(self.find_references(&params.text_document_position, params.context.include_declaration))?,
+                Err(e) => return self.respond_error(id, ErrorCode::InvalidParams, e.to_string()),
+            },
+            _ => return self.respond_error(id, ErrorCode::MethodNotFound, format!("unknown method '{}'", req.method)),
+        };
+
+        self.connection.sender.send(Message::Response(Response::new_ok(id, result)))?;
+        return Ok(());
+    }
+
+    fn respond_error(&self, id: RequestId, code: ErrorCode, msg: String) -> LSPResult<()> {
+        self.connection.sender.send(Message::Response(Response::new_err(id, code as i32, msg)))?;
+        return Ok(());
+    }
+
+    fn handle_notification(&mut self, notification: Notification) -> LSPResult<()> {
+        match notification.method.as_str() {
+            "textDocument/didOpen" => {
+                let params = match serde_json::from_value::<DidOpenTextDocumentParams>(notification.params) {
+                    Ok(v) => v,
+                    Err(e) => return GrammarServer::report_invalid_notification(&notification.method, e),
+                };
+
+                let file_path = GrammarServer::to_file_path(&params.text_document.uri);
+
+                let document = GrammarDocument {
+                    uri: params.text_document.uri,
+                    content: params.text_document.text,
+                    symbol_table: BlockSymbolTable::new(),
+                    rule_map: None,
+                };
+
+                self.documents.insert(file_path, document);
+            },
+            "textDocument/didChange" => {
+                let params = match serde_json::from_value::<DidChangeTextDocumentParams>(notification.params) {
+                    Ok(v) => v,
+                    Err(e) => return GrammarServer::report_invalid_notification(&notification.method, e),
+                };
+
+                let file_path = GrammarServer::to_file_path(&params.text_document.uri);
+
+                // note: 全体同期のため最後の変更が文書全体の内容となる
+                match (self.documents.get_mut(&file_path), params.content_changes.into_iter().last()) {
+                    (Some(document), Some(change)) => document.content = change.text,
+                    _ => return Ok(()),
+                }
+            },
+            "textDocument/didClose" => {
+                let params = match serde_json::from_value::<DidCloseTextDocumentParams>(notification.params) {
+                    Ok(v) => v,
+                    Err(e) => return GrammarServer::report_invalid_notification(&notification.method, e),
+                };
+
+                let file_path = GrammarServer::to_file_path(&params.text_document.uri);
+
+                if self.documents.remove(&file_path).is_some() {
+                    self.publish_diagnostics(params.text_document.uri, Vec::new())?;
+                }
+            },
+            _ => return Ok(()),
+        }
+
+        // note: サブファイルの変更はそれを参照する文書の解析結果にも影響するため、開かれた文書をすべて解析し直す
+        let file_paths = self.documents.keys().cloned().collect::<Vec<String>>();
+
+        for each_file_path in file_paths {
+            self.analyze(&each_file_path)?;
+        }
+
+        return Ok(());
+    }
+
+    // spec: 通知には応答できないため、パラメータが不正な通知はエラーを出力して無視し、サーバを継続する
+    fn report_invalid_notification(method: &str, e: serde_json::Error) -> LSPResult<()> {
+        eprintln!("[err] invalid params of notification '{}': {}", method, e);
+        return Ok(());
+    }
+
+    // spec: 文書をメインファイルとしてブロック解析し、診断を送信する
+    // note: 失敗した場合もそれまでに収集したシンボルがあれば差し替え、規則マップは以前のものを保持する
+    fn analyze(&mut self, file_path: &String) -> LSPResult<()> {
+        let resolver = DocumentSourceResolver {
+            documents: &self.documents,
+            file_resolver: FileSourceResolver::new(),
+        };
+
+        let content = match self.documents.get(file_path) {
+            Some(v) => v.content.clone(),
+            None => return Ok(()),
+        };
+
+        // note: 構成ファイルがなければ既定の構成とする
+        let config_content = resolver.read_source(&FileMan::rename_ext(file_path, "cfg")).unwrap_or_default();
+
+        let mut warnings = Vec::<BlockParsingLog>::new();
+        let mut symbol_table = BlockSymbolTable::new();
+
+        let result = match FCPEGFileMap::load_from_str(file_path.clone(), content, config_content, HashMap::new(), &resolver) {
+            Ok(mut fcpeg_file_map) => BlockParser::get_rule_map_with_symbols(&mut fcpeg_file_map, true, &mut warnings, &mut symbol_table),
+            Err(e) => Err(e),
+        };
+
+        let document = self.documents.get_mut(file_path).unwrap();
+        let mut diagnostics = Vec::<Diagnostic>::new();
+
+        for each_warning in &warnings {
+            diagnostics.push(GrammarServer::to_diagnostic(document, file_path, each_warning.get_log(), each_warning.get_position()));
+        }
+
+        match result {
+            Ok(rule_map) => {
+                document.symbol_table = symbol_table;
+                document.rule_map = Some(rule_map);
+            },
+            Err(e) => {
+                for each_err in GrammarServer::flatten_errors(e) {
+                    for each_log in each_err.get_logs() {
+                        diagnostics.push(GrammarServer::to_diagnostic(document, file_path, each_log, each_err.get_position()));
+                    }
+                }
+
+                if !symbol_table.symbols.is_empty() {
+                    document.symbol_table = symbol_table;
+                }
+            },
+        }
+
+        let uri = document.uri.clone();
+        return self.publish_diagnostics(uri, diagnostics);
+    }
+
+    fn publish_diagnostics(&self, uri: Url, diagnostics: Vec<Diagnostic>) -> LSPResult<()> {
+        let params = PublishDiagnosticsParams {
+            uri: uri,
+            diagnostics: diagnostics,
+            version: None,
+        };
+
+        self.connection.sender.send(Message::Notification(Notification::new("textDocument/publishDiagnostics".to_string(), params)))?;
+        return Ok(());
+    }
+
+    fn flatten_errors(e: FCPEGError) -> Vec<FCPEGError> {
+        return match e {
+            FCPEGError::Multiple(errs) => errs.into_iter().flat_map(GrammarServer::flatten_errors).collect(),
+            _ => vec![e],
+        };
+    }
+
+    // note: 位置情報を持たないログや他のファイルのログは文書の先頭に表示する
+    fn to_diagnostic(document: &GrammarDocument, file_path: &String, log: ConsoleLog, pos: Option<CharacterPosition>) -> Diagnostic {
+        let range = match &pos {
+            Some(v) if v.file_path.as_ref() == Some(file_path) => {
+                let len = GrammarServer::get_word_len(&document.content, v.line, v.column);
+                GrammarServer::to_range(&document.content, v, len)
+            },
+            _ => Range::default(),
+        };
+
+        let severity = match log.kind {
+            ConsoleLogKind::Error => DiagnosticSeverity::ERROR,
+            ConsoleLogKind::Warning => DiagnosticSeverity::WARNING,
+            ConsoleLogKind::Note => DiagnosticSeverity::INFORMATION,
+        };
+
+        // note: 文書内の位置は範囲で示すため位置の説明を除く
+        let mut msg_lines = vec![log.title.clone()];
+
+        for each_desc in &log.descs {
+            if range != Range::default() && each_desc.starts_with("at:\t") {
+                continue;
+            }
+
+            msg_lines.push(each_desc.clone());
+        }
+
+        return Diagnostic {
+            range: range,
+            severity: Some(severity),
+            source: Some("fcpeg".to_string()),
+            message: msg_lines.join("\n"),
+            ..Diagnostic::default()
+        };
+    }
+
+    fn find_definition(&self, params: &TextDocumentPositionParams) -> Option<GotoDefinitionResponse> {
+        let (document, symbol) = self.find_symbol_at(params)?;
+
+        let definition = document.symbol_table.find_definition(&symbol.kind, &symbol.id)?;
+        return self.to_location(definition).map(GotoDefinitionResponse::Scalar);
+    }
+
+    fn find_references(&self, params: &TextDocumentPositionParams, include_declaration: bool) -> Option<Vec<Location>> {
+        let (document, symbol) = self.find_symbol_at(params)?;
+
+        let mut locations = Vec::<Location>::new();
+
+        for each_symbol in document.symbol_table.find_references(&symbol.kind, &symbol.id) {
+            if each_symbol.is_definition && !include_declaration {
+                continue;
+            }
+
+            match self.to_location(each_symbol) {
+                Some(v) => locations.push(v),
+                None => (),
+            }
+        }
+
+        return Some(locations);
+    }
+
+    // note: 規則は Display 形式で、ブロックは ID で表示する
+    fn hover(&self, params: &TextDocumentPositionParams) -> Option<Hover> {
+        let (document, symbol) = self.find_symbol_at(params)?;
+
+        let value = match symbol.kind {
+            BlockSymbolKind::Block => format!("```\n(block) {}\n```", symbol.id),
+            BlockSymbolKind::Rule => {
+                let rule = match &document.rule_map {
+                    Some(rule_map) => rule_map.rule_map.get(&symbol.id),
+                    None => None,
+                };
+
+                match rule {
+                    Some(v) => format!("```\n{}\n```\n\n`{}`", v, symbol.id),
+                    None => format!("```\n(rule) {}\n```", symbol.id),
+                }
+            },
+        };
+
+        let hover = Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: value,
+            }),
+            range: Some(GrammarServer::to_range(&document.content, &symbol.pos, symbol.len)),
+        };
+
+        return Some(hover);
+    }
+
+    fn find_symbol_at(&self, params: &TextDocumentPositionParams) -> Option<(&GrammarDocument, &BlockSymbol)> {
+        let file_path = GrammarServer::to_file_path(&params.text_document.uri);
+
+        let document = self.documents.get(&file_path)?;
+        let (line, column) = GrammarServer::to_line_and_column(&document.content, &params.position);
+
+        return match document.symbol_table.find_at(&file_path, line, column) {
+            Some(v) => Some((document, v)),
+            None => None,
+        };
+    }
+
+    fn to_location(&self, symbol: &BlockSymbol) -> Option<Location> {
+        let file_path = match &symbol.pos.file_path {
+            Some(v) => v,
+            None => return None,
+        };
+
+        // note: 開かれていないファイルは範囲の変換に内容を読み込む
+        let (uri, content) = match self.documents.get(file_path) {
+            Some(document) => (document.uri.clone(), document.content.clone()),
+            None => match (Url::from_file_path(file_path), FileSourceResolver::new().read_source(file_path)) {
+                (Ok(uri), Ok(content)) => (uri, content),
+                _ => return None,
+            },
+        };
+
+        return Some(Location::new(uri, GrammarServer::to_range(&content, &symbol.pos, symbol.len)));
+    }
+
+    // note: file スキーム以外の URI はそのまま仮想ファイル名とする
+    fn to_file_path(uri: &Url) -> String {
+        return match uri.to_file_path() {
+            Ok(v) => v.to_string_lossy().to_string(),
+            Err(_) => uri.to_string(),
+        };
+    }
+
+    // ret: 行の内容; CR は除く
+    fn get_line_chars(content: &str, line: usize) -> Vec<char> {
+        return match content.split('\n').nth(line) {
+            Some(v) => v.chars().filter(|each_char| *each_char != '\r').collect(),
+            None => Vec::new(),
+        };
+    }
+
+    // ret: 位置から始まる ID (連結されたものを含む) の文字数; ID でなければ 1
+    fn get_word_len(content: &str, line: usize, column: usize) -> usize {
+        let line_chars = GrammarServer::get_line_chars(content, line);
+        let len = line_chars.iter().skip(column).take_while(|each_char| each_char.is_alphanumeric() || **each_char == '_' || **each_char == '.').count();
+
+        return if len == 0 {
+            1
+        } else {
+            len
+        };
+    }
+
+    // note: CharacterPosition の列は文字単位、LSP の位置は UTF-16 単位
+    fn to_range(content: &str, pos: &CharacterPosition, len: usize) -> Range {
+        let line_chars = GrammarServer::get_line_chars(content, pos.line);
+        let start_character = line_chars.iter().take(pos.column).map(|each_char| each_char.len_utf16()).sum::<usize>();
+        let end_character = start_character + line_chars.iter().skip(pos.column).take(len).map(|each_char| each_char.len_utf16()).sum::<usize>();
+
+        return Range::new(Position::new(pos.line as u32, start_character as u32), Position::new(pos.line as u32, end_character as u32));
+    }
+
+    // ret: 文字単位の行・列
+    fn to_line_and_column(content: &str, position: &Position) -> (usize, usize) {
+        let line_chars = GrammarServer::get_line_chars(content, position.line as usize);
+        let mut character = 0usize;
+        let mut column = 0usize;
+
+        for each_char in line_chars {
+            if character >= position.character as usize {
+                break;
+            }
+
+            character += each_char.len_utf16();
+            column += 1;
+        }
+
+        return (position.line as usize, column);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    const CONTENT: &str = "% 😀,\r\nMain <- 𝒜b.Rule,\n";
+
+    #[test]
+    fn line_chars_exclude_cr() {
+        assert_eq!(GrammarServer::get_line_chars(CONTENT, 0), vec!['%', ' ', '😀', ',']);
+        assert_eq!(GrammarServer::get_line_chars(CONTENT, 3), Vec::<char>::new());
+    }
+
+    #[test]
+    fn word_len_includes_chained_ids() {
+        assert_eq!(GrammarServer::get_word_len(CONTENT, 1, 8), 7);
+        assert_eq!(GrammarServer::get_word_len(CONTENT, 1, 4), 1);
+    }
+
+    #[test]
+    fn positions_are_converted_by_utf16_units() {
+        let pos = CharacterPosition::new(Some("test.fcpeg".to_string()), 13, 1, 8);
+        assert_eq!(GrammarServer::to_range(CONTENT, &pos, 2), Range::new(Position::new(1, 8), Position::new(1, 11)));
+
+        assert_eq!(GrammarServer::to_line_and_column(CONTENT, &Position::new(1, 10)), (1, 9));
+        // note: サロゲートペアの途中の位置は次の文字の位置とする
+        assert_eq!(GrammarServer::to_line_and_column(CONTENT, &Position::new(1, 9)), (1, 9));
+    }
+
+    // note: 通知には応答できないため、パラメータが不正な通知は無視してサーバを継続する
+    #[test]
+    fn invalid_notification_is_ignored() {
+        let (connection, _client_connection) = Connection::memory();
+        let mut server = GrammarServer::new(connection);
+
+        for each_method in ["textDocument/didOpen", "textDocument/didChange", "textDocument/didClose"] {
+            let notification = Notification::new(each_method.to_string(), serde_json::json!({ "textDocument": 0 }));
+            assert!(server.handle_notification(notification).is_ok());
+        }
+
+        assert!(server.documents.is_empty());
+    }
+}
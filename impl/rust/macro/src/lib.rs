@@ -0,0 +1,453 @@
+use std::cell::RefCell;
+use std::collections::*;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use fcpeg::*;
+use fcpeg::block::*;
+use fcpeg::file::*;
+use fcpeg::rule::*;
+use fcpeg::tree::*;
+
+use proc_macro::TokenStream;
+
+use proc_macro2::{Span, TokenStream as TokenStream2};
+
+use quote::quote;
+
+use syn::{LitStr, Token, parse_macro_input};
+use syn::punctuated::Punctuated;
+
+use rustnutlib::console::*;
+
+// note: fcpeg! に渡したソースの仮想ファイル名
+const INLINE_FCPEG_FILE_PATH: &str = "fcpeg!";
+
+// spec: FCPEG ファイル及び構成ファイルをコンパイル時に読み込み、規則マップを構築する式に展開する
+// note: パスはクレートのマニフェストディレクトリからの相対パス; 構成ファイルは拡張子を cfg に置き換えたパスから読み込む
+#[proc_macro]
+pub fn include_fcpeg(input: TokenStream) -> TokenStream {
+    let path_lit = parse_macro_input!(input as LitStr);
+    let resolver = ManifestSourceResolver::new();
+    let file_map_result = FCPEGFileMap::load_with_resolver(path_lit.value(), HashMap::new(), &resolver);
+
+    // note: 外部ファイル内の位置はパスのリテラルに対応させる
+    return expand(file_map_result, &resolver, &|_| path_lit.span());
+}
+
+// spec: 文字列リテラルの FCPEG コード及び構成をコンパイル時に解析し、規則マップを構築する式に展開する
+// note: fcpeg!(fcpeg_source) または fcpeg!(fcpeg_source, config_source) の形式; サブファイルはマニフェストディレクトリから読み込む
+#[proc_macro]
+pub fn fcpeg(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input with Punctuated::<LitStr, Token![,]>::parse_terminated);
+
+    if args.is_empty() || args.len() > 2 {
+        return syn::Error::new(Span::call_site(), "expected fcpeg source and optional configuration source").to_compile_error().into();
+    }
+
+    let fcpeg_lit = &args[0];
+
+    let config_source = match args.get(1) {
+        Some(v) => v.value(),
+        None => String::new(),
+    };
+
+    let resolver = ManifestSourceResolver::new();
+    let file_map_result = FCPEGFileMap::load_from_str(INLINE_FCPEG_FILE_PATH.to_string(), fcpeg_lit.value(), config_source, HashMap::new(), &resolver);
+
+    return expand(file_map_result, &resolver, &|pos| {
+        match &pos.file_path {
+            Some(file_path) if file_path == INLINE_FCPEG_FILE_PATH => get_literal_span(fcpeg_lit, pos),
+            _ => fcpeg_lit.span(),
+        }
+    });
+}
+
+// spec: 読み込んだファイルを再コンパイルの依存に追加し、規則マップを構築する式を生成する
+// note: 読み込み時の警告はコンパイラに出力できないため報告しない
+fn expand(file_map_result: FCPEGResult<FCPEGFileMap>, resolver: &ManifestSourceResolver, get_span: &dyn Fn(&CharacterPosition) -> Span) -> TokenStream {
+    // note: ログの色付けを無効化する
+    colored::control::set_override(false);
+
+    let rule_map_result = match file_map_result {
+        Ok(mut fcpeg_file_map) => BlockParser::get_rule_map(&mut fcpeg_file_map, true, &mut Vec::new()),
+        Err(e) => Err(e),
+    };
+
+    let rule_map = match rule_map_result {
+        Ok(v) => v,
+        Err(e) => return to_compile_error(&e, get_span).into(),
+    };
+
+    let read_file_paths = resolver.read_file_paths.borrow().clone();
+    let rule_map_tokens = to_rule_map_tokens(&rule_map);
+
+    let tokens = quote! {
+        {
+            #(const _: &[u8] = include_bytes!(#read_file_paths);)*
+            #rule_map_tokens
+        }
+    };
+
+    return tokens.into();
+}
+
+fn to_compile_error(e: &FCPEGError, get_span: &dyn Fn(&CharacterPosition) -> Span) -> TokenStream2 {
+    let mut errs = Vec::<syn::Error>::new();
+    collect_compile_errors(e, get_span, &mut errs);
+
+    let mut errs_iter = errs.into_iter();
+
+    let mut compile_err = match errs_iter.next() {
+        Some(v) => v,
+        None => return TokenStream2::new(),
+    };
+
+    for each_err in errs_iter {
+        compile_err.combine(each_err);
+    }
+
+    return compile_err.to_compile_error();
+}
+
+fn collect_compile_errors(e: &FCPEGError, get_span: &dyn Fn(&CharacterPosition) -> Span, errs: &mut Vec<syn::Error>) {
+    match e {
+        FCPEGError::Multiple(sub_errs) => {
+            for each_err in sub_errs {
+                collect_compile_errors(each_err, get_span, errs);
+            }
+        },
+        _ => {
+            let span = match e.get_position() {
+                Some(pos) => get_span(&pos),
+                None => Span::call_site(),
+            };
+
+            for each_log in e.get_logs() {
+                errs.push(syn::Error::new(span, to_message(&each_log)));
+            }
+        },
+    }
+}
+
+fn to_message(log: &ConsoleLog) -> String {
+    let mut lines = vec![log.title.clone()];
+    lines.append(&mut log.descs.clone());
+    return lines.join("\n");
+}
+
+// ret: 位置に対応するリテラル内の範囲; 特定できなければリテラル全体の範囲
+// note: エスケープシーケンスを含まない raw 文字列リテラルのみ範囲を特定する; 安定版のコンパイラでは常にリテラル全体となる
+fn get_literal_span(lit: &LitStr, pos: &CharacterPosition) -> Span {
+    let token = lit.token();
+    let token_text = token.to_string();
+
+    if !token_text.starts_with('r') {
+        return lit.span();
+    }
+
+    let content_start_i = match token_text.find('"') {
+        Some(v) => v + 1,
+        None => return lit.span(),
+    };
+
+    // note: 位置は改行コード 0x0d を排除した入力の文字単位
+    let mut char_i = 0usize;
+
+    for (byte_i, each_char) in lit.value().char_indices() {
+        if each_char == '\r' {
+            continue;
+        }
+
+        if char_i == pos.index {
+            let start_i = content_start_i + byte_i;
+
+            return match token.subspan(start_i..start_i + each_char.len_utf8()) {
+                Some(v) => v,
+                None => lit.span(),
+            };
+        }
+
+        char_i += 1;
+    }
+
+    return lit.span();
+}
+
+// spec: マニフェストディレクトリからの相対パスでソースを取得し、読み込んだファイルの絶対パスを記録する
+struct ManifestSourceResolver {
+    manifest_dir: PathBuf,
+    read_file_paths: RefCell<Vec<String>>,
+}
+
+impl ManifestSourceResolver {
+    fn new() -> ManifestSourceResolver {
+        let manifest_dir = match std::env::var("CARGO_MANIFEST_DIR") {
+            Ok(v) => PathBuf::from(v),
+            Err(_) => PathBuf::new(),
+        };
+
+        return ManifestSourceResolver {
+            manifest_dir: manifest_dir,
+            read_file_paths: RefCell::new(Vec::new()),
+        };
+    }
+
+    fn to_absolute_path(&self, file_path: &str) -> String {
+        return self.manifest_dir.join(file_path).to_string_lossy().to_string();
+    }
+}
+
+impl SourceResolver for ManifestSourceResolver {
+    fn read_source(&self, file_path: &str) -> std::result::Result<String, FileLog> {
+        let absolute_file_path = self.to_absolute_path(file_path);
+        let source = FileSourceResolver::new().read_source(&absolute_file_path)?;
+        self.read_file_paths.borrow_mut().push(absolute_file_path);
+        return Ok(source);
+    }
+
+    fn is_same_source(&self, file_path: &str, other_file_path: &str) -> std::result::Result<bool, FileLog> {
+        return FileSourceResolver::new().is_same_source(&self.to_absolute_path(file_path), &self.to_absolute_path(other_file_path));
+    }
+}
+
+// note: 展開結果を再現可能にするため規則は ID 順に並べる; メモ化 ID は RuleMap::new() が規則 ID 順に割り当てたものを埋め込む
+fn to_rule_map_tokens(rule_map: &Arc<RuleMap>) -> TokenStream2 {
+    let mut rule_ids = rule_map.rule_map.keys().collect::<Vec<&String>>();
+    rule_ids.sort();
+
+    let rule_tokens = rule_ids.iter().map(|each_rule_id| to_rule_tokens(&rule_map.rule_map[*each_rule_id])).collect::<Vec<TokenStream2>>();
+
+    let mut sync_point_rule_ids = rule_map.sync_point_map.keys().collect::<Vec<&String>>();
+    sync_point_rule_ids.sort();
+    let sync_strings = sync_point_rule_ids.iter().map(|each_rule_id| rule_map.sync_point_map[*each_rule_id].clone()).collect::<Vec<Vec<String>>>();
+
+    let mut memo_rule_ids = rule_map.rule_memo_id_map.keys().collect::<Vec<&String>>();
+    memo_rule_ids.sort();
+    let memo_ids = memo_rule_ids.iter().map(|each_rule_id| rule_map.rule_memo_id_map[*each_rule_id].clone()).collect::<Vec<Vec<usize>>>();
+
+    let start_rule_pos = to_pos_tokens(&rule_map.start_rule_pos);
+    let start_rule_id = &rule_map.start_rule_id;
+    let group_count = rule_map.group_count;
+    let memoized_group_flags = &rule_map.memoized_group_flags;
+
+    let memoization_limit = match rule_map.memoization_limit {
+        Some(v) => quote! { Some(#v) },
+        None => quote! { None },
+    };
+
+    return quote! {
+        {
+            let mut rule_map = ::std::collections::HashMap::<String, Box<::fcpeg::rule::Rule>>::new();
+            #({
+                let rule = #rule_tokens;
+                rule_map.insert(rule.id.clone(), Box::new(rule));
+            })*
+
+            let mut sync_point_map = ::std::collections::HashMap::<String, Vec<String>>::new();
+            #(sync_point_map.insert(#sync_point_rule_ids.to_string(), vec![#(#sync_strings.to_string()),*]);)*
+
+            let mut rule_memo_id_map = ::std::collections::HashMap::<String, Vec<usize>>::new();
+            #(rule_memo_id_map.insert(#memo_rule_ids.to_string(), vec![#(#memo_ids),*]);)*
+
+            ::fcpeg::rule::RuleMap {
+                rule_map: rule_map,
+                start_rule_pos: #start_rule_pos,
+                start_rule_id: #start_rule_id.to_string(),
+                sync_point_map: sync_point_map,
+                group_count: #group_count,
+                memoized_group_flags: vec![#(#memoized_group_flags),*],
+                rule_memo_id_map: rule_memo_id_map,
+                memoization_limit: #memoization_limit,
+            }
+        }
+    };
+}
+
+fn to_rule_tokens(rule: &Rule) -> TokenStream2 {
+    let pos = to_pos_tokens(&rule.pos);
+    let id = &rule.id;
+    let name = &rule.name;
+    let generics_arg_ids = &rule.generics_arg_ids;
+    let template_arg_ids = &rule.template_arg_ids;
+    let group = to_group_tokens(&rule.group);
+
+    return quote! {
+        ::fcpeg::rule::Rule::new(#pos, #id.to_string(), #name.to_string(), vec![#(#generics_arg_ids.to_string()),*], vec![#(#template_arg_ids.to_string()),*], #group)
+    };
+}
+
+fn to_group_tokens(group: &RuleGroup) -> TokenStream2 {
+    let kind = match group.kind {
+        RuleGroupKind::Choice => quote! { ::fcpeg::rule::RuleGroupKind::Choice },
+        RuleGroupKind::Sequence => quote! { ::fcpeg::rule::RuleGroupKind::Sequence },
+    };
+
+    let memo_id = group.memo_id;
+    let sub_elems = group.sub_elems.iter().map(to_elem_tokens).collect::<Vec<TokenStream2>>();
+    let ast_reflection_style = to_style_tokens(&group.ast_reflection_style);
+    let lookahead_kind = to_lookahead_kind_tokens(&group.lookahead_kind);
+    let loop_range = to_loop_range_tokens(&group.loop_range);
+
+    let elem_order = match &group.elem_order {
+        RuleElementOrder::Random(random_order_loop_range) => {
+            let random_order_loop_range = to_loop_range_tokens(random_order_loop_range);
+            quote! { ::fcpeg::rule::RuleElementOrder::Random(#random_order_loop_range) }
+        },
+        RuleElementOrder::Sequential => quote! { ::fcpeg::rule::RuleElementOrder::Sequential },
+    };
+
+    return quote! {
+        {
+            let mut group = ::fcpeg::rule::RuleGroup::new(#kind);
+            group.memo_id = #memo_id;
+            group.sub_elems = vec![#(#sub_elems),*];
+            group.ast_reflection_style = #ast_reflection_style;
+            group.lookahead_kind = #lookahead_kind;
+            group.loop_range = #loop_range;
+            group.elem_order = #elem_order;
+            Box::new(group)
+        }
+    };
+}
+
+fn to_elem_tokens(elem: &RuleElement) -> TokenStream2 {
+    return match elem {
+        RuleElement::Group(group) => {
+            let group = to_group_tokens(group);
+            quote! { ::fcpeg::rule::RuleElement::Group(#group) }
+        },
+        RuleElement::Expression(expr) => {
+            let expr = to_expr_tokens(expr);
+            quote! { ::fcpeg::rule::RuleElement::Expression(#expr) }
+        },
+        RuleElement::Cut(pos) => {
+            let pos = to_pos_tokens(pos);
+            quote! { ::fcpeg::rule::RuleElement::Cut(#pos) }
+        },
+    };
+}
+
+fn to_expr_tokens(expr: &RuleExpression) -> TokenStream2 {
+    let pos = to_pos_tokens(&expr.pos);
+
+    let kind = match &expr.kind {
+        RuleExpressionKind::ArgId => quote! { ::fcpeg::rule::RuleExpressionKind::ArgId },
+        RuleExpressionKind::CharClass => quote! { ::fcpeg::rule::RuleExpressionKind::CharClass },
+        RuleExpressionKind::Id => quote! { ::fcpeg::rule::RuleExpressionKind::Id },
+        RuleExpressionKind::IdWithArgs { generics_args, template_args } => {
+            let generics_args = generics_args.iter().map(|each_group| to_group_tokens(each_group)).collect::<Vec<TokenStream2>>();
+            let template_args = template_args.iter().map(|each_group| to_group_tokens(each_group)).collect::<Vec<TokenStream2>>();
+
+            quote! {
+                ::fcpeg::rule::RuleExpressionKind::IdWithArgs {
+                    generics_args: vec![#(#generics_args),*],
+                    template_args: vec![#(#template_args),*],
+                }
+            }
+        },
+        RuleExpressionKind::String => quote! { ::fcpeg::rule::RuleExpressionKind::String },
+        RuleExpressionKind::Wildcard => quote! { ::fcpeg::rule::RuleExpressionKind::Wildcard },
+    };
+
+    let value = &expr.value;
+    let ast_reflection_style = to_style_tokens(&expr.ast_reflection_style);
+    let lookahead_kind = to_lookahead_kind_tokens(&expr.lookahead_kind);
+    let loop_range = to_loop_range_tokens(&expr.loop_range);
+
+    // note: 文字クラスはコンパイル済みの文字範囲から復元する
+    let char_class = match &expr.char_class {
+        Some(char_class) => {
+            let start_chars = char_class.get_ranges().iter().map(|(start_char, _)| *start_char).collect::<Vec<char>>();
+            let end_chars = char_class.get_ranges().iter().map(|(_, end_char)| *end_char).collect::<Vec<char>>();
+            quote! { Some(::std::sync::Arc::new(::fcpeg::rule::CharacterClass::from_ranges(vec![#((#start_chars, #end_chars)),*]))) }
+        },
+        None => quote! { None },
+    };
+
+    return quote! {
+        {
+            let mut expr = ::fcpeg::rule::RuleExpression::new(#pos, #kind, #value.to_string());
+            expr.ast_reflection_style = #ast_reflection_style;
+            expr.lookahead_kind = #lookahead_kind;
+            expr.loop_range = #loop_range;
+            expr.char_class = #char_class;
+            Box::new(expr)
+        }
+    };
+}
+
+fn to_pos_tokens(pos: &CharacterPosition) -> TokenStream2 {
+    let file_path = match &pos.file_path {
+        Some(v) => quote! { Some(#v.to_string()) },
+        None => quote! { None },
+    };
+
+    let index = pos.index;
+    let line = pos.line;
+    let column = pos.column;
+
+    return quote! { ::fcpeg::tree::CharacterPosition::new(#file_path, #index, #line, #column) };
+}
+
+fn to_style_tokens(ast_reflection_style: &ASTReflectionStyle) -> TokenStream2 {
+    return match ast_reflection_style {
+        ASTReflectionStyle::Reflection(elem_name) => quote! { ::fcpeg::tree::ASTReflectionStyle::Reflection(#elem_name.to_string()) },
+        ASTReflectionStyle::NoReflection => quote! { ::fcpeg::tree::ASTReflectionStyle::NoReflection },
+        ASTReflectionStyle::Expansion => quote! { ::fcpeg::tree::ASTReflectionStyle::Expansion },
+    };
+}
+
+fn to_lookahead_kind_tokens(lookahead_kind: &RuleElementLookaheadKind) -> TokenStream2 {
+    return match lookahead_kind {
+        RuleElementLookaheadKind::None => quote! { ::fcpeg::rule::RuleElementLookaheadKind::None },
+        RuleElementLookaheadKind::Positive => quote! { ::fcpeg::rule::RuleElementLookaheadKind::Positive },
+        RuleElementLookaheadKind::Negative => quote! { ::fcpeg::rule::RuleElementLookaheadKind::Negative },
+    };
+}
+
+fn to_loop_range_tokens(loop_range: &RuleElementLoopRange) -> TokenStream2 {
+    let min = loop_range.min;
+
+    let max = match &loop_range.max {
+        Infinitable::Finite(v) => quote! { ::fcpeg::rule::Infinitable::Finite(#v) },
+        Infinitable::Infinite => quote! { ::fcpeg::rule::Infinitable::Infinite },
+    };
+
+    return quote! { ::fcpeg::rule::RuleElementLoopRange::new(#min, #max) };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::*;
+
+    use fcpeg::block::*;
+    use fcpeg::file::*;
+
+    use crate::*;
+
+    const FCPEG_SOURCE: &str = "[Main]{\n    + start Syntax.Main,\n}\n\n[Syntax]{\n    Main <- (A : B : C)*,\n    A <- \"a\" (\"1\" : \"2\"),\n    B <- \"b\"+,\n    C <- \"c\" [0-9]?,\n}\n";
+
+    fn expand_rule_map() -> String {
+        let mut fcpeg_file_map = FCPEGFileMap::load_from_str("test.fcpeg".to_string(), FCPEG_SOURCE.to_string(), String::new(), HashMap::new(), &MemorySourceResolver::new()).unwrap();
+        let rule_map = BlockParser::get_rule_map(&mut fcpeg_file_map, true, &mut Vec::new()).unwrap();
+        return to_rule_map_tokens(&rule_map).to_string();
+    }
+
+    #[test]
+    fn expansion_is_reproducible() {
+        let expansion = expand_rule_map();
+
+        for _ in 0..8 {
+            assert_eq!(expansion, expand_rule_map());
+        }
+    }
+
+    #[test]
+    fn multiple_errors_are_combined() {
+        let errs = (0..2).map(|each_i| FCPEGError::File(FileLog::FailedToReadSource { file_path: format!("{}.fcpeg", each_i) })).collect::<Vec<FCPEGError>>();
+        let tokens = to_compile_error(&FCPEGError::Multiple(errs), &|_| Span::call_site()).to_string();
+
+        assert_eq!(tokens.matches("compile_error").count(), 2);
+    }
+}